@@ -8,14 +8,32 @@ pub const CONTRACT_VERSION: &str = "1.0.0";
 pub mod topics {
     // Manager 토픽
     pub const MANAGER_COMMAND: &str = "manager/command";
+    /// `ManagerRequest` 에 reply_to 가 없을 때 `ManagerReply` 를 보내는 토픽
+    pub const MANAGER_RESPONSE: &str = "manager/response";
+    /// `ManagerRequest::reply_to` 로 쓸 수 있는 토픽의 접두사 (예: `manager/response/cli`)
+    pub const MANAGER_RESPONSE_PREFIX: &str = "manager/response/";
     pub const MANAGER_HEALTH_REPORT: &str = "manager/health/report";
     pub const MANAGER_PROCESS_STATUS: &str = "manager/process/status";
     pub const MANAGER_PROCESS_ALERT: &str = "manager/process/alert";
 
-    // RinAgent 토픽 (현재는 사용되지 않음)
+    // RinAgent 토픽
     pub const RIN_AGENT_COMMAND: &str = "rin_agent/command";
     pub const RIN_AGENT_STATUS: &str = "rin_agent/status";
     pub const RIN_AGENT_ALERT: &str = "rin_agent/alert";
+
+    /// manager 가 응답을 보내도 되는 토픽인지 확인합니다.
+    ///
+    /// `MANAGER_RESPONSE` 자체나 그 아래 토픽만 허용해서, 요청자가 `rin_agent/command` 같은
+    /// 다른 토픽으로 manager 를 시켜 메시지를 보내지 못하게 합니다.
+    pub fn is_manager_reply_topic(topic: &str) -> bool {
+        if topic == MANAGER_RESPONSE {
+            return true;
+        }
+        match topic.strip_prefix(MANAGER_RESPONSE_PREFIX) {
+            Some(rest) => !rest.is_empty() && !rest.contains(['+', '#', '\0']),
+            None => false,
+        }
+    }
 }
 
 /// 로그 레벨 정의
@@ -70,6 +88,67 @@ pub enum ManagerCommand {
     StopMonitoring { process_name: String },
 }
 
+#[cfg(feature = "mqtt")]
+impl ManagerCommand {
+    /// 직렬화했을 때의 type 값
+    pub fn name(&self) -> &'static str {
+        match self {
+            ManagerCommand::RestartProcess { .. } => "restart_process",
+            ManagerCommand::HealthCheck => "health_check",
+            ManagerCommand::SystemInfo => "system_info",
+            ManagerCommand::StartMonitoring { .. } => "start_monitoring",
+            ManagerCommand::StopMonitoring { .. } => "stop_monitoring",
+        }
+    }
+}
+
+/// manager/command 로 보내는 요청 (MQTT feature 필요)
+///
+/// `{"correlation_id": "abc", "type": "health_check"}` 처럼 명령 필드와 같은 단계에 씁니다.
+/// correlation_id 가 없는 기존 `ManagerCommand` JSON 도 그대로 받습니다.
+#[cfg(feature = "mqtt")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerRequest {
+    /// 응답에 그대로 돌려주는 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// 응답 토픽. 없으면 `topics::MANAGER_RESPONSE`
+    ///
+    /// `topics::MANAGER_RESPONSE_PREFIX` 아래 토픽만 받으며, 그 밖의 토픽이면 명령을 실행하지 않고
+    /// `topics::MANAGER_RESPONSE` 로 오류를 보냅니다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    #[serde(flatten)]
+    pub command: ManagerCommand,
+}
+
+#[cfg(feature = "mqtt")]
+impl ManagerRequest {
+    pub fn new(command: ManagerCommand) -> Self {
+        Self { correlation_id: None, reply_to: None, command }
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    pub fn with_reply_to(mut self, reply_to: impl Into<String>) -> Self {
+        self.reply_to = Some(reply_to.into());
+        self
+    }
+}
+
+/// `ManagerRequest` 처리 결과 (MQTT feature 필요)
+#[cfg(feature = "mqtt")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerReply {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    #[serde(flatten)]
+    pub response: ManagerResponse,
+}
+
 /// Manager 응답 타입 (MQTT feature 필요)
 #[cfg(feature = "mqtt")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,7 +181,7 @@ pub enum ManagerResponse {
     },
 }
 
-/// RinAgent 명령 타입 (MQTT feature 필요 - 현재 사용되지 않음)
+/// RinAgent 명령 타입 (MQTT feature 필요)
 #[cfg(feature = "mqtt")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -115,13 +194,13 @@ pub enum RinAgentCommand {
     },
     /// 서비스 재시작
     Restart { graceful: bool },
-    /// 설정 리로드
+    /// 알람 캐시 리로드. .env 에서 바뀐 값은 알려만 주고, 반영은 재시작해야 합니다.
     ReloadConfig,
     /// Discord 봇 상태 확인
     BotStatus,
 }
 
-/// RinAgent 상태 응답 (MQTT feature 필요 - 현재 사용되지 않음)
+/// RinAgent 상태 응답 (MQTT feature 필요)
 #[cfg(feature = "mqtt")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -141,6 +220,26 @@ pub enum RinAgentStatus {
     Error { error: String, timestamp: u64 },
 }
 
+/// RinAgent 알림 - 명령 처리 결과와 하위 작업 장애를 알립니다 (MQTT feature 필요)
+#[cfg(feature = "mqtt")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RinAgentAlert {
+    pub level: LogLevel,
+    pub message: String,
+    pub timestamp: u64,
+}
+
+#[cfg(feature = "mqtt")]
+impl RinAgentAlert {
+    pub fn new(level: LogLevel, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            timestamp: chrono::Utc::now().timestamp() as u64,
+        }
+    }
+}
+
 /// 프로세스 재시작 결과 (MQTT feature 필요)
 #[cfg(feature = "mqtt")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -209,4 +308,55 @@ mod tests {
             _ => panic!("Wrong command type"),
         }
     }
+
+    #[test]
+    #[cfg(feature = "mqtt")]
+    fn test_manager_request_envelope() {
+        let request: ManagerRequest = serde_json::from_str(
+            r#"{"correlation_id": "req-1", "reply_to": "manager/response/cli", "type": "start_monitoring", "process_name": "rin_agent", "interval_secs": 5}"#,
+        )
+        .unwrap();
+        assert_eq!(request.correlation_id.as_deref(), Some("req-1"));
+        assert_eq!(request.reply_to.as_deref(), Some("manager/response/cli"));
+        assert_eq!(request.command.name(), "start_monitoring");
+
+        // correlation_id 없는 기존 형식
+        let legacy: ManagerRequest = serde_json::from_str(r#"{"type": "health_check"}"#).unwrap();
+        assert!(legacy.correlation_id.is_none());
+        assert!(matches!(legacy.command, ManagerCommand::HealthCheck));
+    }
+
+    #[test]
+    #[cfg(feature = "mqtt")]
+    fn test_manager_reply_topic() {
+        assert!(topics::is_manager_reply_topic(topics::MANAGER_RESPONSE));
+        assert!(topics::is_manager_reply_topic("manager/response/cli"));
+        assert!(topics::is_manager_reply_topic("manager/response/cli/42"));
+
+        assert!(!topics::is_manager_reply_topic("manager/response/"));
+        assert!(!topics::is_manager_reply_topic("manager/response/#"));
+        assert!(!topics::is_manager_reply_topic("manager/responses"));
+        assert!(!topics::is_manager_reply_topic(topics::MANAGER_COMMAND));
+        assert!(!topics::is_manager_reply_topic(topics::RIN_AGENT_COMMAND));
+        assert!(!topics::is_manager_reply_topic("cli/replies"));
+    }
+
+    #[test]
+    #[cfg(feature = "mqtt")]
+    fn test_manager_reply_serialization() {
+        let reply = ManagerReply {
+            correlation_id: Some("req-1".to_string()),
+            response: ManagerResponse::Error {
+                command: "stop_monitoring".to_string(),
+                error: "not monitored".to_string(),
+            },
+        };
+        let json: serde_json::Value = serde_json::to_value(&reply).unwrap();
+        assert_eq!(json["correlation_id"], "req-1");
+        assert_eq!(json["type"], "error");
+
+        let deserialized: ManagerReply = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.correlation_id.as_deref(), Some("req-1"));
+        assert!(matches!(deserialized.response, ManagerResponse::Error { .. }));
+    }
 }
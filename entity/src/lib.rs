@@ -10,4 +10,9 @@ pub mod tb_debtor;
 pub mod tb_discord_ai_context;
 pub mod tb_discord_guilds;
 pub mod tb_discord_message_to_at_context;
+pub mod tb_gemini_usage;
 pub mod tb_image_attach_file;
+pub mod tb_structured_output;
+pub mod tb_voice_transcript;
+pub mod tb_voice_transcript_session;
+pub mod tb_web_auth_token;
@@ -8,4 +8,9 @@ pub use super::tb_debtor::Entity as TbDebtor;
 pub use super::tb_discord_ai_context::Entity as TbDiscordAiContext;
 pub use super::tb_discord_guilds::Entity as TbDiscordGuilds;
 pub use super::tb_discord_message_to_at_context::Entity as TbDiscordMessageToAtContext;
+pub use super::tb_gemini_usage::Entity as TbGeminiUsage;
 pub use super::tb_image_attach_file::Entity as TbImageAttachFile;
+pub use super::tb_structured_output::Entity as TbStructuredOutput;
+pub use super::tb_voice_transcript::Entity as TbVoiceTranscript;
+pub use super::tb_voice_transcript_session::Entity as TbVoiceTranscriptSession;
+pub use super::tb_web_auth_token::Entity as TbWebAuthToken;
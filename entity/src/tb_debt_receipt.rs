@@ -12,6 +12,8 @@ pub struct Model {
     pub created_at: DateTimeWithTimeZone,
     pub is_cleared: bool,
     pub cleared_at: Option<DateTimeWithTimeZone>,
+    pub memo: Option<String>,
+    pub image_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,6 +26,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     TbDebtor,
+    #[sea_orm(
+        belongs_to = "super::tb_image_attach_file::Entity",
+        from = "Column::ImageId",
+        to = "super::tb_image_attach_file::Column::ImageId",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    TbImageAttachFile,
 }
 
 impl Related<super::tb_debtor::Entity> for Entity {
@@ -32,4 +42,10 @@ impl Related<super::tb_debtor::Entity> for Entity {
     }
 }
 
+impl Related<super::tb_image_attach_file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TbImageAttachFile.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -11,6 +11,12 @@ pub struct Model {
     pub guild_id: i64,
     pub joined_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub auto_model_routing: bool,
+    pub allow_pro_model: bool,
+    pub enable_google_search: bool,
+    pub enable_url_context: bool,
+    pub enable_code_execution: bool,
+    pub search_provider: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -16,6 +16,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::tb_ai_context::Entity")]
     TbAiContext,
+    #[sea_orm(has_many = "super::tb_debt_receipt::Entity")]
+    TbDebtReceipt,
 }
 
 impl Related<super::tb_ai_context::Entity> for Entity {
@@ -24,4 +26,10 @@ impl Related<super::tb_ai_context::Entity> for Entity {
     }
 }
 
+impl Related<super::tb_debt_receipt::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TbDebtReceipt.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,31 @@
+use contract::ManagerResponse;
+use sysinfo::System;
+use tokio::sync::RwLock;
+
+pub struct HealthCommand;
+
+impl HealthCommand {
+  /// CPU / 메모리 사용량을 새로 읽어 HealthReport 로 반환합니다.
+  pub async fn execute(system_info: &RwLock<System>) -> ManagerResponse {
+    let mut sys = system_info.write().await;
+    sys.refresh_cpu_usage();
+    sys.refresh_memory();
+    
+    let cpu_usage = sys.global_cpu_usage();
+    let total_memory = sys.total_memory();
+    let used_memory = sys.used_memory();
+    let memory_usage_percent = if total_memory == 0 {
+      0.0
+    } else {
+      (used_memory as f64 / total_memory as f64) * 100.0
+    };
+    
+    ManagerResponse::HealthReport {
+      cpu_usage,
+      memory_usage_percent,
+      total_memory_mb: total_memory / 1024 / 1024,
+      used_memory_mb: used_memory / 1024 / 1024,
+      timestamp: chrono::Utc::now().timestamp() as u64,
+    }
+  }
+}
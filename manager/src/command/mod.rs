@@ -1 +1,4 @@
-pub mod restart;
\ No newline at end of file
+pub mod restart;
+pub mod health;
+pub mod system_info;
+pub mod monitoring;
\ No newline at end of file
@@ -0,0 +1,186 @@
+use crate::libs::bulk_logger::BulkLoggerHandler;
+use crate::libs::find_server::get_server_pid;
+use contract::{LogLevel, LogPacket, ManagerResponse, topics};
+use rumqttc::{AsyncClient, QoS};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::interval;
+
+/// 프로세스별 모니터링 태스크 목록
+///
+/// 같은 프로세스에 StartMonitoring 이 다시 오면 기존 태스크를 멈추고 새 주기로 교체합니다.
+pub struct ProcessMonitors {
+  logger: Arc<BulkLoggerHandler>,
+  mqtt_client: Arc<AsyncClient>,
+  tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+}
+
+impl ProcessMonitors {
+  pub fn new(logger: Arc<BulkLoggerHandler>, mqtt_client: Arc<AsyncClient>) -> Self {
+    Self {
+      logger,
+      mqtt_client,
+      tasks: Mutex::new(HashMap::new()),
+    }
+  }
+
+  pub async fn start(&self, process_name: &str, interval_secs: u64) -> Result<String, String> {
+    if process_name.trim().is_empty() {
+      return Err("process_name must not be empty".to_string());
+    }
+    if interval_secs == 0 {
+      return Err("interval_secs must be greater than 0".to_string());
+    }
+
+    let logger = Arc::clone(&self.logger);
+    let mqtt_client = Arc::clone(&self.mqtt_client);
+    let name = process_name.to_string();
+    let handle = tokio::spawn(async move {
+      process_monitoring_loop(logger, mqtt_client, name, interval_secs).await;
+    });
+
+    let replaced = self.tasks.lock().await.insert(process_name.to_string(), handle);
+
+    match replaced {
+      Some(previous) => {
+        previous.abort();
+        Ok(format!(
+          "Monitoring interval for '{}' changed to {}s",
+          process_name, interval_secs
+        ))
+      }
+      None => Ok(format!(
+        "Started monitoring '{}' every {}s",
+        process_name, interval_secs
+      )),
+    }
+  }
+
+  pub async fn stop(&self, process_name: &str) -> Result<String, String> {
+    match self.tasks.lock().await.remove(process_name) {
+      Some(handle) => {
+        handle.abort();
+        Ok(format!("Stopped monitoring '{}'", process_name))
+      }
+      None => Err(format!("Process '{}' is not being monitored", process_name)),
+    }
+  }
+
+  pub async fn monitored(&self) -> Vec<String> {
+    let mut names: Vec<String> = self.tasks.lock().await.keys().cloned().collect();
+    names.sort();
+    names
+  }
+}
+
+async fn process_monitoring_loop(
+  logger: Arc<BulkLoggerHandler>,
+  mqtt_client: Arc<AsyncClient>,
+  process_name: String,
+  interval_secs: u64,
+) {
+  let mut interval = interval(Duration::from_secs(interval_secs));
+
+  loop {
+    interval.tick().await;
+
+    match get_server_pid(&process_name) {
+      Some(pid) => {
+        let status = ManagerResponse::ProcessStatus {
+          process_name: process_name.clone(),
+          is_running: true,
+          pid: Some(pid.as_u32()),
+          timestamp: chrono::Utc::now().timestamp() as u64,
+        };
+
+        logger
+        .log(
+          LogPacket::new(
+            LogLevel::Info,
+            format!("Process '{}' is running with PID: {:?}", process_name, pid),
+          )
+          .with_source("manager"),
+        )
+        .await;
+
+        let json = serde_json::to_string(&status).unwrap();
+        let _ = mqtt_client
+        .publish(topics::MANAGER_PROCESS_STATUS, QoS::AtLeastOnce, false, json)
+        .await;
+      }
+      None => {
+        let status = ManagerResponse::ProcessStatus {
+          process_name: process_name.clone(),
+          is_running: false,
+          pid: None,
+          timestamp: chrono::Utc::now().timestamp() as u64,
+        };
+
+        logger
+        .log(
+          LogPacket::new(
+            LogLevel::Warn,
+            format!("Process '{}' not found!", process_name),
+          )
+          .with_source("manager"),
+        )
+        .await;
+
+        let json = serde_json::to_string(&status).unwrap();
+        let _ = mqtt_client
+        .publish(topics::MANAGER_PROCESS_ALERT, QoS::AtLeastOnce, false, json)
+        .await;
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::libs::test_support::{test_logger, test_mqtt_client};
+
+  // 첫 tick 이후에는 테스트 중에 다시 돌지 않도록 긴 주기를 씀
+  const LONG_INTERVAL_SECS: u64 = 3600;
+
+  #[tokio::test]
+  async fn start_rejects_invalid_arguments() {
+    let (mqtt_client, _event_loop) = test_mqtt_client();
+    let monitors = ProcessMonitors::new(test_logger().await, mqtt_client);
+
+    assert!(monitors.start("  ", LONG_INTERVAL_SECS).await.is_err());
+    assert!(monitors.start("rin_agent", 0).await.is_err());
+    assert!(monitors.monitored().await.is_empty());
+  }
+
+  #[tokio::test]
+  async fn start_replaces_existing_monitor() {
+    let (mqtt_client, _event_loop) = test_mqtt_client();
+    let monitors = ProcessMonitors::new(test_logger().await, mqtt_client);
+
+    let started = monitors.start("rin_agent", LONG_INTERVAL_SECS).await.unwrap();
+    assert!(started.starts_with("Started monitoring"));
+
+    let replaced = monitors.start("rin_agent", LONG_INTERVAL_SECS * 2).await.unwrap();
+    assert!(replaced.contains("changed to 7200s"));
+    assert_eq!(monitors.monitored().await, vec!["rin_agent".to_string()]);
+
+    let tasks = monitors.tasks.lock().await;
+    assert!(!tasks["rin_agent"].is_finished());
+  }
+
+  #[tokio::test]
+  async fn stop_aborts_and_forgets_monitor() {
+    let (mqtt_client, _event_loop) = test_mqtt_client();
+    let monitors = ProcessMonitors::new(test_logger().await, mqtt_client);
+    monitors.start("rin_agent", LONG_INTERVAL_SECS).await.unwrap();
+    monitors.start("postgres", LONG_INTERVAL_SECS).await.unwrap();
+
+    assert_eq!(monitors.stop("rin_agent").await.unwrap(), "Stopped monitoring 'rin_agent'");
+    assert_eq!(monitors.monitored().await, vec!["postgres".to_string()]);
+    assert!(monitors.stop("rin_agent").await.is_err());
+  }
+}
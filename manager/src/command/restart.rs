@@ -1,37 +1,161 @@
 use crate::libs::find_server::get_server_pid;
-use sysinfo::System;
+use contract::RestartResult;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessStatus, ProcessesToUpdate, Signal, System};
+use tokio::process::Command;
+
+/// SIGTERM 후 프로세스가 스스로 종료되기를 기다리는 최대 시간
+/// (rin_agent 의 graceful shutdown 드레인 시간보다 길어야 함)
+const GRACEFUL_EXIT_TIMEOUT: Duration = Duration::from_secs(60);
+/// SIGKILL 후 종료 확인까지 기다리는 최대 시간
+const FORCE_EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 재실행에 필요한 프로세스 정보 (종료 전에 기록)
+struct LaunchSpec {
+  exe: PathBuf,
+  args: Vec<OsString>,
+  cwd: Option<PathBuf>,
+}
 
 pub struct RestartCommand;
 
 impl RestartCommand {
-  pub async fn execute(process_name: &str) -> Result<String, String> {
-    // Find the process
-    match get_server_pid(process_name) {
-      Some(pid) => {
-        let mut sys = System::new_all();
-        sys.refresh_all();
-        
-        if let Some(process) = sys.process(pid) {
-          // Try to kill the process
-          if process.kill() {
-            Ok(format!(
-              "Successfully sent kill signal to process '{}' (PID: {:?})",
-              process_name, pid
-            ))
-          } else {
-            Err(format!(
-              "Failed to kill process '{}' (PID: {:?})",
-              process_name, pid
-            ))
-          }
-        } else {
-          Err(format!(
-            "Process '{}' with PID {:?} not found in system",
-            process_name, pid
-          ))
-        }
+  /// 프로세스를 종료한 뒤 같은 실행 파일, 인자, 작업 디렉터리로 다시 실행합니다.
+  ///
+  /// `force` 가 false 면 SIGTERM 으로 graceful shutdown 을 기다리고,
+  /// 시간 안에 종료되지 않으면 SIGKILL 로 넘어갑니다.
+  pub async fn execute(process_name: &str, force: bool) -> Result<RestartResult, String> {
+    let pid = get_server_pid(process_name)
+    .ok_or_else(|| format!("Process '{}' not found", process_name))?;
+
+    let spec = {
+      let mut sys = System::new_all();
+      sys.refresh_all();
+
+      let process = sys.process(pid).ok_or_else(|| {
+        format!(
+          "Process '{}' with PID {:?} not found in system",
+          process_name, pid
+        )
+      })?;
+
+      let exe = process.exe().map(|path| path.to_path_buf()).ok_or_else(|| {
+        format!(
+          "Cannot read executable path of process '{}' (PID: {:?})",
+          process_name, pid
+        )
+      })?;
+
+      let spec = LaunchSpec {
+        exe,
+        args: process.cmd().iter().skip(1).cloned().collect(),
+        cwd: process.cwd().map(|path| path.to_path_buf()),
+      };
+
+      let signal_sent = if force {
+        process.kill()
+      } else {
+        // SIGTERM 을 지원하지 않는 플랫폼이면 kill 로 대체
+        process.kill_with(Signal::Term).unwrap_or_else(|| process.kill())
+      };
+
+      if !signal_sent {
+        return Err(format!(
+          "Failed to kill process '{}' (PID: {:?})",
+          process_name, pid
+        ));
       }
-      None => Err(format!("Process '{}' not found", process_name)),
+
+      spec
+    };
+
+    let exit_timeout = if force { FORCE_EXIT_TIMEOUT } else { GRACEFUL_EXIT_TIMEOUT };
+    if !wait_for_exit(pid, exit_timeout).await {
+      if force {
+        return Err(format!(
+          "Process '{}' (PID: {:?}) did not exit after kill signal",
+          process_name, pid
+        ));
+      }
+
+      // graceful 종료 시간 초과 -> 강제 종료
+      let killed = {
+        let mut sys = System::new();
+        sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        sys.process(pid).map(|process| process.kill()).unwrap_or(true)
+      };
+
+      if !killed || !wait_for_exit(pid, FORCE_EXIT_TIMEOUT).await {
+        return Err(format!(
+          "Process '{}' (PID: {:?}) did not exit after SIGTERM and kill",
+          process_name, pid
+        ));
+      }
+    }
+
+    let new_pid = spawn(&spec).await.map_err(|e| {
+      format!(
+        "Process '{}' stopped but failed to respawn {:?}: {}",
+        process_name, spec.exe, e
+      )
+    })?;
+
+    Ok(RestartResult {
+      success: true,
+      message: format!(
+        "Restarted process '{}' (old PID: {:?}, new PID: {})",
+        process_name, pid, new_pid
+      ),
+      pid: Some(new_pid),
+    })
+  }
+}
+
+/// 프로세스가 사라지거나 좀비가 될 때까지 기다립니다. 시간 초과 시 false
+async fn wait_for_exit(pid: Pid, timeout: Duration) -> bool {
+  let deadline = tokio::time::Instant::now() + timeout;
+  let mut sys = System::new();
+
+  loop {
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+    let exited = match sys.process(pid) {
+      Some(process) => matches!(process.status(), ProcessStatus::Zombie | ProcessStatus::Dead),
+      None => true,
+    };
+
+    if exited {
+      return true;
+    }
+
+    if tokio::time::Instant::now() >= deadline {
+      return false;
     }
+
+    tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+  }
+}
+
+async fn spawn(spec: &LaunchSpec) -> std::io::Result<u32> {
+  let mut command = Command::new(&spec.exe);
+  command.args(&spec.args);
+
+  if let Some(cwd) = &spec.cwd {
+    command.current_dir(cwd);
   }
+
+  let mut child = command.spawn()?;
+  let pid = child
+  .id()
+  .ok_or_else(|| std::io::Error::other("spawned process exited immediately"))?;
+
+  // 다음 재시작 때 좀비로 남지 않도록 종료 상태를 회수
+  tokio::spawn(async move {
+    let _ = child.wait().await;
+  });
+
+  Ok(pid)
 }
@@ -0,0 +1,30 @@
+use serde_json::{Value, json};
+use sysinfo::System;
+use tokio::sync::RwLock;
+
+pub struct SystemInfoCommand;
+
+impl SystemInfoCommand {
+  /// 호스트 / OS / 하드웨어 정보와 현재 모니터링 중인 프로세스 목록
+  pub async fn execute(system_info: &RwLock<System>, monitored_processes: Vec<String>) -> Value {
+    let mut sys = system_info.write().await;
+    sys.refresh_memory();
+    
+    json!({
+      "host_name": System::host_name(),
+      "os_name": System::name(),
+      "os_version": System::os_version(),
+      "kernel_version": System::kernel_version(),
+      "cpu_arch": System::cpu_arch(),
+      "cpu_count": sys.cpus().len(),
+      "total_memory_mb": sys.total_memory() / 1024 / 1024,
+      "used_memory_mb": sys.used_memory() / 1024 / 1024,
+      "total_swap_mb": sys.total_swap() / 1024 / 1024,
+      "used_swap_mb": sys.used_swap() / 1024 / 1024,
+      "uptime_secs": System::uptime(),
+      "boot_time": System::boot_time(),
+      "monitored_processes": monitored_processes,
+      "timestamp": chrono::Utc::now().timestamp() as u64,
+    })
+  }
+}
@@ -1,2 +1,4 @@
 pub mod find_server;
-pub mod bulk_logger;
\ No newline at end of file
+pub mod bulk_logger;
+#[cfg(test)]
+pub mod test_support;
\ No newline at end of file
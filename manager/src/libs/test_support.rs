@@ -0,0 +1,19 @@
+use crate::libs::bulk_logger::{BulkLoggerHandler, LoggerArgs};
+use rumqttc::{AsyncClient, EventLoop, MqttOptions};
+use std::sync::Arc;
+
+/// Redis 에 연결하지 못해도 만들어지는 로거 (로그는 버려짐)
+pub async fn test_logger() -> Arc<BulkLoggerHandler> {
+  let logger = BulkLoggerHandler::new(LoggerArgs::Url("redis://127.0.0.1:1".to_string()), 100, 60)
+  .await
+  .expect("logger should be created without a Redis connection");
+  Arc::new(logger)
+}
+
+/// 브로커에 연결하지 않는 MQTT 클라이언트
+///
+/// EventLoop 를 버리면 publish 가 실패하므로 테스트가 끝날 때까지 함께 들고 있어야 함
+pub fn test_mqtt_client() -> (Arc<AsyncClient>, EventLoop) {
+  let (client, event_loop) = AsyncClient::new(MqttOptions::new("manager-test", "127.0.0.1", 1), 10);
+  (Arc::new(client), event_loop)
+}
@@ -1,6 +1,11 @@
+use crate::command::health::HealthCommand;
+use crate::command::monitoring::ProcessMonitors;
+use crate::command::restart::RestartCommand;
+use crate::command::system_info::SystemInfoCommand;
 use crate::libs::bulk_logger::{BulkLoggerHandler, LoggerArgs};
-use crate::libs::find_server::get_server_pid;
-use contract::{LogLevel, LogPacket, ManagerCommand, ManagerResponse, topics};
+use contract::{
+  LogLevel, LogPacket, ManagerCommand, ManagerReply, ManagerRequest, ManagerResponse, topics,
+};
 use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, QoS};
 use sea_orm::{Database, DatabaseConnection};
 use std::sync::Arc;
@@ -17,8 +22,21 @@ pub struct ManagerService {
   db: Arc<DatabaseConnection>,
   mqtt_client: Arc<AsyncClient>,
   system_info: Arc<RwLock<System>>,
+  monitors: Arc<ProcessMonitors>,
 }
 
+/// manager/command 로 들어온 요청을 실행하고 응답을 발행
+#[derive(Clone)]
+struct CommandDispatcher {
+  logger: Arc<BulkLoggerHandler>,
+  mqtt_client: Arc<AsyncClient>,
+  system_info: Arc<RwLock<System>>,
+  monitors: Arc<ProcessMonitors>,
+}
+
+/// MONITORED_PROCESS_NAME 으로 시작하는 프로세스 감시 주기
+const DEFAULT_PROCESS_MONITOR_INTERVAL_SECS: u64 = 10;
+
 impl ManagerService {
   pub async fn new(config: ManagerConfig) -> Result<Self, Box<dyn std::error::Error>> {
     // Initialize logger
@@ -57,17 +75,27 @@ impl ManagerService {
     let (mqtt_client, event_loop) = AsyncClient::new(mqtt_options, 10);
     let mqtt_client = Arc::new(mqtt_client);
     
-    // Spawn MQTT event loop handler
-    let logger_clone = Arc::clone(&logger);
-    tokio::spawn(async move {
-      Self::handle_mqtt_events(event_loop, logger_clone).await;
-    });
-    
     // Initialize system info
     let mut sys = System::new_all();
     sys.refresh_all();
     let system_info = Arc::new(RwLock::new(sys));
     
+    let monitors = Arc::new(ProcessMonitors::new(
+      Arc::clone(&logger),
+      Arc::clone(&mqtt_client),
+    ));
+    
+    // Spawn MQTT event loop handler
+    let dispatcher = CommandDispatcher {
+      logger: Arc::clone(&logger),
+      mqtt_client: Arc::clone(&mqtt_client),
+      system_info: Arc::clone(&system_info),
+      monitors: Arc::clone(&monitors),
+    };
+    tokio::spawn(async move {
+      Self::handle_mqtt_events(event_loop, dispatcher).await;
+    });
+    
     logger
     .log(
       LogPacket::new(LogLevel::Info, "ManagerService initialized successfully")
@@ -81,10 +109,12 @@ impl ManagerService {
       db,
       mqtt_client,
       system_info,
+      monitors,
     })
   }
   
-  async fn handle_mqtt_events(mut event_loop: EventLoop, logger: Arc<BulkLoggerHandler>) {
+  async fn handle_mqtt_events(mut event_loop: EventLoop, dispatcher: CommandDispatcher) {
+    let logger = Arc::clone(&dispatcher.logger);
     loop {
       match event_loop.poll().await {
         Ok(event) => {
@@ -94,18 +124,11 @@ impl ManagerService {
             
             // Handle manager commands
             if topic == topics::MANAGER_COMMAND {
-              if let Ok(cmd) = serde_json::from_str::<ManagerCommand>(&payload) {
-                logger
-                .log(
-                  LogPacket::new(
-                    LogLevel::Info,
-                    format!("Received command: {:?}", cmd),
-                  )
-                  .with_source("manager"),
-                )
-                .await;
-                // TODO: Handle command execution
-              }
+              // 재시작처럼 오래 걸리는 명령이 있으므로 이벤트 루프를 막지 않도록 분리
+              let dispatcher = dispatcher.clone();
+              tokio::spawn(async move {
+                dispatcher.handle_request(&payload).await;
+              });
             } else {
               logger
               .log(
@@ -170,13 +193,9 @@ impl ManagerService {
     
     // Start process monitoring if configured
     if let Some(ref process_name) = self.config.monitored_process_name {
-      let process_name = process_name.clone();
-      let logger_clone = Arc::clone(&self.logger);
-      let mqtt_client_clone = Arc::clone(&self.mqtt_client);
-      
-      tokio::spawn(async move {
-        Self::process_monitoring_loop(logger_clone, mqtt_client_clone, process_name).await;
-      });
+      self.monitors
+      .start(process_name, DEFAULT_PROCESS_MONITOR_INTERVAL_SECS)
+      .await?;
     }
     
     self.logger
@@ -200,31 +219,22 @@ impl ManagerService {
     loop {
       interval.tick().await;
       
-      let mut sys = system_info.write().await;
-      sys.refresh_all();
+      let health_response = HealthCommand::execute(&system_info).await;
       
-      let cpu_usage = sys.global_cpu_usage();
-      let total_memory = sys.total_memory();
-      let used_memory = sys.used_memory();
-      let memory_usage_percent = (used_memory as f64 / total_memory as f64) * 100.0;
-      
-      // Create ManagerResponse for health report
-      let health_response = ManagerResponse::HealthReport {
-        cpu_usage,
-        memory_usage_percent,
-        total_memory_mb: total_memory / 1024 / 1024,
-        used_memory_mb: used_memory / 1024 / 1024,
-        timestamp: chrono::Utc::now().timestamp() as u64,
+      let health_msg = match &health_response {
+        ManagerResponse::HealthReport {
+          cpu_usage,
+          memory_usage_percent,
+          total_memory_mb,
+          used_memory_mb,
+          ..
+        } => format!(
+          "CPU: {:.2}%, Memory: {:.2}% ({}/{} MB)",
+          cpu_usage, memory_usage_percent, used_memory_mb, total_memory_mb
+        ),
+        _ => String::new(),
       };
       
-      let health_msg = format!(
-        "CPU: {:.2}%, Memory: {:.2}% ({}/{} MB)",
-        cpu_usage,
-        memory_usage_percent,
-        used_memory / 1024 / 1024,
-        total_memory / 1024 / 1024
-      );
-      
       logger
       .log(
         LogPacket::new(LogLevel::Info, format!("System Health: {}", health_msg))
@@ -251,67 +261,6 @@ impl ManagerService {
     }
   }
   
-  async fn process_monitoring_loop(
-    logger: Arc<BulkLoggerHandler>,
-    mqtt_client: Arc<AsyncClient>,
-    process_name: String,
-  ) {
-    let mut interval = interval(Duration::from_secs(10));
-    
-    loop {
-      interval.tick().await;
-      
-      match get_server_pid(&process_name) {
-        Some(pid) => {
-          let status = ManagerResponse::ProcessStatus {
-            process_name: process_name.clone(),
-            is_running: true,
-            pid: Some(pid.as_u32()),
-            timestamp: chrono::Utc::now().timestamp() as u64,
-          };
-          
-          logger
-          .log(
-            LogPacket::new(
-              LogLevel::Info,
-              format!("Process '{}' is running with PID: {:?}", process_name, pid),
-            )
-            .with_source("manager"),
-          )
-          .await;
-          
-          let json = serde_json::to_string(&status).unwrap();
-          let _ = mqtt_client
-          .publish(topics::MANAGER_PROCESS_STATUS, QoS::AtLeastOnce, false, json)
-          .await;
-        }
-        None => {
-          let status = ManagerResponse::ProcessStatus {
-            process_name: process_name.clone(),
-            is_running: false,
-            pid: None,
-            timestamp: chrono::Utc::now().timestamp() as u64,
-          };
-          
-          logger
-          .log(
-            LogPacket::new(
-              LogLevel::Warn,
-              format!("Process '{}' not found!", process_name),
-            )
-            .with_source("manager"),
-          )
-          .await;
-          
-          let json = serde_json::to_string(&status).unwrap();
-          let _ = mqtt_client
-          .publish(topics::MANAGER_PROCESS_ALERT, QoS::AtLeastOnce, false, json)
-          .await;
-        }
-      }
-    }
-  }
-  
   pub fn logger(&self) -> &Arc<BulkLoggerHandler> {
     &self.logger
   }
@@ -324,3 +273,291 @@ impl ManagerService {
     &self.mqtt_client
   }
 }
+
+/// 응답을 보낼 곳
+#[derive(Debug)]
+struct ReplyTarget {
+  correlation_id: Option<String>,
+  topic: String,
+}
+
+/// payload 를 요청으로 읽고 응답 토픽을 정합니다.
+///
+/// 명령이 깨졌거나 reply_to 가 허용되지 않은 토픽이면 명령 대신 바로 보낼 오류 응답을 돌려줍니다.
+/// 이때도 correlation_id 는 읽을 수 있으면 그대로 돌려줍니다.
+fn parse_request(payload: &str) -> (ReplyTarget, Result<ManagerCommand, ManagerResponse>) {
+  let request = match serde_json::from_str::<ManagerRequest>(payload) {
+    Ok(request) => request,
+    Err(e) => {
+      let value = serde_json::from_str::<serde_json::Value>(payload).ok();
+      let field = |key: &str| {
+        value
+        .as_ref()
+        .and_then(|value| value.get(key))
+        .and_then(|field| field.as_str())
+        .map(str::to_string)
+      };
+      
+      let response = ManagerResponse::Error {
+        command: field("type").unwrap_or_else(|| "unknown".to_string()),
+        error: format!("Invalid command: {}", e),
+      };
+      let target = ReplyTarget {
+        correlation_id: field("correlation_id"),
+        topic: reply_topic(field("reply_to")).unwrap_or_else(|_| topics::MANAGER_RESPONSE.to_string()),
+      };
+      return (target, Err(response));
+    }
+  };
+  
+  match reply_topic(request.reply_to) {
+    Ok(topic) => (ReplyTarget { correlation_id: request.correlation_id, topic }, Ok(request.command)),
+    Err(error) => {
+      let target = ReplyTarget {
+        correlation_id: request.correlation_id,
+        topic: topics::MANAGER_RESPONSE.to_string(),
+      };
+      let response = ManagerResponse::Error {
+        command: request.command.name().to_string(),
+        error,
+      };
+      (target, Err(response))
+    }
+  }
+}
+
+/// reply_to 가 없으면 기본 응답 토픽, 있으면 `manager/response/` 아래 토픽만 허용
+fn reply_topic(reply_to: Option<String>) -> Result<String, String> {
+  match reply_to {
+    None => Ok(topics::MANAGER_RESPONSE.to_string()),
+    Some(topic) if topics::is_manager_reply_topic(&topic) => Ok(topic),
+    Some(topic) => Err(format!(
+      "reply_to '{}' is not allowed; use '{}' or a topic under '{}'",
+      topic,
+      topics::MANAGER_RESPONSE,
+      topics::MANAGER_RESPONSE_PREFIX
+    )),
+  }
+}
+
+impl CommandDispatcher {
+  async fn handle_request(&self, payload: &str) {
+    let (target, command) = parse_request(payload);
+    let command = match command {
+      Ok(command) => command,
+      Err(response) => {
+        if let ManagerResponse::Error { error, .. } = &response {
+          self.log(LogLevel::Warn, format!("Rejected manager command: {} ({})", error, payload)).await;
+        }
+        self.reply(target, response).await;
+        return;
+      }
+    };
+    
+    self.log(
+      LogLevel::Info,
+      format!(
+        "Received command: {:?} (correlation_id: {:?})",
+        command, target.correlation_id
+      ),
+    )
+    .await;
+    
+    let response = self.execute(command).await;
+    
+    if let ManagerResponse::Error { command, error } = &response {
+      self.log(LogLevel::Error, format!("Command '{}' failed: {}", command, error)).await;
+    }
+    
+    self.reply(target, response).await;
+  }
+  
+  async fn execute(&self, command: ManagerCommand) -> ManagerResponse {
+    let name = command.name().to_string();
+    
+    let result = match command {
+      ManagerCommand::RestartProcess { process_name, force } => {
+        RestartCommand::execute(&process_name, force.unwrap_or(false))
+        .await
+        .map(|result| ManagerResponse::Success {
+          command: name.clone(),
+          message: result.message.clone(),
+          data: serde_json::to_value(&result).ok(),
+        })
+      }
+      ManagerCommand::HealthCheck => Ok(HealthCommand::execute(&self.system_info).await),
+      ManagerCommand::SystemInfo => {
+        let monitored = self.monitors.monitored().await;
+        let data = SystemInfoCommand::execute(&self.system_info, monitored).await;
+        Ok(ManagerResponse::Success {
+          command: name.clone(),
+          message: "System information collected".to_string(),
+          data: Some(data),
+        })
+      }
+      ManagerCommand::StartMonitoring { process_name, interval_secs } => self
+      .monitors
+      .start(&process_name, interval_secs)
+      .await
+      .map(|message| ManagerResponse::Success {
+        command: name.clone(),
+        message,
+        data: None,
+      }),
+      ManagerCommand::StopMonitoring { process_name } => self
+      .monitors
+      .stop(&process_name)
+      .await
+      .map(|message| ManagerResponse::Success {
+        command: name.clone(),
+        message,
+        data: None,
+      }),
+    };
+    
+    result.unwrap_or_else(|error| ManagerResponse::Error { command: name, error })
+  }
+  
+  async fn reply(&self, target: ReplyTarget, response: ManagerResponse) {
+    let ReplyTarget { correlation_id, topic } = target;
+    let reply = ManagerReply {
+      correlation_id,
+      response,
+    };
+    
+    let json = match serde_json::to_string(&reply) {
+      Ok(json) => json,
+      Err(e) => {
+        self.log(LogLevel::Error, format!("Failed to serialize reply: {}", e)).await;
+        return;
+      }
+    };
+    
+    if let Err(e) = self
+    .mqtt_client
+    .publish(topic.as_str(), QoS::AtLeastOnce, false, json)
+    .await
+    {
+      self.log(
+        LogLevel::Error,
+        format!("Failed to publish reply to '{}': {}", topic, e),
+      )
+      .await;
+    }
+  }
+  
+  async fn log(&self, level: LogLevel, message: String) {
+    self.logger
+    .log(LogPacket::new(level, message).with_source("manager"))
+    .await;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::libs::test_support::{test_logger, test_mqtt_client};
+  
+  async fn test_dispatcher() -> (CommandDispatcher, EventLoop) {
+    let logger = test_logger().await;
+    let (mqtt_client, event_loop) = test_mqtt_client();
+    let monitors = Arc::new(ProcessMonitors::new(Arc::clone(&logger), Arc::clone(&mqtt_client)));
+    let dispatcher = CommandDispatcher {
+      logger,
+      mqtt_client,
+      system_info: Arc::new(RwLock::new(System::new())),
+      monitors,
+    };
+    (dispatcher, event_loop)
+  }
+  
+  #[test]
+  fn parse_request_keeps_correlation_and_reply_topic() {
+    let (target, command) = parse_request(
+      r#"{"correlation_id": "req-1", "reply_to": "manager/response/cli", "type": "health_check"}"#,
+    );
+    
+    assert_eq!(target.correlation_id.as_deref(), Some("req-1"));
+    assert_eq!(target.topic, "manager/response/cli");
+    assert!(matches!(command, Ok(ManagerCommand::HealthCheck)));
+    
+    let (target, _) = parse_request(r#"{"type": "health_check"}"#);
+    assert_eq!(target.topic, topics::MANAGER_RESPONSE);
+  }
+  
+  #[test]
+  fn parse_request_answers_invalid_command_with_correlation_id() {
+    let (target, response) = parse_request(
+      r#"{"correlation_id": "req-2", "reply_to": "manager/response/cli", "type": "reboot"}"#,
+    );
+    
+    assert_eq!(target.correlation_id.as_deref(), Some("req-2"));
+    assert_eq!(target.topic, "manager/response/cli");
+    match response.unwrap_err() {
+      ManagerResponse::Error { command, error } => {
+        assert_eq!(command, "reboot");
+        assert!(error.starts_with("Invalid command"));
+      }
+      other => panic!("unexpected response: {:?}", other),
+    }
+  }
+  
+  #[test]
+  fn parse_request_rejects_foreign_reply_topic() {
+    let (target, response) = parse_request(
+      r#"{"correlation_id": "req-3", "reply_to": "rin_agent/command", "type": "restart_process", "process_name": "rin_agent"}"#,
+    );
+    
+    assert_eq!(target.correlation_id.as_deref(), Some("req-3"));
+    assert_eq!(target.topic, topics::MANAGER_RESPONSE);
+    match response.unwrap_err() {
+      ManagerResponse::Error { command, error } => {
+        assert_eq!(command, "restart_process");
+        assert!(error.contains("rin_agent/command"));
+      }
+      other => panic!("unexpected response: {:?}", other),
+    }
+    
+    // 명령이 깨진 경우에도 허용되지 않은 토픽으로는 보내지 않음
+    let (target, _) = parse_request(r#"{"reply_to": "rin_agent/command", "type": "reboot"}"#);
+    assert_eq!(target.topic, topics::MANAGER_RESPONSE);
+  }
+  
+  #[tokio::test]
+  async fn execute_reports_command_errors() {
+    let (dispatcher, _event_loop) = test_dispatcher().await;
+    
+    let response = dispatcher
+    .execute(ManagerCommand::StopMonitoring { process_name: "rin_agent".to_string() })
+    .await;
+    match response {
+      ManagerResponse::Error { command, error } => {
+        assert_eq!(command, "stop_monitoring");
+        assert_eq!(error, "Process 'rin_agent' is not being monitored");
+      }
+      other => panic!("unexpected response: {:?}", other),
+    }
+    
+    let response = dispatcher
+    .execute(ManagerCommand::StartMonitoring { process_name: "rin_agent".to_string(), interval_secs: 0 })
+    .await;
+    assert!(matches!(response, ManagerResponse::Error { ref command, .. } if command == "start_monitoring"));
+  }
+  
+  #[tokio::test]
+  async fn execute_starts_and_stops_monitoring() {
+    let (dispatcher, _event_loop) = test_dispatcher().await;
+    
+    let response = dispatcher
+    .execute(ManagerCommand::StartMonitoring { process_name: "rin_agent".to_string(), interval_secs: 3600 })
+    .await;
+    assert!(matches!(response, ManagerResponse::Success { ref command, .. } if command == "start_monitoring"));
+    assert_eq!(dispatcher.monitors.monitored().await, vec!["rin_agent".to_string()]);
+    
+    let response = dispatcher
+    .execute(ManagerCommand::StopMonitoring { process_name: "rin_agent".to_string() })
+    .await;
+    assert!(matches!(response, ManagerResponse::Success { ref command, .. } if command == "stop_monitoring"));
+    assert!(dispatcher.monitors.monitored().await.is_empty());
+  }
+}
@@ -10,6 +10,15 @@ mod m20250718_074038_add_ai_though_show;
 mod m20251230_130434_add_discord_guild;
 mod m20260101_060734_add_debtor_table;
 mod m20260101_150000_add_debt_receipt;
+mod m20260201_120000_add_voice_transcript;
+mod m20260215_090000_add_gemini_usage;
+mod m20260220_090000_add_guild_model_policy;
+mod m20260301_090000_add_structured_output;
+mod m20260305_090000_add_guild_builtin_tools;
+mod m20260310_090000_add_guild_search_provider;
+mod m20260315_090000_extend_debt_ledger;
+mod m20260320_090000_add_debt_receipt_image;
+mod m20260325_090000_add_web_auth_token;
 
 pub struct Migrator;
 
@@ -27,6 +36,15 @@ impl MigratorTrait for Migrator {
             Box::new(m20251230_130434_add_discord_guild::Migration),
             Box::new(m20260101_060734_add_debtor_table::Migration),
             Box::new(m20260101_150000_add_debt_receipt::Migration),
+            Box::new(m20260201_120000_add_voice_transcript::Migration),
+            Box::new(m20260215_090000_add_gemini_usage::Migration),
+            Box::new(m20260220_090000_add_guild_model_policy::Migration),
+            Box::new(m20260301_090000_add_structured_output::Migration),
+            Box::new(m20260305_090000_add_guild_builtin_tools::Migration),
+            Box::new(m20260310_090000_add_guild_search_provider::Migration),
+            Box::new(m20260315_090000_extend_debt_ledger::Migration),
+            Box::new(m20260320_090000_add_debt_receipt_image::Migration),
+            Box::new(m20260325_090000_add_web_auth_token::Migration),
         ]
     }
 }
@@ -0,0 +1,165 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VoiceTranscriptSession::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::GuildId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::VoiceChannelId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::ThreadId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::StartedBy)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::EndedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscriptSession::Summary)
+                            .json_binary()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VoiceTranscript::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VoiceTranscript::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscript::SessionId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscript::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscript::UserName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscript::Content)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VoiceTranscript::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-voice_transcript-session_id")
+                            .from(VoiceTranscript::Table, VoiceTranscript::SessionId)
+                            .to(VoiceTranscriptSession::Table, VoiceTranscriptSession::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-voice_transcript_session-guild_id")
+                    .table(VoiceTranscriptSession::Table)
+                    .col(VoiceTranscriptSession::GuildId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-voice_transcript-session_id")
+                    .table(VoiceTranscript::Table)
+                    .col(VoiceTranscript::SessionId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(VoiceTranscript::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(VoiceTranscriptSession::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum VoiceTranscriptSession {
+    #[sea_orm(iden = "tb_voice_transcript_session")]
+    Table,
+    Id,
+    GuildId,
+    VoiceChannelId,
+    ThreadId,
+    StartedBy,
+    StartedAt,
+    EndedAt,
+    Summary,
+}
+
+#[derive(DeriveIden)]
+enum VoiceTranscript {
+    #[sea_orm(iden = "tb_voice_transcript")]
+    Table,
+    Id,
+    SessionId,
+    UserId,
+    UserName,
+    Content,
+    CreatedAt,
+}
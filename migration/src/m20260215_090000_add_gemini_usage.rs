@@ -0,0 +1,160 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GeminiUsage::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GeminiUsage::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::Model)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::Source)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::GuildId)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::ChannelId)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::UserId)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::ContextId)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::PromptTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::CachedTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::ThoughtsTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::OutputTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::ToolUsePromptTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::TotalTokens)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::CostUsd)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(GeminiUsage::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-gemini_usage-guild_id")
+                    .table(GeminiUsage::Table)
+                    .col(GeminiUsage::GuildId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-gemini_usage-user_id")
+                    .table(GeminiUsage::Table)
+                    .col(GeminiUsage::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-gemini_usage-created_at")
+                    .table(GeminiUsage::Table)
+                    .col(GeminiUsage::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GeminiUsage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GeminiUsage {
+    #[sea_orm(iden = "tb_gemini_usage")]
+    Table,
+    Id,
+    Model,
+    Source,
+    GuildId,
+    ChannelId,
+    UserId,
+    ContextId,
+    PromptTokens,
+    CachedTokens,
+    ThoughtsTokens,
+    OutputTokens,
+    ToolUsePromptTokens,
+    TotalTokens,
+    CostUsd,
+    CreatedAt,
+}
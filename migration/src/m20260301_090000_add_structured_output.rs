@@ -0,0 +1,97 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(StructuredOutput::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StructuredOutput::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::Kind)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::Query)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::Data)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::Model)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::GuildId)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::ChannelId)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StructuredOutput::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-structured_output-guild_id")
+                    .table(StructuredOutput::Table)
+                    .col(StructuredOutput::GuildId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StructuredOutput::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum StructuredOutput {
+    #[sea_orm(iden = "tb_structured_output")]
+    Table,
+    Id,
+    Kind,
+    Query,
+    Data,
+    Model,
+    GuildId,
+    ChannelId,
+    UserId,
+    CreatedAt,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TbDiscordGuilds::Table)
+                    .add_column(
+                        ColumnDef::new(TbDiscordGuilds::EnableGoogleSearch)
+                            .boolean()
+                            .not_null()
+                            .default(false)
+                            .to_owned(),
+                    )
+                    .add_column(
+                        ColumnDef::new(TbDiscordGuilds::EnableUrlContext)
+                            .boolean()
+                            .not_null()
+                            .default(false)
+                            .to_owned(),
+                    )
+                    .add_column(
+                        ColumnDef::new(TbDiscordGuilds::EnableCodeExecution)
+                            .boolean()
+                            .not_null()
+                            .default(false)
+                            .to_owned(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TbDiscordGuilds::Table)
+                    .drop_column(TbDiscordGuilds::EnableGoogleSearch)
+                    .drop_column(TbDiscordGuilds::EnableUrlContext)
+                    .drop_column(TbDiscordGuilds::EnableCodeExecution)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TbDiscordGuilds {
+    Table,
+    EnableGoogleSearch,
+    EnableUrlContext,
+    EnableCodeExecution,
+}
@@ -0,0 +1,115 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Debtor::Table)
+                    .add_column(
+                        ColumnDef::new(Debtor::OwnerId)
+                            .big_integer()
+                            .not_null()
+                            .default(0)
+                            .to_owned(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 이전 장부는 운영자 한 사람의 것이었으므로, MANAGER_ID 가 있으면 기존 채무자를 그 사용자 몫으로 옮깁니다.
+        let db = manager.get_connection();
+        if let Some(owner_id) = std::env::var("MANAGER_ID").ok().and_then(|id| id.parse::<i64>().ok()) {
+            db.execute_unprepared(&format!("UPDATE tb_debtor SET owner_id = {} WHERE owner_id = 0", owner_id))
+                .await?;
+        }
+
+        // 같은 이름의 채무자가 여럿이면 가장 먼저 만든 쪽으로 기록을 합친 뒤 나머지를 지웁니다.
+        // 그래야 아래의 (owner_id, name) 유일 인덱스를 만들 수 있습니다.
+        db.execute_unprepared(
+            "UPDATE tb_debt_receipt AS r SET debtor_id = d.keep_id \
+             FROM (SELECT id, MIN(id) OVER (PARTITION BY owner_id, name) AS keep_id FROM tb_debtor) AS d \
+             WHERE r.debtor_id = d.id AND d.id <> d.keep_id",
+        )
+        .await?;
+        db.execute_unprepared(
+            "DELETE FROM tb_debtor AS d USING tb_debtor AS k \
+             WHERE d.owner_id = k.owner_id AND d.name = k.name AND d.id > k.id",
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-debtor-owner_id-name")
+                    .table(Debtor::Table)
+                    .col(Debtor::OwnerId)
+                    .col(Debtor::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DebtReceipt::Table)
+                    .add_column(
+                        ColumnDef::new(DebtReceipt::Memo)
+                            .string()
+                            .null()
+                            .to_owned(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DebtReceipt::Table)
+                    .drop_column(DebtReceipt::Memo)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-debtor-owner_id-name")
+                    .table(Debtor::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Debtor::Table)
+                    .drop_column(Debtor::OwnerId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Debtor {
+    #[sea_orm(iden = "tb_debtor")]
+    Table,
+    Name,
+    OwnerId,
+}
+
+#[derive(DeriveIden)]
+enum DebtReceipt {
+    #[sea_orm(iden = "tb_debt_receipt")]
+    Table,
+    Memo,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DebtReceipt::Table)
+                    .add_column(
+                        ColumnDef::new(DebtReceipt::ImageId)
+                            .big_integer()
+                            .null()
+                            .to_owned(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-debt_receipt-image_id")
+                    .from(DebtReceipt::Table, DebtReceipt::ImageId)
+                    .to(ImageAttachFile::Table, ImageAttachFile::ImageId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk-debt_receipt-image_id")
+                    .table(DebtReceipt::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DebtReceipt::Table)
+                    .drop_column(DebtReceipt::ImageId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DebtReceipt {
+    #[sea_orm(iden = "tb_debt_receipt")]
+    Table,
+    ImageId,
+}
+
+#[derive(DeriveIden)]
+enum ImageAttachFile {
+    #[sea_orm(iden = "tb_image_attach_file")]
+    Table,
+    ImageId,
+}
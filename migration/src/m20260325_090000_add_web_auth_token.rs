@@ -0,0 +1,113 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebAuthToken::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebAuthToken::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::TokenHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::Kind)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::UserName)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::Name)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::GuildScopes)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebAuthToken::LastUsedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-web_auth_token-user_id")
+                    .table(WebAuthToken::Table)
+                    .col(WebAuthToken::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-web_auth_token-user_id")
+                    .table(WebAuthToken::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WebAuthToken::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WebAuthToken {
+    #[sea_orm(iden = "tb_web_auth_token")]
+    Table,
+    Id,
+    TokenHash,
+    Kind,
+    UserId,
+    UserName,
+    Name,
+    GuildScopes,
+    CreatedAt,
+    ExpiresAt,
+    LastUsedAt,
+}
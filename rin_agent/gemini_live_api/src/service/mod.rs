@@ -1,2 +1,4 @@
 pub mod socket_client;
-pub mod socket_client_manager;
\ No newline at end of file
+pub mod socket_client_manager;
+pub mod session_stats;
+pub mod rest_client;
@@ -0,0 +1,373 @@
+use std::{collections::VecDeque, env, pin::Pin, time::Duration};
+
+use futures_util::{stream, Stream, StreamExt};
+use reqwest::{Client, RequestBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::types::{
+    rest_api_error::GeminiApiError,
+    rest_api_types::{
+        CachedContentUpdate,
+        CountTokensRequest,
+        CountTokensResponse,
+        EmbedContentRequest,
+        EmbedContentResponse,
+        FileEnvelope,
+        GeminiFile,
+        GenerateContentRequest,
+        GenerateContentResponse,
+        ListCachedContentsResponse,
+        ListFilesResponse
+    },
+    GeminiCachedContent,
+    GeminiCachedContentResponse
+};
+
+pub const GEMINI_REST_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+const API_VERSION: &str = "v1beta";
+// 응답이 오지 않는 호출이 종료 대기를 붙잡지 않도록 시간을 제한합니다.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// 스트리밍이 아닌 호출 전체에 걸리는 시간. 생각이 긴 모델도 끝낼 수 있게 넉넉히 잡습니다.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+// 스트리밍은 전체 시간 대신 청크 사이의 간격을 제한합니다.
+const READ_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub type GenerateContentStream = Pin<Box<dyn Stream<Item = Result<GenerateContentResponse, GeminiApiError>> + Send>>;
+
+// Gemini REST API 클라이언트. 복제해도 내부 연결 풀을 공유합니다.
+#[derive(Debug, Clone)]
+pub struct GeminiRestClient {
+    http: Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl GeminiRestClient {
+    pub fn new(api_key: String) -> Self {
+        let http = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(READ_TIMEOUT)
+            .build()
+            .expect("Failed to build Gemini REST client");
+        GeminiRestClient {
+            http,
+            api_key,
+            base_url: GEMINI_REST_BASE_URL.to_string(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, GeminiApiError> {
+        env::var("GEMINI_API_KEY")
+            .map(Self::new)
+            .map_err(|_| GeminiApiError::MissingApiKey)
+    }
+
+    // 테스트나 프록시를 위해 호스트를 바꿉니다. 경로(/v1beta/...)는 그대로 붙습니다.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/{}", self.base_url, API_VERSION, path)
+    }
+
+    // "gemini-2.5-flash" 와 "models/gemini-2.5-flash" 를 모두 받습니다.
+    fn model_path(model: &str, method: &str) -> String {
+        if model.starts_with("models/") {
+            format!("{}:{}", model, method)
+        } else {
+            format!("models/{}:{}", model, method)
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, GeminiApiError> {
+        let response = request
+            .header("x-goog-api-key", &self.api_key)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            LOGGER.log(LogLevel::Debug, format!("Gemini REST > Error {}: {}", status, body).as_str());
+            return Err(GeminiApiError::from_response(status.as_u16(), &body));
+        }
+        parse_body(&body)
+    }
+
+    async fn send_empty(&self, request: RequestBuilder) -> Result<(), GeminiApiError> {
+        let response = request
+            .header("x-goog-api-key", &self.api_key)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(GeminiApiError::from_response(status.as_u16(), &body));
+        }
+        Ok(())
+    }
+
+    fn post_json<B: Serialize>(&self, url: String, body: &B) -> RequestBuilder {
+        self.http.post(url).json(body)
+    }
+
+    // ---- models ----
+
+    // 후보가 하나도 없고 promptFeedback.blockReason 이 있으면 Blocked 로 돌려줍니다.
+    pub async fn generate_content(
+        &self,
+        model: &str,
+        request: &GenerateContentRequest,
+    ) -> Result<GenerateContentResponse, GeminiApiError> {
+        let url = self.url(&Self::model_path(model, "generateContent"));
+        let response: GenerateContentResponse = self.send(self.post_json(url, request)).await?;
+        check_blocked(response)
+    }
+
+    // SSE(alt=sse)로 받은 청크를 하나씩 돌려줍니다. 전체 시간 제한 없이 청크 사이 간격(READ_TIMEOUT)만 봅니다.
+    pub async fn stream_generate_content(
+        &self,
+        model: &str,
+        request: &GenerateContentRequest,
+    ) -> Result<GenerateContentStream, GeminiApiError> {
+        let url = format!("{}?alt=sse", self.url(&Self::model_path(model, "streamGenerateContent")));
+        let response = self
+            .post_json(url, request)
+            .header("x-goog-api-key", &self.api_key)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(GeminiApiError::from_response(status.as_u16(), &body));
+        }
+
+        let bytes = response.bytes_stream().boxed();
+        let events = stream::unfold(
+            (bytes, SseBuffer::default(), false),
+            |(mut bytes, mut buffer, mut done)| async move {
+                loop {
+                    if let Some(data) = buffer.next_event() {
+                        let item = parse_body::<GenerateContentResponse>(&data).and_then(check_blocked);
+                        return Some((item, (bytes, buffer, done)));
+                    }
+                    if done {
+                        return None;
+                    }
+                    match bytes.next().await {
+                        Some(Ok(chunk)) => buffer.push(&chunk),
+                        Some(Err(e)) => {
+                            done = true;
+                            buffer.clear();
+                            return Some((Err(GeminiApiError::from(e)), (bytes, buffer, done)));
+                        }
+                        None => {
+                            // 마지막 이벤트 뒤에 빈 줄이 없어도 남은 데이터를 처리합니다.
+                            done = true;
+                            buffer.finish();
+                        }
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(events))
+    }
+
+    pub async fn count_tokens(
+        &self,
+        model: &str,
+        request: &CountTokensRequest,
+    ) -> Result<CountTokensResponse, GeminiApiError> {
+        let url = self.url(&Self::model_path(model, "countTokens"));
+        self.send(self.post_json(url, request)).await
+    }
+
+    pub async fn embed_content(
+        &self,
+        model: &str,
+        request: &EmbedContentRequest,
+    ) -> Result<EmbedContentResponse, GeminiApiError> {
+        let url = self.url(&Self::model_path(model, "embedContent"));
+        self.send(self.post_json(url, request)).await
+    }
+
+    // ---- cachedContents ----
+
+    pub async fn create_cached_content(
+        &self,
+        content: &GeminiCachedContent,
+    ) -> Result<GeminiCachedContentResponse, GeminiApiError> {
+        self.send(self.post_json(self.url("cachedContents"), content)).await
+    }
+
+    // name: "cachedContents/xxx"
+    pub async fn get_cached_content(&self, name: &str) -> Result<GeminiCachedContentResponse, GeminiApiError> {
+        self.send(self.http.get(self.url(name))).await
+    }
+
+    pub async fn list_cached_contents(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<ListCachedContentsResponse, GeminiApiError> {
+        let request = self.http.get(self.url("cachedContents")).query(&page_query(page_size, page_token));
+        self.send(request).await
+    }
+
+    pub async fn update_cached_content(
+        &self,
+        name: &str,
+        update: &CachedContentUpdate,
+    ) -> Result<GeminiCachedContentResponse, GeminiApiError> {
+        let request = self
+            .http
+            .patch(self.url(name))
+            .query(&[("updateMask", update.update_mask())])
+            .json(update);
+        self.send(request).await
+    }
+
+    pub async fn delete_cached_content(&self, name: &str) -> Result<(), GeminiApiError> {
+        self.send_empty(self.http.delete(self.url(name))).await
+    }
+
+    // ---- files ----
+
+    // resumable 업로드: 시작 요청으로 업로드 URL을 받은 뒤 한 번에 올리고 마무리합니다.
+    pub async fn upload_file(
+        &self,
+        data: Vec<u8>,
+        mime_type: &str,
+        display_name: &str,
+    ) -> Result<GeminiFile, GeminiApiError> {
+        let start_url = format!("{}/upload/{}/files", self.base_url, API_VERSION);
+        let start = self
+            .http
+            .post(start_url)
+            .header("x-goog-api-key", &self.api_key)
+            .header("X-Goog-Upload-Protocol", "resumable")
+            .header("X-Goog-Upload-Command", "start")
+            .header("X-Goog-Upload-Header-Content-Length", data.len())
+            .header("X-Goog-Upload-Header-Content-Type", mime_type)
+            .json(&serde_json::json!({ "file": { "display_name": display_name } }))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?;
+        let status = start.status();
+        if !status.is_success() {
+            let body = start.text().await.unwrap_or_default();
+            return Err(GeminiApiError::from_response(status.as_u16(), &body));
+        }
+        let upload_url = start
+            .headers()
+            .get("x-goog-upload-url")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+            .ok_or(GeminiApiError::UploadUrlMissing)?;
+
+        let request = self
+            .http
+            .post(upload_url)
+            .header("Content-Length", data.len())
+            .header("X-Goog-Upload-Offset", 0)
+            .header("X-Goog-Upload-Command", "upload, finalize")
+            .body(data);
+        let envelope: FileEnvelope = self.send(request).await?;
+        Ok(envelope.file)
+    }
+
+    // name: "files/xxx"
+    pub async fn get_file(&self, name: &str) -> Result<GeminiFile, GeminiApiError> {
+        self.send(self.http.get(self.url(name))).await
+    }
+
+    pub async fn list_files(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<ListFilesResponse, GeminiApiError> {
+        let request = self.http.get(self.url("files")).query(&page_query(page_size, page_token));
+        self.send(request).await
+    }
+
+    pub async fn delete_file(&self, name: &str) -> Result<(), GeminiApiError> {
+        self.send_empty(self.http.delete(self.url(name))).await
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(body: &str) -> Result<T, GeminiApiError> {
+    serde_json::from_str(body).map_err(|e| GeminiApiError::Parse { error: e.to_string(), raw: body.to_string() })
+}
+
+fn check_blocked(response: GenerateContentResponse) -> Result<GenerateContentResponse, GeminiApiError> {
+    if response.candidates.is_empty() {
+        if let Some(reason) = response.prompt_feedback.as_ref().and_then(|f| f.block_reason.as_ref()) {
+            return Err(GeminiApiError::Blocked { reason: format!("{:?}", reason) });
+        }
+    }
+    Ok(response)
+}
+
+fn page_query(page_size: Option<u32>, page_token: Option<&str>) -> Vec<(&'static str, String)> {
+    let mut query = Vec::new();
+    if let Some(size) = page_size {
+        query.push(("pageSize", size.to_string()));
+    }
+    if let Some(token) = page_token {
+        query.push(("pageToken", token.to_string()));
+    }
+    query
+}
+
+// text/event-stream 을 이벤트 단위로 자릅니다. data: 줄만 모으고 나머지 필드는 무시합니다.
+// 청크 경계에서 한글 등 멀티바이트 문자가 잘릴 수 있어 이벤트가 끝날 때까지 바이트로 모읍니다.
+#[derive(Default)]
+struct SseBuffer {
+    pending: Vec<u8>,
+    events: VecDeque<String>,
+}
+
+impl SseBuffer {
+    fn push(&mut self, chunk: &[u8]) {
+        self.pending.extend(chunk.iter().filter(|b| **b != b'\r'));
+        while let Some(end) = self.pending.windows(2).position(|w| w == b"\n\n") {
+            let block = self.pending.drain(..end + 2).collect::<Vec<_>>();
+            self.push_block(&block);
+        }
+    }
+
+    fn finish(&mut self) {
+        let block = std::mem::take(&mut self.pending);
+        self.push_block(&block);
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.events.clear();
+    }
+
+    fn push_block(&mut self, block: &[u8]) {
+        let block = String::from_utf8_lossy(block);
+        let data = block
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !data.is_empty() {
+            self.events.push_back(data);
+        }
+    }
+
+    fn next_event(&mut self) -> Option<String> {
+        self.events.pop_front()
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH}
+};
+
+// Live 세션 하나의 송수신 통계. 소켓 이벤트 루프가 갱신하고 풀에서 읽습니다.
+#[derive(Debug)]
+pub struct LiveSessionStats {
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+    turns: AtomicU64,
+    created_at: SystemTime,
+    // UNIX epoch 기준 밀리초
+    last_activity_ms: AtomicU64,
+}
+
+// 모니터링용 스냅샷
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionStats {
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub turns: u64,
+    pub created_at: SystemTime,
+    pub last_activity: SystemTime,
+}
+
+impl SessionStats {
+    pub fn idle_for(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.last_activity)
+            .unwrap_or_default()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl Default for LiveSessionStats {
+    fn default() -> Self {
+        LiveSessionStats {
+            bytes_in: AtomicU64::new(0),
+            bytes_out: AtomicU64::new(0),
+            turns: AtomicU64::new(0),
+            created_at: SystemTime::now(),
+            last_activity_ms: AtomicU64::new(now_ms()),
+        }
+    }
+}
+
+impl LiveSessionStats {
+    pub fn record_in(&self, bytes: usize) {
+        self.bytes_in.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    pub fn record_out(&self, bytes: usize) {
+        self.bytes_out.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    pub fn record_turn(&self) {
+        self.turns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn touch(&self) {
+        self.last_activity_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> SessionStats {
+        SessionStats {
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+            turns: self.turns.load(Ordering::Relaxed),
+            created_at: self.created_at,
+            last_activity: UNIX_EPOCH + Duration::from_millis(self.last_activity_ms.load(Ordering::Relaxed)),
+        }
+    }
+}
@@ -1,35 +1,56 @@
 use tokio::{
     net::TcpStream,
-    sync::mpsc
+    sync::{mpsc, Notify}
 };
 use tokio_tungstenite::{
-    connect_async, 
-    tungstenite::Message, 
-    MaybeTlsStream, 
+    connect_async,
+    tungstenite::{
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Message
+    },
+    MaybeTlsStream,
     WebSocketStream
 };
 use futures_util::{
     stream::{
-        self, SplitSink, SplitStream
-    }, 
-    SinkExt, 
+        SplitSink, SplitStream
+    },
+    SinkExt,
+    Stream,
     StreamExt
 };
 use std::{
-    fmt::Debug, 
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
     time::Duration
 };
 
+use super::session_stats::LiveSessionStats;
 use crate::{
     libs::logger::{
-        LogLevel, 
+        LogLevel,
         LOGGER
-    }, types::live_api_types::{
-        BidiGenerateContentClientContent, 
-        BidiGenerateContentServerMessage, 
-        BidiGenerateContentSetup, GeminiLiveApiWebSocketMessage
+    }, types::{
+        live_api_error::LiveApiError,
+        live_api_types::{
+            BidiGenerateContentClientContent,
+            BidiGenerateContentRealTimeInput,
+            BidiGenerateContentServerMessage,
+            BidiGenerateContentSetup, BidiGenerateContentToolResponse, GeminiLiveApiWebSocketMessage,
+            LiveEvent
+        }
     }
-}; // 재연결 시 딜레이 등에 사용
+};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type WsFrame = Option<Result<Message, tokio_tungstenite::tungstenite::Error>>;
+
+// 이벤트 루프로 보내는 명령과 밖으로 내보내는 이벤트의 버퍼 크기.
+// 가득 차면 명령은 보내는 쪽이 기다리고, 실시간 오디오는 버립니다.
+const COMMAND_BUFFER: usize = 256;
+const EVENT_BUFFER: usize = 256;
 
 // 클라이언트 상태를 나타내는 Enum (선택 사항)
 #[derive(Debug, Clone, PartialEq)]
@@ -41,15 +62,50 @@ pub enum ClientState {
     Reconnecting,
 }
 
+// 연결이 끊겼을 때의 재연결 정책.
+// attempt 번째 시도 전에 initial_backoff * 2^(attempt-1) 만큼 (최대 max_backoff) 기다립니다.
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub fn disabled() -> Self {
+        ReconnectPolicy {
+            max_attempts: 0,
+            ..Default::default()
+        }
+    }
 
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
 
 pub struct GeminiSocketClient<TKey: Ord + Debug+Clone> {
     pub id: TKey,
-    tx: Option<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>,
-    rx: Option<SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>>, // 추가
+    tx: Option<SplitSink<WsStream, Message>>,
+    rx: Option<SplitStream<WsStream>>, // 추가
     url: String, // 재연결 시 사용
     state: ClientState,
-    connect_init:BidiGenerateContentSetup
+    connect_init:BidiGenerateContentSetup,
+    reconnect_policy: ReconnectPolicy,
+    // 서버가 마지막으로 알려준 세션 재개 핸들 (setup 에 session_resumption 이 있을 때만 사용)
+    resumption_handle: Option<String>,
+    stats: Arc<LiveSessionStats>,
 }
 
 impl<TKey: Ord + Debug+Clone> GeminiSocketClient<TKey> {
@@ -65,114 +121,406 @@ impl<TKey: Ord + Debug+Clone> GeminiSocketClient<TKey> {
             rx: None, // 수신
             url,
             state: ClientState::Initial, // 초기 상태
-            connect_init
+            connect_init,
+            reconnect_policy: ReconnectPolicy::default(),
+            resumption_handle: None,
+            stats: Arc::new(LiveSessionStats::default()),
         }
     }
 
-    // 실제 연결 시도
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    pub fn state(&self) -> ClientState {
+        self.state.clone()
+    }
+
+    pub fn resumption_handle(&self) -> Option<&str> {
+        self.resumption_handle.as_deref()
+    }
+
+    // 이벤트 루프로 넘긴 뒤에도 읽을 수 있도록 공유 핸들을 돌려줍니다.
+    pub fn stats(&self) -> Arc<LiveSessionStats> {
+        self.stats.clone()
+    }
+
+    // 실제 연결 시도. 재개 핸들이 있으면 setup 에 실어 이전 세션을 이어받습니다.
     pub async fn connect(
         &mut self,
-    ) -> Result<(),String> {
+    ) -> Result<(), LiveApiError> {
         LOGGER.log(LogLevel::Info,
-            format!("Attempting to connect to WebSocket: {}", self.url).as_str()
+            format!("[{:?}] Attempting to connect to WebSocket", self.id).as_str()
         );
+        self.state = ClientState::Connecting;
+        let mut setup = self.connect_init.clone();
+        if let Some(resumption) = setup.session_resumption.as_mut() {
+            resumption.handle = self.resumption_handle.clone();
+        }
         match connect_async(&self.url).await {
             Ok((socket_stream, response)) => {
                 LOGGER.log(LogLevel::Info,
-                    format!("Connected to WebSocket: {}. Response: {:?}", self.url, response).as_str()
+                    format!("[{:?}] Connected to WebSocket. Response: {:?}", self.id, response.status()).as_str()
                 );
                 let (tx, rx) = socket_stream.split();
                 let mut init_msg= GeminiLiveApiWebSocketMessage::default();
-                init_msg.set_setup(self.connect_init.clone());
-                let init_msg = init_msg;
+                init_msg.set_setup(setup);
                 self.tx = Some(tx);
-                self.send_message(
-                    serde_json::to_string(&init_msg)
-                        .map_err(|e| format!("Failed to serialize message: {}", e))?
-                ).await
-                    .map_err(|e| format!("Failed to send init: {}", e))?;
+                if let Err(e) = self.send_ws_message(&init_msg).await {
+                    self.tx = None;
+                    self.state = ClientState::Disconnected;
+                    return Err(LiveApiError::Connect(format!("Failed to send init: {}", e)));
+                }
                 self.state = ClientState::Connected; // 연결 상태로 변경
-                self.rx = Some(rx); 
+                self.rx = Some(rx);
                 Ok(())
             }
             Err(e) => {
                 LOGGER.log(LogLevel::Error,
-                    format!("Failed to connect to WebSocket {}: {}", self.url, e).as_str()
+                    format!("[{:?}] Failed to connect to WebSocket: {}", self.id, e).as_str()
                 );
-                Err(format!("Failed to connect: {}", e))
+                self.state = ClientState::Disconnected;
+                Err(LiveApiError::Connect(e.to_string()))
             }
         }
     }
 
-
-    // 리스닝 태스크와 재연결 로직 관리
-
-    async fn send_message(&mut self, message: String) -> Result<(), String> {
+    async fn send_message(&mut self, message: String) -> Result<(), LiveApiError> {
         if let Some(tx) = self.tx.as_mut() {
-            let message = Message::Text(message.into());
-            LOGGER.log(LogLevel::Debug,
-                format!("Sending message: {}", message).as_str()
-            );
-            tx.send(message)
+            self.stats.record_out(message.len());
+            tx.send(Message::Text(message.into()))
                 .await
-                .map_err(|e| format!("Failed to send message: {}", e))
+                .map_err(|e| LiveApiError::Send(e.to_string()))
         } else {
-            Err("WebSocket not connected or tx is not available.".to_string())
+            Err(LiveApiError::NotConnected)
         }
     }
 
-    async fn send_byte(&mut self, message: Vec<u8>) -> Result<(), String> {
-        if let Some(tx) = self.tx.as_mut() {
-
-            tx.send(Message::Binary(message.into()))
-                .await
-                .map_err(|e| format!("Failed to send binary message: {}", e))
+    async fn send_ws_message(&mut self, message: &GeminiLiveApiWebSocketMessage) -> Result<(), LiveApiError> {
+        let str_msg = serde_json::to_string(message)
+            .map_err(|e| LiveApiError::Serialize(e.to_string()))?;
+        // 실시간 입력에는 base64 오디오가 그대로 들어 있으므로 크기만 남깁니다.
+        if message.real_time_input.is_some() {
+            LOGGER.log(LogLevel::Debug, format!("[{:?}] Sending realtimeInput ({} bytes)", self.id, str_msg.len()).as_str());
         } else {
-            Err("WebSocket not connected or tx is not available.".to_string())
+            LOGGER.log(LogLevel::Debug, format!("[{:?}] Sending message: {}", self.id, str_msg).as_str());
         }
+        self.send_message(str_msg).await
     }
 
     // 클라이언트 종료 (외부에서 호출)
-    pub async fn shutdown(&mut self) -> Result<(), String> {
-        println!("[{:?}] Shutdown requested.", self.id);
+    pub async fn shutdown(&mut self) -> Result<(), LiveApiError> {
+        LOGGER.log(LogLevel::Debug, format!("[{:?}] Shutdown requested.", self.id).as_str());
+        self.state = ClientState::Disconnected;
+        self.rx = None;
         if let Some(mut tx) = self.tx.take() { // tx의 소유권을 가져와서 close
-            self.state = ClientState::Disconnected;
             tx.close()
                 .await
-                .map_err(|e| format!("Error during WebSocket close: {}", e))
+                .map_err(|e| LiveApiError::Send(format!("Error during WebSocket close: {}", e)))
         } else {
-            self.state = ClientState::Disconnected;
             Ok(()) // 이미 연결이 없거나 tx가 없음
         }
     }
-    
+
     pub async fn send_new_part(
         &mut self,
         part:BidiGenerateContentClientContent
-    ) -> Result<(), String> {
-        let str_msg = serde_json::to_string(&part)
-            .map_err(|e| format!("Failed to serialize message: {}", e))?;
-        self.send_message(str_msg).await
-            .map_err(|e| format!("Failed to send message: {}", e))
+    ) -> Result<(), LiveApiError> {
+        let mut msg = GeminiLiveApiWebSocketMessage::default();
+        msg.set_client_content(part);
+        self.send_ws_message(&msg).await
+    }
+
+    // 실시간 오디오/텍스트 입력 전송
+    pub async fn send_realtime_input(
+        &mut self,
+        input: BidiGenerateContentRealTimeInput
+    ) -> Result<(), LiveApiError> {
+        let mut msg = GeminiLiveApiWebSocketMessage::default();
+        msg.set_real_time_input(input);
+        self.send_ws_message(&msg).await
     }
-    pub async fn listen<F>(&mut self, mut handler: F)
+
+    // 서버의 toolCall 에 대한 함수 실행 결과 전송
+    pub async fn send_tool_response(
+        &mut self,
+        response: BidiGenerateContentToolResponse
+    ) -> Result<(), LiveApiError> {
+        let mut msg = GeminiLiveApiWebSocketMessage::default();
+        msg.set_tool_response(response);
+        self.send_ws_message(&msg).await
+    }
+
+    // 소켓을 백그라운드 태스크로 넘기고, 송신 핸들과 이벤트 스트림을 돌려줍니다.
+    // 태스크는 연결이 끊기면 정책에 따라 재연결(가능하면 세션 재개)하고,
+    // 재연결 중 보낸 메시지는 연결이 복구된 뒤 전송합니다.
+    pub fn into_event_stream(self) -> (LiveSender, LiveEventStream)
     where
-        F: FnMut(BidiGenerateContentServerMessage) + Send,
+        TKey: Send + 'static,
     {
-        if let Some(rx) = self.rx.as_mut() {
-            while let Some(msg) = rx.next().await {
-                LOGGER.log(LogLevel::Debug,
-                    format!("Received message: {:?}", msg).as_str()
-                );
-                if let Ok(msg) = msg {
-                    if msg.is_text() {
-                        let text = msg.into_text().unwrap();
-                        let parsed_msg: BidiGenerateContentServerMessage = serde_json::from_str(&text)
-                            .expect("Failed to parse message");
-                        handler(parsed_msg);
+        let (cmd_tx, cmd_rx) = mpsc::channel(COMMAND_BUFFER);
+        let (event_tx, event_rx) = mpsc::channel(EVENT_BUFFER);
+        let shutdown = Arc::new(Notify::new());
+        tokio::spawn(self.run_event_loop(cmd_rx, event_tx, shutdown.clone()));
+        (LiveSender { tx: cmd_tx, shutdown }, LiveEventStream { rx: event_rx })
+    }
+
+    async fn run_event_loop(
+        mut self,
+        mut cmd_rx: mpsc::Receiver<LiveCommand>,
+        event_tx: mpsc::Sender<LiveEvent>,
+        shutdown: Arc<Notify>,
+    ) {
+        if self.rx.is_none() && !self.reconnect(&event_tx, LiveApiError::NotConnected).await {
+            return;
+        }
+        loop {
+            let action = tokio::select! {
+                cmd = cmd_rx.recv() => LoopAction::Command(cmd),
+                _ = shutdown.notified() => LoopAction::Command(None),
+                frame = next_frame(&mut self.rx) => LoopAction::Frame(frame),
+            };
+            let outcome = match action {
+                LoopAction::Command(Some(msg)) => match self.send_ws_message(&msg).await {
+                    Ok(()) => FrameOutcome::Events(vec![]),
+                    Err(e @ LiveApiError::Serialize(_)) => FrameOutcome::Events(vec![LiveEvent::Error(e)]),
+                    Err(e) => {
+                        // 보내지 못한 메시지는 재연결 후 다시 보냅니다.
+                        if !self.reconnect(&event_tx, e).await {
+                            return;
+                        }
+                        match self.send_ws_message(&msg).await {
+                            Ok(()) => FrameOutcome::Events(vec![]),
+                            Err(e) => FrameOutcome::Disconnected(e),
+                        }
+                    }
+                },
+                LoopAction::Command(None) => {
+                    FrameOutcome::Closed(None)
+                }
+                LoopAction::Frame(frame) => self.handle_frame(frame),
+            };
+
+            match outcome {
+                FrameOutcome::Events(events) => {
+                    let mut going_away = false;
+                    for event in events {
+                        match &event {
+                            LiveEvent::SessionResumptionUpdate(update) if update.resumable => {
+                                if let Some(handle) = &update.new_handle {
+                                    self.resumption_handle = Some(handle.clone());
+                                }
+                            }
+                            LiveEvent::GoAway { time_left } => {
+                                LOGGER.log(LogLevel::Info, format!("[{:?}] GoAway received (time left: {:?})", self.id, time_left).as_str());
+                                going_away = true;
+                            }
+                            _ => {}
+                        }
+                        if event_tx.send(event).await.is_err() {
+                            // 스트림을 받는 쪽이 사라졌으면 소켓을 닫습니다.
+                            let _ = self.shutdown().await;
+                            return;
+                        }
+                    }
+                    // 서버가 끊기 전에 미리 새 연결로 옮깁니다.
+                    if going_away && self.reconnect_policy.max_attempts > 0 {
+                        let _ = self.shutdown().await;
+                        if !self.reconnect(&event_tx, LiveApiError::GoAway { time_left: None }).await {
+                            return;
+                        }
                     }
                 }
+                FrameOutcome::Disconnected(cause) => {
+                    LOGGER.log(LogLevel::Warning, format!("[{:?}] WebSocket disconnected: {}", self.id, cause).as_str());
+                    if !self.reconnect(&event_tx, cause).await {
+                        return;
+                    }
+                }
+                FrameOutcome::Closed(error) => {
+                    let _ = self.shutdown().await;
+                    let _ = event_tx.send(LiveEvent::Closed { error }).await;
+                    return;
+                }
             }
         }
     }
-}
\ No newline at end of file
+
+    fn handle_frame(&self, frame: WsFrame) -> FrameOutcome {
+        let text = match frame {
+            Some(Ok(Message::Text(text))) => text.to_string(),
+            // Live API 는 JSON 을 바이너리 프레임으로 보내기도 합니다.
+            Some(Ok(Message::Binary(bytes))) => String::from_utf8_lossy(&bytes).to_string(),
+            Some(Ok(Message::Close(frame))) => {
+                LOGGER.log(LogLevel::Info, format!("[{:?}] WebSocket closed: {:?}", self.id, frame).as_str());
+                return close_outcome(frame);
+            }
+            Some(Ok(_)) => return FrameOutcome::Events(vec![]),
+            Some(Err(e)) => return FrameOutcome::Disconnected(LiveApiError::Connect(e.to_string())),
+            None => return FrameOutcome::Disconnected(LiveApiError::Connect("connection lost".to_string())),
+        };
+        self.stats.record_in(text.len());
+        match serde_json::from_str::<BidiGenerateContentServerMessage>(&text) {
+            Ok(parsed) => {
+                if parsed.server_content.as_ref().is_some_and(|c| c.turn_complete) {
+                    self.stats.record_turn();
+                }
+                FrameOutcome::Events(parsed.into_events())
+            }
+            Err(e) => {
+                LOGGER.log(LogLevel::Warning, format!("[{:?}] Failed to parse server message: {}", self.id, e).as_str());
+                FrameOutcome::Events(vec![LiveEvent::Error(LiveApiError::Parse {
+                    error: e.to_string(),
+                    raw: text.chars().take(200).collect(),
+                })])
+            }
+        }
+    }
+
+    // 정책에 따라 재연결합니다. 실패하면 Closed 이벤트를 보내고 false 를 돌려줍니다.
+    async fn reconnect(&mut self, event_tx: &mpsc::Sender<LiveEvent>, cause: LiveApiError) -> bool {
+        self.tx = None;
+        self.rx = None;
+        let policy = self.reconnect_policy.clone();
+        if policy.max_attempts == 0 {
+            self.state = ClientState::Disconnected;
+            let _ = event_tx.send(LiveEvent::Closed { error: Some(cause) }).await;
+            return false;
+        }
+
+        self.state = ClientState::Reconnecting;
+        for attempt in 1..=policy.max_attempts {
+            if event_tx.send(LiveEvent::Reconnecting { attempt }).await.is_err() {
+                return false;
+            }
+            tokio::time::sleep(policy.backoff(attempt)).await;
+            let resumed = self.connect_init.session_resumption.is_some() && self.resumption_handle.is_some();
+            match self.connect().await {
+                Ok(()) => {
+                    LOGGER.log(LogLevel::Info, format!("[{:?}] Reconnected (attempt {}, resumed: {})", self.id, attempt, resumed).as_str());
+                    return event_tx.send(LiveEvent::Reconnected { resumed }).await.is_ok();
+                }
+                Err(e) => {
+                    LOGGER.log(LogLevel::Warning, format!("[{:?}] Reconnect attempt {} failed: {}", self.id, attempt, e).as_str());
+                }
+            }
+        }
+
+        self.state = ClientState::Disconnected;
+        let _ = event_tx.send(LiveEvent::Closed {
+            error: Some(LiveApiError::ReconnectExhausted { attempts: policy.max_attempts }),
+        }).await;
+        false
+    }
+}
+
+// 이벤트 루프가 소켓으로 보낼 메시지. None 은 종료 요청이나 송신 핸들이 모두 사라졌음을 뜻합니다.
+type LiveCommand = Box<GeminiLiveApiWebSocketMessage>;
+
+enum LoopAction {
+    Command(Option<LiveCommand>),
+    Frame(WsFrame),
+}
+
+enum FrameOutcome {
+    Events(Vec<LiveEvent>),
+    Disconnected(LiveApiError),
+    Closed(Option<LiveApiError>),
+}
+
+async fn next_frame(rx: &mut Option<SplitStream<WsStream>>) -> WsFrame {
+    match rx.as_mut() {
+        Some(rx) => rx.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+// 정상 종료나 잘못된 요청으로 닫힌 경우는 재연결해도 소용이 없습니다.
+fn close_outcome(frame: Option<CloseFrame>) -> FrameOutcome {
+    let Some(frame) = frame else {
+        return FrameOutcome::Closed(None);
+    };
+    let error = LiveApiError::ClosedByServer {
+        code: frame.code.into(),
+        reason: frame.reason.to_string(),
+    };
+    match frame.code {
+        CloseCode::Normal => FrameOutcome::Closed(None),
+        CloseCode::Invalid | CloseCode::Policy | CloseCode::Unsupported | CloseCode::Size | CloseCode::Protocol => {
+            FrameOutcome::Closed(Some(error))
+        }
+        _ => FrameOutcome::Disconnected(error),
+    }
+}
+
+// 이벤트 루프 태스크로 메시지를 넘기는 송신 핸들. 복제해서 여러 곳에서 쓸 수 있습니다.
+#[derive(Clone)]
+pub struct LiveSender {
+    tx: mpsc::Sender<LiveCommand>,
+    shutdown: Arc<Notify>,
+}
+
+impl LiveSender {
+    // 버퍼가 가득 차 있으면 자리가 날 때까지 기다립니다.
+    pub async fn send(&self, msg: GeminiLiveApiWebSocketMessage) -> Result<(), LiveApiError> {
+        self.tx
+            .send(Box::new(msg))
+            .await
+            .map_err(|_| LiveApiError::NotConnected)
+    }
+
+    pub async fn send_client_content(&self, content: BidiGenerateContentClientContent) -> Result<(), LiveApiError> {
+        let mut msg = GeminiLiveApiWebSocketMessage::default();
+        msg.set_client_content(content);
+        self.send(msg).await
+    }
+
+    // 오디오 청크는 밀려 있으면 버립니다. 늦게 도착한 음성은 쓸모가 없고, 음성 수신 쪽을 붙잡지 않기 위해서입니다.
+    // 텍스트와 audioStreamEnd 는 다른 메시지처럼 기다렸다가 보냅니다.
+    pub async fn send_realtime_input(&self, input: BidiGenerateContentRealTimeInput) -> Result<(), LiveApiError> {
+        let is_audio = input.audio.is_some();
+        let mut msg = GeminiLiveApiWebSocketMessage::default();
+        msg.set_real_time_input(input);
+        if !is_audio {
+            return self.send(msg).await;
+        }
+        match self.tx.try_send(Box::new(msg)) {
+            Ok(()) => Ok(()),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                LOGGER.log(LogLevel::Debug, "Live send queue is full, dropping audio chunk");
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(LiveApiError::NotConnected),
+        }
+    }
+
+    pub async fn send_tool_response(&self, response: BidiGenerateContentToolResponse) -> Result<(), LiveApiError> {
+        let mut msg = GeminiLiveApiWebSocketMessage::default();
+        msg.set_tool_response(response);
+        self.send(msg).await
+    }
+
+    // 소켓을 닫습니다. 스트림에는 Closed 이벤트가 전달됩니다.
+    // 버퍼가 가득 차 있어도 전달되도록 명령 채널과 따로 알립니다.
+    pub fn shutdown(&self) {
+        self.shutdown.notify_one();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+// 서버 이벤트 스트림. Closed 이벤트 이후에는 None 을 돌려줍니다.
+pub struct LiveEventStream {
+    rx: mpsc::Receiver<LiveEvent>,
+}
+
+impl Stream for LiveEventStream {
+    type Item = LiveEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<LiveEvent>> {
+        self.rx.poll_recv(cx)
+    }
+}
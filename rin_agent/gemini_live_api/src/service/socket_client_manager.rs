@@ -1,49 +1,329 @@
 use std::{
-    collections::BTreeMap, 
-    fmt::Debug
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak
+    },
+    time::Duration
 };
 
-use crate::types::live_api_types::BidiGenerateContentSetup;
+use tokio::{
+    sync::{broadcast, Mutex},
+    task::JoinHandle
+};
+use futures_util::StreamExt;
+
+use crate::types::{
+    live_api_error::LiveApiError,
+    live_api_types::{
+        BidiGenerateContentClientContent,
+        BidiGenerateContentRealTimeInput,
+        BidiGenerateContentSetup,
+        BidiGenerateContentToolResponse,
+        LiveEvent
+    }
+};
+
+use crate::libs::logger::{LogLevel, LOGGER};
+use super::{
+    session_stats::{LiveSessionStats, SessionStats},
+    socket_client::{GeminiSocketClient, LiveSender, ReconnectPolicy}
+};
 
-use crate::libs::logger::LOGGER;
-use super::socket_client::GeminiSocketClient;
+// 한 세션의 이벤트를 구독자들에게 나눠줄 때의 버퍼 크기.
+// 오디오 청크가 몰려도 구독자가 뒤처지지 않도록 넉넉히 잡습니다.
+const EVENT_BUFFER: usize = 1024;
+
+#[derive(Debug, Clone)]
+pub struct SocketPoolConfig {
+    // 동시에 열 수 있는 Live 세션 수
+    pub max_sessions: usize,
+    // 이 시간 동안 송수신이 없으면 세션을 닫습니다.
+    pub idle_timeout: Duration,
+    // 최초 연결 실패 시 재시도 횟수와 첫 대기 시간 (시도마다 두 배)
+    pub connect_retries: u32,
+    pub connect_backoff: Duration,
+    // 연결된 뒤 끊겼을 때의 재연결 정책
+    pub reconnect_policy: ReconnectPolicy,
+}
+
+impl Default for SocketPoolConfig {
+    fn default() -> Self {
+        SocketPoolConfig {
+            max_sessions: 16,
+            idle_timeout: Duration::from_secs(300),
+            connect_retries: 2,
+            connect_backoff: Duration::from_millis(500),
+            reconnect_policy: ReconnectPolicy::default(),
+        }
+    }
+}
+
+// 풀에 등록된 세션을 여러 태스크에서 함께 쓰기 위한 핸들
+#[derive(Clone)]
+pub struct LiveSessionHandle<TKey: Clone> {
+    pub id: TKey,
+    sender: LiveSender,
+    events: broadcast::Sender<LiveEvent>,
+    stats: Arc<LiveSessionStats>,
+    holds: Arc<AtomicUsize>,
+}
+
+// 세션을 쓰는 쪽이 살아 있는 동안 잡고 있는 표시. 잡혀 있는 세션은 조용해도 유휴 정리에서 빠집니다.
+// 음성 대화처럼 말할 때만 입력을 보내는 세션이 침묵 중에 닫히지 않게 합니다.
+pub struct SessionHold {
+    holds: Arc<AtomicUsize>,
+}
+
+impl Drop for SessionHold {
+    fn drop(&mut self) {
+        self.holds.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl<TKey: Clone> LiveSessionHandle<TKey> {
+    pub fn hold(&self) -> SessionHold {
+        self.holds.fetch_add(1, Ordering::AcqRel);
+        SessionHold { holds: self.holds.clone() }
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.holds.load(Ordering::Acquire) > 0
+    }
 
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.events.subscribe()
+    }
+
+    pub async fn send_client_content(&self, content: BidiGenerateContentClientContent) -> Result<(), LiveApiError> {
+        self.sender.send_client_content(content).await
+    }
+
+    pub async fn send_realtime_input(&self, input: BidiGenerateContentRealTimeInput) -> Result<(), LiveApiError> {
+        self.sender.send_realtime_input(input).await
+    }
+
+    pub async fn send_tool_response(&self, response: BidiGenerateContentToolResponse) -> Result<(), LiveApiError> {
+        self.sender.send_tool_response(response).await
+    }
+
+    pub fn stats(&self) -> SessionStats {
+        self.stats.snapshot()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    pub fn shutdown(&self) {
+        self.sender.shutdown();
+    }
+}
+
+struct PoolInner<TKey: Clone> {
+    sessions: BTreeMap<TKey, LiveSessionHandle<TKey>>,
+}
+
+// Live 세션 풀. 복제해도 같은 풀을 가리킵니다.
+#[derive(Clone)]
 pub struct GeminiSocketManager<TKey: Ord+Debug+Clone> {
-    socket_map: BTreeMap<TKey, GeminiSocketClient<TKey>>,
+    inner: Arc<Mutex<PoolInner<TKey>>>,
+    config: SocketPoolConfig,
 }
 
-impl<TKey: Ord+Debug+Clone> GeminiSocketManager<TKey> {
+impl<TKey: Ord+Debug+Clone+Send+Sync+'static> Default for GeminiSocketManager<TKey> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<TKey: Ord+Debug+Clone+Send+Sync+'static> GeminiSocketManager<TKey> {
     pub fn new() -> Self {
+        Self::with_config(SocketPoolConfig::default())
+    }
+
+    pub fn with_config(config: SocketPoolConfig) -> Self {
         GeminiSocketManager {
-            socket_map: BTreeMap::new(),
+            inner: Arc::new(Mutex::new(PoolInner { sessions: BTreeMap::new() })),
+            config,
         }
     }
 
+    pub fn config(&self) -> &SocketPoolConfig {
+        &self.config
+    }
+
+    // 열려 있는 세션이 있으면 그대로, 없으면 새로 연결해 돌려줍니다.
+    // 함께 돌려주는 수신부는 세션이 이벤트를 내보내기 전에 구독되어 있어 첫 이벤트부터 받을 수 있습니다.
     pub async fn get_or_create_socket_client(
-        &mut self,
+        &self,
         id: TKey,
         url: String,
         connect_init: BidiGenerateContentSetup,
-    ) -> &mut GeminiSocketClient<TKey> {
-        let id_clone = id.clone();
-        if !self.socket_map.contains_key(&id) {
-            let url_clone = url.clone();
-            let mut client = GeminiSocketClient::new(
-                id, 
-                url, 
-                connect_init
-            );
+    ) -> Result<(LiveSessionHandle<TKey>, broadcast::Receiver<LiveEvent>), LiveApiError> {
+        if let Some(handle) = self.get(&id).await {
+            let events = handle.subscribe();
+            return Ok((handle, events));
+        }
+        self.ensure_capacity().await?;
+
+        // 연결은 잠금 밖에서 합니다. 재시도 동안 다른 세션 조회가 막히지 않도록.
+        let client = self.connect_with_retry(id.clone(), url, connect_init).await?;
+        let stats = client.stats();
+        let (sender, mut stream) = client.into_event_stream();
+        let (events, first_rx) = broadcast::channel(EVENT_BUFFER);
+        let handle = LiveSessionHandle { id: id.clone(), sender, events: events.clone(), stats, holds: Arc::default() };
+
+        let mut inner = self.inner.lock().await;
+        if let Some(existing) = inner.sessions.get(&id).filter(|h| !h.is_closed()).cloned() {
+            // 그 사이 다른 태스크가 같은 세션을 만들었으면 그쪽을 씁니다.
+            drop(inner);
+            handle.shutdown();
+            let events = existing.subscribe();
+            return Ok((existing, events));
+        }
+        let open = inner.sessions.values().filter(|h| !h.is_closed()).count();
+        if open >= self.config.max_sessions {
+            drop(inner);
+            handle.shutdown();
+            return Err(LiveApiError::PoolExhausted { max_sessions: self.config.max_sessions });
+        }
+        inner.sessions.insert(id.clone(), handle.clone());
+        drop(inner);
+
+        // 소켓 이벤트를 구독자들에게 나눠주고, 세션이 닫히면 풀에서 뺍니다.
+        let pool = Arc::downgrade(&self.inner);
+        tokio::spawn(async move {
+            while let Some(event) = stream.next().await {
+                let _ = events.send(event);
+            }
+            remove_closed(&pool, &id).await;
+        });
+
+        Ok((handle, first_rx))
+    }
+
+    async fn connect_with_retry(
+        &self,
+        id: TKey,
+        url: String,
+        connect_init: BidiGenerateContentSetup,
+    ) -> Result<GeminiSocketClient<TKey>, LiveApiError> {
+        let mut client = GeminiSocketClient::new(id, url, connect_init)
+            .with_reconnect_policy(self.config.reconnect_policy.clone());
+        let mut attempt = 0;
+        loop {
             match client.connect().await {
-                Ok(_) => {
-                    LOGGER.log(crate::libs::logger::LogLevel::Debug, format!("Connected to WebSocket: {}", url_clone).as_str());
-                }
-                Err(e) => {
-                    LOGGER.log(crate::libs::logger::LogLevel::Error, format!("Failed to connect to WebSocket {}: {}", url_clone, e).as_str());
+                Ok(()) => return Ok(client),
+                Err(e) if attempt < self.config.connect_retries => {
+                    let backoff = self.config.connect_backoff.saturating_mul(2u32.saturating_pow(attempt));
+                    LOGGER.log(LogLevel::Warning, format!("[{:?}] Connect failed, retrying in {:?}: {}", client.id, backoff, e).as_str());
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
                 }
+                Err(e) => return Err(e),
             }
-            self.socket_map.insert(id_clone.clone(), client);
         }
-        self.socket_map.get_mut(&id_clone).unwrap()
     }
 
-}
\ No newline at end of file
+    // 제한에 걸리면 먼저 유휴 세션을 정리해 자리를 만들어 봅니다.
+    async fn ensure_capacity(&self) -> Result<(), LiveApiError> {
+        if self.open_sessions().await < self.config.max_sessions {
+            return Ok(());
+        }
+        self.evict_idle().await;
+        if self.open_sessions().await < self.config.max_sessions {
+            Ok(())
+        } else {
+            Err(LiveApiError::PoolExhausted { max_sessions: self.config.max_sessions })
+        }
+    }
+
+    pub async fn get(&self, id: &TKey) -> Option<LiveSessionHandle<TKey>> {
+        let mut inner = self.inner.lock().await;
+        match inner.sessions.get(id) {
+            Some(handle) if !handle.is_closed() => Some(handle.clone()),
+            Some(_) => {
+                inner.sessions.remove(id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    // 세션을 닫고 풀에서 뺍니다.
+    pub async fn remove(&self, id: &TKey) -> bool {
+        let handle = self.inner.lock().await.sessions.remove(id);
+        match handle {
+            Some(handle) => {
+                handle.shutdown();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // idle_timeout 동안 활동이 없거나 이미 닫힌 세션을 정리하고, 정리한 세션 ID를 돌려줍니다.
+    // hold() 로 잡혀 있는 세션은 조용해도 남겨 둡니다.
+    pub async fn evict_idle(&self) -> Vec<TKey> {
+        let mut inner = self.inner.lock().await;
+        let expired = inner
+            .sessions
+            .iter()
+            .filter(|(_, h)| h.is_closed() || (!h.is_held() && h.stats().idle_for() >= self.config.idle_timeout))
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        for id in &expired {
+            if let Some(handle) = inner.sessions.remove(id) {
+                LOGGER.log(LogLevel::Info, format!("[{:?}] Evicting idle Live session", id).as_str());
+                handle.shutdown();
+            }
+        }
+        expired
+    }
+
+    // 주기적으로 유휴 세션을 정리하는 태스크. 풀이 모두 drop 되면 스스로 끝납니다.
+    pub fn spawn_idle_reaper(&self, interval: Duration) -> JoinHandle<()> {
+        let pool = Arc::downgrade(&self.inner);
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(inner) = pool.upgrade() else { break };
+                GeminiSocketManager { inner, config: config.clone() }.evict_idle().await;
+            }
+        })
+    }
+
+    pub async fn open_sessions(&self) -> usize {
+        self.inner.lock().await.sessions.values().filter(|h| !h.is_closed()).count()
+    }
+
+    pub async fn stats(&self) -> Vec<(TKey, SessionStats)> {
+        self.inner
+            .lock()
+            .await
+            .sessions
+            .iter()
+            .map(|(id, h)| (id.clone(), h.stats()))
+            .collect()
+    }
+
+    pub async fn shutdown_all(&self) {
+        let sessions = std::mem::take(&mut self.inner.lock().await.sessions);
+        for handle in sessions.values() {
+            handle.shutdown();
+        }
+    }
+}
+
+async fn remove_closed<TKey: Ord+Clone>(pool: &Weak<Mutex<PoolInner<TKey>>>, id: &TKey) {
+    let Some(inner) = pool.upgrade() else { return };
+    let mut inner = inner.lock().await;
+    // 같은 ID로 새 세션이 들어왔을 수 있으므로 닫힌 경우에만 뺍니다.
+    if inner.sessions.get(id).is_some_and(|h| h.is_closed()) {
+        inner.sessions.remove(id);
+    }
+}
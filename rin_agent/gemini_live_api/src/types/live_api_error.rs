@@ -0,0 +1,48 @@
+use std::{fmt, time::Duration};
+
+// Live API 소켓에서 발생하는 오류.
+// 연결/송신 실패는 재연결 대상이고, 파싱 실패는 해당 프레임만 버립니다.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiveApiError {
+    // WebSocket 연결 또는 setup 전송 실패
+    Connect(String),
+    // 연결되지 않은 상태에서 송신을 시도함
+    NotConnected,
+    Send(String),
+    Serialize(String),
+    // 서버 프레임을 BidiGenerateContentServerMessage 로 해석하지 못함
+    Parse { error: String, raw: String },
+    // 서버가 재시도해도 소용없는 이유로 연결을 닫음 (잘못된 setup 등)
+    ClosedByServer { code: u16, reason: String },
+    // 재연결을 정해진 횟수만큼 시도했지만 실패함
+    ReconnectExhausted { attempts: u32 },
+    // 서버가 곧 연결을 끊겠다고 알림
+    GoAway { time_left: Option<Duration> },
+    // 풀의 동시 세션 수 제한에 걸림
+    PoolExhausted { max_sessions: usize },
+}
+
+impl fmt::Display for LiveApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveApiError::Connect(e) => write!(f, "Failed to connect: {}", e),
+            LiveApiError::NotConnected => write!(f, "WebSocket not connected or tx is not available."),
+            LiveApiError::Send(e) => write!(f, "Failed to send message: {}", e),
+            LiveApiError::Serialize(e) => write!(f, "Failed to serialize message: {}", e),
+            LiveApiError::Parse { error, raw } => write!(f, "Failed to parse server message: {} ({})", error, raw),
+            LiveApiError::ClosedByServer { code, reason } => write!(f, "Closed by server: {} {}", code, reason),
+            LiveApiError::ReconnectExhausted { attempts } => write!(f, "Reconnect failed after {} attempts", attempts),
+            LiveApiError::GoAway { time_left } => write!(f, "Server is going away (time left: {:?})", time_left),
+            LiveApiError::PoolExhausted { max_sessions } => write!(f, "Live session pool is full ({} sessions)", max_sessions),
+        }
+    }
+}
+
+impl std::error::Error for LiveApiError {}
+
+// 기존 Result<_, String> 호출부와 호환
+impl From<LiveApiError> for String {
+    fn from(e: LiveApiError) -> Self {
+        e.to_string()
+    }
+}
@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use super::live_api_error::LiveApiError;
 use super::{GeminiContents, GeminiFunctionCall, GeminiFunctionResponse, GeminiGenerationConfig, GeminiGenerationConfigTool, GeminiInlineBlob, GroundingMetadata};
 
 //https://ai.google.dev/api/live?hl=ko#receive-messages
@@ -31,7 +32,7 @@ pub struct GeminiLiveApiGenerationConfig {
     pub frequency_penalty: Option<f32>,
     pub response_modalities: Option<Vec<GeminiResponseModalities>>,
 }
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RealtimeInputConfig {
     pub automatic_activity_detection:Option<AutomaticActivityDetection>,
@@ -54,7 +55,7 @@ pub enum TurnCoverage {
     TurnIncludesAllInput
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AutomaticActivityDetection{
     pub disable: Option<bool>,
@@ -94,7 +95,7 @@ pub struct GeminiLiveApiWebSocketMessage {
     pub setup: Option<BidiGenerateContentSetup>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_content: Option<BidiGenerateContentClientContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "realtimeInput", skip_serializing_if = "Option::is_none")]
     pub real_time_input: Option<BidiGenerateContentRealTimeInput>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_response: Option<BidiGenerateContentToolResponse>,
@@ -141,7 +142,7 @@ impl Default for GeminiLiveApiWebSocketMessage {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BidiGenerateContentRealTimeInput{
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -156,12 +157,32 @@ pub struct BidiGenerateContentRealTimeInput{
     pub activity_end: Option<ActivityEnd>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub audio_stream_end: Option<bool>,
-    pub text:String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
 }
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ActivityStart;
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ActivityEnd;
+impl BidiGenerateContentRealTimeInput {
+    // 16kHz s16le mono PCM 오디오 청크
+    pub fn audio(pcm: &[u8], sample_rate: u32) -> Self {
+        BidiGenerateContentRealTimeInput {
+            audio: Some(GeminiInlineBlob {
+                mime_type: format!("audio/pcm;rate={}", sample_rate),
+                data: base64::Engine::encode(&base64::engine::general_purpose::STANDARD, pcm),
+            }),
+            ..Default::default()
+        }
+    }
+    pub fn text(text: String) -> Self {
+        BidiGenerateContentRealTimeInput {
+            text: Some(text),
+            ..Default::default()
+        }
+    }
+}
+// 빈 객체(`{}`)로 직렬화되어야 하므로 unit struct 대신 빈 중괄호 구조체를 사용합니다.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ActivityStart {}
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ActivityEnd {}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -182,7 +203,7 @@ pub struct BidiGenerateContentSetup {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input_audio_transcription: Option<AudioTranscriptionConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub output_audio_generation: Option<AudioTranscriptionConfig>,
+    pub output_audio_transcription: Option<AudioTranscriptionConfig>,
 }
 impl Default for BidiGenerateContentSetup {
     fn default() -> Self {
@@ -195,37 +216,51 @@ impl Default for BidiGenerateContentSetup {
             session_resumption: None,
             context_window_compression: None,
             input_audio_transcription: None,
-            output_audio_generation: None
+            output_audio_transcription: None
         }
     }
 }
 
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AudioTranscriptionConfig;
-#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AudioTranscriptionConfig {}
+// handle 이 없으면 새 세션을 시작하고, 있으면 이전 세션을 이어받습니다.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionResumptionConfig{
     #[serde(skip_serializing_if = "Option::is_none")]
     pub handle: Option<String>
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+// 컨텍스트가 trigger_tokens 를 넘으면 target_tokens 까지 앞부분을 잘라냅니다.
+// 설정하지 않으면 오디오 세션은 약 15분 후 종료됩니다.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ContextWindowCompression{
-    pub sliding_window: SlidingWindow,
-    pub trigger_tokens: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sliding_window: Option<SlidingWindow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_tokens: Option<i64>,
 }
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl ContextWindowCompression {
+    pub fn sliding_window(trigger_tokens: i64, target_tokens: i64) -> Self {
+        ContextWindowCompression {
+            sliding_window: Some(SlidingWindow { target_tokens: Some(target_tokens) }),
+            trigger_tokens: Some(trigger_tokens),
+        }
+    }
+}
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SlidingWindow{
-    pub target_tokens: i32
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_tokens: Option<i64>
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct BidiGenerateContentSetupComplete;
+pub struct BidiGenerateContentSetupComplete {}
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BidiGenerateContentToolCall{
@@ -261,8 +296,11 @@ pub struct BidiGenerateContentClientContent{
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BidiGenerateContentServerContent{
+    #[serde(default)]
     pub generation_complete: bool,
+    #[serde(default)]
     pub turn_complete: bool,
+    #[serde(default)]
     pub interrupted: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub grounding_metadata: Option<GroundingMetadata>,
@@ -278,16 +316,42 @@ pub struct BidiGenerateContentServerContent{
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GoAway{
-    #[serde(skip_serializing_if = "Option::is_none")]
+    // 서버는 protobuf Duration 의 JSON 표현("10s", "1.5s")으로 보냅니다.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "proto_duration")]
     pub time_left:Option<Duration>,
 }
 
+mod proto_duration {
+    use std::time::Duration;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(d) => serializer.serialize_str(&format!("{}s", d.as_secs_f64())),
+            None => serializer.serialize_none(),
+        }
+    }
 
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        let Some(text) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let seconds = text
+            .strip_suffix('s')
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|s| s.is_finite() && *s >= 0.0)
+            .ok_or_else(|| de::Error::custom(format!("invalid duration: {}", text)))?;
+        Ok(Some(Duration::from_secs_f64(seconds)))
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionResumptionUpdate{
+    #[serde(default)]
     pub new_handle: Option<String>,
+    #[serde(default)]
     pub resumable: bool,
 }
 
@@ -300,4 +364,49 @@ pub struct BidiGenerateContentServerMessage {
     pub tool_call: Option<BidiGenerateContentToolCall>,
     pub tool_call_cancellation: Option<BidiGenerateContentToolCallCancellation>,
     pub session_resumption_update: Option<SessionResumptionUpdate>,
-}
\ No newline at end of file
+}
+// GeminiSocketClient 의 이벤트 스트림이 내보내는 이벤트
+#[derive(Debug, Clone)]
+pub enum LiveEvent {
+    SetupComplete,
+    ServerContent(BidiGenerateContentServerContent),
+    ToolCall(BidiGenerateContentToolCall),
+    ToolCallCancellation(BidiGenerateContentToolCallCancellation),
+    // 서버가 곧 연결을 끊음. 클라이언트는 이어서 재연결합니다.
+    GoAway { time_left: Option<Duration> },
+    SessionResumptionUpdate(SessionResumptionUpdate),
+    // 연결이 끊겨 재연결을 시도하는 중 (1부터 시작)
+    Reconnecting { attempt: u32 },
+    // 재연결 성공. resumed 가 false 면 이전 대화 맥락 없이 새 세션으로 시작했습니다.
+    Reconnected { resumed: bool },
+    // 스트림을 끝내지 않는 오류 (파싱 실패 등)
+    Error(LiveApiError),
+    // 더 이상 이벤트가 없음. 이후 스트림은 None 을 돌려줍니다.
+    Closed { error: Option<LiveApiError> },
+}
+
+impl BidiGenerateContentServerMessage {
+    // 한 메시지에 여러 필드가 들어올 수 있으므로 순서대로 이벤트로 나눕니다.
+    pub fn into_events(self) -> Vec<LiveEvent> {
+        let mut events = Vec::new();
+        if self.setup_complete.is_some() {
+            events.push(LiveEvent::SetupComplete);
+        }
+        if let Some(update) = self.session_resumption_update {
+            events.push(LiveEvent::SessionResumptionUpdate(update));
+        }
+        if let Some(tool_call) = self.tool_call {
+            events.push(LiveEvent::ToolCall(tool_call));
+        }
+        if let Some(cancellation) = self.tool_call_cancellation {
+            events.push(LiveEvent::ToolCallCancellation(cancellation));
+        }
+        if let Some(content) = self.server_content {
+            events.push(LiveEvent::ServerContent(content));
+        }
+        if let Some(go_away) = self.go_away {
+            events.push(LiveEvent::GoAway { time_left: go_away.time_left });
+        }
+        events
+    }
+}
@@ -1,5 +1,8 @@
 pub mod enums;
 pub mod live_api_types;
+pub mod live_api_error;
+pub mod rest_api_types;
+pub mod rest_api_error;
 
 
 
@@ -30,7 +33,6 @@ pub enum HarmBlockThreshold{
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum HarmCategory{
-    HarmCategoryUnspecified,//카테고리가 지정되지 않았습니다.
     HarmCategoryDerogatory,//PaLM - ID 또는 보호 속성을 대상으로 하는 부정적이거나 유해한 댓글
     HarmCategoryToxicity,//PaLM - 무례하거나 모욕적이거나 욕설이 있는 콘텐츠
     HarmCategoryViolence,//PaLM - 개인 또는 그룹에 대한 폭력을 묘사하는 시나리오 또는 유혈 콘텐츠에 대한 일반적인 설명을 묘사
@@ -42,6 +44,9 @@ pub enum HarmCategory{
     HarmCategorySexuallyExplicit,//Gemini - 성적으로 노골적인 콘텐츠
     HarmCategoryDangerousContent,//Gemini - 위험한 콘텐츠
     HarmCategoryCivicIntegrity,//Gemini - 시민의 품위를 해치는 데 사용될 수 있는 콘텐츠
+    // 모르는 카테고리도 응답 전체를 버리지 않도록 여기로 받습니다.
+    #[serde(other)]
+    HarmCategoryUnspecified,//카테고리가 지정되지 않았습니다.
 }
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -234,6 +239,7 @@ impl Default for GeminiSchema {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GeminiContents{
+    #[serde(default)]
     pub parts:Vec<GeminiParts>,
     pub role: GeminiContentRole
 }
@@ -557,9 +563,12 @@ pub struct GeminiFunctionDeclaration{
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GroundingMetadata{
+    #[serde(default)]
     pub grounding_chunks: Vec<GroundingChunk>,
+    #[serde(default)]
     pub grounding_supports: Vec<GroundingSupport>,
-    pub web_search_queries: String,
+    #[serde(default)]
+    pub web_search_queries: Vec<String>,
     pub search_entry_point: Option<SearchEntryPoint>,
     pub retrieval_metadata: Option<RetrievalMetadata>,
 
@@ -568,6 +577,7 @@ pub struct GroundingMetadata{
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RetrievalMetadata{
+    #[serde(default)]
     pub google_search_dynamic_retrieval_score: f32,
 }
 
@@ -582,17 +592,24 @@ pub struct SearchEntryPoint{
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GroundingSupport{
+    #[serde(default)]
     pub grounding_chunk_indices: Vec<i32>,
+    #[serde(default)]
     pub confidence_scores: Vec<f32>,
-    pub segments: Vec<GroundingSegment>,
+    pub segment: Option<GroundingSegment>,
 
 }
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GroundingSegment{
+    // 0 인 값은 응답에서 빠지므로 기본값을 둡니다.
+    #[serde(default)]
     pub part_index: i32,
+    #[serde(default)]
     pub start_index: i32,
+    #[serde(default)]
     pub end_index: i32,
+    #[serde(default)]
     pub text: String,
 }
 
@@ -643,10 +660,22 @@ pub struct GeminiCachedContentResponse {
     pub usage_metadata: Option<GeminiUsageMetadata>, // Usage metadata for the cached content
 }
 
-#[derive(Debug, Clone, Serialize,Deserialize)]
+#[derive(Debug, Clone, Default, Serialize,Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GeminiUsageMetadata {
+    #[serde(default)]
     pub total_token_count: i32, // Total token count used in the cached content
+    // 아래는 generateContent 응답에만 오는 값입니다.
+    #[serde(default)]
+    pub prompt_token_count: i32,
+    #[serde(default)]
+    pub candidates_token_count: i32,
+    #[serde(default)]
+    pub cached_content_token_count: i32,
+    #[serde(default)]
+    pub thoughts_token_count: i32,
+    #[serde(default)]
+    pub tool_use_prompt_token_count: i32,
 }
 
 
@@ -0,0 +1,82 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+// Gemini REST API 호출에서 발생하는 오류.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeminiApiError {
+    // GEMINI_API_KEY 가 설정되지 않음
+    MissingApiKey,
+    // 요청 전송/응답 수신 실패 (DNS, TLS, 타임아웃 등)
+    Transport(String),
+    // 서버가 성공이 아닌 상태 코드로 응답함. status 는 "INVALID_ARGUMENT" 같은 gRPC 상태 이름
+    Api { http_status: u16, status: Option<String>, message: String },
+    // 응답 본문을 기대한 타입으로 해석하지 못함
+    Parse { error: String, raw: String },
+    // 프롬프트가 안전 필터 등으로 차단되어 후보가 없음
+    Blocked { reason: String },
+    // Files API 업로드 시작 응답에 업로드 URL이 없음
+    UploadUrlMissing,
+}
+
+impl GeminiApiError {
+    // 오류 응답 본문({"error": {...}})을 해석합니다. 형식이 다르면 본문을 그대로 메시지로 씁니다.
+    pub fn from_response(http_status: u16, body: &str) -> Self {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            error: ErrorDetail,
+        }
+        #[derive(Deserialize)]
+        struct ErrorDetail {
+            #[serde(default)]
+            message: String,
+            status: Option<String>,
+        }
+        match serde_json::from_str::<ErrorBody>(body) {
+            Ok(parsed) => GeminiApiError::Api {
+                http_status,
+                status: parsed.error.status,
+                message: parsed.error.message,
+            },
+            Err(_) => GeminiApiError::Api { http_status, status: None, message: body.to_string() },
+        }
+    }
+
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            GeminiApiError::Api { http_status, .. } => Some(*http_status),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GeminiApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeminiApiError::MissingApiKey => write!(f, "GEMINI_API_KEY must be set"),
+            GeminiApiError::Transport(e) => write!(f, "Request failed: {}", e),
+            GeminiApiError::Api { http_status, status, message } => match status {
+                Some(status) => write!(f, "{} {}: {}", http_status, status, message),
+                None => write!(f, "{}: {}", http_status, message),
+            },
+            GeminiApiError::Parse { error, raw } => write!(f, "Failed to parse response: {} ({})", error, raw),
+            GeminiApiError::Blocked { reason } => write!(f, "Prompt blocked: {}", reason),
+            GeminiApiError::UploadUrlMissing => write!(f, "Upload URL missing in response"),
+        }
+    }
+}
+
+impl std::error::Error for GeminiApiError {}
+
+impl From<reqwest::Error> for GeminiApiError {
+    fn from(e: reqwest::Error) -> Self {
+        GeminiApiError::Transport(e.to_string())
+    }
+}
+
+// 기존 Result<_, String> 호출부와 호환
+impl From<GeminiApiError> for String {
+    fn from(e: GeminiApiError) -> Self {
+        e.to_string()
+    }
+}
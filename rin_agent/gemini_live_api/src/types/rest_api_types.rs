@@ -0,0 +1,369 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    GeminiCachedContentResponse,
+    GeminiContents,
+    GeminiFunctionCall,
+    GeminiGenerationConfig,
+    GeminiGenerationConfigTool,
+    GeminiToolConfig,
+    GeminiUsageMetadata,
+    GroundingMetadata,
+    HarmCategory,
+    SafetySetting
+};
+
+// https://ai.google.dev/api/generate-content#request-body
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentRequest {
+    pub contents: Vec<GeminiContents>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_instruction: Option<GeminiContents>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<GeminiGenerationConfigTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_config: Option<GeminiToolConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GeminiGenerationConfig>,
+    // "cachedContents/xxx" 형식의 캐시 이름
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_content: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateContentResponse {
+    #[serde(default)]
+    pub candidates: Vec<GeminiCandidate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_feedback: Option<PromptFeedback>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_metadata: Option<GeminiUsageMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_id: Option<String>,
+}
+
+impl GenerateContentResponse {
+    pub fn first_candidate(&self) -> Option<&GeminiCandidate> {
+        self.candidates.first()
+    }
+
+    // 첫 후보의 생각(thought)이 아닌 텍스트를 이어 붙입니다.
+    pub fn text(&self) -> Option<String> {
+        let texts = self.first_candidate()?
+            .parts()
+            .iter()
+            .filter(|p| p.thought != Some(true))
+            .filter_map(|p| p.text.clone())
+            .collect::<Vec<_>>();
+        if texts.is_empty() { None } else { Some(texts.concat()) }
+    }
+
+    pub fn thoughts(&self) -> Option<String> {
+        let texts = self.first_candidate()?
+            .parts()
+            .iter()
+            .filter(|p| p.thought == Some(true))
+            .filter_map(|p| p.text.clone())
+            .collect::<Vec<_>>();
+        if texts.is_empty() { None } else { Some(texts.concat()) }
+    }
+
+    pub fn function_calls(&self) -> Vec<&GeminiFunctionCall> {
+        self.first_candidate()
+            .map(|c| c.parts().iter().filter_map(|p| p.function_call.as_ref()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn finish_reason(&self) -> Option<&FinishReason> {
+        self.first_candidate().and_then(|c| c.finish_reason.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiCandidate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<GeminiContents>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub safety_ratings: Vec<SafetyRating>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation_metadata: Option<CitationMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grounding_metadata: Option<GroundingMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_logprobs: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_count: Option<i32>,
+    #[serde(default)]
+    pub index: i32,
+}
+
+impl GeminiCandidate {
+    pub fn parts(&self) -> &[super::GeminiParts] {
+        self.content.as_ref().map(|c| c.parts.as_slice()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinishReason {
+    FinishReasonUnspecified,
+    Stop,
+    MaxTokens,
+    Safety,
+    Recitation,
+    Language,
+    Other,
+    Blocklist,
+    ProhibitedContent,
+    Spii,
+    MalformedFunctionCall,
+    ImageSafety,
+    UnexpectedToolCall,
+    TooManyToolCalls,
+    // 아직 모르는 값이 와도 응답 전체가 깨지지 않도록 받아둡니다.
+    #[serde(other)]
+    Unknown,
+}
+
+impl FinishReason {
+    // 기존 GeminiResponse.finish_reason 문자열과 같은 표기
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FinishReason::FinishReasonUnspecified => "FINISH_REASON_UNSPECIFIED",
+            FinishReason::Stop => "STOP",
+            FinishReason::MaxTokens => "MAX_TOKENS",
+            FinishReason::Safety => "SAFETY",
+            FinishReason::Recitation => "RECITATION",
+            FinishReason::Language => "LANGUAGE",
+            FinishReason::Other => "OTHER",
+            FinishReason::Blocklist => "BLOCKLIST",
+            FinishReason::ProhibitedContent => "PROHIBITED_CONTENT",
+            FinishReason::Spii => "SPII",
+            FinishReason::MalformedFunctionCall => "MALFORMED_FUNCTION_CALL",
+            FinishReason::ImageSafety => "IMAGE_SAFETY",
+            FinishReason::UnexpectedToolCall => "UNEXPECTED_TOOL_CALL",
+            FinishReason::TooManyToolCalls => "TOO_MANY_TOOL_CALLS",
+            FinishReason::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafetyRating {
+    pub category: HarmCategory,
+    pub probability: HarmProbability,
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HarmProbability {
+    Negligible,
+    Low,
+    Medium,
+    High,
+    // 모르는 값도 여기로 받습니다.
+    #[serde(other)]
+    HarmProbabilityUnspecified,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFeedback {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_reason: Option<BlockReason>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub safety_ratings: Vec<SafetyRating>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlockReason {
+    Safety,
+    Other,
+    Blocklist,
+    ProhibitedContent,
+    ImageSafety,
+    // 모르는 값도 여기로 받습니다.
+    #[serde(other)]
+    BlockReasonUnspecified,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationMetadata {
+    #[serde(default)]
+    pub citation_sources: Vec<CitationSource>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationSource {
+    #[serde(default)]
+    pub start_index: i32,
+    #[serde(default)]
+    pub end_index: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+}
+
+// https://ai.google.dev/api/tokens#method:-models.counttokens
+// contents 만 보내거나, 시스템 지시/도구까지 포함하려면 generate_content_request 를 씁니다.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountTokensRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contents: Option<Vec<GeminiContents>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generate_content_request: Option<Box<GenerateContentRequest>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountTokensResponse {
+    #[serde(default)]
+    pub total_tokens: i32,
+    #[serde(default)]
+    pub cached_content_token_count: i32,
+}
+
+// https://ai.google.dev/api/embeddings#method:-models.embedcontent
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedContentRequest {
+    pub content: GeminiContents,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<EmbeddingTaskType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dimensionality: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EmbeddingTaskType {
+    TaskTypeUnspecified,
+    RetrievalQuery,
+    RetrievalDocument,
+    SemanticSimilarity,
+    Classification,
+    Clustering,
+    QuestionAnswering,
+    FactVerification,
+    CodeRetrievalQuery,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedContentResponse {
+    pub embedding: ContentEmbedding,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentEmbedding {
+    #[serde(default)]
+    pub values: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCachedContentsResponse {
+    #[serde(default)]
+    pub cached_contents: Vec<GeminiCachedContentResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+// https://ai.google.dev/api/files#File
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiFile {
+    // "files/xxx"
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    // int64 는 문자열로 옵니다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256_hash: Option<String>,
+    // 생성 요청에서 fileData.fileUri 로 쓰는 값
+    #[serde(default)]
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<FileState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FileState {
+    Processing,
+    Active,
+    Failed,
+    // 모르는 값도 여기로 받습니다.
+    #[serde(other)]
+    StateUnspecified,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct FileEnvelope {
+    pub file: GeminiFile,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFilesResponse {
+    #[serde(default)]
+    pub files: Vec<GeminiFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+// cachedContents.patch 본문. 만료 시각은 ttl 이나 expire_time 중 하나로 바꿉니다.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedContentUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expire_time: Option<String>,
+}
+
+impl CachedContentUpdate {
+    // updateMask 쿼리에 넣을 필드 목록
+    pub fn update_mask(&self) -> String {
+        let mut fields = Vec::new();
+        if self.ttl.is_some() {
+            fields.push("ttl");
+        }
+        if self.expire_time.is_some() {
+            fields.push("expireTime");
+        }
+        fields.join(",")
+    }
+}
@@ -1,30 +1,256 @@
-use std::collections::HashMap;
-
-enum WebRequestType {
-    Get,
-    Post,
-    Put,
-    Delete,
-}
-
-pub async fn get_web_result(
-    url: String,
-    request_type: WebRequestType,
-    body: Option<HashMap<String, String>>,
-    headers: Option<HashMap<String, String>>,
-) -> Result<String, String> {
-    let request = reqwest::Client::new()
-        .get(format!("https://webdocu.com/api/v1/documents?url={}", url))
-        .send()
-        .await;
-    match request {
-        Ok(response) => {
-            let response_text = response.text().await;
-            match response_text {
-                Ok(text) => Ok(text),
-                Err(e) => Err(format!("Failed to read response text: {}", e)),
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::Url;
+
+use crate::api::html_extract::{extract_readable, WebLink};
+
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; RinAgent/0.1)";
+// 글로 읽을 수 있는 응답 형식. 비어 있으면 HTML 로 보고 읽습니다.
+const READABLE_CONTENT_TYPES: [&str; 4] = ["application/xhtml+xml", "application/json", "application/xml", "application/rss+xml"];
+
+// 모델이 넘긴 URL 을 가져올 때의 제한
+#[derive(Debug, Clone)]
+pub struct WebFetchOptions {
+    // 리다이렉트를 포함한 전체 시간
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    pub max_redirects: usize,
+    // 이보다 긴 본문은 잘라서 읽습니다.
+    pub max_bytes: usize,
+    // 사설/루프백 주소 허용. 로컬 대역 서버를 쓰는 테스트에서만 켭니다.
+    pub allow_private: bool,
+}
+
+impl Default for WebFetchOptions {
+    fn default() -> Self {
+        WebFetchOptions {
+            timeout: Duration::from_secs(15),
+            connect_timeout: Duration::from_secs(5),
+            max_redirects: 5,
+            max_bytes: 2 * 1024 * 1024,
+            allow_private: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebFetchResult {
+    // 리다이렉트를 따라간 뒤의 주소
+    pub url: Url,
+    pub content_type: String,
+    pub body: String,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebPage {
+    pub url: String,
+    pub title: Option<String>,
+    pub text: String,
+    pub links: Vec<WebLink>,
+    pub truncated: bool,
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || octets[0] == 0
+        // 100.64.0.0/10 (CGNAT)
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+        // 192.0.0.0/24 (IETF 프로토콜 할당)
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0)
+        // 198.18.0.0/15 (벤치마크)
+        || (octets[0] == 198 && (octets[1] & 0xfe) == 18)
+        // 240.0.0.0/4 (예약)
+        || octets[0] >= 240)
+}
+
+// 인터넷에서 닿을 수 있는 주소인지 봅니다. IPv4 를 품은 IPv6 주소는 안쪽 IPv4 로 판단합니다.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_ipv4(v4),
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ipv4(v4);
+            }
+            let segments = v6.segments();
+            let embedded_v4 = |high: u16, low: u16| Ipv4Addr::from(((high as u32) << 16) | low as u32);
+            // 64:ff9b::/96 (NAT64)
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                return is_public_ipv4(embedded_v4(segments[6], segments[7]));
+            }
+            // 2002::/16 (6to4). 2002 바로 뒤 32비트가 IPv4 주소입니다.
+            if segments[0] == 0x2002 {
+                return is_public_ipv4(embedded_v4(segments[1], segments[2]));
+            }
+            // 2001::/32 (Teredo). 32~63비트는 서버 주소, 마지막 32비트는 비트를 뒤집은 클라이언트 주소입니다.
+            if segments[0] == 0x2001 && segments[1] == 0 {
+                return is_public_ipv4(embedded_v4(segments[2], segments[3]))
+                    && is_public_ipv4(embedded_v4(!segments[6], !segments[7]));
             }
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // ::/96 (IPv4 호환 주소)
+                || segments[..6] == [0, 0, 0, 0, 0, 0]
+                // fc00::/7 (고유 로컬)
+                || (segments[0] & 0xfe00) == 0xfc00
+                // fe80::/10 (링크 로컬), fec0::/10 (사이트 로컬)
+                || (segments[0] & 0xffc0) == 0xfe80
+                || (segments[0] & 0xffc0) == 0xfec0
+                // 2001:db8::/32 (문서용)
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8))
         }
-        Err(e) => Err(format!("Failed to perform request: {}", e)),
     }
 }
+
+pub fn parse_fetch_url(raw: &str) -> Result<Url, String> {
+    let url = Url::parse(raw.trim()).map_err(|e| format!("잘못된 URL 입니다: {}", e))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(format!("http, https 주소만 열 수 있습니다: {}", url.scheme()));
+    }
+    if url.host_str().is_none_or(|host| host.is_empty()) {
+        return Err("호스트가 없는 URL 입니다.".to_string());
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err("계정 정보가 들어 있는 URL 은 열 수 없습니다.".to_string());
+    }
+    Ok(url)
+}
+
+// DNS 를 풀어 모든 주소가 공인 주소인지 확인합니다. 하나라도 내부 주소면 막습니다.
+pub async fn resolve_public_addrs(url: &Url, allow_private: bool) -> Result<Vec<SocketAddr>, String> {
+    let host = url.host_str().ok_or("호스트가 없는 URL 입니다.".to_string())?;
+    let port = url.port_or_known_default().ok_or("포트를 알 수 없는 URL 입니다.".to_string())?;
+    // IPv6 주소는 [] 로 감싸져 있습니다.
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("주소를 찾지 못했습니다 ({}): {}", host, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("주소를 찾지 못했습니다: {}", host));
+    }
+    if !allow_private {
+        if let Some(blocked) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+            return Err(format!("내부 네트워크 주소로는 접속할 수 없습니다: {} ({})", host, blocked.ip()));
+        }
+    }
+    Ok(addrs)
+}
+
+fn is_readable_content_type(content_type: &str) -> bool {
+    content_type.is_empty() || content_type.starts_with("text/") || READABLE_CONTENT_TYPES.contains(&content_type)
+}
+
+async fn fetch_hops(url: Url, options: &WebFetchOptions) -> Result<WebFetchResult, String> {
+    let mut url = url;
+    let mut redirects = 0;
+    loop {
+        let addrs = resolve_public_addrs(&url, options.allow_private).await?;
+        let mut builder = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .no_proxy()
+            .user_agent(USER_AGENT)
+            .connect_timeout(options.connect_timeout)
+            .timeout(options.timeout);
+        // 확인한 주소로만 접속하도록 고정해, 확인 뒤에 DNS 응답이 바뀌어도 내부 주소로 가지 않게 합니다.
+        if let Some(domain) = url.domain() {
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+        let client = builder.build().map_err(|e| e.to_string())?;
+        let mut response = client
+            .get(url.clone())
+            .header("Accept", "text/html,application/xhtml+xml,text/plain;q=0.9,*/*;q=0.5")
+            .send()
+            .await
+            .map_err(|e| format!("요청에 실패했습니다: {}", e))?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or(format!("리다이렉트 주소가 없습니다 (HTTP {})", status.as_u16()))?;
+            redirects += 1;
+            if redirects > options.max_redirects {
+                return Err(format!("리다이렉트가 {}번을 넘었습니다.", options.max_redirects));
+            }
+            let next = url.join(location).map_err(|e| format!("잘못된 리다이렉트 주소입니다: {}", e))?;
+            url = parse_fetch_url(next.as_str())?;
+            continue;
+        }
+        if !status.is_success() {
+            return Err(format!("페이지를 가져오지 못했습니다 (HTTP {})", status.as_u16()));
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or_default().trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        if !is_readable_content_type(&content_type) {
+            return Err(format!("글로 읽을 수 없는 형식입니다: {}", content_type));
+        }
+
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response.chunk().await.map_err(|e| format!("본문을 읽지 못했습니다: {}", e))? {
+            let remaining = options.max_bytes - body.len();
+            if chunk.len() > remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        return Ok(WebFetchResult {
+            url,
+            content_type,
+            body: String::from_utf8_lossy(&body).to_string(),
+            truncated,
+        });
+    }
+}
+
+// 제한을 지키며 URL 을 가져옵니다. 리다이렉트마다 주소를 다시 확인합니다.
+pub async fn fetch_web(raw_url: &str, options: &WebFetchOptions) -> Result<WebFetchResult, String> {
+    let url = parse_fetch_url(raw_url)?;
+    tokio::time::timeout(options.timeout, fetch_hops(url, options))
+        .await
+        .map_err(|_| format!("{}초 안에 응답이 오지 않았습니다.", options.timeout.as_secs()))?
+}
+
+// 페이지를 가져와 제목, 본문, 링크로 정리합니다. HTML 이 아니면 본문을 그대로 씁니다.
+pub async fn get_web_result(raw_url: &str, options: &WebFetchOptions) -> Result<WebPage, String> {
+    let fetched = fetch_web(raw_url, options).await?;
+    let is_html = fetched.content_type.is_empty() || fetched.content_type.contains("html");
+    if !is_html {
+        return Ok(WebPage {
+            url: fetched.url.to_string(),
+            title: None,
+            text: fetched.body,
+            links: Vec::new(),
+            truncated: fetched.truncated,
+        });
+    }
+    let extracted = extract_readable(&fetched.body, Some(&fetched.url));
+    Ok(WebPage {
+        url: fetched.url.to_string(),
+        title: extracted.title,
+        text: extracted.text,
+        links: extracted.links,
+        truncated: fetched.truncated,
+    })
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::api::search_provider::{get_search_json, GOOGLE_SEARCH_URL};
+
 #[derive(Debug, Clone,Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GoogleSearchItem{
@@ -12,37 +14,31 @@ pub struct GoogleSearchItem{
     pub pagemap: Option<Value>
 }
 
+// Custom Search JSON API 호출. num 은 API 제한상 1 ~ 10 입니다.
+pub async fn google_search_items(base_url: &str, token: &str, cx: &str, query: &str, num: usize) -> Result<Vec<GoogleSearchItem>, String> {
+    let request_url = reqwest::Url::parse_with_params(
+        &format!("{}/customsearch/v1", base_url.trim_end_matches('/')),
+        &[("key", token), ("cx", cx), ("q", query), ("num", &num.clamp(1, 10).to_string())],
+    )
+    .map_err(|e| format!("Invalid search url: {}", e))?;
+
+    let json = get_search_json(reqwest::Client::new().get(request_url)).await?;
+
+    // 결과가 없으면 items 키 자체가 빠져 있습니다.
+    let Some(items) = json.get("items") else {
+        return Ok(Vec::new());
+    };
+    let results: Vec<GoogleSearchItem> = serde_json::from_value(items.clone())
+        .map_err(|e| format!("Failed to parse items: {}", e))?;
+
+    Ok(results)
+}
+
 pub async fn google_searching(query: String) -> Result<Vec<GoogleSearchItem>, String> {
     let token = std::env::var("GOOGLE_SEARCH_TOKEN")
         .map_err(|_| "Missing GOOGLE_SEARCH_TOKEN environment variable".to_string())?;
     let cx = std::env::var("GOOGLE_SEARCH_CX")
         .map_err(|_| "Missing GOOGLE_SEARCH_CX environment variable".to_string())?;
 
-    let request_url = format!(
-        "https://www.googleapis.com/customsearch/v1?key={}&cx={}&q={}",
-        token, cx, query
-    );
-
-    let response = reqwest::Client::new()
-        .get(&request_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to perform search: {}", e))?;
-
-    let response_txt = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read response text: {}", e))?;
-
-    let json: serde_json::Value = serde_json::from_str(&response_txt)
-        .map_err(|e| format!("Failed to parse JSON response: {}", e))?;
-
-    let items = json.get("items")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| "No items found in the response".to_string())?;
-
-    let results: Vec<GoogleSearchItem> = serde_json::from_value(items.clone().into())
-        .map_err(|e| format!("Failed to parse items: {}", e))?;
-
-    Ok(results)
+    google_search_items(&GOOGLE_SEARCH_URL, &token, &cx, &query, 10).await
 }
@@ -0,0 +1,384 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+// 내용을 통째로 버리는 태그 (안쪽을 HTML 로 읽지 않습니다)
+const RAW_TEXT_TAGS: [&str; 4] = ["script", "style", "template", "textarea"];
+// 본문이 아닌 탐색/장식 영역
+const BOILERPLATE_TAGS: [&str; 10] = ["nav", "header", "footer", "aside", "form", "noscript", "svg", "iframe", "button", "select"];
+const BLOCK_TAGS: [&str; 22] = [
+    "p", "div", "section", "article", "main", "ul", "ol", "table", "tr", "blockquote", "pre", "dl", "dt", "dd",
+    "figure", "figcaption", "address", "details", "summary", "hr", "br", "tbody",
+];
+// main/article 안의 글이 이보다 짧으면 잘못 잡은 것으로 보고 body 전체를 씁니다.
+const MIN_MAIN_CHARS: usize = 200;
+const MAX_LINKS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebLink {
+    pub text: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtractedHtml {
+    pub title: Option<String>,
+    pub text: String,
+    pub links: Vec<WebLink>,
+}
+
+#[derive(Default)]
+struct Extractor<'a> {
+    base_url: Option<&'a Url>,
+    body: String,
+    main: String,
+    main_depth: usize,
+    skip_depth: usize,
+    pre_depth: usize,
+    links: Vec<WebLink>,
+    // 열려 있는 <a> 의 주소와 글자
+    anchor: Option<(String, String)>,
+}
+
+impl Extractor<'_> {
+    fn push_raw(&mut self, text: &str) {
+        self.body.push_str(text);
+        if self.main_depth > 0 {
+            self.main.push_str(text);
+        }
+        if let Some((_, anchor_text)) = self.anchor.as_mut() {
+            anchor_text.push_str(text);
+        }
+    }
+
+    fn push_text(&mut self, raw: &str) {
+        if self.skip_depth > 0 || raw.is_empty() {
+            return;
+        }
+        let decoded = decode_entities(raw);
+        if self.pre_depth > 0 {
+            self.push_raw(&decoded);
+            return;
+        }
+        let mut collapsed = String::with_capacity(decoded.len());
+        let mut last_space = self.body.is_empty() || self.body.ends_with([' ', '\n']);
+        for c in decoded.chars() {
+            if c.is_whitespace() {
+                if !last_space {
+                    collapsed.push(' ');
+                    last_space = true;
+                }
+            } else {
+                collapsed.push(c);
+                last_space = false;
+            }
+        }
+        self.push_raw(&collapsed);
+    }
+
+    fn push_break(&mut self, prefix: &str) {
+        if self.skip_depth > 0 {
+            return;
+        }
+        let buffers = if self.main_depth > 0 { vec![&mut self.body, &mut self.main] } else { vec![&mut self.body] };
+        for buffer in buffers {
+            let trimmed = buffer.trim_end_matches(' ').len();
+            buffer.truncate(trimmed);
+            // 블록이 연달아 닫히고 열려도 줄바꿈은 하나만 넣습니다.
+            if !buffer.is_empty() && !buffer.ends_with('\n') {
+                buffer.push('\n');
+            }
+            buffer.push_str(prefix);
+        }
+    }
+
+    fn open_anchor(&mut self, attrs: &str) {
+        self.close_anchor();
+        let Some(href) = attr_value(attrs, "href") else {
+            return;
+        };
+        let resolved = match self.base_url {
+            Some(base) => base.join(&href),
+            None => Url::parse(&href),
+        };
+        if let Ok(mut url) = resolved {
+            if url.scheme() == "http" || url.scheme() == "https" {
+                url.set_fragment(None);
+                self.anchor = Some((url.to_string(), String::new()));
+            }
+        }
+    }
+
+    fn close_anchor(&mut self) {
+        let Some((url, text)) = self.anchor.take() else {
+            return;
+        };
+        if self.skip_depth > 0 || self.links.len() >= MAX_LINKS || self.links.iter().any(|l| l.url == url) {
+            return;
+        }
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        self.links.push(WebLink { text, url });
+    }
+
+    fn handle_tag(&mut self, name: &str, closing: bool, self_closing: bool, attrs: &str) {
+        if BOILERPLATE_TAGS.contains(&name) {
+            if closing {
+                self.skip_depth = self.skip_depth.saturating_sub(1);
+            } else if !self_closing {
+                self.skip_depth += 1;
+            }
+            return;
+        }
+        match name {
+            "main" | "article" if closing => self.main_depth = self.main_depth.saturating_sub(1),
+            "main" | "article" => self.main_depth += 1,
+            "pre" if closing => self.pre_depth = self.pre_depth.saturating_sub(1),
+            "pre" => self.pre_depth += 1,
+            "a" if closing => self.close_anchor(),
+            "a" => self.open_anchor(attrs),
+            _ => {}
+        }
+        let heading_level = match name.as_bytes() {
+            [b'h', level @ b'1'..=b'6'] => Some((level - b'0') as usize),
+            _ => None,
+        };
+        if let Some(level) = heading_level {
+            if closing {
+                self.push_break("");
+            } else {
+                self.push_break(&format!("{} ", "#".repeat(level)));
+            }
+        } else if name == "li" && !closing {
+            self.push_break("- ");
+        } else if name == "td" || name == "th" {
+            if !closing {
+                self.push_text(" | ");
+            }
+        } else if BLOCK_TAGS.contains(&name) || name == "li" {
+            self.push_break("");
+        }
+    }
+}
+
+// 따옴표 밖의 첫 '>' 위치
+fn find_tag_end(tag: &str) -> Option<usize> {
+    let mut quote: Option<u8> = None;
+    for (i, b) in tag.bytes().enumerate().skip(1) {
+        match (quote, b) {
+            (Some(q), _) if q == b => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// 대소문자 구분 없이 </name 이 시작하는 위치
+fn find_close_tag(text: &str, name: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut from = 0;
+    while let Some(pos) = text[from..].find("</") {
+        let start = from + pos + 2;
+        let end = start + name.len();
+        if end <= bytes.len() && bytes[start..end].eq_ignore_ascii_case(name.as_bytes()) {
+            return Some(from + pos);
+        }
+        from = start;
+    }
+    None
+}
+
+// 태그 속성 문자열에서 값을 찾습니다. 따옴표 없는 값도 받습니다.
+pub fn attr_value(attrs: &str, name: &str) -> Option<String> {
+    let bytes = attrs.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            i += 1;
+        }
+        let name_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'=' | b'>' | b'/') {
+            i += 1;
+        }
+        let attr_name = &attrs[name_start..i];
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = None;
+        if i < bytes.len() && bytes[i] == b'=' {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i < bytes.len() && matches!(bytes[i], b'"' | b'\'') {
+                let quote = bytes[i];
+                let value_start = i + 1;
+                i = value_start;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += 1;
+                }
+                value = Some(&attrs[value_start..i]);
+                i += 1;
+            } else {
+                let value_start = i;
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                value = Some(&attrs[value_start..i]);
+            }
+        }
+        if attr_name.eq_ignore_ascii_case(name) {
+            return value.map(|v| decode_entities(v.trim()));
+        }
+        if attr_name.is_empty() && value.is_none() {
+            i += 1;
+        }
+    }
+    None
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse::<u32>().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "copy" => '©',
+        "reg" => '®',
+        "middot" => '·',
+        "hellip" => '…',
+        "ndash" => '–',
+        "mdash" => '—',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        _ => return None,
+    })
+}
+
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let end = rest[1..].find(|c: char| c == ';' || c == '&' || c.is_whitespace()).map(|i| i + 1);
+        if let Some(end) = end.filter(|end| rest.as_bytes()[*end] == b';') {
+            if let Some(c) = decode_entity(&rest[1..end]) {
+                result.push(c);
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        result.push('&');
+        rest = &rest[1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+// 줄 끝 공백을 지우고 빈 줄은 하나만 남깁니다.
+fn tidy_lines(text: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+// HTML 에서 제목, 읽을 수 있는 본문, 링크를 뽑습니다.
+// 스크립트/스타일과 nav, header, footer 같은 주변 영역은 버리고, main/article 이 충분히 길면 그 안만 씁니다.
+pub fn extract_readable(html: &str, base_url: Option<&Url>) -> ExtractedHtml {
+    let mut extractor = Extractor { base_url, ..Default::default() };
+    let mut title = None;
+    let mut rest = html;
+    while let Some(lt) = rest.find('<') {
+        extractor.push_text(&rest[..lt]);
+        rest = &rest[lt..];
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|end| &rest[end + 3..]).unwrap_or("");
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map(|end| &rest[end + 1..]).unwrap_or("");
+            continue;
+        }
+        let closing = rest[1..].starts_with('/');
+        let name_start = if closing { 2 } else { 1 };
+        let name_len = rest[name_start..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len() - name_start);
+        if name_len == 0 {
+            extractor.push_text("<");
+            rest = &rest[1..];
+            continue;
+        }
+        let Some(tag_end) = find_tag_end(rest) else {
+            break;
+        };
+        let name = rest[name_start..name_start + name_len].to_ascii_lowercase();
+        let attrs = &rest[name_start + name_len..tag_end];
+        let self_closing = attrs.trim_end().ends_with('/');
+        rest = &rest[tag_end + 1..];
+
+        if !closing && (name == "title" || RAW_TEXT_TAGS.contains(&name.as_str())) {
+            let end = find_close_tag(rest, &name).unwrap_or(rest.len());
+            if name == "title" && title.is_none() {
+                let text = decode_entities(&rest[..end]).split_whitespace().collect::<Vec<_>>().join(" ");
+                title = Some(text).filter(|t| !t.is_empty());
+            }
+            rest = &rest[end..];
+            rest = rest.find('>').map(|gt| &rest[gt + 1..]).unwrap_or("");
+            continue;
+        }
+        extractor.handle_tag(&name, closing, self_closing, attrs);
+    }
+    extractor.push_text(rest);
+    extractor.close_anchor();
+
+    let main = tidy_lines(&extractor.main);
+    let text = if main.chars().count() >= MIN_MAIN_CHARS { main } else { tidy_lines(&extractor.body) };
+    ExtractedHtml { title, text, links: extractor.links }
+}
+
+// 긴 글을 page_chars 글자 안팎으로 나눕니다. 가능하면 줄바꿈에서 자릅니다.
+pub fn split_pages(text: &str, page_chars: usize) -> Vec<&str> {
+    let page_chars = page_chars.max(1);
+    let mut pages = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let Some((limit, _)) = rest.char_indices().nth(page_chars) else {
+            pages.push(rest);
+            break;
+        };
+        let cut = match rest[..limit].rfind('\n') {
+            Some(pos) if pos >= limit / 2 => pos + 1,
+            _ => limit,
+        };
+        pages.push(rest[..cut].trim_end());
+        rest = rest[cut..].trim_start();
+    }
+    pages
+}
@@ -2,4 +2,6 @@ pub mod handlers;
 pub mod schedule;
 pub mod google_searching;
 pub mod get_web_result;
-pub mod instances;
\ No newline at end of file
+pub mod instances;
+pub mod search_provider;
+pub mod html_extract;
\ No newline at end of file
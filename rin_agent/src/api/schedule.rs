@@ -8,6 +8,7 @@ use serenity::all::{ChannelId, UserId};
 use sqlx::types::{chrono::Local, time};
 use std::sync::{Arc};
 use tokio::sync::{watch::Sender, Mutex};
+use crate::libs::shutdown::SHUTDOWN;
 use crate::{libs::{thread_message::GeminiFunctionAlarm, thread_pipelines::SCHEDULE_TO_DISCORD_PIPELINE}, model::db::driver::DB_CONNECTION_POOL, service::discord_error_msg::{send_additional_log, send_debug_error_log}};
 
 
@@ -49,7 +50,6 @@ pub fn make_alarm_schedule(
 
 pub struct ScheduleService {
     alarm_target_model: Option<Vec<tb_alarm_model::Model>>,
-    alarm_thread: Option<tokio::task::JoinHandle<()>>,
     alarm_pipe_sender: Sender<GeminiFunctionAlarm<Option<tb_alarm_model::Model>>>,
 }
 
@@ -64,9 +64,7 @@ impl RSContextService for ScheduleService {
     }
 
     async fn on_all_services_built(&self, context: &rs_ervice::RSContext) -> Result<(), rs_ervice::RsServiceError> {
-        ScheduleService::start_alarm_thread(
-            context.call::<ScheduleService>().unwrap()
-        );
+        // 알람 루프는 main 의 감시자가 run_alarm_loop 로 띄웁니다.
         Ok(())
     }
 }
@@ -75,66 +73,29 @@ impl ScheduleService {
     pub fn new() -> Self {
         Self {
             alarm_target_model:None,
-            alarm_thread: None,
             alarm_pipe_sender: SCHEDULE_TO_DISCORD_PIPELINE.sender.clone(),
         }
     }
 
-    pub fn start_alarm_thread(this: Arc<Mutex<Self>>) {
-        let cloned_this = this.clone();
-        let handle = tokio::spawn(async move {
-
-            let db = DB_CONNECTION_POOL.get();
-            if db.is_none() {
-                send_debug_error_log("DB connection pool is not initialized".to_string()).await;
-                return;
-            }
-            let db = db.unwrap();
-            let last_cache = tb_alarm_model::Entity::find()
-                .order_by_asc(tb_alarm_model::Column::Time)
-                .filter(Condition::all()
-                    .add(Expr::col(tb_alarm_model::Column::Time)
-                        .gt(Local::now().to_utc())
-                    )
-                ) // 0은 모든 유저를 의미한다.
-                .one(db)
-                .await;            
-            LOGGER.log(
-                gemini_live_api::libs::logger::LogLevel::Debug,
-                &format!("Last alarm cache fetched: {:?}", last_cache),
-            );
-
-            if last_cache.is_ok() && last_cache.as_ref().unwrap().is_some() {
-                let last_cache = last_cache.unwrap().unwrap();
-                LOGGER.log(
-                    gemini_live_api::libs::logger::LogLevel::Debug,
-                    &format!("Last alarm cache: {:?}", last_cache),
-                );
-                this.lock().await.alarm_target_model = Some(vec![last_cache]);
-            } else {
-                LOGGER.log(
-                    gemini_live_api::libs::logger::LogLevel::Debug,
-                    "No alarms found in the database.",
-                );
-            }
-
-            loop {
+    // 1초마다 다음 알람을 확인합니다. 감시자가 재시작할 때마다 캐시를 DB 에서 다시 채웁니다.
+    // 종료 신호를 받으면 루프를 빠져나옵니다.
+    pub async fn run_alarm_loop(this: Arc<Mutex<Self>>) -> Result<(), String> {
+        this.lock().await.reload_next_alarm().await?;
+        while !SHUTDOWN.is_cancelled() {
+            {
                 let mut guard = this.lock().await;
                 guard.simulate_schedule().await;
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             }
-        });
-
-        tokio::spawn(async move {
-            let mut this = cloned_this.lock().await;
-            this.alarm_thread = Some(handle);
-        });
-
-        
+            tokio::select! {
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(1)) => {}
+                _ = SHUTDOWN.cancelled() => {}
+            }
+        }
+        Ok(())
     }
 
 
-    pub async fn add_schedule(&mut self, schedule: ScheduleRequest) {
+    pub async fn add_schedule(&mut self, schedule: ScheduleRequest) -> Option<tb_alarm_model::Model> {
         let db = DB_CONNECTION_POOL.get();
         let conn = db.unwrap();
 
@@ -163,7 +124,7 @@ impl ScheduleService {
 
         if let Err(e) = insert_result {
             send_debug_error_log(format!("Failed to insert schedule: {}", e)).await;
-            return;
+            return None;
         }
         let inserted_schedule = insert_result.unwrap();
         // 이하는 캐싱 교체 !
@@ -197,6 +158,35 @@ impl ScheduleService {
             );
             self.alarm_target_model = Some(vec![inserted_schedule.clone()]);
         }
+        Some(inserted_schedule)
+    }
+
+    // 알람이 바깥(웹 API 등)에서 수정/삭제되었을 때 다음 알람 캐시를 DB 기준으로 다시 채운다.
+    pub async fn reload_next_alarm(&mut self) -> Result<(), String> {
+        let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+        let now = Local::now().to_utc();
+        let next = tb_alarm_model::Entity::find()
+            .order_by_asc(tb_alarm_model::Column::Time)
+            .filter(Expr::col(tb_alarm_model::Column::Time).gt(now))
+            .one(db)
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(next) = next else {
+            self.alarm_target_model = None;
+            return Ok(());
+        };
+        // 같은 시간에 울릴 알람은 함께 묶는다.
+        let same_time = tb_alarm_model::Entity::find()
+            .filter(Expr::col(tb_alarm_model::Column::Time).eq(next.time))
+            .all(db)
+            .await
+            .map_err(|e| e.to_string())?;
+        LOGGER.log(
+            gemini_live_api::libs::logger::LogLevel::Debug,
+            &format!("알람 캐시를 다시 불러왔습니다: {:?}", same_time),
+        );
+        self.alarm_target_model = Some(same_time);
+        Ok(())
     }
 
     async fn simulate_schedule(&mut self){
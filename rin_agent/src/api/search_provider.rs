@@ -0,0 +1,383 @@
+use std::{env, sync::LazyLock};
+
+use entity::tb_discord_guilds;
+use redis::Commands;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serenity::async_trait;
+
+use crate::api::google_searching::google_search_items;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::cache::redis_client::REDIS_DRIVER;
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::setting::search_setting::{DEFAULT_SEARCH_PROVIDER, SEARCH_CACHE_TTL_SECS, SEARCH_RESULT_COUNT};
+
+// 테스트나 프록시에서 바꿀 수 있도록 기본 주소도 환경변수로 덮어쓸 수 있습니다.
+pub static GOOGLE_SEARCH_URL: LazyLock<String> = LazyLock::new(|| {
+    env::var("GOOGLE_SEARCH_URL").unwrap_or("https://www.googleapis.com".to_string())
+});
+pub static BRAVE_SEARCH_URL: LazyLock<String> = LazyLock::new(|| {
+    env::var("BRAVE_SEARCH_URL").unwrap_or("https://api.search.brave.com".to_string())
+});
+pub static BING_SEARCH_URL: LazyLock<String> = LazyLock::new(|| {
+    env::var("BING_SEARCH_URL").unwrap_or("https://api.bing.microsoft.com".to_string())
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchProviderKind {
+    Google,
+    Searxng,
+    Brave,
+    Bing,
+}
+
+impl SearchProviderKind {
+    pub const ALL: [SearchProviderKind; 4] = [
+        SearchProviderKind::Google,
+        SearchProviderKind::Searxng,
+        SearchProviderKind::Brave,
+        SearchProviderKind::Bing,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "google" => Some(SearchProviderKind::Google),
+            "searxng" | "searx" => Some(SearchProviderKind::Searxng),
+            "brave" => Some(SearchProviderKind::Brave),
+            "bing" => Some(SearchProviderKind::Bing),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchProviderKind::Google => "google",
+            SearchProviderKind::Searxng => "searxng",
+            SearchProviderKind::Brave => "brave",
+            SearchProviderKind::Bing => "bing",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SearchProviderKind::Google => "Google",
+            SearchProviderKind::Searxng => "SearxNG",
+            SearchProviderKind::Brave => "Brave",
+            SearchProviderKind::Bing => "Bing",
+        }
+    }
+}
+
+// 제공자와 상관없이 같은 모양으로 맞춘 검색 결과
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub link: String,
+    pub snippet: String,
+}
+
+#[async_trait]
+pub trait SearchProvider: Send + Sync {
+    fn kind(&self) -> SearchProviderKind;
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, String>;
+}
+
+// 상태 코드를 확인하고 본문을 JSON 으로 읽습니다. 오류 본문은 앞부분만 남깁니다.
+pub async fn get_search_json(request: reqwest::RequestBuilder) -> Result<Value, String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to perform search: {}", e))?;
+    let status = response.status();
+    let response_txt = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read response text: {}", e))?;
+    if !status.is_success() {
+        let body: String = response_txt.chars().take(200).collect();
+        return Err(format!("Search request failed ({}): {}", status.as_u16(), body));
+    }
+    serde_json::from_str(&response_txt).map_err(|e| format!("Failed to parse JSON response: {}", e))
+}
+
+fn search_url(base_url: &str, path: &str, params: &[(&str, &str)]) -> Result<reqwest::Url, String> {
+    reqwest::Url::parse_with_params(&format!("{}{}", base_url.trim_end_matches('/'), path), params)
+        .map_err(|e| format!("Invalid search url: {}", e))
+}
+
+// Brave, Bing 요약문에 섞여 오는 <strong> 같은 강조 태그를 지웁니다.
+pub fn strip_html_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+// 배열의 각 항목에서 제목/링크/요약 키를 골라 SearchResult 로 바꿉니다. 링크가 없는 항목은 버립니다.
+fn collect_results(items: Option<&Value>, title_key: &str, link_key: &str, snippet_key: &str, count: usize) -> Vec<SearchResult> {
+    let text = |item: &Value, key: &str| item.get(key).and_then(|v| v.as_str()).map(strip_html_tags).unwrap_or_default();
+    items
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let link = text(item, link_key);
+                    if link.is_empty() {
+                        return None;
+                    }
+                    Some(SearchResult { title: text(item, title_key), link, snippet: text(item, snippet_key) })
+                })
+                .take(count)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn parse_searxng_results(json: &Value, count: usize) -> Vec<SearchResult> {
+    collect_results(json.get("results"), "title", "url", "content", count)
+}
+
+pub fn parse_brave_results(json: &Value, count: usize) -> Vec<SearchResult> {
+    collect_results(json.pointer("/web/results"), "title", "url", "description", count)
+}
+
+pub fn parse_bing_results(json: &Value, count: usize) -> Vec<SearchResult> {
+    collect_results(json.pointer("/webPages/value"), "name", "url", "snippet", count)
+}
+
+pub struct GoogleCseProvider {
+    pub base_url: String,
+    pub token: String,
+    pub cx: String,
+}
+
+impl GoogleCseProvider {
+    pub fn from_env() -> Result<Self, String> {
+        Ok(GoogleCseProvider {
+            base_url: GOOGLE_SEARCH_URL.clone(),
+            token: env::var("GOOGLE_SEARCH_TOKEN").map_err(|_| "Missing GOOGLE_SEARCH_TOKEN environment variable".to_string())?,
+            cx: env::var("GOOGLE_SEARCH_CX").map_err(|_| "Missing GOOGLE_SEARCH_CX environment variable".to_string())?,
+        })
+    }
+}
+
+#[async_trait]
+impl SearchProvider for GoogleCseProvider {
+    fn kind(&self) -> SearchProviderKind {
+        SearchProviderKind::Google
+    }
+
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, String> {
+        let items = google_search_items(&self.base_url, &self.token, &self.cx, query, count).await?;
+        Ok(items
+            .into_iter()
+            .filter_map(|item| {
+                Some(SearchResult {
+                    title: item.title.unwrap_or_default(),
+                    link: item.link?,
+                    snippet: item.snippet.unwrap_or_default(),
+                })
+            })
+            .take(count)
+            .collect())
+    }
+}
+
+// 직접 띄운 SearxNG 인스턴스. settings.yml 의 search.formats 에 json 이 켜져 있어야 합니다.
+pub struct SearxngProvider {
+    pub base_url: String,
+}
+
+impl SearxngProvider {
+    pub fn from_env() -> Result<Self, String> {
+        let base_url = env::var("SEARXNG_URL").unwrap_or_default();
+        if base_url.trim().is_empty() {
+            return Err("Missing SEARXNG_URL environment variable".to_string());
+        }
+        Ok(SearxngProvider { base_url })
+    }
+}
+
+#[async_trait]
+impl SearchProvider for SearxngProvider {
+    fn kind(&self) -> SearchProviderKind {
+        SearchProviderKind::Searxng
+    }
+
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, String> {
+        let url = search_url(&self.base_url, "/search", &[("q", query), ("format", "json")])?;
+        let json = get_search_json(reqwest::Client::new().get(url)).await?;
+        Ok(parse_searxng_results(&json, count))
+    }
+}
+
+pub struct BraveProvider {
+    pub base_url: String,
+    pub token: String,
+}
+
+impl BraveProvider {
+    pub fn from_env() -> Result<Self, String> {
+        Ok(BraveProvider {
+            base_url: BRAVE_SEARCH_URL.clone(),
+            token: env::var("BRAVE_SEARCH_TOKEN").map_err(|_| "Missing BRAVE_SEARCH_TOKEN environment variable".to_string())?,
+        })
+    }
+}
+
+#[async_trait]
+impl SearchProvider for BraveProvider {
+    fn kind(&self) -> SearchProviderKind {
+        SearchProviderKind::Brave
+    }
+
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, String> {
+        let url = search_url(&self.base_url, "/res/v1/web/search", &[("q", query), ("count", &count.to_string())])?;
+        let request = reqwest::Client::new()
+            .get(url)
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", &self.token);
+        let json = get_search_json(request).await?;
+        Ok(parse_brave_results(&json, count))
+    }
+}
+
+pub struct BingProvider {
+    pub base_url: String,
+    pub key: String,
+}
+
+impl BingProvider {
+    pub fn from_env() -> Result<Self, String> {
+        Ok(BingProvider {
+            base_url: BING_SEARCH_URL.clone(),
+            key: env::var("BING_SEARCH_KEY").map_err(|_| "Missing BING_SEARCH_KEY environment variable".to_string())?,
+        })
+    }
+}
+
+#[async_trait]
+impl SearchProvider for BingProvider {
+    fn kind(&self) -> SearchProviderKind {
+        SearchProviderKind::Bing
+    }
+
+    async fn search(&self, query: &str, count: usize) -> Result<Vec<SearchResult>, String> {
+        let url = search_url(&self.base_url, "/v7.0/search", &[("q", query), ("count", &count.to_string())])?;
+        let request = reqwest::Client::new().get(url).header("Ocp-Apim-Subscription-Key", &self.key);
+        let json = get_search_json(request).await?;
+        Ok(parse_bing_results(&json, count))
+    }
+}
+
+pub fn provider_from_env(kind: SearchProviderKind) -> Result<Box<dyn SearchProvider>, String> {
+    Ok(match kind {
+        SearchProviderKind::Google => Box::new(GoogleCseProvider::from_env()?),
+        SearchProviderKind::Searxng => Box::new(SearxngProvider::from_env()?),
+        SearchProviderKind::Brave => Box::new(BraveProvider::from_env()?),
+        SearchProviderKind::Bing => Box::new(BingProvider::from_env()?),
+    })
+}
+
+// 대소문자와 공백만 다른 검색어는 같은 캐시를 씁니다.
+pub fn search_cache_key(kind: SearchProviderKind, query: &str, count: usize) -> String {
+    let normalized = query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    format!("search:{}:{}:{}", kind.as_str(), count, normalized)
+}
+
+async fn read_cached_results(key: String) -> Option<Vec<SearchResult>> {
+    let cached = tokio::task::spawn_blocking(move || -> Result<Option<String>, String> {
+        let Some(driver) = REDIS_DRIVER.as_ref() else {
+            return Ok(None);
+        };
+        let mut conn = driver.get_pool().get().map_err(|e| e.to_string())?;
+        conn.get::<_, Option<String>>(&key).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    match cached {
+        Ok(cached) => cached.and_then(|raw| serde_json::from_str(&raw).ok()),
+        Err(e) => {
+            LOGGER.log(LogLevel::Warning, &format!("Search > 캐시를 읽지 못했습니다: {}", e));
+            None
+        }
+    }
+}
+
+async fn write_cached_results(key: String, results: &[SearchResult], ttl_secs: u64) {
+    let Ok(raw) = serde_json::to_string(results) else {
+        return;
+    };
+    let written = tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let Some(driver) = REDIS_DRIVER.as_ref() else {
+            return Ok(());
+        };
+        let mut conn = driver.get_pool().get().map_err(|e| e.to_string())?;
+        conn.set_ex::<_, _, ()>(&key, raw, ttl_secs).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    if let Err(e) = written {
+        LOGGER.log(LogLevel::Warning, &format!("Search > 캐시를 저장하지 못했습니다: {}", e));
+    }
+}
+
+// Redis 를 쓸 수 없으면 캐시 없이 바로 검색합니다. 빈 결과는 캐시하지 않습니다.
+pub async fn search_with_cache(provider: &dyn SearchProvider, query: &str, count: usize) -> Result<Vec<SearchResult>, String> {
+    let ttl_secs = *SEARCH_CACHE_TTL_SECS;
+    let key = search_cache_key(provider.kind(), query, count);
+    if ttl_secs > 0 {
+        if let Some(cached) = read_cached_results(key.clone()).await {
+            return Ok(cached);
+        }
+    }
+    let results = provider.search(query, count).await?;
+    if ttl_secs > 0 && !results.is_empty() {
+        write_cached_results(key, &results, ttl_secs).await;
+    }
+    Ok(results)
+}
+
+pub async fn load_guild_search_provider(guild_id: u64) -> SearchProviderKind {
+    let Some(db) = DB_CONNECTION_POOL.get() else {
+        return *DEFAULT_SEARCH_PROVIDER;
+    };
+    match tb_discord_guilds::Entity::find()
+        .filter(tb_discord_guilds::Column::GuildId.eq(guild_id as i64))
+        .one(db)
+        .await
+    {
+        Ok(Some(guild)) => guild
+            .search_provider
+            .as_deref()
+            .and_then(SearchProviderKind::parse)
+            .unwrap_or(*DEFAULT_SEARCH_PROVIDER),
+        Ok(None) => *DEFAULT_SEARCH_PROVIDER,
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("Search > 길드 설정 조회 실패: {}", e));
+            *DEFAULT_SEARCH_PROVIDER
+        }
+    }
+}
+
+// 길드 설정에 맞는 제공자로 검색합니다. 길드 밖(DM 등)에서는 기본 제공자를 씁니다.
+pub async fn search_for_guild(guild_id: Option<u64>, query: &str) -> Result<(SearchProviderKind, Vec<SearchResult>), String> {
+    let kind = match guild_id {
+        Some(guild_id) => load_guild_search_provider(guild_id).await,
+        None => *DEFAULT_SEARCH_PROVIDER,
+    };
+    let provider = provider_from_env(kind)?;
+    let results = search_with_cache(provider.as_ref(), query, SEARCH_RESULT_COUNT).await?;
+    Ok((kind, results))
+}
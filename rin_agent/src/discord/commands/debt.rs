@@ -0,0 +1,164 @@
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::discord::utils::GuildCommandResponse;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::service::debt_service::{
+    clear_debtor, clear_receipt, format_amount, get_debt_history, get_debt_summary, record_debt, DebtEntry,
+    MAX_DEBTOR_NAME_CHARS, MAX_MEMO_CHARS,
+};
+
+const MAX_HISTORY_ROWS: u64 = 15;
+
+// 빚 기록은 본인만 보도록 숨김 메시지로 보냅니다.
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content).ephemeral(true)),
+        do_not_send: false,
+    }
+}
+
+fn format_entry(entry: &DebtEntry) -> String {
+    let date = entry.created_at.get(..10).unwrap_or(&entry.created_at);
+    let memo = entry.memo.as_deref().map(|m| format!(" · {}", m)).unwrap_or_default();
+    let cleared = if entry.is_cleared { " · ✅ 정산" } else { "" };
+    format!("`#{}` {} · **{}** {}{}{}", entry.id, date, entry.debtor_name, format_amount(entry.amount), memo, cleared)
+}
+
+// 양수는 받을 돈, 음수는 갚을 돈
+fn describe_balance(name: &str, amount: i64) -> String {
+    match amount {
+        0 => format!("{} 님과는 정산할 금액이 없습니다.", name),
+        a if a > 0 => format!("{} 님에게 받을 돈 {}", name, format_amount(a)),
+        a => format!("{} 님에게 갚을 돈 {}", name, format_amount(-a)),
+    }
+}
+
+async fn run_sub_command(owner_id: u64, name: &str, options: &[ResolvedOption<'_>]) -> Result<String, String> {
+    let find_str = |key: &str| options.iter().find(|o| o.name == key).and_then(|o| match o.value {
+        ResolvedValue::String(s) => Some(s),
+        _ => None,
+    });
+    let find_int = |key: &str| options.iter().find(|o| o.name == key).and_then(|o| match o.value {
+        ResolvedValue::Integer(i) => Some(i),
+        _ => None,
+    });
+
+    match name {
+        "add" => {
+            let (Some(person), Some(amount)) = (find_str("name"), find_int("amount")) else {
+                return Err("이름과 금액을 입력하세요.".to_string());
+            };
+            let entry = record_debt(owner_id, person, amount, find_str("memo")).await?;
+            Ok(format!("기록했습니다.\n{}", format_entry(&entry)))
+        }
+        "clear" => {
+            if let Some(receipt_id) = find_int("receipt_id") {
+                let entry = clear_receipt(owner_id, receipt_id).await?;
+                return Ok(format!("정산했습니다.\n{}", format_entry(&entry)));
+            }
+            let Some(person) = find_str("name") else {
+                return Err("정산할 영수증 번호나 이름을 입력하세요.".to_string());
+            };
+            let (debtor, count, amount) = clear_debtor(owner_id, person).await?;
+            if count == 0 {
+                return Ok(format!("{} 님에게 남은 기록이 없습니다.", debtor.name));
+            }
+            Ok(format!("{} 님의 영수증 {}건 ({}) 을 정산했습니다.", debtor.name, count, format_amount(amount)))
+        }
+        "balance" => {
+            let summary = get_debt_summary(owner_id).await?;
+            let open = summary.balances.iter().filter(|b| b.outstanding != 0).collect::<Vec<_>>();
+            if open.is_empty() {
+                return Ok("정산할 금액이 없습니다.".to_string());
+            }
+            let mut lines = vec!["**사람별 잔액**".to_string()];
+            lines.extend(open.iter().map(|b| format!("- {} ({}건)", describe_balance(&b.name, b.outstanding), b.open_count)));
+            lines.push(format!(
+                "받을 돈 합계 {} · 갚을 돈 합계 {}",
+                format_amount(summary.total_receivable),
+                format_amount(summary.total_payable)
+            ));
+            Ok(lines.join("\n"))
+        }
+        "history" => {
+            let person = find_str("name");
+            let include_cleared = options
+                .iter()
+                .find(|o| o.name == "include_cleared")
+                .is_some_and(|o| matches!(o.value, ResolvedValue::Boolean(true)));
+            let entries = get_debt_history(owner_id, person, include_cleared, MAX_HISTORY_ROWS).await?;
+            if entries.is_empty() {
+                return Ok("기록이 없습니다.".to_string());
+            }
+            let mut lines = vec![match person {
+                Some(person) => format!("**{} 님 기록** (최근 {}건)", person.trim(), entries.len()),
+                None => format!("**최근 기록** ({}건)", entries.len()),
+            }];
+            lines.extend(entries.iter().map(format_entry));
+            Ok(lines.join("\n"))
+        }
+        _ => Err("알 수 없는 하위 명령입니다.".to_string()),
+    }
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let Some(sub_command) = _options.data.options().into_iter().next() else {
+        return Ok(make_response("알 수 없는 하위 명령입니다.".to_string()));
+    };
+    let sub_options: &[ResolvedOption] = match &sub_command.value {
+        ResolvedValue::SubCommand(options) => options,
+        _ => &[],
+    };
+
+    match run_sub_command(_options.user.id.get(), sub_command.name, sub_options).await {
+        Ok(content) => Ok(make_response(content)),
+        Err(e) => {
+            LOGGER.log(LogLevel::Debug, &format!("빚 장부 명령 실패: {}", e));
+            Ok(make_response(e))
+        }
+    }
+}
+
+fn name_option(required: bool) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::String, "name", "상대 이름")
+        .max_length(MAX_DEBTOR_NAME_CHARS as u16)
+        .required(required)
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("debt")
+        .description("누구에게 얼마를 받고 갚아야 하는지 기록합니다.")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "add", "빌려준 돈(양수)이나 빌린 돈(음수)을 기록합니다.")
+                .add_sub_option(name_option(true))
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "amount", "금액(원). 내가 빌렸으면 음수")
+                        .required(true),
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::String, "memo", "메모")
+                        .max_length(MAX_MEMO_CHARS as u16)
+                        .required(false),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "clear", "영수증 하나나 한 사람의 기록을 모두 정산합니다.")
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "receipt_id", "정산할 영수증 번호")
+                        .min_int_value(1)
+                        .required(false),
+                )
+                .add_sub_option(name_option(false)),
+        )
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "balance", "사람별로 남은 금액을 봅니다."))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "history", "최근 기록을 봅니다.")
+                .add_sub_option(name_option(false))
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Boolean, "include_cleared", "정산한 기록도 보기")
+                        .required(false),
+                ),
+        )
+}
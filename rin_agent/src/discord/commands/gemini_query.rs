@@ -18,12 +18,17 @@ use chrono::{DateTime as ChronoDateTime, Utc, Duration as ChronoDuration};
 use crate::discord::constant::DISCORD_DB_ERROR;
 use crate::discord::utils::GuildCommandResponse;
 use crate::gemini::gemini_client::{self, GeminiCacheInfo, GeminiClientTrait};
-use crate::gemini::types::{DiscordUserInfo, GeminiChatChunk, GeminiImageInputType, GeminiResponse};
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::builtin_tools::{format_code_execution, outcome_label, render_grounded_text};
+use crate::gemini::model_router::{load_guild_policy, route_query, GuildModelPolicy, ModelRoute};
+use crate::gemini::types::{DiscordUserInfo, GeminiChatChunk, GeminiImageInputType, GeminiResponse, GeminiUsageContext};
 use crate::gemini::utils::upload_image_to_gemini;
 use crate::libs::logger::{LOGGER, LogLevel};
 use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::quota_service::{can_use_pro, check_quota, clamp_thinking_budget, resolve_user_limits, QuotaSubject};
+use crate::setting::quota_setting::QUOTA_CONFIG;
 use crate::utils::split_text::split_text_by_length_and_markdown;
-use crate::setting::gemini_setting::{get_begin_query, GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO};
+use crate::setting::gemini_setting::get_begin_query;
 
 use entity::tb_ai_context::{self, ActiveModel as AiContextModel};
 use entity::tb_ai_context::Entity as AiContextEntity;
@@ -56,6 +61,20 @@ fn user_mention(user: &User) -> String {
     format!("<@{}>\n", user.id.get())
 }
 
+// 실패 이유를 사용자에게 설명하는 메시지. 일시적인 오류는 주황색, 그 외는 빨간색입니다.
+fn make_gemini_error_message(error: &GeminiError, user: &User) -> CreateMessage {
+    let color = if error.is_transient() { 0xFFA500 } else { 0xFF0000 };
+    CreateMessage::new()
+        .content(user_mention(user))
+        .add_embed(
+            CreateEmbed::new()
+                .title(error.title())
+                .description(error.user_message())
+                .color(color)
+                .footer(CreateEmbedFooter::new(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()))
+        )
+}
+
 fn context_process(origin:&PastQuery) -> GeminiChatChunk {
     GeminiChatChunk{
         query: origin.0.context.clone(),
@@ -77,11 +96,18 @@ fn context_process(origin:&PastQuery) -> GeminiChatChunk {
         user_id: Some(origin.0.user_id.to_string()),
     }
 }
-async fn send_split_msg(_ctx: &Context,channel_context:ChannelId,origin_user:User,message_context:GeminiResponse,ref_msg:Option<Message>,need_mention_first:bool,use_pro:bool, show_thought:bool)->Vec<Message> {
-    let origin_msg = message_context.discord_msg;
+async fn send_split_msg(_ctx: &Context,channel_context:ChannelId,origin_user:User,message_context:GeminiResponse,ref_msg:Option<Message>,need_mention_first:bool,route:&ModelRoute, show_thought:bool)->Vec<Message> {
+    // 검색 근거가 있으면 본문에 출처 번호와 목록을 붙입니다. DB 에는 원문만 저장됩니다.
+    let origin_msg = render_grounded_text(&message_context.discord_msg, message_context.grounding.as_ref());
     let mut send_msgs:Vec<Message> = vec![];
 
-    let chuncks: Vec<String> = split_text_by_length_and_markdown(&origin_msg, DISCORD_MAX_MSG_LENGTH);
+    // 라우터가 고른 경우에는 임베드가 없어도 마지막 메시지 아래에 선택 이유를 붙입니다.
+    let route_line = if route.routed && !show_thought {
+        format!("\n-# {}", route.footer())
+    } else {
+        String::new()
+    };
+    let chuncks: Vec<String> = split_text_by_length_and_markdown(&origin_msg, DISCORD_MAX_MSG_LENGTH - route_line.len());
     for chunk in 0..chuncks.len() {
         let msg_last = if need_mention_first == true && chunk == 0 {
             user_mention(&origin_user) + &chuncks.get(chunk).unwrap()
@@ -97,14 +123,9 @@ async fn send_split_msg(_ctx: &Context,channel_context:ChannelId,origin_user:Use
             } else {
                 chuncks.get(chunk).unwrap().clone()
             };
-            let used_model = if use_pro {
-                GEMINI_MODEL_PRO.to_string()
-            } else {
-                GEMINI_MODEL_FLASH.to_string()
-            };
-            response_msg = generate_message_block(strs,
+            response_msg = generate_message_block(strs + &route_line,
                 "Gemini API".to_string(), sub_items,
-                used_model,(chunk == chuncks.len() - 1) && show_thought
+                route.footer(),(chunk == chuncks.len() - 1) && show_thought
             );
         }
         if chunk == 0 {
@@ -114,6 +135,20 @@ async fn send_split_msg(_ctx: &Context,channel_context:ChannelId,origin_user:Use
         }
         send_msgs.push(channel_context.send_message(_ctx,response_msg).await.unwrap());
     }
+    // 실행한 코드와 결과는 답변 아래에 실행마다 임베드 하나로 보여줍니다. (메시지당 임베드 최대 10개)
+    if !message_context.code_executions.is_empty() {
+        let embeds: Vec<CreateEmbed> = message_context.code_executions.iter().take(10).enumerate().map(|(i, execution)| {
+            let outcome = outcome_label(execution.outcome.as_ref());
+            CreateEmbed::new()
+                .title(format!("코드 실행 {} · {}", i + 1, outcome))
+                .description(format_code_execution(execution))
+                .color(if outcome == "성공" { 0x00FF00 } else { 0xFF0000 })
+        }).collect();
+        match channel_context.send_message(_ctx, CreateMessage::new().embeds(embeds)).await {
+            Ok(msg) => send_msgs.push(msg),
+            Err(e) => LOGGER.log(LogLevel::Error, &format!("코드 실행 결과 전송 실패: {}", e)),
+        }
+    }
     send_msgs
 }
 
@@ -121,14 +156,15 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
     let options = _options.data.options();
     let query = options.iter().find(|o| o.name == "query");
     let use_pro = options.iter().find(|o| o.name == "use_pro");
-    let use_pro = if use_pro.is_some() {
+    // 옵션을 주지 않았으면 None 으로 두고, 길드에서 자동 선택을 켰다면 라우터가 정합니다.
+    let use_pro_option = if use_pro.is_some() {
         let unwarped = use_pro.unwrap().value.clone();
         match unwarped {
-            ResolvedValue::Boolean(s) => s,
-            _ => false,
+            ResolvedValue::Boolean(s) => Some(s),
+            _ => None,
         }
     } else {
-        false
+        None
     };
     if query.is_none() {
         _options.create_response(_ctx,CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content("질문을 입력하세요"))).await?;
@@ -149,7 +185,25 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
     } else {
         None
     };
-    
+
+    let quota_subject = QuotaSubject {
+        user_id: _options.user.id.get(),
+        guild_id: _options.guild_id.map(|g| g.get()),
+        role_ids: _options.member.as_ref()
+            .map(|m| m.roles.iter().map(|r| r.get()).collect())
+            .unwrap_or_default(),
+    };
+    if let Err(denied) = check_quota(&quota_subject, use_pro_option.unwrap_or(false)).await {
+        return Ok(
+            GuildCommandResponse {
+                content: CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new().content(denied.to_message()).ephemeral(true)
+                ),
+                do_not_send: false,
+            }
+        );
+    }
+    let user_limits = resolve_user_limits(&QUOTA_CONFIG, &quota_subject.role_ids);
 
     let query = query.unwrap().value.clone();
     match query {
@@ -176,6 +230,31 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
 
             // Send a response to the interaction
             _options.create_response(_ctx,CreateInteractionResponse::Message(discord_response_message)).await?;
+
+            // 라우터의 분류 호출이 길어질 수 있으므로 응답을 보낸 뒤에 모델을 정합니다.
+            let guild_policy = load_guild_policy(guild_id).await;
+            let allow_pro = guild_policy.allow_pro_model && can_use_pro(&QUOTA_CONFIG, &quota_subject);
+            let route = if guild_policy.auto_model_routing && use_pro_option.is_none() && thinking_bought.is_none() {
+                let usage_context = GeminiUsageContext {
+                    source: "chat".to_string(),
+                    guild_id: Some(guild_id),
+                    channel_id: Some(_options.channel_id.get()),
+                    user_id: Some(_options.user.id.get()),
+                    context_id: None,
+                };
+                route_query(s, false, allow_pro, &usage_context).await
+            } else if use_pro_option == Some(true) && !guild_policy.allow_pro_model {
+                ModelRoute::manual(false, thinking_bought, "서버 정책으로 Flash")
+            } else {
+                ModelRoute::manual(use_pro_option.unwrap_or(false), thinking_bought, "직접 선택")
+            };
+            let route = ModelRoute {
+                thinking_budget: clamp_thinking_budget(&user_limits, route.thinking_budget),
+                ..route
+            };
+            let use_pro = route.use_pro;
+            let thinking_bought = route.thinking_budget;
+            LOGGER.log(LogLevel::Debug, &format!("Router > {}", route.footer()));
             let is_show_thought = options.iter().find(|o| o.name == "show_thought");
             let is_show_thought = if is_show_thought.is_some() {
                 let unwarped = is_show_thought.unwrap().value.clone();
@@ -260,6 +339,7 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
                     user_id: _options.user.id,
                     username: Some(_options.user.name.clone()),
                     channel_id: _options.channel_id,
+                    guild_id: _options.guild_id,
                     context_id: Some(make_context.id),
                 }
             );
@@ -276,14 +356,22 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
                     make_context.id
                 )
                 .await;
-            if response.is_err() {
-                LOGGER.log(LogLevel::Error, 
-                    &format!("Gemini API Error: {:?}", response)
-                );
-                typing.stop();
-                return Err(SerenityError::Other("Gemini API Error"));
-            }
-            let response = response.unwrap();
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    LOGGER.log(LogLevel::Error, &format!("Gemini API Error: {}", e));
+                    typing.stop();
+                    _options.channel_id.send_message(_ctx, make_gemini_error_message(&e, &_options.user)).await?;
+                    return Ok(
+                        GuildCommandResponse {
+                            content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+                                .content(e.user_message())),
+                            do_not_send: true,
+                        }
+                    );
+                }
+            };
+            let route = route.with_response_model(&response.model);
             let show_thought = options.iter().find(|o| o.name == "show_thought");
             let show_thought = if show_thought.is_some() {
                 let unwarped = show_thought.unwrap().value.clone();
@@ -300,7 +388,7 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
                 _options.user.clone(),
                 response.clone(),
                 None,true,
-                use_pro,
+                &route,
                 show_thought.unwrap_or(false)
             ).await;
             typing.stop();
@@ -469,6 +557,18 @@ pub fn register() -> CreateCommand {
 }
 
 pub async fn continue_query(_ctx: &Context,calling_msg:&Message,user:&User) -> Result<(), String> {
+    let quota_subject = QuotaSubject {
+        user_id: calling_msg.author.id.get(),
+        guild_id: calling_msg.guild_id.map(|g| g.get()),
+        role_ids: calling_msg.member.as_ref()
+            .map(|m| m.roles.iter().map(|r| r.get()).collect())
+            .unwrap_or_default(),
+    };
+    // Pro 여부는 이어가는 컨텍스트에 따라 정해지므로 여기서는 횟수와 일일 한도만 봅니다.
+    if let Err(denied) = check_quota(&quota_subject, false).await {
+        calling_msg.reply(_ctx, denied.to_message()).await.map_err(|e| e.to_string())?;
+        return Ok(());
+    }
     let channel_lock = _ctx.http.get_channel(calling_msg.channel_id)
     .await
     .unwrap();
@@ -617,7 +717,14 @@ pub async fn continue_query(_ctx: &Context,calling_msg:&Message,user:&User) -> R
 
     LOGGER.log(LogLevel::Debug, &format!("before_messages: {:?}", before_messages));
 
-    let context_using_pro = ai_context_info.using_pro_model;
+    // Pro 권한이 없거나 길드 정책이 막으면 Pro 컨텍스트라도 Flash 로 대신 답합니다.
+    let guild_policy = match calling_msg.guild_id {
+        Some(guild_id) => load_guild_policy(guild_id.get()).await,
+        None => GuildModelPolicy::default(),
+    };
+    let pro_downgraded = ai_context_info.using_pro_model
+        && !(guild_policy.allow_pro_model && can_use_pro(&QUOTA_CONFIG, &quota_subject));
+    let context_using_pro = ai_context_info.using_pro_model && !pro_downgraded;
     LOGGER.log(LogLevel::Debug, &format!("context_info: {:?}", before_messages));
 
     let ai_context_map = need_load_context_list.iter().map(|x| (
@@ -781,6 +888,15 @@ pub async fn continue_query(_ctx: &Context,calling_msg:&Message,user:&User) -> R
     } else {
         None
     };
+    let thinking_bought = clamp_thinking_budget(
+        &resolve_user_limits(&QUOTA_CONFIG, &quota_subject.role_ids),
+        thinking_bought
+    );
+    let route = ModelRoute::manual(
+        context_using_pro,
+        thinking_bought,
+        if pro_downgraded { "Pro 제한으로 Flash" } else { "대화 설정 유지" }
+    );
     let send_vector = if cache_is_valid {
         vec![user_msg_current.clone()]
     } else {
@@ -796,6 +912,7 @@ pub async fn continue_query(_ctx: &Context,calling_msg:&Message,user:&User) -> R
             user_id: calling_msg.author.id,
             username: Some(calling_msg.author.name.clone()),
             channel_id: calling_msg.channel_id,
+            guild_id: calling_msg.guild_id,
             context_id: Some(ai_context_info.id as i64),
         }
     );
@@ -812,23 +929,20 @@ pub async fn continue_query(_ctx: &Context,calling_msg:&Message,user:&User) -> R
         continue_context
     )
     .await;
-    if ai_response.is_err() {
-        typing.stop();
-        LOGGER.log(LogLevel::Error, &format!("Gemini API Error: {:?}", ai_response));
-        let error_msg = ai_response.unwrap_err();
-        let mut response_msg: CreateMessage = CreateMessage::new()
-        .content(error_msg);
-        
-        if let Some(ref ref_msg) = calling_msg.referenced_message {
-            response_msg = response_msg.reference_message(&**ref_msg);
+    let ai_response = match ai_response {
+        Ok(ai_response) => ai_response,
+        Err(e) => {
+            typing.stop();
+            LOGGER.log(LogLevel::Error, &format!("Gemini API Error: {}", e));
+            // 이유를 직접 답장했으므로 호출부의 일반 오류 안내는 보내지 않습니다.
+            let response_msg = make_gemini_error_message(&e, &calling_msg.author).reference_message(calling_msg);
+            if let Err(send_err) = calling_msg.channel_id.send_message(_ctx, response_msg).await {
+                LOGGER.log(LogLevel::Error, &format!("Gemini 오류 안내 전송 실패: {}", send_err));
+            }
+            return Ok(());
         }
-        calling_msg.channel_id.send_message(_ctx, 
-            response_msg
-        ).await.unwrap();
-        LOGGER.log(LogLevel::Error, "Gemini API Error");
-        return Err("Gemini API Error".to_string() );
-    }
-    let ai_response = ai_response.unwrap();
+    };
+    let route = route.with_response_model(&ai_response.model);
     let guild_id = calling_msg.guild_id.unwrap().get();
     let show_thought = ai_context_info.show_thought.clone();
     let send_msgs:Vec<Message> = send_split_msg(
@@ -838,7 +952,7 @@ pub async fn continue_query(_ctx: &Context,calling_msg:&Message,user:&User) -> R
         ai_response.clone(), 
         Some(calling_msg.clone()),
         false,
-        context_using_pro,
+        &route,
         show_thought
     ).await;
     typing.stop();
@@ -0,0 +1,124 @@
+use entity::tb_discord_guilds;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::EntityTrait;
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::discord::utils::GuildCommandResponse;
+use crate::gemini::builtin_tools::{load_guild_builtin_tools, GuildBuiltinTools};
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content)),
+        do_not_send: false,
+    }
+}
+
+fn describe(tools: &GuildBuiltinTools) -> String {
+    let on_off = |enabled: bool| if enabled { "켜짐" } else { "꺼짐" };
+    format!(
+        "Google 검색: {} / URL 읽기: {} / 코드 실행: {}",
+        on_off(tools.google_search),
+        on_off(tools.url_context),
+        on_off(tools.code_execution)
+    )
+}
+
+async fn save_guild_builtin_tools(guild_id: u64, tools: &GuildBuiltinTools) -> Result<(), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let now = chrono::Utc::now();
+    let model = tb_discord_guilds::ActiveModel {
+        guild_id: sea_orm::Set(guild_id as i64),
+        joined_at: sea_orm::Set(now.into()),
+        updated_at: sea_orm::Set(now.into()),
+        enable_google_search: sea_orm::Set(tools.google_search),
+        enable_url_context: sea_orm::Set(tools.url_context),
+        enable_code_execution: sea_orm::Set(tools.code_execution),
+        ..Default::default()
+    };
+    tb_discord_guilds::Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(tb_discord_guilds::Column::GuildId)
+                .update_columns([
+                    tb_discord_guilds::Column::EnableGoogleSearch,
+                    tb_discord_guilds::Column::EnableUrlContext,
+                    tb_discord_guilds::Column::EnableCodeExecution,
+                    tb_discord_guilds::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let guild_id = match _options.guild_id {
+        Some(id) => id.get(),
+        None => return Err(
+            serenity::Error::Other(" 길드 ID가 제공되지 않았습니다."),
+        ),
+    };
+    let options = _options.data.options();
+    let find_bool = |name: &str| options.iter().find(|o| o.name == name).and_then(|o| match o.value {
+        ResolvedValue::Boolean(b) => Some(b),
+        _ => None,
+    });
+    let google_search = find_bool("google_search");
+    let url_context = find_bool("url_context");
+    let code_execution = find_bool("code_execution");
+
+    let current = load_guild_builtin_tools(guild_id).await;
+    if google_search.is_none() && url_context.is_none() && code_execution.is_none() {
+        return Ok(make_response(describe(&current)));
+    }
+    let tools = GuildBuiltinTools {
+        google_search: google_search.unwrap_or(current.google_search),
+        url_context: url_context.unwrap_or(current.url_context),
+        code_execution: code_execution.unwrap_or(current.code_execution),
+    };
+    match save_guild_builtin_tools(guild_id, &tools).await {
+        Ok(()) => Ok(make_response(format!(
+            "설정을 바꿨습니다. {}\n-# 대화 캐시가 만료된 뒤의 새 질문부터 적용됩니다.",
+            describe(&tools)
+        ))),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("내장 도구 설정 저장 실패: {}", e));
+            Ok(make_response(format!("설정을 저장하지 못했습니다. ({})", e)))
+        }
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("gemini_tools")
+        .description("이 서버에서 Gemini 가 쓸 수 있는 내장 도구(검색, URL 읽기, 코드 실행)를 설정합니다.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "google_search",
+                "Google 검색으로 답변 근거를 찾고 출처를 표시합니다.",
+            )
+            .required(false)
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "url_context",
+                "질문에 있는 URL 의 내용을 읽습니다.",
+            )
+            .required(false)
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "code_execution",
+                "Python 코드를 실행해 계산하고 코드와 결과를 보여줍니다.",
+            )
+            .required(false)
+        )
+}
@@ -1,9 +1,8 @@
 use serenity::builder::*;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
-use songbird::SerenityInit; // songbird 임포트
 
-use crate::{discord::utils::GuildCommandResponse, libs::logger::{LogLevel, LOGGER}};
+use crate::{discord::utils::GuildCommandResponse, libs::logger::{LogLevel, LOGGER}, service::voice_session_manager::start_voice_session};
 
 pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
     let guild_id = match _options.guild_id {
@@ -42,35 +41,27 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
         LogLevel::Info,
         &format!("사용자 {}의 음성 채널 {}에 참여 시도 중", user_name, channel_id_to_join),
     );
-    // songbird 관리자를 가져옵니다.
-    let manager = songbird::get(_ctx).await;
-    let manager = match manager {
-        Some(m) => m,
-        None => return Ok(GuildCommandResponse{
-            content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
-                .content("음성 채널 관리자에 접근할 수 없습니다.")),
-            do_not_send: false,
-        }),
-    };
-
-
-    // `join`을 사용하여 음성 채널에 연결합니다.
-    if manager.join(guild_id, channel_id_to_join).await.is_ok() {
-        LOGGER.log(LogLevel::Info, &format!("음성 채널 {}에 성공적으로 참여했습니다.", channel_id_to_join));
-        Ok(GuildCommandResponse{
-            content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
-                .content("음성 채널에 성공적으로 참여했습니다!")),
-            do_not_send: false,
+    // 음성 채널에 참여하고, 이 길드의 Gemini Live 음성 세션을 시작합니다.
+    let locale = _options.user.locale.clone().unwrap_or("ko".to_string());
+    match start_voice_session(_ctx, guild_id, channel_id_to_join, _options.channel_id, _options.user.id, user_name.clone(), locale).await {
+        Ok(_) => {
+            LOGGER.log(LogLevel::Info, &format!("음성 채널 {}에 성공적으로 참여했습니다.", channel_id_to_join));
+            Ok(GuildCommandResponse{
+                content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+                    .content("음성 채널에 성공적으로 참여했습니다! 말을 걸어 주세요.")),
+                do_not_send: false,
 
-        })
-    } else {
-        LOGGER.log(LogLevel::Error, &format!("음성 채널 {} 참여에 실패했습니다.", channel_id_to_join));
-        Ok(GuildCommandResponse{
-            content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
-                .content("음성 채널에 참여하는 데 실패했습니다.")),
-            do_not_send: false,
+            })
+        }
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("음성 채널 {} 참여에 실패했습니다: {}", channel_id_to_join, e));
+            Ok(GuildCommandResponse{
+                content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+                    .content(format!("음성 채널에 참여하는 데 실패했습니다. ({})", e))),
+                do_not_send: false,
 
-        })
+            })
+        }
     }
 }
 
@@ -4,6 +4,7 @@ use serenity::model::prelude::*;
 use serenity::prelude::*;
 
 use crate::discord::utils::GuildCommandResponse;
+use crate::service::voice_session_manager::stop_voice_session;
 
 
 pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
@@ -45,19 +46,8 @@ pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildC
         // user_name도 미리 복사해 둡니다.
         (connect_to, _options.user.name.clone())
     };
-    let manager = songbird::get(_ctx).await;
-    let manager = match manager {
-        Some(m) => m,
-        None => return Ok(GuildCommandResponse{
-            content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
-                .content(
-                    "음성 채널 관리자에 접근할 수 없습니다."
-                )),
-            do_not_send: false,
-        }),
-    };
-
-    manager.leave(guild_id).await.map_err(|e| {
+    // Live 음성 세션을 닫고 채널에서 나갑니다.
+    stop_voice_session(_ctx, guild_id).await.map_err(|e| {
         LOGGER.log(LogLevel::Error, 
             &format!("음성 채널에서 나가는 데 실패했습니다: {}", e)
         );
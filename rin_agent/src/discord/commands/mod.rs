@@ -2,4 +2,12 @@ pub mod lping;
 pub mod gemini_query;
 pub mod lutica_repo;
 pub mod join_voice;
-pub mod leave_voice;
\ No newline at end of file
+pub mod leave_voice;
+pub mod transcribe;
+pub mod usage;
+pub mod model_routing;
+pub mod structured;
+pub mod gemini_tools;
+pub mod search_provider;
+pub mod debt;
+pub mod receipt;
\ No newline at end of file
@@ -0,0 +1,107 @@
+use entity::tb_discord_guilds;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::EntityTrait;
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::discord::utils::GuildCommandResponse;
+use crate::gemini::model_router::{load_guild_policy, GuildModelPolicy};
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content)),
+        do_not_send: false,
+    }
+}
+
+fn describe(policy: &GuildModelPolicy) -> String {
+    format!(
+        "자동 모델 선택: {} / Pro 모델 허용: {}",
+        if policy.auto_model_routing { "켜짐" } else { "꺼짐" },
+        if policy.allow_pro_model { "예" } else { "아니오" }
+    )
+}
+
+async fn save_guild_policy(guild_id: u64, policy: &GuildModelPolicy) -> Result<(), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let now = chrono::Utc::now();
+    let model = tb_discord_guilds::ActiveModel {
+        guild_id: sea_orm::Set(guild_id as i64),
+        joined_at: sea_orm::Set(now.into()),
+        updated_at: sea_orm::Set(now.into()),
+        auto_model_routing: sea_orm::Set(policy.auto_model_routing),
+        allow_pro_model: sea_orm::Set(policy.allow_pro_model),
+        ..Default::default()
+    };
+    tb_discord_guilds::Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(tb_discord_guilds::Column::GuildId)
+                .update_columns([
+                    tb_discord_guilds::Column::AutoModelRouting,
+                    tb_discord_guilds::Column::AllowProModel,
+                    tb_discord_guilds::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let guild_id = match _options.guild_id {
+        Some(id) => id.get(),
+        None => return Err(
+            serenity::Error::Other(" 길드 ID가 제공되지 않았습니다."),
+        ),
+    };
+    let options = _options.data.options();
+    let find_bool = |name: &str| options.iter().find(|o| o.name == name).and_then(|o| match o.value {
+        ResolvedValue::Boolean(b) => Some(b),
+        _ => None,
+    });
+    let auto = find_bool("auto");
+    let allow_pro = find_bool("allow_pro");
+
+    let current = load_guild_policy(guild_id).await;
+    if auto.is_none() && allow_pro.is_none() {
+        return Ok(make_response(describe(&current)));
+    }
+    let policy = GuildModelPolicy {
+        auto_model_routing: auto.unwrap_or(current.auto_model_routing),
+        allow_pro_model: allow_pro.unwrap_or(current.allow_pro_model),
+    };
+    match save_guild_policy(guild_id, &policy).await {
+        Ok(()) => Ok(make_response(format!("설정을 바꿨습니다. {}", describe(&policy)))),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("모델 정책 저장 실패: {}", e));
+            Ok(make_response(format!("설정을 저장하지 못했습니다. ({})", e)))
+        }
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("model_routing")
+        .description("이 서버의 Gemini 모델 자동 선택과 Pro 모델 허용 여부를 설정합니다.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "auto",
+                "use_pro/thinking_bought 를 주지 않은 질문의 모델을 자동으로 고릅니다.",
+            )
+            .required(false)
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "allow_pro",
+                "Pro 모델 사용을 허용합니다.",
+            )
+            .required(false)
+        )
+}
@@ -0,0 +1,291 @@
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::discord::utils::GuildCommandResponse;
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::receipt_ocr::{extract_receipt, ReceiptData};
+use crate::gemini::types::{GeminiImageInputType, GeminiUsageContext};
+use crate::gemini::utils::upload_image_to_gemini;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::debt_service::format_amount;
+use crate::service::quota_service::{check_quota, QuotaSubject};
+use crate::service::receipt_split_service::{
+    confirm_split, encode_receipt_image, parse_user_mentions, propose_split, receipt_image_src, store_split_proposal,
+    take_split_proposal, ReceiptSplitProposal, MAX_RECEIPT_IMAGE_BYTES, SPLIT_CONFIRM_TTL,
+};
+use crate::setting::gemini_setting::GEMINI_MODEL_FLASH;
+
+// 버튼 custom_id 는 "receipt_split:<confirm|cancel>:<interaction id>" 입니다.
+pub const RECEIPT_SPLIT_PREFIX: &str = "receipt_split:";
+const MAX_ITEM_LINES: usize = 20;
+
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content).ephemeral(true)),
+        do_not_send: false,
+    }
+}
+
+fn format_receipt_amount(receipt: &ReceiptData, amount: f64) -> String {
+    if receipt.is_krw() {
+        format_amount(amount.round() as i64)
+    } else {
+        format!("{} {}", amount, receipt.currency)
+    }
+}
+
+fn make_receipt_embed(proposal: &ReceiptSplitProposal) -> CreateEmbed {
+    let receipt = &proposal.receipt;
+    let mut lines = receipt
+        .items
+        .iter()
+        .take(MAX_ITEM_LINES)
+        .map(|item| {
+            let quantity = item.quantity.filter(|q| *q != 1.0).map(|q| format!(" x{}", q)).unwrap_or_default();
+            format!("- {}{} · {}", item.name, quantity, format_receipt_amount(receipt, item.amount))
+        })
+        .collect::<Vec<_>>();
+    if receipt.items.len() > MAX_ITEM_LINES {
+        lines.push(format!("외 {}개 품목", receipt.items.len() - MAX_ITEM_LINES));
+    }
+    let mut totals = Vec::new();
+    for (label, value) in [("소계", receipt.subtotal), ("세금", receipt.tax), ("팁", receipt.tip)] {
+        if let Some(value) = value {
+            totals.push(format!("{} {}", label, format_receipt_amount(receipt, value)));
+        }
+    }
+    totals.push(format!("**합계 {}**", format_receipt_amount(receipt, receipt.total)));
+
+    let title = match (&receipt.merchant, &receipt.date) {
+        (Some(merchant), Some(date)) => format!("🧾 {} · {}", merchant, date),
+        (Some(merchant), None) => format!("🧾 {}", merchant),
+        (None, Some(date)) => format!("🧾 영수증 · {}", date),
+        (None, None) => "🧾 영수증".to_string(),
+    };
+    let embed = CreateEmbed::new()
+        .title(title)
+        .description(if lines.is_empty() { "품목을 읽지 못했습니다.".to_string() } else { lines.join("\n") })
+        .field("금액", totals.join("\n"), false)
+        .color(0x00FF00);
+    if proposal.shares.is_empty() {
+        return embed;
+    }
+    // 나눈 몫은 반올림한 원화 합계 기준입니다.
+    let mut split_lines = vec![format!("<@{}> (결제) {}", proposal.owner_id, format_amount(proposal.owner_share))];
+    split_lines.extend(proposal.shares.iter().map(|s| format!("<@{}> {}", s.user_id, format_amount(s.amount))));
+    embed.field("나눠 내기 제안", split_lines.join("\n"), false)
+}
+
+fn make_error_embed(error: &GeminiError) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(error.title())
+        .description(error.user_message())
+        .color(if error.is_transient() { 0xFFA500 } else { 0xFF0000 })
+}
+
+fn make_buttons(key: u64) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}confirm:{}", RECEIPT_SPLIT_PREFIX, key))
+            .label("장부에 기록")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{}cancel:{}", RECEIPT_SPLIT_PREFIX, key))
+            .label("취소")
+            .style(ButtonStyle::Secondary),
+    ])]
+}
+
+// 감사용으로 원본 이미지를 data URL 로 남깁니다. 첨부 주소는 서명이 만료되면 열 수 없습니다.
+async fn save_receipt_image(base64_image: &str, mime_type: &str) -> Result<i64, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let image = entity::tb_image_attach_file::ActiveModel {
+        file_src: sea_orm::Set(receipt_image_src(mime_type, base64_image)),
+        mime_type: sea_orm::Set(Some(mime_type.to_string())),
+        ..Default::default()
+    };
+    entity::tb_image_attach_file::Entity::insert(image)
+        .exec_with_returning(db)
+        .await
+        .map(|image| image.image_id)
+        .map_err(|e| e.to_string())
+}
+
+// 멘션한 사람의 표시 이름을 장부 이름으로 씁니다. 봇과 찾을 수 없는 사용자는 뺍니다.
+async fn resolve_participants(ctx: &Context, owner_id: u64, request: &str) -> Vec<(u64, String)> {
+    let mut participants = Vec::new();
+    for user_id in parse_user_mentions(request).into_iter().filter(|id| *id != owner_id) {
+        match UserId::new(user_id).to_user(ctx).await {
+            Ok(user) if !user.bot => participants.push((user_id, user.display_name().to_string())),
+            Ok(_) => {}
+            Err(e) => LOGGER.log(LogLevel::Warning, &format!("영수증 > 사용자 {} 조회 실패: {:?}", user_id, e)),
+        }
+    }
+    participants
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let options = _options.data.options();
+    let attachment = options.iter().find(|o| o.name == "image").and_then(|o| match o.value {
+        ResolvedValue::Attachment(a) => Some(a.clone()),
+        _ => None,
+    });
+    let request = options.iter().find(|o| o.name == "with").and_then(|o| match o.value {
+        ResolvedValue::String(s) => Some(s.to_string()),
+        _ => None,
+    }).unwrap_or_default();
+    let Some(attachment) = attachment else {
+        return Ok(make_response("영수증 사진을 첨부하세요.".to_string()));
+    };
+    let Some(mime_type) = attachment.content_type.clone().filter(|t| t.starts_with("image/")) else {
+        return Ok(make_response("이미지 파일만 읽을 수 있습니다.".to_string()));
+    };
+    if u64::from(attachment.size) > MAX_RECEIPT_IMAGE_BYTES {
+        return Ok(make_response(format!(
+            "영수증 사진은 {}MB 까지 읽을 수 있습니다.",
+            MAX_RECEIPT_IMAGE_BYTES / 1024 / 1024
+        )));
+    }
+
+    let owner_id = _options.user.id.get();
+    let quota_subject = QuotaSubject {
+        user_id: owner_id,
+        guild_id: _options.guild_id.map(|g| g.get()),
+        role_ids: _options.member.as_ref()
+            .map(|m| m.roles.iter().map(|r| r.get()).collect())
+            .unwrap_or_default(),
+    };
+    if let Err(denied) = check_quota(&quota_subject, false).await {
+        return Ok(make_response(denied.to_message()));
+    }
+
+    // 이미지 업로드와 인식에 시간이 걸리므로 먼저 응답을 미뤄 둡니다.
+    _options.create_response(_ctx, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new())).await?;
+
+    let base64_image = match attachment.download().await {
+        Ok(bytes) => encode_receipt_image(&bytes),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("영수증 > 이미지 다운로드 실패: {:?}", e));
+            _options.edit_response(_ctx, EditInteractionResponse::new().content("영수증 사진을 받지 못했습니다.")).await?;
+            return Ok(GuildCommandResponse { content: CreateInteractionResponse::Acknowledge, do_not_send: true });
+        }
+    };
+    let image_id = match save_receipt_image(&base64_image, &mime_type).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("영수증 > 이미지 저장 실패: {}", e));
+            None
+        }
+    };
+    let uploaded = upload_image_to_gemini(
+        GeminiImageInputType {
+            base64_image: Some(base64_image),
+            file_url: None,
+            mime_type: mime_type.clone(),
+        },
+        format!("receipt_{}", image_id.map(|id| id.to_string()).unwrap_or(attachment.id.to_string())),
+    )
+    .await;
+    let uploaded = match uploaded {
+        Ok(uploaded) => uploaded,
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("영수증 > 이미지 업로드 실패: {}", e));
+            _options.edit_response(_ctx, EditInteractionResponse::new().content("이미지 전송에 실패했습니다.")).await?;
+            return Ok(GuildCommandResponse { content: CreateInteractionResponse::Acknowledge, do_not_send: true });
+        }
+    };
+
+    let usage_context = GeminiUsageContext {
+        source: "receipt".to_string(),
+        guild_id: _options.guild_id.map(|g| g.get()),
+        channel_id: Some(_options.channel_id.get()),
+        user_id: Some(owner_id),
+        context_id: None,
+    };
+    let edit = match extract_receipt(&uploaded, &request, GEMINI_MODEL_FLASH, &usage_context).await {
+        Ok(receipt) => {
+            let participants = resolve_participants(_ctx, owner_id, &request).await;
+            let proposal = propose_split(owner_id, image_id, receipt, &participants);
+            let embed = make_receipt_embed(&proposal);
+            if !proposal.receipt.is_krw() && !participants.is_empty() {
+                EditInteractionResponse::new()
+                    .content(format!(
+                        "장부는 원화로만 기록하므로 {} 영수증은 나눠 기록하지 않고 읽기만 했습니다.",
+                        proposal.receipt.currency
+                    ))
+                    .embed(embed)
+            } else if proposal.shares.is_empty() {
+                EditInteractionResponse::new()
+                    .content("나눠 낼 사람을 멘션하지 않아 영수증만 읽었습니다.")
+                    .embed(embed)
+            } else {
+                let key = _options.id.get();
+                store_split_proposal(key, proposal);
+                EditInteractionResponse::new()
+                    .content(format!("{}분 안에 확인하면 장부에 기록합니다.", SPLIT_CONFIRM_TTL.as_secs() / 60))
+                    .embed(embed)
+                    .components(make_buttons(key))
+            }
+        }
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("영수증 > 인식 실패: {}", e));
+            EditInteractionResponse::new().embed(make_error_embed(&e))
+        }
+    };
+    _options.edit_response(_ctx, edit).await?;
+    Ok(GuildCommandResponse {
+        content: CreateInteractionResponse::Acknowledge,
+        do_not_send: true,
+    })
+}
+
+// 확인/취소 버튼 처리
+pub async fn handle_component(ctx: &Context, component: &ComponentInteraction) -> Result<(), serenity::Error> {
+    let custom_id = component.data.custom_id.trim_start_matches(RECEIPT_SPLIT_PREFIX);
+    let (action, key) = custom_id.split_once(':').unwrap_or((custom_id, ""));
+    let Ok(key) = key.parse::<u64>() else {
+        return Ok(());
+    };
+    let proposal = match take_split_proposal(key, component.user.id.get()) {
+        Ok(proposal) => proposal,
+        Err(e) => {
+            return component
+                .create_response(ctx, CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(e).ephemeral(true)))
+                .await;
+        }
+    };
+    if action != "confirm" {
+        return component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new().content("취소했습니다.").components(vec![])),
+            )
+            .await;
+    }
+
+    component.create_response(ctx, CreateInteractionResponse::Acknowledge).await?;
+    let (entries, errors) = confirm_split(&proposal).await;
+    let mut lines = vec![format!("장부에 {}건 기록했습니다.", entries.len())];
+    lines.extend(entries.iter().map(|e| format!("`#{}` {} {}", e.id, e.debtor_name, format_amount(e.amount))));
+    if !errors.is_empty() {
+        LOGGER.log(LogLevel::Error, &format!("영수증 > 장부 기록 실패: {:?}", errors));
+        lines.push(format!("기록하지 못한 항목: {}", errors.join(", ")));
+    }
+    component
+        .edit_response(ctx, EditInteractionResponse::new().content(lines.join("\n")).components(vec![]))
+        .await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("receipt")
+        .description("영수증 사진을 읽어 멘션한 사람들과 나눠 낼 금액을 장부에 기록합니다.")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Attachment, "image", "영수증 사진")
+                .required(true)
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "with", "함께 나눠 낼 사람 멘션 (예: @A @B 랑 나눠줘)")
+                .required(false)
+        )
+}
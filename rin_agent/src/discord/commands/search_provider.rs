@@ -0,0 +1,84 @@
+use entity::tb_discord_guilds;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::EntityTrait;
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::api::search_provider::{load_guild_search_provider, provider_from_env, SearchProviderKind};
+use crate::discord::utils::GuildCommandResponse;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content)),
+        do_not_send: false,
+    }
+}
+
+async fn save_guild_search_provider(guild_id: u64, kind: SearchProviderKind) -> Result<(), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let now = chrono::Utc::now();
+    let model = tb_discord_guilds::ActiveModel {
+        guild_id: sea_orm::Set(guild_id as i64),
+        joined_at: sea_orm::Set(now.into()),
+        updated_at: sea_orm::Set(now.into()),
+        search_provider: sea_orm::Set(Some(kind.as_str().to_string())),
+        ..Default::default()
+    };
+    tb_discord_guilds::Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(tb_discord_guilds::Column::GuildId)
+                .update_columns([
+                    tb_discord_guilds::Column::SearchProvider,
+                    tb_discord_guilds::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let guild_id = match _options.guild_id {
+        Some(id) => id.get(),
+        None => return Err(
+            serenity::Error::Other(" 길드 ID가 제공되지 않았습니다."),
+        ),
+    };
+    let options = _options.data.options();
+    let provider = options.iter().find(|o| o.name == "provider").and_then(|o| match o.value {
+        ResolvedValue::String(s) => SearchProviderKind::parse(s),
+        _ => None,
+    });
+
+    let Some(kind) = provider else {
+        let current = load_guild_search_provider(guild_id).await;
+        return Ok(make_response(format!("현재 검색 제공자: {}", current.label())));
+    };
+    // 서버에 키가 없는 제공자를 고르면 검색할 때마다 실패하므로 미리 막습니다.
+    if let Err(e) = provider_from_env(kind) {
+        return Ok(make_response(format!("{} 검색을 쓸 수 없습니다. ({})", kind.label(), e)));
+    }
+    match save_guild_search_provider(guild_id, kind).await {
+        Ok(()) => Ok(make_response(format!("검색 제공자를 {} 로 바꿨습니다.", kind.label()))),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("검색 제공자 설정 저장 실패: {}", e));
+            Ok(make_response(format!("설정을 저장하지 못했습니다. ({})", e)))
+        }
+    }
+}
+
+pub fn register() -> CreateCommand {
+    let provider_option = SearchProviderKind::ALL.into_iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "provider", "검색 제공자").required(false),
+        |option, kind| option.add_string_choice(kind.label(), kind.as_str()),
+    );
+    CreateCommand::new("search_provider")
+        .description("이 서버에서 searching 도구가 쓸 검색 제공자를 설정합니다.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(provider_option)
+}
@@ -0,0 +1,175 @@
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::discord::utils::GuildCommandResponse;
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::model_router::load_guild_policy;
+use crate::gemini::structured_output::{generate_structured, StructuredKind, StructuredTable};
+use crate::gemini::types::GeminiUsageContext;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::service::quota_service::{can_use_pro, check_quota, QuotaSubject};
+use crate::service::structured_output_service::save_structured_output;
+use crate::setting::gemini_setting::{GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO};
+use crate::setting::quota_setting::QUOTA_CONFIG;
+
+// 디스코드 임베드 한도
+const EMBED_DESCRIPTION_MAX: usize = 4096;
+const EMBED_FIELD_MAX: usize = 25;
+const EMBED_FIELD_NAME_MAX: usize = 256;
+const EMBED_FIELD_VALUE_MAX: usize = 1024;
+
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content).ephemeral(true)),
+        do_not_send: false,
+    }
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    text.chars().take(max - 1).collect::<String>() + "…"
+}
+
+// 필드 값은 비어 있으면 안 되므로 폭 없는 공백을 넣습니다.
+fn field_value(text: &str) -> String {
+    if text.trim().is_empty() { "\u{200b}".to_string() } else { truncate_chars(text, EMBED_FIELD_VALUE_MAX) }
+}
+
+// 비교표는 코드 블록 표로, 체크리스트와 타임라인은 항목마다 필드 하나로 보여줍니다.
+fn make_embed(kind: StructuredKind, table: &StructuredTable) -> CreateEmbed {
+    let embed = CreateEmbed::new()
+        .title(truncate_chars(&format!("{} · {}", kind.label(), table.title), EMBED_FIELD_NAME_MAX))
+        .color(0x00FF00);
+    match kind {
+        StructuredKind::Comparison => {
+            let summary = table.summary.clone().map(|s| format!("\n{}", s)).unwrap_or_default();
+            let summary = truncate_chars(&summary, EMBED_DESCRIPTION_MAX / 4);
+            let code_block = table.to_code_block(EMBED_DESCRIPTION_MAX - summary.chars().count());
+            embed.description(code_block + &summary)
+        }
+        StructuredKind::Checklist | StructuredKind::Timeline => {
+            let fields = table.rows.iter().take(EMBED_FIELD_MAX).map(|row| {
+                let cell = |i: usize| row.get(i).map(String::as_str).unwrap_or_default();
+                let name = if kind == StructuredKind::Checklist {
+                    format!("{} {}", if cell(0) == "O" { "✅" } else { "⬜" }, cell(1))
+                } else {
+                    format!("{} · {}", cell(0), cell(1))
+                };
+                (truncate_chars(&name, EMBED_FIELD_NAME_MAX), field_value(cell(2)), false)
+            });
+            let embed = embed.fields(fields);
+            if table.rows.len() > EMBED_FIELD_MAX {
+                embed.description(format!("외 {}개 항목은 첨부한 CSV 에서 확인해 주세요.", table.rows.len() - EMBED_FIELD_MAX))
+            } else {
+                embed
+            }
+        }
+    }
+}
+
+fn make_error_embed(error: &GeminiError) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(error.title())
+        .description(error.user_message())
+        .color(if error.is_transient() { 0xFFA500 } else { 0xFF0000 })
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let options = _options.data.options();
+    let kind = options.iter().find(|o| o.name == "kind").and_then(|o| match o.value {
+        ResolvedValue::String(s) => StructuredKind::parse(s),
+        _ => None,
+    });
+    let query = options.iter().find(|o| o.name == "query").and_then(|o| match o.value {
+        ResolvedValue::String(s) => Some(s.to_string()),
+        _ => None,
+    });
+    let use_pro = options.iter().find(|o| o.name == "use_pro").is_some_and(|o| matches!(o.value, ResolvedValue::Boolean(true)));
+    let (Some(kind), Some(query)) = (kind, query) else {
+        return Ok(make_response("형식과 질문을 입력하세요.".to_string()));
+    };
+
+    let quota_subject = QuotaSubject {
+        user_id: _options.user.id.get(),
+        guild_id: _options.guild_id.map(|g| g.get()),
+        role_ids: _options.member.as_ref()
+            .map(|m| m.roles.iter().map(|r| r.get()).collect())
+            .unwrap_or_default(),
+    };
+    if let Err(denied) = check_quota(&quota_subject, use_pro).await {
+        return Ok(make_response(denied.to_message()));
+    }
+    let allow_pro = match _options.guild_id {
+        Some(guild_id) => load_guild_policy(guild_id.get()).await.allow_pro_model,
+        None => true,
+    } && can_use_pro(&QUOTA_CONFIG, &quota_subject);
+    let mut model = if use_pro && allow_pro { GEMINI_MODEL_PRO } else { GEMINI_MODEL_FLASH };
+
+    // 생성에 시간이 걸리므로 먼저 응답을 미뤄 둡니다.
+    _options.create_response(_ctx, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new())).await?;
+
+    let usage_context = GeminiUsageContext {
+        source: "structured".to_string(),
+        guild_id: _options.guild_id.map(|g| g.get()),
+        channel_id: Some(_options.channel_id.get()),
+        user_id: Some(_options.user.id.get()),
+        context_id: None,
+    };
+    let mut result = generate_structured(kind, &query, model, &usage_context).await;
+    if model == GEMINI_MODEL_PRO && result.as_ref().is_err_and(|e| e.should_fallback_to_flash()) {
+        LOGGER.log(LogLevel::Warning, "구조화 응답 > Pro 실패로 Flash 로 다시 요청합니다");
+        model = GEMINI_MODEL_FLASH;
+        result = generate_structured(kind, &query, model, &usage_context).await;
+    }
+
+    let edit = match result {
+        Ok(data) => {
+            let table = StructuredTable::from_value(kind, &data);
+            let saved = save_structured_output(kind, &query, model, &data, &usage_context).await;
+            let footer = match &saved {
+                Ok(id) => format!("{} · #{} · /api/structured/{}/csv", model, id, id),
+                Err(e) => {
+                    LOGGER.log(LogLevel::Error, &format!("구조화 응답 저장 실패: {}", e));
+                    model.to_string()
+                }
+            };
+            let filename = match &saved {
+                Ok(id) => format!("{}_{}.csv", kind.as_str(), id),
+                Err(_) => format!("{}.csv", kind.as_str()),
+            };
+            EditInteractionResponse::new()
+                .embed(make_embed(kind, &table).footer(CreateEmbedFooter::new(footer)))
+                .new_attachment(CreateAttachment::bytes(table.to_csv().into_bytes(), filename))
+        }
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("구조화 응답 실패: {}", e));
+            EditInteractionResponse::new().embed(make_error_embed(&e))
+        }
+    };
+    _options.edit_response(_ctx, edit).await?;
+    Ok(GuildCommandResponse {
+        content: CreateInteractionResponse::Acknowledge,
+        do_not_send: true,
+    })
+}
+
+pub fn register() -> CreateCommand {
+    let kind_option = StructuredKind::ALL.into_iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "kind", "응답 형식").required(true),
+        |option, kind| option.add_string_choice(kind.label(), kind.as_str()),
+    );
+    CreateCommand::new("structured")
+        .description("질문을 비교표, 체크리스트, 타임라인 형식으로 정리합니다.")
+        .add_option(kind_option)
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "query", "정리할 내용")
+                .required(true)
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Boolean, "use_pro", "Pro 모델 사용 여부")
+                .required(false)
+        )
+}
@@ -0,0 +1,88 @@
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::discord::utils::GuildCommandResponse;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::service::transcribe_service::{start_transcription, stop_transcription};
+
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content)),
+        do_not_send: false,
+    }
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let guild_id = match _options.guild_id {
+        Some(id) => id,
+        None => return Err(
+            serenity::Error::Other(" 길드 ID가 제공되지 않았습니다."),
+        ),
+    };
+    let sub_command = _options.data.options.first().map(|o| o.name.clone()).unwrap_or_default();
+
+    match sub_command.as_str() {
+        "start" => {
+            let voice_channel = {
+                let guild = match _ctx.cache.guild(guild_id) {
+                    Some(guild) => guild,
+                    None => return Err(serenity::Error::Other("길드를 캐시에서 찾을 수 없습니다.")),
+                };
+                guild
+                    .voice_states
+                    .get(&_options.user.id)
+                    .and_then(|voice_state| voice_state.channel_id)
+            };
+            let Some(voice_channel) = voice_channel else {
+                return Ok(make_response("먼저 음성 채널에 들어가 주세요.".to_string()));
+            };
+
+            match start_transcription(_ctx, guild_id, voice_channel, _options.channel_id, _options.user.id).await {
+                Ok(session) => Ok(make_response(format!(
+                    "<#{}>의 받아쓰기를 시작했습니다. 기록은 <#{}>에 올라갑니다.",
+                    voice_channel, session.thread_id
+                ))),
+                Err(e) => {
+                    LOGGER.log(LogLevel::Error, &format!("받아쓰기 시작 실패: {}", e));
+                    Ok(make_response(format!("받아쓰기를 시작하지 못했습니다. ({})", e)))
+                }
+            }
+        }
+        "stop" => {
+            // 요약에 시간이 걸리므로 먼저 응답합니다.
+            _options.create_response(_ctx, CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new().content("받아쓰기를 종료하고 요약을 만드는 중입니다...")
+            )).await?;
+
+            let content = match stop_transcription(_ctx, guild_id, _options.user.id).await {
+                Ok(summary) => format!(
+                    "받아쓰기를 종료했습니다. 결정 사항 {}건, 할 일 {}건, 제안 알람 {}건을 정리했습니다.",
+                    summary.decisions.len(), summary.action_items.len(), summary.proposed_alarms.len()
+                ),
+                Err(e) => format!("받아쓰기를 종료하지 못했습니다. ({})", e),
+            };
+            _options.edit_response(_ctx, EditInteractionResponse::new().content(content)).await?;
+            Ok(GuildCommandResponse {
+                content: CreateInteractionResponse::Acknowledge,
+                do_not_send: true,
+            })
+        }
+        _ => Ok(make_response("알 수 없는 하위 명령입니다.".to_string())),
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("transcribe")
+        .description("음성 채널의 대화를 받아쓰고 요약합니다.")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "start",
+            "지금 있는 음성 채널의 받아쓰기를 시작합니다.",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "stop",
+            "받아쓰기를 끝내고 요약을 올립니다.",
+        ))
+}
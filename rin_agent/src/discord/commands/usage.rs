@@ -0,0 +1,138 @@
+use serenity::builder::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use crate::discord::utils::GuildCommandResponse;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::service::usage_service::{summarize_usage, UsageFilter, UsageGroupBy, UsageSummary};
+use crate::setting::gemini_setting::MANAGER_ID;
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_ROWS: u64 = 10;
+
+fn make_response(content: String) -> GuildCommandResponse {
+    GuildCommandResponse {
+        content: CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().content(content)),
+        do_not_send: false,
+    }
+}
+
+fn format_row(label: &str, row: &UsageSummary) -> String {
+    format!(
+        "{} · 요청 {}회 · 입력 {} (캐시 {}) / 생각 {} / 출력 {} 토큰 · ${:.4}",
+        label, row.requests, row.prompt_tokens, row.cached_tokens, row.thoughts_tokens, row.output_tokens, row.cost_usd
+    )
+}
+
+fn format_key(group_by: UsageGroupBy, key: &Option<String>) -> String {
+    match (group_by, key) {
+        (_, None) => "(없음)".to_string(),
+        (UsageGroupBy::Guild, Some(id)) => format!("길드 `{}`", id),
+        (UsageGroupBy::User, Some(id)) => format!("<@{}>", id),
+        (UsageGroupBy::Model, Some(model)) => format!("`{}`", model),
+    }
+}
+
+// 길드 사용량에는 멤버별 사용량이 들어가므로 웹 /api/usage 처럼 길드 관리자와 봇 관리자만 봅니다.
+pub fn can_view_guild_usage(user_id: u64, permissions: Option<Permissions>) -> bool {
+    user_id as i64 == *MANAGER_ID
+        || permissions.is_some_and(|p| p.administrator() || p.manage_guild())
+}
+
+async fn format_section(title: &str, filter: &UsageFilter, group_by: UsageGroupBy) -> Result<String, String> {
+    let rows = summarize_usage(filter, group_by, MAX_ROWS).await?;
+    if rows.is_empty() {
+        return Ok(format!("**{}**\n기록이 없습니다.", title));
+    }
+    let mut lines = vec![format!("**{}**", title)];
+    lines.extend(rows.iter().map(|row| format_row(&format_key(group_by, &row.key), row)));
+    lines.push(format_row("합계", &UsageSummary::total(&rows)));
+    Ok(lines.join("\n"))
+}
+
+pub async fn run(_ctx: &Context, _options: &CommandInteraction) -> Result<GuildCommandResponse, serenity::Error> {
+    let Some(sub_command) = _options.data.options().into_iter().next() else {
+        return Ok(make_response("알 수 없는 하위 명령입니다.".to_string()));
+    };
+    let days = match &sub_command.value {
+        ResolvedValue::SubCommand(options) => options
+            .iter()
+            .find(|o| o.name == "days")
+            .and_then(|o| match o.value {
+                ResolvedValue::Integer(i) => Some(i),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_DAYS),
+        _ => DEFAULT_DAYS,
+    };
+
+    let result = match sub_command.name {
+        "guild" => {
+            let Some(guild_id) = _options.guild_id else {
+                return Err(serenity::Error::Other(" 길드 ID가 제공되지 않았습니다."));
+            };
+            let permissions = _options.member.as_ref().and_then(|member| member.permissions);
+            if !can_view_guild_usage(_options.user.id.get(), permissions) {
+                return Ok(make_response("서버 관리 권한이 있어야 길드 사용량을 볼 수 있습니다.".to_string()));
+            }
+            let filter = UsageFilter { guild_id: Some(guild_id.get()), user_id: None, days };
+            match format_section("모델별", &filter, UsageGroupBy::Model).await {
+                Ok(models) => format_section("사용자별", &filter, UsageGroupBy::User)
+                    .await
+                    .map(|users| format!("최근 {}일 이 길드의 Gemini 사용량\n{}\n\n{}", days, models, users)),
+                Err(e) => Err(e),
+            }
+        }
+        "me" => {
+            let filter = UsageFilter { guild_id: None, user_id: Some(_options.user.id.get()), days };
+            format_section("모델별", &filter, UsageGroupBy::Model)
+                .await
+                .map(|models| format!("최근 {}일 내 Gemini 사용량\n{}", days, models))
+        }
+        "all" => {
+            if _options.user.id.get() as i64 != *MANAGER_ID {
+                return Ok(make_response("관리자만 전체 사용량을 볼 수 있습니다.".to_string()));
+            }
+            let filter = UsageFilter { guild_id: None, user_id: None, days };
+            match format_section("길드별", &filter, UsageGroupBy::Guild).await {
+                Ok(guilds) => format_section("모델별", &filter, UsageGroupBy::Model)
+                    .await
+                    .map(|models| format!("최근 {}일 전체 Gemini 사용량\n{}\n\n{}", days, guilds, models)),
+                Err(e) => Err(e),
+            }
+        }
+        _ => return Ok(make_response("알 수 없는 하위 명령입니다.".to_string())),
+    };
+
+    match result {
+        Ok(content) => Ok(make_response(content)),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("사용량 조회 실패: {}", e));
+            Ok(make_response(format!("사용량을 조회하지 못했습니다. ({})", e)))
+        }
+    }
+}
+
+fn days_option() -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::Integer, "days", "조회할 기간(일), 기본 30일")
+        .min_int_value(1)
+        .max_int_value(365)
+        .required(false)
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("usage")
+        .description("Gemini 토큰 사용량과 예상 비용을 보여줍니다.")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "guild", "이 길드의 모델별/사용자별 사용량 (서버 관리자 전용)")
+                .add_sub_option(days_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "me", "내 모델별 사용량")
+                .add_sub_option(days_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "all", "전체 길드별 사용량 (관리자 전용)")
+                .add_sub_option(days_option()),
+        )
+}
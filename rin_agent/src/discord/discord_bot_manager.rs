@@ -18,6 +18,7 @@ use serenity::all::EditMessage;
 use serenity::all::Guild;
 use serenity::all::GuildId;
 use serenity::all::Http;
+use serenity::all::ShardStageUpdateEvent;
 use serenity::all::UnavailableGuild;
 use serenity::client::Context;
 use serenity::prelude::*;
@@ -26,6 +27,7 @@ use serenity::model::gateway::Ready;
 use serenity::model::gateway::GatewayIntents;
 use serenity::model::application::{Command, Interaction};
 use songbird::SerenityInit;
+use songbird::driver::{DecodeConfig, DecodeMode};
 use sqlx::types::chrono;
 use tokio::sync::watch::Receiver;
 use std::collections::HashMap;
@@ -49,10 +51,14 @@ use crate::service::discord_error_msg::send_additional_log;
 use crate::service::discord_error_msg::send_debug_error_log;
 use crate::service::discord_message_service::{MessageSendReceiver, MessageSendSender, create_message_channel, init_message_sender};
 use crate::service::voice_session_manager;
+use crate::service::health_service::set_discord_shard_stage;
+use crate::libs::shutdown::{is_shutting_down, IN_FLIGHT_WORK};
+use crate::service::metrics_service::record_command;
+use std::time::Instant;
 use std::sync::LazyLock;
-use tokio::signal;
 
 use super::commands::gemini_query;
+use super::commands::receipt;
 use super::constant::DISCORD_DB_ERROR;
 
 macro_rules! register_commands_module {
@@ -124,7 +130,15 @@ define_lazy_static!(USING_COMMANDS, USING_ACTIVATE_COMMANDS,
         gemini_query,
         lutica_repo,
         join_voice,
-        leave_voice
+        leave_voice,
+        transcribe,
+        usage,
+        model_routing,
+        structured,
+        gemini_tools,
+        search_provider,
+        debt,
+        receipt
     ]
 );
 
@@ -135,6 +149,7 @@ pub async fn remove_message_process_map_entry(message_id: u64) {
     MESSAGE_PROCESS_MAP.lock().await.remove(&message_id);
 }
 
+const SHUTTING_DOWN_MESSAGE: &str = "Rin 이 종료 중입니다. 잠시 후 다시 시도해 주세요.";
 static CLIENT_ID: LazyLock<Option<UserId>> = LazyLock::new(|| (std::env::var("DISCORD_CLIENT_ID").ok()).and_then(|id| id.parse::<u64>().ok()).map(UserId::new));
 
 fn make_message_embed(title: &str, description: &str) -> CreateEmbed {
@@ -158,9 +173,68 @@ pub struct BotManager {
     alarm_channel: &'static Receiver<GeminiFunctionAlarm<Option<tb_alarm_model::Model>>>,
     pub message_sender: Option<MessageSendSender>,
     message_receiver: Option<MessageSendReceiver>,
+    // 한 번 start 한 Client 는 다시 연결하지 못하므로, 재시작 때 새로 만들었는지 판단합니다.
+    client_started: bool,
 }
 static DISCORD_SERVICE: OnceLock<rs_ervice::RSContext> = OnceLock::new();
 static MESSAGE_RECEIVER: OnceLock<std::sync::Mutex<Option<MessageSendReceiver>>> = OnceLock::new();
+// run 중에는 BotManager 가 잠겨 있으므로, 종료 절차에서 샤드를 닫을 수 있게 따로 들고 있습니다.
+// 재시작 때마다 Client 를 새로 만들기 때문에 마지막 Client 의 것으로 바꿔 끼웁니다.
+static SHARD_MANAGER: std::sync::RwLock<Option<Arc<serenity::gateway::ShardManager>>> = std::sync::RwLock::new(None);
+static DISCORD_CACHE: std::sync::RwLock<Option<Arc<serenity::cache::Cache>>> = std::sync::RwLock::new(None);
+
+/// 게이트웨이로 받은 길드 수. 봇이 아직 만들어지지 않았으면 0 입니다.
+pub fn connected_guild_count() -> usize {
+    DISCORD_CACHE
+        .read()
+        .ok()
+        .and_then(|cache| cache.as_ref().map(|cache| cache.guilds().len()))
+        .unwrap_or(0)
+}
+
+/// 모든 샤드의 게이트웨이 연결을 닫습니다. `client.start()` 가 끝나면서 Discord 작업도 함께 끝납니다.
+pub async fn shutdown_discord_shards() {
+    let shard_manager = SHARD_MANAGER.read().ok().and_then(|shard_manager| shard_manager.clone());
+    if let Some(shard_manager) = shard_manager {
+        shard_manager.shutdown_all().await;
+    }
+}
+
+// 새 Client 를 만들고 종료 절차와 상태 보고가 보는 샤드 매니저, 캐시를 이 Client 의 것으로 바꿉니다.
+async fn build_client() -> Result<Client, serenity::Error> {
+    let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
+    let intents = GatewayIntents::GUILDS
+        |GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::DIRECT_MESSAGES
+        | GatewayIntents::GUILD_VOICE_STATES // 음성 상태를 위해 필수!
+        | GatewayIntents::MESSAGE_CONTENT;
+    let client = Client::builder(token, intents)
+            .event_handler(Handler)
+            .voice_manager(
+                VoiceHandler {}
+            )
+            // 음성 세션에서 화자별 음성을 받기 위해 수신 오디오를 디코딩합니다.
+            .register_songbird_from_config(
+                songbird::Config::default()
+                    .decode_mode(DecodeMode::Decode(DecodeConfig::default()))
+            )
+            .await?;
+    {
+        let mut data = client.data.write().await;
+        if let Some(voice_manager) = client.voice_manager.as_ref() {
+            data.insert::<VoiceHandler>(
+                Arc::new(Mutex::new(Arc::clone(voice_manager)))
+            )
+        }
+    }
+    if let Ok(mut shard_manager) = SHARD_MANAGER.write() {
+        *shard_manager = Some(client.shard_manager.clone());
+    }
+    if let Ok(mut cache) = DISCORD_CACHE.write() {
+        *cache = Some(client.cache.clone());
+    }
+    Ok(client)
+}
 
 
 pub fn get_message_receiver() -> Option<MessageSendReceiver> {
@@ -185,30 +259,9 @@ pub async fn get_discord_service() -> &'static RSContext {
 
 impl BotManager{
     pub async fn new() -> (Self, MessageSendReceiver) {
-        let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
-        let intents = GatewayIntents::GUILDS
-            |GatewayIntents::GUILD_MESSAGES
-            | GatewayIntents::DIRECT_MESSAGES
-            | GatewayIntents::GUILD_VOICE_STATES // 음성 상태를 위해 필수!
-            | GatewayIntents::MESSAGE_CONTENT;
         let gemini_function_channel = &GEMINI_FUNCTION_EXECUTION_ALARM.receiver;
         let alarm_channel = &SCHEDULE_TO_DISCORD_PIPELINE.receiver;
-        let client = Client::builder(token, intents)
-                .event_handler(Handler)
-                .voice_manager(
-                    VoiceHandler {}
-                )
-                .register_songbird()
-                .await
-                .expect("Error creating client");
-        {
-            let mut data = client.data.write().await;
-            if let Some(voice_manager) = client.voice_manager.as_ref() {
-                data.insert::<VoiceHandler>(
-                    Arc::new(Mutex::new(Arc::clone(voice_manager)))
-                )
-            }
-        }
+        let client = build_client().await.expect("Error creating client");
         let (message_sender, message_receiver) = create_message_channel();
         
         // 전역 메시지 리시버 저장소 초기화 (빈 상태로)
@@ -221,6 +274,7 @@ impl BotManager{
             alarm_channel,
             message_sender: Some(message_sender),
             message_receiver: Some(message_receiver),
+            client_started: false,
         }, MESSAGE_RECEIVER.get().unwrap().lock().unwrap().take().unwrap_or_else(|| {
             let (_, rx) = create_message_channel();
             rx
@@ -230,26 +284,33 @@ impl BotManager{
         channel_id.send_message(&self.client.http, content).await
     }
     pub async fn run(&mut self) -> Result<(), serenity::Error> {
-        let mut message_receiver = self.message_receiver.take()
-            .ok_or_else(|| serenity::Error::Other("Message receiver not available"))?;
+        // 앞선 start 가 끝나면 샤드 큐가 이미 멈춰 있어 같은 Client 로는 다시 연결되지 않습니다.
+        if self.client_started {
+            LOGGER.log(LogLevel::Info, "Discord > Rebuilding client for restart");
+            self.client = build_client().await?;
+            self.client_started = false;
+        }
         let mut fun_alarm_receiver = self.gemini_function_channel.to_owned();
         let mut alarm_channel = self.alarm_channel.clone();
         let client_control = self.client.http.clone();
         
         // 메시지 전송 채널 처리를 위한 태스크 생성
-        let http_for_messages = self.client.http.clone();
-        tokio::spawn(async move {
-            while let Some(request) = message_receiver.recv().await {
-                let result = request.channel_id
-                    .send_message(&http_for_messages, request.content)
-                    .await;
-                
-                let _ = request.response_sender.send(result);
-            }
-            LOGGER.log(LogLevel::Debug, "Message sender channel closed");
-        });
+        // 재시작 때는 처음 띄운 태스크가 계속 돌고 있으므로 다시 만들지 않는다.
+        if let Some(mut message_receiver) = self.message_receiver.take() {
+            let http_for_messages = self.client.http.clone();
+            tokio::spawn(async move {
+                while let Some(request) = message_receiver.recv().await {
+                    let result = request.channel_id
+                        .send_message(&http_for_messages, request.content)
+                        .await;
+                    
+                    let _ = request.response_sender.send(result);
+                }
+                LOGGER.log(LogLevel::Debug, "Message sender channel closed");
+            });
+        }
         
-        tokio::spawn(async move {
+        let dispatcher = tokio::spawn(async move {
             loop {
                 tokio::select! {
                     res = fun_alarm_receiver.changed() => {
@@ -357,24 +418,21 @@ impl BotManager{
                             }
                         }
                     }
-
-                res = signal::ctrl_c() => {
-                        match res {
-                            Ok(_) => {
-                                LOGGER.log(LogLevel::Info, "SIGINT received, shutting down...");
-                                std::process::exit(0);
-                            }
-                            Err(err) => {
-                                LOGGER.log(LogLevel::Error, &format!("Failed to listen for SIGINT: {:?}", err));
-                            }
-                        }
-                    }
                 }
             }
         });
         
-        // Discord 봇 시작
-        self.client.start().await
+        // 새 Client 를 만드는 사이 종료가 시작됐으면 연결하지 않습니다.
+        if is_shutting_down() {
+            dispatcher.abort();
+            return Ok(());
+        }
+
+        // Discord 봇 시작. 끝나면 감시자가 다시 run 을 부르므로 알람 처리 태스크도 함께 정리한다.
+        self.client_started = true;
+        let result = self.client.start().await;
+        dispatcher.abort();
+        result
     }
 
     
@@ -426,6 +484,7 @@ impl RSContextService for BotManager {
 impl EventHandler for Handler {
     //https://github.com/serenity-rs/serenity/blob/current/examples/e14_slash_commands/src/main.rs
     async fn ready(&self, ctx: Context, _ready: Ready) {
+        set_discord_shard_stage(ctx.shard_id.0, "connected");
         // Delete remaining commands and register new ones
         let db = DB_CONNECTION_POOL.get().expect("Database connection not initialized");
         let guilds = ctx.cache.guilds().len();
@@ -450,6 +509,10 @@ impl EventHandler for Handler {
         }
         LOGGER.log(LogLevel::Info, "Discord > Bot is ready!");
     }
+    async fn shard_stage_update(&self, _ctx: Context, event: ShardStageUpdateEvent) {
+        LOGGER.log(LogLevel::Info, &format!("Discord > Shard {} stage: {:?} -> {:?}", event.shard_id.0, event.old, event.new));
+        set_discord_shard_stage(event.shard_id.0, &format!("{:?}", event.new));
+    }
     async fn guild_create(&self, ctx: Context, guild: Guild, is_new: Option<bool>) {
 
         LOGGER.log(LogLevel::Info, &format!("Guild created: {:?}", guild.id));
@@ -489,6 +552,12 @@ impl EventHandler for Handler {
                 if content.len() > 0 || cpy_msg.attachments.len() > 0 {
                     // Handle the query here
                     LOGGER.log(LogLevel::Info, &format!("Query found: {:?}", content));
+                    // 종료 중에는 새 질의를 받지 않고, 진행 중인 질의는 끝날 때까지 기다린다.
+                    if is_shutting_down() {
+                        let _ = msg.reply(&ctx, SHUTTING_DOWN_MESSAGE).await;
+                        return;
+                    }
+                    let _in_flight = IN_FLIGHT_WORK.begin();
                     // Directly call the async function without catch_unwind
                     let cq = gemini_query::continue_query(&ctx, &msg, &msg.author).await;
                     if let Err(err) = cq {
@@ -516,20 +585,20 @@ impl EventHandler for Handler {
                 // and send a response back to the user.
                 let command_future = &USING_ACTIVATE_COMMANDS;
 
-                if let Err(err) = command_future(command_name.clone(), &ctx, &command).await {
+                if is_shutting_down() {
+                    let _ = command.create_response(&ctx, CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new().content(SHUTTING_DOWN_MESSAGE).ephemeral(true)
+                    )).await;
+                    return;
+                }
+                let _in_flight = IN_FLIGHT_WORK.begin();
+                let started = Instant::now();
+                let result = command_future(command_name.clone(), &ctx, &command).await;
+                record_command(&command_name, result.is_ok(), started.elapsed());
+                if let Err(err) = result {
                     LOGGER.log(LogLevel::Error, &format!("Discord > Error executing command {}: {:?}", command_name, err));
-                    if matches!(err, serenity::Error::Other("Gemini API Error")) {
-                        LOGGER.log(LogLevel::Error,  err.to_string().as_str());
-                        command.channel_id.send_message(ctx, 
-                            CreateMessage::new().embed(
-                                make_message_embed("Gemini API Error", "Gemini API Error - Please contact the administrator: @lutica_canard")
-                                .footer(
-                                    CreateEmbedFooter::new("time... : ".to_string() + &chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string())
-                                )
-                                .color(0xFF0000)
-                            )                         
-                        ).await.unwrap();
-                    } else if matches!(err, serenity::Error::Other(DISCORD_DB_ERROR)) {
+                    // Gemini 오류는 gemini_query 가 이유를 직접 안내하므로 여기서는 다루지 않습니다.
+                    if matches!(err, serenity::Error::Other(DISCORD_DB_ERROR)) {
                         LOGGER.log(LogLevel::Error, err.to_string().as_str());
                         command.channel_id.send_message(ctx, 
                             CreateMessage::new().embed(
@@ -562,6 +631,11 @@ impl EventHandler for Handler {
                 }
 
             }
+            Interaction::Component(component) if component.data.custom_id.starts_with(receipt::RECEIPT_SPLIT_PREFIX) => {
+                if let Err(err) = receipt::handle_component(&ctx, &component).await {
+                    LOGGER.log(LogLevel::Error, &format!("Discord > Error handling receipt button: {:?}", err));
+                }
+            }
             Interaction::Ping(ping) =>{
                 LOGGER.log(LogLevel::Debug, "Discord > Ping interaction received");
             }
@@ -1,4 +1,4 @@
-use serenity::all::{Context, CreateInteractionResponse, Guild, GuildId, PartialGuild};
+use serenity::all::{Channel, ChannelId, Context, CreateInteractionResponse, Guild, GuildId, Http, PartialGuild, UserId};
 
 pub enum GuildInfo {
     Full(Guild),
@@ -27,4 +27,38 @@ pub async fn get_guild_info(ctx: &Context, guild_id: GuildId) -> Result<GuildInf
 pub struct GuildCommandResponse {
   pub content: CreateInteractionResponse,
   pub do_not_send : bool, // If true, do not send the response immediately
+}
+
+// 채널이 어느 길드에 속하는지, DM 이면 상대가 누구인지
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelScope {
+    Guild(u64),
+    Direct { recipient_id: u64 },
+}
+
+// 디스코드 Context 가 없는 곳(웹 API 등)에서 쓰는 봇 토큰 클라이언트
+fn bot_http() -> Result<Http, String> {
+    let token = std::env::var("DISCORD_TOKEN").map_err(|_| "DISCORD_TOKEN is not set".to_string())?;
+    Ok(Http::new(&token))
+}
+
+pub async fn fetch_channel_scope(channel_id: u64) -> Result<ChannelScope, String> {
+    if channel_id == 0 {
+        return Err("잘못된 채널입니다.".to_string());
+    }
+    match bot_http()?.get_channel(ChannelId::new(channel_id)).await {
+        Ok(Channel::Guild(channel)) => Ok(ChannelScope::Guild(channel.guild_id.get())),
+        Ok(Channel::Private(channel)) => Ok(ChannelScope::Direct { recipient_id: channel.recipient.id.get() }),
+        Ok(_) => Err("지원하지 않는 채널입니다.".to_string()),
+        Err(e) => Err(format!("채널을 찾을 수 없습니다: {}", e)),
+    }
+}
+
+// 유저와의 DM 채널을 열고 (이미 있으면 그대로) 채널 ID 를 돌려줍니다.
+pub async fn open_dm_channel(user_id: u64) -> Result<u64, String> {
+    UserId::new(user_id)
+        .create_dm_channel(&bot_http()?)
+        .await
+        .map(|channel| channel.id.get())
+        .map_err(|e| format!("DM 채널을 열 수 없습니다: {}", e))
 }
\ No newline at end of file
@@ -1 +1,3 @@
-pub mod voice_thread_manager;
\ No newline at end of file
+pub mod voice_thread_manager;
+pub mod pcm_stream;
+pub mod voice_receiver;
\ No newline at end of file
@@ -0,0 +1,218 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use symphonia_core::io::MediaSource;
+
+/// Gemini Live 가 내려주는 오디오 포맷 (s16le, mono)
+pub const GEMINI_OUTPUT_SAMPLE_RATE: u32 = 24_000;
+/// songbird(Discord) 가 사용하는 재생 포맷 (f32, stereo)
+pub const DISCORD_SAMPLE_RATE: u32 = 48_000;
+pub const DISCORD_CHANNELS: u32 = 2;
+/// Gemini Live 에 보내는 입력 포맷 (s16le, mono)
+pub const GEMINI_INPUT_SAMPLE_RATE: u32 = 16_000;
+
+/// 버퍼가 비었을 때 한 번에 채워 줄 무음 길이 (20ms, 스테레오 f32)
+const SILENCE_BYTES: usize = (DISCORD_SAMPLE_RATE as usize / 50) * DISCORD_CHANNELS as usize * 4;
+
+// --- s16le mono PCM 을 f32 stereo 로 선형 보간 리샘플링 ---
+// 청크 경계에서 샘플이 잘려 들어와도 이어서 처리할 수 있도록 상태를 유지합니다.
+#[derive(Debug, Clone)]
+pub struct PcmResampler {
+    step: f64,
+    position: f64,
+    prev_sample: f32,
+    pending_byte: Option<u8>,
+}
+
+impl PcmResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        PcmResampler {
+            step: from_rate as f64 / to_rate as f64,
+            position: 0.0,
+            prev_sample: 0.0,
+            pending_byte: None,
+        }
+    }
+
+    /// 24kHz mono -> 48kHz stereo 기본 리샘플러
+    pub fn gemini_to_discord() -> Self {
+        Self::new(GEMINI_OUTPUT_SAMPLE_RATE, DISCORD_SAMPLE_RATE)
+    }
+
+    /// 끊긴 스트림을 새로 시작할 때 보간 상태를 초기화합니다.
+    pub fn reset(&mut self) {
+        self.position = 0.0;
+        self.prev_sample = 0.0;
+        self.pending_byte = None;
+    }
+
+    /// s16le mono 바이트를 받아 interleaved f32 stereo 샘플로 변환합니다.
+    pub fn process(&mut self, chunk: &[u8]) -> Vec<f32> {
+        let mut bytes = Vec::with_capacity(chunk.len() + 1);
+        if let Some(b) = self.pending_byte.take() {
+            bytes.push(b);
+        }
+        bytes.extend_from_slice(chunk);
+        if bytes.len() % 2 == 1 {
+            self.pending_byte = bytes.pop();
+        }
+
+        let ratio = (1.0 / self.step).ceil() as usize;
+        let mut out = Vec::with_capacity(bytes.len() / 2 * ratio * DISCORD_CHANNELS as usize);
+        for pair in bytes.chunks_exact(2) {
+            let current = i16::from_le_bytes([pair[0], pair[1]]) as f32 / 32768.0;
+            while self.position < 1.0 {
+                let sample = self.prev_sample + (current - self.prev_sample) * self.position as f32;
+                out.push(sample);
+                out.push(sample);
+                self.position += self.step;
+            }
+            self.position -= 1.0;
+            self.prev_sample = current;
+        }
+        out
+    }
+
+    /// process 결과를 songbird RawAdapter 가 읽는 f32le 바이트로 변환합니다.
+    pub fn process_to_bytes(&mut self, chunk: &[u8]) -> Vec<u8> {
+        self.process(chunk)
+            .into_iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect()
+    }
+}
+
+// --- 재생 대기 중인 하나의 클립(발화) 버퍼 ---
+// 매니저 태스크가 쓰고, songbird 믹서 스레드가 PcmStreamSource 를 통해 읽습니다.
+#[derive(Debug, Default)]
+pub struct PcmBuffer {
+    data: Mutex<VecDeque<u8>>,
+    closed: AtomicBool,
+    interrupted: AtomicBool,
+}
+
+impl PcmBuffer {
+    pub fn new() -> Arc<Self> {
+        Arc::new(PcmBuffer::default())
+    }
+
+    pub fn push(&self, bytes: &[u8]) {
+        if self.closed.load(Ordering::Acquire) || self.interrupted.load(Ordering::Acquire) {
+            return;
+        }
+        let mut data = self.data.lock().unwrap();
+        data.extend(bytes.iter().copied());
+    }
+
+    /// 더 이상 데이터가 들어오지 않음을 표시합니다. 남은 데이터를 재생한 뒤 트랙이 끝납니다.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+    }
+
+    /// 남은 데이터를 버리고 즉시 트랙을 끝냅니다.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Release);
+        self.data.lock().unwrap().clear();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Acquire)
+    }
+
+    pub fn buffered_len(&self) -> usize {
+        self.data.lock().unwrap().len()
+    }
+}
+
+// --- songbird RawAdapter 에 넘길 동기 MediaSource ---
+// 믹서 스레드를 막지 않도록, 데이터가 아직 도착하지 않았으면 무음을 채워 반환합니다.
+pub struct PcmStreamSource {
+    buffer: Arc<PcmBuffer>,
+}
+
+impl PcmStreamSource {
+    pub fn new(buffer: Arc<PcmBuffer>) -> Self {
+        PcmStreamSource { buffer }
+    }
+}
+
+impl Read for PcmStreamSource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.buffer.is_interrupted() {
+            return Ok(0);
+        }
+
+        let mut data = self.buffer.data.lock().unwrap();
+        if !data.is_empty() {
+            // f32 샘플 경계가 깨지지 않도록 4바이트 단위로 잘라 읽습니다.
+            let mut len = buf.len().min(data.len());
+            if len >= 4 {
+                len -= len % 4;
+            }
+            for (dst, src) in buf.iter_mut().zip(data.drain(..len)) {
+                *dst = src;
+            }
+            return Ok(len);
+        }
+        drop(data);
+
+        if self.buffer.is_closed() {
+            return Ok(0);
+        }
+
+        let len = buf.len().min(SILENCE_BYTES);
+        let len = if len >= 8 { len - len % 8 } else { len };
+        buf[..len].fill(0);
+        Ok(len)
+    }
+}
+
+impl Seek for PcmStreamSource {
+    fn seek(&mut self, _pos: SeekFrom) -> io::Result<u64> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "PcmStreamSource is not seekable"))
+    }
+}
+
+impl MediaSource for PcmStreamSource {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// songbird 가 디코딩한 48kHz stereo i16 을 Gemini Live 입력용 16kHz mono s16le 로 변환합니다.
+/// 좌우 채널을 평균낸 뒤 3 프레임씩 평균내어 간단한 저역 통과 겸 다운샘플링을 합니다.
+pub fn downmix_to_gemini_input(samples: &[i16]) -> Vec<u8> {
+    let factor = (DISCORD_SAMPLE_RATE / GEMINI_INPUT_SAMPLE_RATE) as usize;
+    let frame_len = DISCORD_CHANNELS as usize * factor;
+    let mut out = Vec::with_capacity(samples.len() / frame_len * 2);
+    for group in samples.chunks_exact(frame_len) {
+        let sum: i32 = group.iter().map(|s| *s as i32).sum();
+        let avg = (sum / frame_len as i32) as i16;
+        out.extend_from_slice(&avg.to_le_bytes());
+    }
+    out
+}
+
+/// 여러 화자의 같은 틱 오디오를 하나로 합칩니다. (포화 덧셈)
+pub fn mix_voices<'a>(voices: impl IntoIterator<Item = &'a [i16]>) -> Vec<i16> {
+    let mut mixed: Vec<i16> = Vec::new();
+    for voice in voices {
+        if mixed.len() < voice.len() {
+            mixed.resize(voice.len(), 0);
+        }
+        for (dst, src) in mixed.iter_mut().zip(voice.iter()) {
+            *dst = dst.saturating_add(*src);
+        }
+    }
+    mixed
+}
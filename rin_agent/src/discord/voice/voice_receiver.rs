@@ -0,0 +1,125 @@
+use dashmap::DashMap;
+use serenity::all::UserId;
+use serenity::async_trait;
+use songbird::{Event, EventContext, EventHandler as VoiceEventHandler};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+use crate::discord::voice::pcm_stream::{downmix_to_gemini_input, mix_voices};
+use crate::gemini::live_voice_bridge::VoiceBridgeInput;
+
+// --- songbird 가 디코딩한 화자 음성을 Live 세션으로 넘기는 수신 핸들러 ---
+// 20ms 틱마다 말하고 있는 모든 화자의 음성을 합쳐 16kHz mono 로 변환해 보냅니다.
+// 송신부는 약한 참조로만 들고 있어, 세션이 끝나면 핸들러도 스스로 해제됩니다.
+#[derive(Clone)]
+pub struct VoiceReceiver {
+    sender: mpsc::WeakSender<VoiceBridgeInput>,
+    speaking: Arc<AtomicBool>,
+}
+
+impl VoiceReceiver {
+    pub fn new(sender: &mpsc::Sender<VoiceBridgeInput>) -> Self {
+        VoiceReceiver {
+            sender: sender.downgrade(),
+            speaking: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+#[async_trait]
+impl VoiceEventHandler for VoiceReceiver {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let EventContext::VoiceTick(tick) = ctx else {
+            return None;
+        };
+        let Some(sender) = self.sender.upgrade() else {
+            return Some(Event::Cancel);
+        };
+
+        let mixed = mix_voices(
+            tick.speaking
+                .values()
+                .filter_map(|data| data.decoded_voice.as_deref()),
+        );
+
+        if mixed.is_empty() {
+            // 모두 조용해졌으면 한 번만 스트림 종료를 알립니다.
+            if self.speaking.swap(false, Ordering::Relaxed) {
+                let _ = sender.try_send(VoiceBridgeInput::AudioStreamEnd);
+            }
+            return None;
+        }
+
+        self.speaking.store(true, Ordering::Relaxed);
+        // 믹서 스레드를 막지 않도록 채널이 가득 차면 해당 틱은 버립니다.
+        let _ = sender.try_send(VoiceBridgeInput::Audio(downmix_to_gemini_input(&mixed)));
+        None
+    }
+}
+
+/// 받아쓰기용 화자별 오디오
+#[derive(Debug)]
+pub enum SpeakerAudio {
+    /// 16kHz mono s16le PCM
+    Audio(UserId, Vec<u8>),
+    /// 해당 화자가 말을 멈춤
+    End(UserId),
+}
+
+// --- 화자를 구분해서 음성을 넘기는 받아쓰기용 수신 핸들러 ---
+// SpeakingStateUpdate 로 SSRC -> 유저를 기억해 두고, 틱마다 화자별로 따로 보냅니다.
+#[derive(Clone)]
+pub struct SpeakerReceiver {
+    sender: mpsc::WeakSender<SpeakerAudio>,
+    speakers: Arc<DashMap<u32, UserId>>,
+    active: Arc<Mutex<HashSet<u32>>>,
+}
+
+impl SpeakerReceiver {
+    pub fn new(sender: &mpsc::Sender<SpeakerAudio>) -> Self {
+        SpeakerReceiver {
+            sender: sender.downgrade(),
+            speakers: Arc::new(DashMap::new()),
+            active: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl VoiceEventHandler for SpeakerReceiver {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let Some(sender) = self.sender.upgrade() else {
+            return Some(Event::Cancel);
+        };
+
+        match ctx {
+            EventContext::SpeakingStateUpdate(speaking) => {
+                if let Some(user_id) = speaking.user_id {
+                    self.speakers.insert(speaking.ssrc, UserId::new(user_id.0));
+                }
+            }
+            EventContext::VoiceTick(tick) => {
+                let mut active = self.active.lock().unwrap();
+                for (ssrc, data) in tick.speaking.iter() {
+                    let (Some(voice), Some(user_id)) = (data.decoded_voice.as_deref(), self.speakers.get(ssrc)) else {
+                        continue;
+                    };
+                    active.insert(*ssrc);
+                    let _ = sender.try_send(SpeakerAudio::Audio(*user_id, downmix_to_gemini_input(voice)));
+                }
+                for ssrc in tick.silent.iter() {
+                    if !active.remove(ssrc) {
+                        continue;
+                    }
+                    if let Some(user_id) = self.speakers.get(ssrc) {
+                        let _ = sender.try_send(SpeakerAudio::End(*user_id));
+                    }
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
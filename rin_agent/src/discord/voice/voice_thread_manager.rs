@@ -1,45 +1,164 @@
 use bytes::Bytes;
 use dashmap::DashMap;
 use serenity::all::{ChannelId, Context, GuildId};
-use songbird::{Call, Songbird};
-use std::io;
-use std::pin::Pin;
+use serenity::async_trait;
+use songbird::input::{Input, RawAdapter};
+use songbird::tracks::TrackHandle;
+use songbird::{Call, Event, EventContext, EventHandler as VoiceEventHandler, TrackEvent};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, LazyLock};
-use std::task::{self, Poll};
-use tokio::io::AsyncRead;
 use tokio::sync::{mpsc, Mutex};
 
+use crate::discord::voice::pcm_stream::{PcmBuffer, PcmResampler, PcmStreamSource, DISCORD_CHANNELS, DISCORD_SAMPLE_RATE};
 use crate::libs::logger::{LogLevel, LOGGER};
 
-// --- 오디오 청크를 비동기 채널로부터 읽어오는 커스텀 리더 ---
-// songbird가 오디오를 재생할 수 있도록 AsyncRead 트레이트를 구현합니다.
-pub struct ChunkStream {
-    rx: mpsc::Receiver<Bytes>,
-}
+pub const DEFAULT_VOLUME: f32 = 1.0;
+const MAX_VOLUME: f32 = 2.0;
 
-impl AsyncRead for ChunkStream {
-    fn poll_read(
-        mut self: Pin<&mut Self>,
-        cx: &mut task::Context<'_>,
-        buf: &mut tokio::io::ReadBuf<'_>,
-    ) -> Poll<io::Result<()>> {
-        // 채널에서 다음 오디오 청크를 비동기적으로 수신 시도합니다.
-        match self.rx.poll_recv(cx) {
-            Poll::Ready(Some(chunk)) => {
-                buf.put_slice(&chunk); // 버퍼에 데이터 복사
-                Poll::Ready(Ok(()))
-            }
-            Poll::Ready(None) => Poll::Ready(Ok(())), // 채널이 닫힘 (스트림 끝)
-            Poll::Pending => Poll::Pending,           // 데이터가 아직 없음
-        }
-    }
+// --- 재생 태스크로 보내는 명령 ---
+#[derive(Debug)]
+pub enum PlaybackCommand {
+    /// 24kHz mono s16le PCM 청크. 현재 작성 중인 클립 뒤에 이어 붙습니다.
+    Chunk(Bytes),
+    /// 현재 클립을 닫습니다. 이후 들어오는 청크는 다음 클립으로 큐에 쌓입니다.
+    EndOfClip,
+    /// 재생 중인 트랙과 대기 중인 클립을 모두 버립니다. (barge-in)
+    Interrupt,
+    SetVolume(f32),
+    TrackEnded(u64),
 }
 
 // --- 길드별 음성 상태를 저장하는 구조체 ---
-// 음성 핸들러와 오디오 청크를 보낼 채널의 송신부를 가집니다.
+// 음성 핸들러와 재생 태스크로 명령을 보낼 채널의 송신부를 가집니다.
 pub struct GuildVoiceState {
     pub call: Arc<Mutex<Call>>,
-    pub audio_sender: mpsc::Sender<Bytes>,
+    pub audio_sender: mpsc::Sender<PlaybackCommand>,
+    volume: AtomicU32,
+}
+
+impl GuildVoiceState {
+    pub fn volume(&self) -> f32 {
+        f32::from_bits(self.volume.load(Ordering::Relaxed))
+    }
+}
+
+// --- 트랙 종료를 재생 태스크에 알려주는 이벤트 핸들러 ---
+struct TrackEndNotifier {
+    clip_id: u64,
+    sender: mpsc::WeakSender<PlaybackCommand>,
+}
+
+#[async_trait]
+impl VoiceEventHandler for TrackEndNotifier {
+    async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+        if let Some(sender) = self.sender.upgrade() {
+            let _ = sender.send(PlaybackCommand::TrackEnded(self.clip_id)).await;
+        }
+        None
+    }
+}
+
+struct PlayingClip {
+    id: u64,
+    buffer: Arc<PcmBuffer>,
+    handle: TrackHandle,
+}
+
+// --- 길드 하나의 재생 큐를 관리하는 태스크 ---
+// 청크를 48kHz stereo 로 변환해 클립 버퍼에 쌓고, 클립 단위로 songbird 트랙을 순서대로 재생합니다.
+struct PlaybackWorker {
+    guild_id: GuildId,
+    call: Arc<Mutex<Call>>,
+    sender: mpsc::WeakSender<PlaybackCommand>,
+    resampler: PcmResampler,
+    queue: VecDeque<(u64, Arc<PcmBuffer>)>,
+    writing: Option<Arc<PcmBuffer>>,
+    playing: Option<PlayingClip>,
+    next_clip_id: u64,
+    volume: f32,
+}
+
+impl PlaybackWorker {
+    async fn run(mut self, mut rx: mpsc::Receiver<PlaybackCommand>) {
+        while let Some(command) = rx.recv().await {
+            match command {
+                PlaybackCommand::Chunk(chunk) => {
+                    let pcm = self.resampler.process_to_bytes(&chunk);
+                    let buffer = match &self.writing {
+                        Some(buffer) => buffer.clone(),
+                        None => {
+                            let buffer = PcmBuffer::new();
+                            self.queue.push_back((self.next_clip_id, buffer.clone()));
+                            self.next_clip_id += 1;
+                            self.writing = Some(buffer.clone());
+                            buffer
+                        }
+                    };
+                    buffer.push(&pcm);
+                    if self.playing.is_none() {
+                        self.start_next().await;
+                    }
+                }
+                PlaybackCommand::EndOfClip => {
+                    if let Some(buffer) = self.writing.take() {
+                        buffer.close();
+                    }
+                    self.resampler.reset();
+                }
+                PlaybackCommand::Interrupt => {
+                    self.writing = None;
+                    for (_, buffer) in self.queue.drain(..) {
+                        buffer.interrupt();
+                    }
+                    if let Some(playing) = self.playing.take() {
+                        playing.buffer.interrupt();
+                        let _ = playing.handle.stop();
+                    }
+                    self.resampler.reset();
+                }
+                PlaybackCommand::SetVolume(volume) => {
+                    self.volume = volume;
+                    if let Some(playing) = &self.playing {
+                        let _ = playing.handle.set_volume(volume);
+                    }
+                }
+                PlaybackCommand::TrackEnded(clip_id) => {
+                    if self.playing.as_ref().is_some_and(|p| p.id == clip_id) {
+                        self.playing = None;
+                        self.start_next().await;
+                    }
+                }
+            }
+        }
+
+        // 채널이 닫힘 (leave) -> 남은 트랙 정리
+        if let Some(playing) = self.playing.take() {
+            playing.buffer.interrupt();
+            let _ = playing.handle.stop();
+        }
+        LOGGER.log(LogLevel::Info, &format!("[VoiceManager] Playback task finished for guild {}", self.guild_id));
+    }
+
+    async fn start_next(&mut self) {
+        let Some((clip_id, buffer)) = self.queue.pop_front() else {
+            return;
+        };
+
+        let source = PcmStreamSource::new(buffer.clone());
+        let input: Input = RawAdapter::new(source, DISCORD_SAMPLE_RATE, DISCORD_CHANNELS).into();
+        let handle = self.call.lock().await.play_input(input);
+        let _ = handle.set_volume(self.volume);
+
+        for event in [TrackEvent::End, TrackEvent::Error] {
+            let notifier = TrackEndNotifier { clip_id, sender: self.sender.clone() };
+            if let Err(e) = handle.add_event(Event::Track(event), notifier) {
+                LOGGER.log(LogLevel::Warning, &format!("[VoiceManager] Failed to attach track event: {:?}", e));
+            }
+        }
+
+        self.playing = Some(PlayingClip { id: clip_id, buffer, handle });
+    }
 }
 
 // --- 싱글턴 음성 매니저 ---
@@ -78,25 +197,29 @@ impl VoiceManager {
             }
         };
 
-        // 오디오 청크를 전송할 채널을 생성합니다.
+        // 재생 명령을 전송할 채널을 생성합니다.
         let (audio_sender, audio_receiver) = mpsc::channel(100);
 
         // 길드 상태 객체를 생성합니다.
         let state = Arc::new(GuildVoiceState {
             call: call_lock.clone(),
-            audio_sender,
+            audio_sender: audio_sender.clone(),
+            volume: AtomicU32::new(DEFAULT_VOLUME.to_bits()),
         });
 
-        // 이 길드의 음성 연결을 위한 전용 태스크를 생성합니다.
-        // 이 태스크는 채널에서 오디오를 받아 재생하는 역할만 담당합니다.
-        tokio::spawn(async move {
-            // 현재 Songbird 0.5에서는 AsyncRead 기반의 raw PCM 스트리밍 어댑터가 직접 제공되지 않습니다.
-            // 기존 RawAdapter/RawCodec 코드는 제거했습니다. 필요 시, 파일/URL/FFmpeg 입력으로 대체 구현하세요.
-            // 여기는 채널 소비 루프만 두고, 데이터는 폐기합니다(빌드 안정화 목적의 임시 처리).
-            let mut _stream_reader = ChunkStream { rx: audio_receiver };
-            let _call = call_lock.lock().await;
-            LOGGER.log(LogLevel::Info, &format!("[VoiceManager] Voice connection established (no-op playback) for guild {}", guild_id));
-        });
+        // 이 길드의 음성 연결을 위한 전용 재생 태스크를 생성합니다.
+        let worker = PlaybackWorker {
+            guild_id,
+            call: call_lock,
+            sender: audio_sender.downgrade(),
+            resampler: PcmResampler::gemini_to_discord(),
+            queue: VecDeque::new(),
+            writing: None,
+            playing: None,
+            next_clip_id: 0,
+            volume: DEFAULT_VOLUME,
+        };
+        tokio::spawn(worker.run(audio_receiver));
 
         // 생성된 상태를 DashMap에 저장하고 복사본을 반환합니다.
         self.guild_states.insert(guild_id, state.clone());
@@ -110,7 +233,13 @@ impl VoiceManager {
             .await
             .expect("Songbird Voice client placed in scope at initialization.")
             .clone();
-        
+
+        // 재생 중인 트랙을 먼저 멈춥니다. DashMap 참조를 쥔 채로 await 하지 않도록 송신부만 복제합니다.
+        let sender = self.guild_states.get(&guild_id).map(|state| state.audio_sender.clone());
+        if let Some(sender) = sender {
+            let _ = sender.send(PlaybackCommand::Interrupt).await;
+        }
+
         if let Err(e) = manager.leave(guild_id).await {
             return Err(format!("Failed to leave channel: {:?}", e));
         }
@@ -118,20 +247,64 @@ impl VoiceManager {
         // DashMap에서 상태를 제거합니다.
         // audio_sender가 drop되면서 채널이 닫히고, 재생 태스크는 자동으로 종료됩니다.
         self.guild_states.remove(&guild_id);
-        
+
         Ok(())
     }
 
-    /// 특정 길드의 음성 채널로 오디오 청크를 보냅니다.
+    /// 참여 중인 모든 음성 채널에서 나갑니다. 종료할 때처럼 `Context` 가 없을 때 씁니다.
+    pub async fn leave_all(&self) {
+        let guild_ids: Vec<GuildId> = self.guild_states.iter().map(|entry| *entry.key()).collect();
+        for guild_id in guild_ids {
+            let Some((_, state)) = self.guild_states.remove(&guild_id) else { continue };
+            let _ = state.audio_sender.send(PlaybackCommand::Interrupt).await;
+            if let Err(e) = state.call.lock().await.leave().await {
+                LOGGER.log(LogLevel::Warning, &format!("[VoiceManager] Failed to leave guild {}: {:?}", guild_id, e));
+            }
+        }
+    }
+
+    pub fn get_state(&self, guild_id: GuildId) -> Option<Arc<GuildVoiceState>> {
+        self.guild_states.get(&guild_id).map(|s| s.clone())
+    }
+
+    async fn send_command(&self, guild_id: GuildId, command: PlaybackCommand) -> Result<(), String> {
+        let sender = match self.guild_states.get(&guild_id) {
+            Some(state) => state.audio_sender.clone(),
+            None => return Err("Not in a voice channel in this guild.".to_string()),
+        };
+        sender
+            .send(command)
+            .await
+            .map_err(|e| format!("Failed to send playback command: {:?}", e))
+    }
+
+    /// 특정 길드의 음성 채널로 오디오 청크(24kHz mono s16le)를 보냅니다.
+    /// 재생 중인 클립이 있으면 그 뒤에 이어서 재생됩니다.
     pub async fn play(&self, guild_id: GuildId, chunk: Bytes) -> Result<(), String> {
+        self.send_command(guild_id, PlaybackCommand::Chunk(chunk)).await
+    }
+
+    /// 현재 클립의 끝을 표시합니다. 이후의 청크는 다음 클립으로 큐에 들어갑니다.
+    pub async fn finish_clip(&self, guild_id: GuildId) -> Result<(), String> {
+        self.send_command(guild_id, PlaybackCommand::EndOfClip).await
+    }
+
+    /// 재생 중인 오디오와 대기열을 즉시 비웁니다.
+    pub async fn interrupt(&self, guild_id: GuildId) -> Result<(), String> {
+        self.send_command(guild_id, PlaybackCommand::Interrupt).await
+    }
+
+    /// 길드별 재생 볼륨을 설정합니다. (0.0 ~ 2.0)
+    pub async fn set_volume(&self, guild_id: GuildId, volume: f32) -> Result<(), String> {
+        let volume = volume.clamp(0.0, MAX_VOLUME);
         if let Some(state) = self.guild_states.get(&guild_id) {
-            if let Err(e) = state.audio_sender.send(chunk).await {
-                return Err(format!("Failed to send audio chunk: {:?}", e));
-            }
-            Ok(())
-        } else {
-            Err("Not in a voice channel in this guild.".to_string())
+            state.volume.store(volume.to_bits(), Ordering::Relaxed);
         }
+        self.send_command(guild_id, PlaybackCommand::SetVolume(volume)).await
+    }
+
+    pub fn get_volume(&self, guild_id: GuildId) -> Option<f32> {
+        self.guild_states.get(&guild_id).map(|s| s.volume())
     }
 }
-pub static VOICE_MANAGER: LazyLock<VoiceManager> = LazyLock::new(VoiceManager::default);
\ No newline at end of file
+pub static VOICE_MANAGER: LazyLock<VoiceManager> = LazyLock::new(VoiceManager::default);
@@ -0,0 +1,179 @@
+use entity::tb_discord_guilds;
+use gemini_live_api::types::enums::GeminiCodeExecutionResultOutcome;
+use gemini_live_api::types::{
+    GeminiCodeExecutionTool, GeminiFunctionCallingConfig, GeminiGenerationConfigTool, GeminiGoogleSearchTool,
+    GeminiToolConfigMode, GroundingMetadata, UrlContext,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::gemini::types::GeminiCodeExecution;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::setting::gemini_setting::{GEMINI_BOT_TOOLS_JSON, GEMINI_BOT_TOOLS_MODULES};
+
+// 코드 실행 결과 임베드에 넣을 코드/출력 길이 상한 (글자 수)
+const CODE_PREVIEW_MAX_CHARS: usize = 1500;
+const OUTPUT_PREVIEW_MAX_CHARS: usize = 1000;
+
+// 길드별로 켜는 Gemini 내장 도구. 길드 행이 없으면 모두 꺼져 있습니다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GuildBuiltinTools {
+    pub google_search: bool,
+    pub url_context: bool,
+    pub code_execution: bool,
+}
+
+impl GuildBuiltinTools {
+    pub fn any(&self) -> bool {
+        self.google_search || self.url_context || self.code_execution
+    }
+
+    // 봇 함수 선언 뒤에 켜진 내장 도구를 붙입니다.
+    pub fn config_tools(&self) -> Vec<GeminiGenerationConfigTool> {
+        let mut tools = GEMINI_BOT_TOOLS_JSON.clone();
+        if self.url_context {
+            tools.push(GeminiGenerationConfigTool { url_context: Some(UrlContext {}), ..Default::default() });
+        }
+        if self.google_search {
+            tools.push(GeminiGenerationConfigTool { google_search: Some(GeminiGoogleSearchTool {}), ..Default::default() });
+        }
+        if self.code_execution {
+            tools.push(GeminiGenerationConfigTool { code_execution: Some(GeminiCodeExecutionTool {}), ..Default::default() });
+        }
+        tools
+    }
+
+    // ANY 모드에서는 함수 호출만 나오므로 내장 도구를 쓰려면 AUTO 로 풀어야 합니다.
+    // AUTO 에서는 모델이 response_msg 대신 텍스트로 바로 답할 수 있고, 클라이언트는 둘 다 받습니다.
+    pub fn function_calling_config(&self) -> GeminiFunctionCallingConfig {
+        if self.any() {
+            GeminiFunctionCallingConfig {
+                mode: Some(GeminiToolConfigMode::Auto),
+                allowed_function_names: None,
+            }
+        } else {
+            GeminiFunctionCallingConfig {
+                mode: Some(GeminiToolConfigMode::Any),
+                allowed_function_names: Some(GEMINI_BOT_TOOLS_MODULES.iter().map(|tool| tool.name.clone()).collect()),
+            }
+        }
+    }
+}
+
+pub async fn load_guild_builtin_tools(guild_id: u64) -> GuildBuiltinTools {
+    let Some(db) = DB_CONNECTION_POOL.get() else {
+        return GuildBuiltinTools::default();
+    };
+    match tb_discord_guilds::Entity::find()
+        .filter(tb_discord_guilds::Column::GuildId.eq(guild_id as i64))
+        .one(db)
+        .await
+    {
+        Ok(Some(guild)) => GuildBuiltinTools {
+            google_search: guild.enable_google_search,
+            url_context: guild.enable_url_context,
+            code_execution: guild.enable_code_execution,
+        },
+        Ok(None) => GuildBuiltinTools::default(),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("Builtin tools > 길드 설정 조회 실패: {}", e));
+            GuildBuiltinTools::default()
+        }
+    }
+}
+
+// 근거가 된 문장 뒤에 [1][2] 처럼 출처 번호를 붙입니다.
+// segment 의 바이트 위치는 응답 파트 기준이라 합쳐진 메시지와 어긋날 수 있어, 문장 자체를 찾아 위치를 정합니다.
+pub fn add_citation_markers(text: &str, metadata: &GroundingMetadata) -> String {
+    let mut inserts: Vec<(usize, String)> = Vec::new();
+    for support in &metadata.grounding_supports {
+        let Some(segment) = &support.segment else {
+            continue;
+        };
+        if segment.text.is_empty() || support.grounding_chunk_indices.is_empty() {
+            continue;
+        }
+        let Some(start) = text.find(&segment.text) else {
+            continue;
+        };
+        let marker: String = support
+            .grounding_chunk_indices
+            .iter()
+            .filter(|i| (**i as usize) < metadata.grounding_chunks.len())
+            .map(|i| format!("[{}]", i + 1))
+            .collect();
+        if !marker.is_empty() {
+            inserts.push((start + segment.text.len(), marker));
+        }
+    }
+    // 뒤에서부터 넣어야 앞쪽 위치가 밀리지 않습니다.
+    inserts.sort_by(|a, b| b.0.cmp(&a.0));
+    inserts.dedup_by_key(|(at, _)| *at);
+    let mut result = text.to_string();
+    for (at, marker) in inserts {
+        result.insert_str(at, &marker);
+    }
+    result
+}
+
+// 번호가 붙은 출처 목록과 검색어. 링크 미리보기가 뜨지 않도록 <> 로 감쌉니다.
+pub fn format_sources(metadata: &GroundingMetadata) -> Option<String> {
+    let sources: Vec<String> = metadata
+        .grounding_chunks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, chunk)| chunk.web.as_ref().map(|web| format!("-# [{}] [{}](<{}>)", i + 1, web.title, web.uri)))
+        .collect();
+    if sources.is_empty() {
+        return None;
+    }
+    let mut lines = vec!["-# 출처".to_string()];
+    lines.extend(sources);
+    if !metadata.web_search_queries.is_empty() {
+        lines.push(format!("-# 검색어: {}", metadata.web_search_queries.join(", ")));
+    }
+    Some(lines.join("\n"))
+}
+
+// 답변 본문에 출처 번호와 목록을 붙인 결과
+pub fn render_grounded_text(text: &str, metadata: Option<&GroundingMetadata>) -> String {
+    let Some(metadata) = metadata else {
+        return text.to_string();
+    };
+    let marked = add_citation_markers(text, metadata);
+    match format_sources(metadata) {
+        Some(sources) => format!("{}\n\n{}", marked, sources),
+        None => marked,
+    }
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    text.chars().take(max).collect::<String>() + "\n..."
+}
+
+pub fn outcome_label(outcome: Option<&GeminiCodeExecutionResultOutcome>) -> &'static str {
+    match outcome {
+        Some(GeminiCodeExecutionResultOutcome::OutcomeOk) => "성공",
+        Some(GeminiCodeExecutionResultOutcome::OutcomeError) => "오류",
+        Some(GeminiCodeExecutionResultOutcome::OutcomeDeadlineExceeded) => "시간 초과",
+        Some(GeminiCodeExecutionResultOutcome::OutcomeUnspecified) | None => "결과 없음",
+    }
+}
+
+// 코드 실행 임베드 설명. 코드는 스포일러로 접어 두고 출력만 바로 보이게 합니다.
+pub fn format_code_execution(execution: &GeminiCodeExecution) -> String {
+    let code = truncate_chars(execution.code.trim_end(), CODE_PREVIEW_MAX_CHARS).replace("```", "`\u{200b}``");
+    let output = execution
+        .output
+        .as_deref()
+        .map(|o| truncate_chars(o.trim_end(), OUTPUT_PREVIEW_MAX_CHARS).replace("```", "`\u{200b}``"))
+        .filter(|o| !o.is_empty())
+        .unwrap_or("(출력 없음)".to_string());
+    format!(
+        "**코드** (눌러서 펼치기)\n||```{}\n{}\n```||\n**출력**\n```\n{}\n```",
+        execution.language, code, output
+    )
+}
@@ -1,13 +1,17 @@
 use std::collections::{hash_map, BTreeMap, HashMap};
 use std::hash::Hasher;
-use std::{env, hash};
+use std::hash;
 use std::hash::Hash;
+use std::time::Instant;
 
 use gemini_live_api::types::enums::GeminiContentRole;
-use gemini_live_api::types::{GeminiCachedContent, GeminiCachedContentResponse, GeminiContents, GeminiExecutableCode, GeminiExecutableCodeResult, GeminiFileData, GeminiFunctionCall, GeminiFunctionCallingConfig, GeminiFunctionResponse, GeminiGenerationConfigTool, GeminiInlineBlob, GeminiParts, GeminiToolConfig, GeminiToolConfigMode, ThinkingConfig};
-use reqwest::Client;
-use sea_orm::sea_query::IdenList;
-use serde_json::{json, Map, Value};
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use gemini_live_api::types::rest_api_error::GeminiApiError;
+use gemini_live_api::types::rest_api_types::{FinishReason, GenerateContentRequest, GenerateContentResponse};
+use gemini_live_api::types::{GeminiCachedContent, GeminiCachedContentResponse, GeminiContents, GeminiFunctionCall, GeminiFunctionCallingConfig, GeminiFunctionResponse, GeminiParts, GeminiToolConfig, GeminiToolConfigMode, ThinkingConfig};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
 use serenity::all::{ChannelId, CreateAttachment, CreateMessage, Message, MessageId};
 use crate::discord::discord_bot_manager::remove_message_process_map_entry;
 use crate::service::discord_message_service::{send_discord_message,edit_discord_message};
@@ -15,10 +19,14 @@ use crate::gemini::utils::generate_gemini_user_chunk;
 use crate::libs::logger::{LOGGER, LogLevel};
 use crate::libs::thread_pipelines::{GeminiChannelResult, GEMINI_FUNCTION_EXECUTION_ALARM};
 use crate::service::discord_error_msg::send_debug_error_log;
-use crate::setting::gemini_setting::{GEMINI_BOT_TOOLS, GEMINI_BOT_TOOLS_JSON, GEMINI_BOT_TOOLS_MODULES, GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO, GEMINI_NANO_BANANA, GENERATE_CONF, SAFETY_SETTINGS};
-use crate::gemini::types::{GeminiChatChunk, GeminiResponse};
+use crate::service::usage_service::spawn_record_usage;
+use crate::service::metrics_service::{record_gemini_request, record_tool_call, record_unknown_tool_call};
+use crate::setting::gemini_setting::{GEMINI_BOT_TOOLS, GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO, GEMINI_NANO_BANANA, GENERATE_CONF, SAFETY_SETTINGS};
+use crate::gemini::types::{GeminiChatChunk, GeminiCodeExecution, GeminiResponse};
+use crate::gemini::builtin_tools::{load_guild_builtin_tools, GuildBuiltinTools};
+use crate::gemini::gemini_error::{GeminiError, GeminiRetryPolicy};
 
-use super::types::{DiscordUserInfo, GeminiBotToolInputValue, GeminiBotToolInputValueType};
+use super::types::{DiscordUserInfo, GeminiBotToolInputValue, GeminiBotToolInputValueType, GeminiStreamEvent, GeminiUsageContext};
 
 struct ImageContainer {
     image_data: Vec<u8>,
@@ -30,20 +38,15 @@ pub struct GeminiCacheInfo {
 }
 
 pub struct GeminiClient {
-    net_client: Client,
+    rest: GeminiRestClient,
 }
 
-fn generate_gemini_error_message(error: &str) -> String {
-    format!("Gemini API > Error: {}", error)
-}
-
-fn make_fncall_result(fn_name:String, origin_argu:Map<String, Value>) -> GeminiContents {
-    let origin_argu_btree: BTreeMap<String, Value> = origin_argu.into_iter().collect();
+fn make_fncall_result(fn_name:String, origin_argu:BTreeMap<String, Value>) -> GeminiContents {
     let function_execution_result = GeminiParts::new().set_function_call(
         GeminiFunctionCall {
             name: fn_name.clone(),
             id: None,
-            args: Some(origin_argu_btree),
+            args: Some(origin_argu),
         }
     );
     GeminiContents{
@@ -129,17 +132,18 @@ pub fn generate_gemini_cache_setting(
     query: Vec<GeminiChatChunk>,
     begin_query: &GeminiChatChunk,
     use_pro: bool,
-    ttl: f32
+    ttl: f32,
+    builtin_tools: &GuildBuiltinTools,
 ) -> GeminiCachedContent {
     GeminiCachedContent {
         contents: query.iter().map(generate_gemini_user_chunk).collect(),
         system_instruction: Some(generate_gemini_user_chunk(begin_query)),
-        tools: GEMINI_BOT_TOOLS_JSON.clone(),
+        tools: builtin_tools.config_tools(),
         tool_config: Some(GeminiToolConfig{
             function_calling_config: Some(
                 GeminiFunctionCallingConfig {
-                    mode:Some(GeminiToolConfigMode::Any),
-                    allowed_function_names:None
+                    allowed_function_names:None,
+                    ..builtin_tools.function_calling_config()
                 }
             ),
         }),
@@ -149,63 +153,6 @@ pub fn generate_gemini_cache_setting(
     }
 }
 
-fn generate_value_to_content(value: &Value) -> Result<GeminiParts, String> {
-    if let Some(v) = value.get("text") {
-        Ok(GeminiParts::new().set_text(v.as_str().unwrap_or("").to_string()))
-    } else if let Some(v) = value.get("inlineData") {
-        let inline_data = serde_json::from_value::<GeminiInlineBlob>(v.clone()).unwrap();
-        Ok(GeminiParts::new().set_inline_data(inline_data))
-    }  else if let Some(v) = value.get("functionCall") {
-        let function_call = match v.as_object() {
-            Some(obj) => obj,
-            None => Err(()).expect("Expected functionCall to be an object")
-        };
-        if function_call.get("name").is_none() {
-            return Err("Function call without name".to_string());
-        }
-        let name = function_call.get("name").and_then(|n| n.as_str())
-            .unwrap_or_else(|| {
-                "There is no function name in the function call"
-            });
-        let args = function_call.get("args").cloned().unwrap_or(Value::Null);
-        Ok(GeminiParts::new().set_function_call(
-            GeminiFunctionCall {
-                name: name.to_string(),
-                id: None,
-                args: Some(args.as_object().unwrap().clone().into_iter()
-                    .map(|(k, v)| (k.clone(), v.clone()))
-                    .into_iter().collect::<BTreeMap<_, _>>())
-                }
-            )
-        )
-    } else if let Some(v) = value.get("functionResponse") {
-        let function_res = serde_json::from_value::<GeminiFunctionResponse>(v.clone()).unwrap();
-        Ok(GeminiParts::new().set_function_response(
-            function_res.clone()
-        ))
-    } else if let Some(v) = value.get("fileData") {
-        let inline_data = serde_json::from_value::<GeminiFileData>(v.clone()).unwrap();
-        Ok(GeminiParts::new().set_file_data(inline_data))
-    } else if let Some(v) = value.get("executableCode") {
-        let code = serde_json::from_value::<GeminiExecutableCode>(v.clone()).unwrap();
-        Ok(GeminiParts::new().set_executable_code(
-            code
-        ))
-    } else if let Some(v) =   value.get("executableCodeResult") {
-        let code = serde_json::from_value::<GeminiExecutableCodeResult>(v.clone()).unwrap();
-        Ok(GeminiParts::new().set_code_execution_result(
-            code
-        ))
-    } else if let Some(v) = value.get("image") {
-        Ok(GeminiParts::new().set_image_link(v.as_str().unwrap_or_else(|| {
-            "There is no image link in the image data"
-        }).to_string()))
-    } else {
-        Ok(GeminiParts::new().set_text(value.to_string()))
-    }
-}
-
-
 pub trait GeminiClientTrait {
     async fn start_gemini_cache(&mut self, query: Vec<GeminiChatChunk>, begin_query: &GeminiChatChunk, use_pro: bool, ttl:f32) -> 
     Result<GeminiCachedContentResponse, String>;
@@ -216,10 +163,10 @@ pub trait GeminiClientTrait {
         cached:Option<String>,
         user_info:Option<DiscordUserInfo>,
         context_id: i64
-) -> Result<GeminiResponse, String>;
+) -> Result<GeminiResponse, GeminiError>;
     fn generate_to_gemini_query(&self, query: Vec<GeminiChatChunk>,
         begin_query:&GeminiChatChunk,thinking_bought:Option<i32>,
-        cached:Option<String>,is_start:bool) -> Value {
+        cached:Option<String>,is_start:bool,builtin_tools:&GuildBuiltinTools) -> GenerateContentRequest {
         let generation_conf = if thinking_bought.is_some() {
             let mut origin = GENERATE_CONF.clone();
             origin.thinking_config = Some(
@@ -232,34 +179,24 @@ pub trait GeminiClientTrait {
         } else {
             GENERATE_CONF.clone()
         };
-        let cached_info = cached;
-        let ret = if is_start {
-            json!({
-            "contents": query.iter().map(generate_gemini_user_chunk).collect::<Vec<_>>(),
-            "generationConfig": generation_conf,
-            "safetySettings": SAFETY_SETTINGS.clone(),
-            "toolConfig": {
-                "functionCallingConfig": {
-                    "mode": "ANY",
-                    "allowedFunctionNames": GEMINI_BOT_TOOLS_MODULES.iter().map(|tool| tool.name.clone()).collect::<Vec<_>>()
-                }
-            },
-            "systemInstruction": generate_gemini_user_chunk(begin_query),
-            "tools": GEMINI_BOT_TOOLS_JSON.clone()
-        })
-        } else {
-            json!({
-            "contents": query.iter().map(generate_gemini_user_chunk).collect::<Vec<_>>(),
-            "generationConfig": generation_conf,
-            "safetySettings": SAFETY_SETTINGS.clone()
-        })
+        let request = GenerateContentRequest {
+            contents: query.iter().map(generate_gemini_user_chunk).collect(),
+            generation_config: Some(generation_conf),
+            safety_settings: Some(SAFETY_SETTINGS.clone()),
+            cached_content: cached,
+            ..Default::default()
         };
-        if let Some(cached_key) = cached_info {
-            let mut ret_map = ret.as_object().unwrap().clone();
-            ret_map.insert("cachedContent".to_string(), Value::String(cached_key));
-            Value::Object(ret_map)
+        if is_start {
+            GenerateContentRequest {
+                tool_config: Some(GeminiToolConfig {
+                    function_calling_config: Some(builtin_tools.function_calling_config()),
+                }),
+                system_instruction: Some(generate_gemini_user_chunk(begin_query)),
+                tools: Some(builtin_tools.config_tools()),
+                ..request
+            }
         } else {
-            ret
+            request
         }
     }
 
@@ -268,7 +205,7 @@ pub trait GeminiClientTrait {
 impl GeminiClientTrait for GeminiClient {
     fn new() -> Self {
         GeminiClient {
-            net_client: Client::new(),
+            rest: GeminiRestClient::from_env().expect("GEMINI_API_KEY must be set"),
         }
     }
     async fn send_query_to_gemini(
@@ -280,107 +217,81 @@ impl GeminiClientTrait for GeminiClient {
         cached:Option<String>,
         user_info:Option<DiscordUserInfo>,
         context_id: i64,
-    ) -> Result<GeminiResponse, String> {
-        let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
-        
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-            if use_pro{ GEMINI_MODEL_PRO }else{ GEMINI_MODEL_FLASH },
-            api_key
-        );
-        let objected_query = self.generate_to_gemini_query(query,begin_query,thinking_bought,cached.clone(),cached.is_none());
-        
-        LOGGER.log(LogLevel::Debug, &format!("Gemini API > Req: {}", objected_query));
-        let contents = objected_query.get("contents");
-        let mut integral_content_part:Vec<GeminiContents> = if contents.is_some() && contents.unwrap().as_array().is_some() {
-            let contents = contents.unwrap();
-            contents.as_array().unwrap().into_iter().map(|c| {
-                GeminiContents {
-                    role : if c.get("role").is_some_and(|r| r.as_str() == Some("user")) {
-                        GeminiContentRole::User
-                    } else {
-                        GeminiContentRole::Model
-                    },
-                    parts: c.get("parts").and_then(|p| p.as_array())
-                        .map_or_else(|| vec![GeminiParts::new().set_text(c.to_string())], |parts| {
-                            parts.iter().map(generate_value_to_content).filter_map(
-                                |part| part.ok()
-                            ).collect()
-                        }),
-                }
-            }).collect::<Vec<GeminiContents>>()
-        } else {
-            vec![]
+    ) -> Result<GeminiResponse, GeminiError> {
+        let mut model = if use_pro { GEMINI_MODEL_PRO } else { GEMINI_MODEL_FLASH };
+        // 캐시는 만든 모델에서만 쓸 수 있으므로 캐시가 없을 때만 Flash 로 바꿉니다.
+        let can_fallback = cached.is_none();
+        let usage_context = GeminiUsageContext {
+            source: "chat".to_string(),
+            guild_id: begin_query.guild_id,
+            channel_id: begin_query.channel_id,
+            user_id: begin_query.user_id.as_ref().and_then(|id| id.parse::<u64>().ok()),
+            context_id: Some(context_id).filter(|id| *id > 0),
         };
-        let response = self.net_client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .body(objected_query.to_string())
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        let response_result = response.text().await.unwrap();
-        LOGGER.log(LogLevel::Debug, &format!("Gemini API > Resp: {}", response_result));
+        let builtin_tools = match begin_query.guild_id {
+            Some(guild_id) => load_guild_builtin_tools(guild_id).await,
+            None => GuildBuiltinTools::default(),
+        };
+        let objected_query = self.generate_to_gemini_query(query,begin_query,thinking_bought,cached.clone(),cached.is_none(),&builtin_tools);
+
+        LOGGER.log(LogLevel::Debug, &format!("Gemini API > Req: {}", serde_json::to_string(&objected_query).unwrap_or_default()));
+        let mut integral_content_part:Vec<GeminiContents> = objected_query.contents.clone();
+        let response_result = request_with_fallback(&self.rest, &mut model, &objected_query, &usage_context, can_fallback).await?;
         let mut response_found = false;
         let mut gemini_sending_query = objected_query.clone();
         let maximum_function_call = 9;
         let mut function_call_count = 0;
-        let mut last_contents = response_result.clone();
+        let mut last_contents = response_result;
         let mut discord_msg = String::new();
         let mut command_result = Vec::new();
         let mut finish_reason = String::new();
+        let mut last_finish_reason: Option<FinishReason> = None;
         let mut sub_items: Option<Vec<String>> = None;
         let mut avg_logprobs = 0.0;
         let mut trycount = 0;
         let mut thoughts: Option<String> = None;
+        let mut grounding = None;
+        let mut code_executions: Vec<GeminiCodeExecution> = Vec::new();
         let mut response_message_id:Option<MessageId> = None;
         let mut hasher = hash::DefaultHasher::new();
         let mut last_hash: u64 = {
-            response_result.hash(&mut hasher);
+            serde_json::to_string(&last_contents).unwrap_or_default().hash(&mut hasher);
             hasher.finish()
         };
         while response_found == false && trycount < 10 {
-            let now_contents = serde_json::from_str::<Value>(&last_contents)
-                .map_err(|e| generate_gemini_error_message(&format!("Failed to parse response: {}", e)))?;
-
-            if now_contents.get("error").is_some() {
-                let error_message = now_contents.get("error")
-                    .and_then(|e| e.get("message"))
-                    .and_then(|m| m.as_str())
-                    .unwrap_or("Unknown error")
-                    .to_string();
-                send_debug_error_log(
-                    format!("Gemini API > Error: {}", error_message)
-                ).await;
-                return Err(generate_gemini_error_message(&error_message));
-            }
+            let now_contents = last_contents.clone();
 
             LOGGER.log(LogLevel::Debug, &format!("Gemini API > Response: {}", serde_json::to_string_pretty(&now_contents).unwrap_or_default()));
-            let now_parts = now_contents.get("candidates")
-                .and_then(|candidates| candidates.as_array())
-                .and_then(|candidates| candidates.last())
-                .and_then(|candidates| candidates.as_object())
-                .and_then(|candidates| candidates.get("content"))
-                .and_then(|contents| contents.as_object())
-                .and_then(|candidates| candidates.get("parts"))
-                .and_then(|parts| parts.as_array());
+            let now_candidate = now_contents.candidates.last();
+            let now_parts = now_candidate.map(|c| c.parts());
+            let now_finish_reason = now_candidate.and_then(|c| c.finish_reason.clone());
+            // 안전 차단, 인용, 길이 초과로 내용 없이 끝난 응답은 같은 요청을 다시 보내도 소용이 없습니다.
+            // 잘못된 함수 호출은 다시 보내면 고쳐지는 경우가 있어 그대로 둡니다.
+            let terminal_error = now_finish_reason.as_ref()
+                .and_then(GeminiError::from_finish_reason)
+                .filter(|e| *e != GeminiError::MalformedFunctionCall);
+            if now_parts.is_some_and(|parts| parts.is_empty()) && terminal_error.is_some() {
+                LOGGER.log(LogLevel::Warning, &format!("Gemini API > Response stopped without content: {:?}", now_finish_reason));
+                finish_reason = now_finish_reason.as_ref().map_or("unknown", |r| r.as_str()).to_string();
+                last_finish_reason = now_finish_reason;
+                break;
+            }
             if let Some(parts) = now_parts {
                 for part in parts {
-                    if let Some(fn_call) = part.get("functionCall") {
-                        if let Some(fn_name) = fn_call.get("name").and_then(|n| n.as_str()) {
+                    if let Some(fn_call) = &part.function_call {
+                        let fn_name = fn_call.name.as_str();
+                        if !fn_name.is_empty() {
                             if fn_name == "response_msg" {
                                 LOGGER.log(LogLevel::Debug, "Gemini API > Function call: response_msg");
                                 response_found = true;
-                                discord_msg = fn_call.get("args")
-                                    .and_then(|args| args.as_object())
+                                discord_msg = fn_call.args.as_ref()
                                     .and_then(|args_obj| args_obj.get("msg"))
                                     .and_then(|text| text.as_str())
                                     .map_or_else(|| "".to_string(), |s| s.to_string());
                             } else if fn_name == "sub_items" {
                                 LOGGER.log(LogLevel::Debug, "Gemini API > Function call: sub_items");
                                 response_found = true;
-                                sub_items = fn_call.get("args")
-                                    .and_then(|args| args.as_object())
+                                sub_items = fn_call.args.as_ref()
                                     .and_then(|args_obj| args_obj.get("items"))
                                     .and_then(|items| items.as_array())
                                     .map(|items| items.iter()
@@ -388,16 +299,14 @@ impl GeminiClientTrait for GeminiClient {
                                         .collect());
                             } else {
                                 function_call_count += 1;
-                                let args = fn_call.get("args")
-                                    .and_then(|args| args.as_object())
-                                    .cloned()
-                                    .unwrap_or_default();
+                                let args = fn_call.args.clone().unwrap_or_default();
                                 if let Some(fn_result) = GEMINI_BOT_TOOLS.get(fn_name) {
                                     let fn_args: HashMap<String, GeminiBotToolInputValue> = args.clone().into_iter()
                                         .map(|(k,v)| generate_to_value(k,v))
                                         .collect();
                                     integral_content_part.push(make_fncall_result(fn_name.to_string(), args));
                                     let res = (fn_result.action)(fn_args,user_info.clone()).await;
+                                    record_tool_call("chat", fn_name, if res.is_ok() { "ok" } else { "error" });
                                     match res {
                                         Ok(result) => {
                                             command_result.push(Ok(result.clone()));
@@ -417,7 +326,9 @@ impl GeminiClientTrait for GeminiClient {
                                             let mime = result.clone().result["mime"].as_str().unwrap_or("image/png").to_string();  
                                             ImageContainer { image_data: img_data, mime_type: mime }  
                                         });
-                                        if response_message_id.is_none() {
+                                        // OpenAI 호환 API 처럼 대화 채널이 없는 요청은 도구 결과를 디스코드로 보내지 않습니다.
+                                        let has_channel = begin_query.channel_id.is_some();
+                                        if has_channel && response_message_id.is_none() {
                                             let msg = result.clone().result_message;
                                             let channel = ChannelId::new(begin_query.channel_id.unwrap_or(0));
                                             let create_message = CreateMessage::new().content(msg);
@@ -458,12 +369,12 @@ impl GeminiClientTrait for GeminiClient {
                                                 message: result.clone(),
                                                 channel_id: begin_query.channel_id.unwrap().to_string(),
                                                 sender: begin_query.user_id.clone().unwrap().clone(),
-                                                guild_id: begin_query.guild_id.unwrap().to_string(),
+                                                guild_id: begin_query.guild_id.unwrap_or(0).to_string(),
                                                 message_id: response_message_id.unwrap().get().to_string(),
                                                 need_send: false,
                                                 context_id: context_id
                                             });
-                                        } else {
+                                        } else if has_channel {
 
                                             // GO TO : 
                                             let _ = GEMINI_FUNCTION_EXECUTION_ALARM.sender.send(
@@ -471,7 +382,7 @@ impl GeminiClientTrait for GeminiClient {
                                                 message: result.clone(),
                                                 channel_id: begin_query.channel_id.unwrap().to_string(),
                                                 sender: begin_query.user_id.clone().unwrap().clone(),
-                                                guild_id: begin_query.guild_id.unwrap().to_string(),
+                                                guild_id: begin_query.guild_id.unwrap_or(0).to_string(),
                                                 message_id: response_message_id.unwrap().get().to_string(),
                                                 need_send: true,
                                                 context_id: context_id
@@ -489,6 +400,7 @@ impl GeminiClientTrait for GeminiClient {
                                         }
                                     }
                                 } else {
+                                    record_unknown_tool_call("chat");
                                     command_result.push(Err("Gemini API > Function call not found".to_string()));
                                 }
                             }
@@ -497,13 +409,13 @@ impl GeminiClientTrait for GeminiClient {
                                 "Gemini API > Function call without name".to_string()
                             ).await;
                         }
-                    } else if let Some(though) = part.get("thought") {
+                    } else if part.thought.is_some() {
                         LOGGER.log(LogLevel::Debug, "Gemini API > Thought received");
-                        thoughts = part.get("text").and_then(|t| t.as_str()).map(|s| s.to_string());
+                        thoughts = part.text.clone();
                         LOGGER.log(LogLevel::Debug, &format!("Gemini API > Thought: {}", thoughts.as_deref().unwrap_or("No thought")));
-                    } else if let Some(text) = part.get("text") {
+                    } else if let Some(text) = &part.text {
                         LOGGER.log(LogLevel::Debug, "Gemini API > Text received");
-                        let text_content = text.as_str().unwrap_or("");
+                        let text_content = text.as_str();
                         if !text_content.is_empty() {
                             integral_content_part.push(
                                 GeminiContents {
@@ -515,15 +427,38 @@ impl GeminiClientTrait for GeminiClient {
                             );
                         }
                         response_found = true;
-                        discord_msg = text_content.to_string();
-                    } else if let Some(image) = part.get("image") {
+                        // 코드 실행이나 검색을 거치면 텍스트가 여러 파트로 나뉘어 옵니다.
+                        discord_msg.push_str(text_content);
+                    } else if let Some(code) = &part.executable_code {
+                        LOGGER.log(LogLevel::Debug, "Gemini API > Executable code received");
+                        code_executions.push(GeminiCodeExecution {
+                            language: code.language.as_deref().unwrap_or("python").to_lowercase(),
+                            code: code.code.clone(),
+                            outcome: None,
+                            output: None,
+                        });
+                    } else if let Some(result) = &part.code_execution_result {
+                        LOGGER.log(LogLevel::Debug, "Gemini API > Code execution result received");
+                        match code_executions.last_mut() {
+                            Some(last) if last.outcome.is_none() => {
+                                last.outcome = Some(result.outcome.clone());
+                                last.output = result.output.clone();
+                            },
+                            _ => code_executions.push(GeminiCodeExecution {
+                                language: "python".to_string(),
+                                code: String::new(),
+                                outcome: Some(result.outcome.clone()),
+                                output: result.output.clone(),
+                            }),
+                        }
+                    } else if let Some(image) = &part.image {
                         LOGGER.log(LogLevel::Debug, "Gemini API > Image received");
                         integral_content_part.push(
                             GeminiContents {
                                 role: GeminiContentRole::Model,
                                 parts: vec![
                                     GeminiParts::new().set_image_link(
-                                        image.to_string()
+                                        image.clone()
                                     )
                                 ]
                             }
@@ -535,33 +470,14 @@ impl GeminiClientTrait for GeminiClient {
                     }
                 }
                 trycount += 1;
-                gemini_sending_query["contents"] = json!(integral_content_part);
+                gemini_sending_query.contents = integral_content_part.clone();
                 if response_found == false {
-                    let response = self.net_client
-                        .post(&url)
-                        .header("Content-Type", "application/json")
-                        .body(gemini_sending_query.to_string())
-                        .send()
-                        .await
-                        .map_err(|e| e.to_string())?;
-                    let response_result = response.text().await.unwrap();
-                    let body_jsoned = serde_json::from_str::<Value>(&response_result).unwrap()
-                        .get("candidates")
-                        .and_then(|candidates| candidates.as_array())
-                        .and_then(|candidates| candidates.last())
-                        .and_then(|candidates| candidates.as_object())
-                        .and_then(|candidates| candidates.get("content"))
-                        .and_then(|contents| contents.as_object())
-                        .and_then(|candidates| candidates.get("parts"))
-                        .and_then(|parts| parts.as_array())
-                        .map(|arr| {
-                            arr.iter().filter(|p| {
-                                p.as_object()
-                                    .map(|obj| obj.get("thought").is_none())
-                                    .unwrap_or(true)
-                            }).collect::<Vec<_>>()
+                    let response_result = request_with_fallback(&self.rest, &mut model, &gemini_sending_query, &usage_context, can_fallback).await?;
+                    let body_jsoned = response_result.candidates.last()
+                        .map(|candidate| {
+                            candidate.parts().iter().filter(|p| p.thought.is_none()).collect::<Vec<_>>()
                         })
-                        .map(|vec| serde_json::to_string(&vec).unwrap_or_default()) // <-- 변경
+                        .map(|vec| serde_json::to_string(&vec).unwrap_or_default())
                         .unwrap_or_default();
 
                     hasher.write(body_jsoned.as_bytes());
@@ -570,26 +486,49 @@ impl GeminiClientTrait for GeminiClient {
                         send_debug_error_log(
                             format!("Gemini API > No new response received, hash value unchanged: {}", hash_value)
                         ).await;
-                        gemini_sending_query["toolConfig"]["functionCallingConfig"]["allowedFunctionNames"] = json!(["response_msg"]);
+                        force_response_msg(&mut gemini_sending_query);
                     }
                     last_hash = hash_value;
-                    last_contents = response_result.clone();
+                    last_contents = response_result;
                 }
                 if function_call_count >= maximum_function_call {
                     // 최대 함수 호출 횟수에 도달했음을 기록하고, 강제로 response_msg 함수만 허용하도록 설정
                     LOGGER.log(LogLevel::Warning, "Gemini API > Maximum function call attempts reached, forcing response_msg");
-                    gemini_sending_query["toolConfig"]["functionCallingConfig"]["allowedFunctionNames"] = json!(["response_msg"]);
+                    force_response_msg(&mut gemini_sending_query);
                 }
-                avg_logprobs = now_contents.get("averageLogprobs")
-                    .and_then(|v| v.as_f64())
+                avg_logprobs = now_candidate
+                    .and_then(|c| c.avg_logprobs)
                     .unwrap_or(0.0);
-                finish_reason = now_contents.get("finishReason")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
+                if let Some(metadata) = now_candidate.and_then(|c| c.grounding_metadata.clone()) {
+                    grounding = Some(metadata);
+                }
+                finish_reason = now_finish_reason.as_ref()
+                    .map_or("unknown", |r| r.as_str())
                     .to_string();
+                last_finish_reason = now_finish_reason;
+            } else {
+                // 후보가 없으면 같은 응답을 다시 볼 이유가 없으므로 끝냅니다.
+                LOGGER.log(LogLevel::Warning, "Gemini API > Response without candidates");
+                break;
             }
         }
 
+        if response_message_id.is_some() {
+            remove_message_process_map_entry(
+            response_message_id.unwrap().get()
+            ).await;
+        }
+        // 보낼 내용이 하나도 없으면 빈 메시지 대신 끝난 이유를 오류로 돌려줍니다.
+        if discord_msg.trim().is_empty() && sub_items.is_none() && command_result.is_empty() && code_executions.is_empty() {
+            let error = last_finish_reason.as_ref()
+                .and_then(GeminiError::from_finish_reason)
+                .unwrap_or(GeminiError::EmptyResponse);
+            send_debug_error_log(
+                format!("Gemini API > Empty response ({}): {}", finish_reason, error)
+            ).await;
+            return Err(error);
+        }
+
         let thoughts = thoughts;
         let gemini_response = GeminiResponse {
             discord_msg,
@@ -598,12 +537,10 @@ impl GeminiClientTrait for GeminiClient {
             avg_logprobs,
             command_result,
             thoughts,
+            model: model.to_string(),
+            grounding,
+            code_executions,
         };
-        if response_message_id.is_some() {
-            remove_message_process_map_entry(
-            response_message_id.unwrap().get()
-            ).await;
-        }
 
         Ok(gemini_response)
     }
@@ -615,46 +552,28 @@ impl GeminiClientTrait for GeminiClient {
         use_pro:bool,
         ttl: f32
     ) -> Result<GeminiCachedContentResponse, String> {
-        let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/cachedContents?key={}",
-            api_key
-        );
-        let start_cache = generate_gemini_cache_setting(query, begin_query, use_pro, ttl);
-        let body = serde_json::to_string(&start_cache).expect("Failed to serialize cache content");
-        LOGGER.log(LogLevel::Debug, &format!("Gemini Cache API > Start post Req: {:?}", &body));
-        let response = self.net_client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .body(body)
-            .send()
-            .await;
-        match response {
-            Ok(resp) => {
-                if !resp.status().is_success() {
-                    let resp_status = resp.status();
-                    let reason = resp.text().await.unwrap_or_else(|_| "No response text".to_string());
-                    let error_message = format!(
-                        "Gemini API > Send Post Cache > Error_status: {} / {}", resp_status, 
-                        reason
-                    );
-                    if reason.contains("Cached content is too small") {
-                        return Err(format!("Gemini API > Error: {}", reason));
-                    } else {
-                        send_debug_error_log(error_message.clone()).await;
-                        return Err(error_message);
-                    }
-
-                }
-                let rest_text = resp.text().await.map_err(|e| format!("Failed to read response text: {}", e))?;
-
-                LOGGER.log(LogLevel::Debug, &format!("Gemini API > Resp: {}", rest_text));
-                let response_result = serde_json::from_str::<GeminiCachedContentResponse>(
-                    rest_text.as_str()
-                ).map_err(|e: serde_json::Error| format!("Failed to parse response: {}", e))?;
+        let builtin_tools = match begin_query.guild_id {
+            Some(guild_id) => load_guild_builtin_tools(guild_id).await,
+            None => GuildBuiltinTools::default(),
+        };
+        let start_cache = generate_gemini_cache_setting(query, begin_query, use_pro, ttl, &builtin_tools);
+        LOGGER.log(LogLevel::Debug, &format!("Gemini Cache API > Start post Req: {:?}", serde_json::to_string(&start_cache).unwrap_or_default()));
+        match self.rest.create_cached_content(&start_cache).await {
+            Ok(response_result) => {
                 LOGGER.log(LogLevel::Debug, &format!("Gemini API > Cache created: {:?}", response_result));
                 Ok(response_result)
             },
+            Err(GeminiApiError::Api { http_status, message, .. }) => {
+                if message.contains("Cached content is too small") {
+                    return Err(format!("Gemini API > Error: {}", message));
+                }
+                let error_message = format!(
+                    "Gemini API > Send Post Cache > Error_status: {} / {}", http_status,
+                    message
+                );
+                send_debug_error_log(error_message.clone()).await;
+                Err(error_message)
+            },
             Err(e) => {
                 LOGGER.log(LogLevel::Error, &format!("Gemini API > Error: {}", e));
                 send_debug_error_log(
@@ -665,22 +584,8 @@ impl GeminiClientTrait for GeminiClient {
         }
     }
     async fn drop_cache(&mut self, cache_key: &str) -> Result<(), String> {
-        let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/{}?key={}",
-            cache_key, api_key
-        );
-        let response = self.net_client
-            .delete(&url)
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-        if !response.status().is_success() {
-            let error_message = format!(
-                "Gemini API > Drop Cache > Error_status: {} / {}", response.status(), 
-                response.text().await.unwrap_or_else(|_| "No response text".to_string())
-            );
+        if let Err(e) = self.rest.delete_cached_content(cache_key).await {
+            let error_message = format!("Gemini API > Drop Cache > Error: {}", e);
             send_debug_error_log(error_message.clone()).await;
             return Err(error_message);
         }
@@ -689,6 +594,269 @@ impl GeminiClientTrait for GeminiClient {
     }
 }
 
+// 생성 요청을 보내고 토큰 사용량을 기록합니다.
+// 429, 5xx, 연결 실패는 지터를 섞은 지수 백오프로 다시 보내고,
+// 끝내 실패하면 디버그 채널에 남긴 뒤 분류한 오류로 돌려줍니다.
+pub(crate) async fn request_generate_content(
+    rest: &GeminiRestClient,
+    model: &str,
+    request: &GenerateContentRequest,
+    usage_context: &GeminiUsageContext,
+) -> Result<GenerateContentResponse, GeminiError> {
+    let policy = GeminiRetryPolicy::default();
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let started = Instant::now();
+        let result = rest.generate_content(model, request).await;
+        record_gemini_request(model, result.is_ok(), started.elapsed());
+        match result {
+            Ok(response) => {
+                spawn_record_usage(model, usage_context, response.usage_metadata.as_ref());
+                return Ok(response);
+            },
+            Err(e) => {
+                let error = GeminiError::from(e);
+                if error.is_transient() && attempt < policy.max_attempts {
+                    let wait = policy.jittered_backoff(attempt);
+                    LOGGER.log(LogLevel::Warning, &format!(
+                        "Gemini API > {} 재시도 {}/{} ({}ms 후): {}",
+                        model, attempt, policy.max_attempts - 1, wait.as_millis(), error
+                    ));
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+                send_debug_error_log(
+                    format!("Gemini API > Error ({}): {}", model, error)
+                ).await;
+                return Err(error);
+            }
+        }
+    }
+}
+
+// Pro 요청이 재시도 뒤에도 과부하나 한도 초과로 실패하면 Flash 로 한 번 더 보냅니다.
+// 바뀐 모델은 model 에 남아 이후 요청과 응답 표시에 쓰입니다.
+async fn request_with_fallback(
+    rest: &GeminiRestClient,
+    model: &mut &'static str,
+    request: &GenerateContentRequest,
+    usage_context: &GeminiUsageContext,
+    can_fallback: bool,
+) -> Result<GenerateContentResponse, GeminiError> {
+    match request_generate_content(rest, model, request, usage_context).await {
+        Err(e) if can_fallback && *model == GEMINI_MODEL_PRO && e.should_fallback_to_flash() => {
+            LOGGER.log(LogLevel::Warning, &format!("Gemini API > Pro 실패로 Flash 로 전환합니다: {}", e));
+            *model = GEMINI_MODEL_FLASH;
+            request_generate_content(rest, model, request, usage_context).await
+        },
+        result => result,
+    }
+}
+
+// stream 응답은 response_msg 함수 인자로는 글자를 나눠 받을 수 없으므로 일반 텍스트로 답하게 합니다.
+const STREAM_TEXT_INSTRUCTION: &str = "이 대화는 OpenAI 호환 API 로 스트리밍됩니다. 최종 답변은 response_msg 함수 대신 일반 텍스트로 작성하세요.";
+const MAX_STREAM_TURNS: usize = 10;
+
+/// OpenAI 호환 API 의 stream 응답. 턴마다 SSE 로 받아 텍스트는 받는 대로 events 로 보내고,
+/// 함수 호출이 있으면 실행 결과를 붙여 다음 턴을 다시 스트리밍합니다. 끝나면 Done 이나 Failed 를 보냅니다.
+pub async fn stream_query_to_gemini(
+    rest: &GeminiRestClient,
+    query: Vec<GeminiChatChunk>,
+    begin_query: &GeminiChatChunk,
+    use_pro: bool,
+    user_info: Option<DiscordUserInfo>,
+    events: mpsc::Sender<GeminiStreamEvent>,
+) {
+    let last = match stream_turns(rest, query, begin_query, use_pro, user_info, &events).await {
+        Ok(finish_reason) => GeminiStreamEvent::Done { finish_reason },
+        Err(e) => GeminiStreamEvent::Failed(e),
+    };
+    let _ = events.send(last).await;
+}
+
+async fn stream_turns(
+    rest: &GeminiRestClient,
+    query: Vec<GeminiChatChunk>,
+    begin_query: &GeminiChatChunk,
+    use_pro: bool,
+    user_info: Option<DiscordUserInfo>,
+    events: &mpsc::Sender<GeminiStreamEvent>,
+) -> Result<String, GeminiError> {
+    let model = if use_pro { GEMINI_MODEL_PRO } else { GEMINI_MODEL_FLASH };
+    let usage_context = GeminiUsageContext {
+        source: "chat".to_string(),
+        guild_id: begin_query.guild_id,
+        channel_id: begin_query.channel_id,
+        user_id: begin_query.user_id.as_ref().and_then(|id| id.parse::<u64>().ok()),
+        context_id: None,
+    };
+    let builtin_tools = GuildBuiltinTools::default();
+    let client = GeminiClient { rest: rest.clone() };
+    let mut request = client.generate_to_gemini_query(query, begin_query, None, None, true, &builtin_tools);
+    request.tool_config = Some(GeminiToolConfig {
+        function_calling_config: Some(GeminiFunctionCallingConfig {
+            mode: Some(GeminiToolConfigMode::Auto),
+            allowed_function_names: None,
+        }),
+    });
+    if let Some(system_instruction) = request.system_instruction.as_mut() {
+        system_instruction.parts.push(GeminiParts::new().set_text(STREAM_TEXT_INSTRUCTION.to_string()));
+    }
+    let send_delta = |text: String| async move {
+        events
+            .send(GeminiStreamEvent::Delta(text))
+            .await
+            .map_err(|_| GeminiError::Other("스트림을 받는 쪽이 끊겼습니다.".to_string()))
+    };
+
+    for turn in 0..MAX_STREAM_TURNS {
+        // 마지막 턴에는 함수 호출을 막아 답을 마무리하게 합니다.
+        if turn + 1 == MAX_STREAM_TURNS {
+            LOGGER.log(LogLevel::Warning, "Gemini API > Maximum stream turns reached, disabling function calls");
+            request.tool_config = Some(GeminiToolConfig {
+                function_calling_config: Some(GeminiFunctionCallingConfig {
+                    mode: Some(GeminiToolConfigMode::None),
+                    allowed_function_names: None,
+                }),
+            });
+        }
+
+        let started = Instant::now();
+        let policy = GeminiRetryPolicy::default();
+        let mut attempt = 0;
+        let mut stream = loop {
+            attempt += 1;
+            match rest.stream_generate_content(model, &request).await {
+                Ok(stream) => break stream,
+                Err(e) => {
+                    let error = GeminiError::from(e);
+                    if error.is_transient() && attempt < policy.max_attempts {
+                        tokio::time::sleep(policy.jittered_backoff(attempt)).await;
+                        continue;
+                    }
+                    record_gemini_request(model, false, started.elapsed());
+                    return Err(error);
+                }
+            }
+        };
+
+        let mut text = String::new();
+        let mut calls: Vec<GeminiFunctionCall> = Vec::new();
+        let mut finish_reason: Option<FinishReason> = None;
+        let mut usage_metadata = None;
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    record_gemini_request(model, false, started.elapsed());
+                    return Err(GeminiError::from(e));
+                }
+            };
+            if chunk.usage_metadata.is_some() {
+                usage_metadata = chunk.usage_metadata.clone();
+            }
+            let Some(candidate) = chunk.candidates.last() else {
+                continue;
+            };
+            if candidate.finish_reason.is_some() {
+                finish_reason = candidate.finish_reason.clone();
+            }
+            for part in candidate.parts() {
+                if let Some(call) = &part.function_call {
+                    calls.push(call.clone());
+                } else if part.thought != Some(true) {
+                    if let Some(delta) = part.text.as_ref().filter(|t| !t.is_empty()) {
+                        text.push_str(delta);
+                        send_delta(delta.clone()).await?;
+                    }
+                }
+            }
+        }
+        record_gemini_request(model, true, started.elapsed());
+        spawn_record_usage(model, &usage_context, usage_metadata.as_ref());
+
+        if !text.is_empty() {
+            request.contents.push(GeminiContents {
+                role: GeminiContentRole::Model,
+                parts: vec![GeminiParts::new().set_text(text.clone())],
+            });
+        }
+        let finish = finish_reason.as_ref().map_or("STOP", |r| r.as_str()).to_string();
+
+        let mut called_tool = false;
+        for call in calls {
+            let args = call.args.clone().unwrap_or_default();
+            match call.name.as_str() {
+                // 지시를 어기고 응답 함수로 답하면 그 내용을 한 번에 보냅니다.
+                "response_msg" => {
+                    if let Some(msg) = args.get("msg").and_then(|m| m.as_str()).filter(|m| !m.is_empty()) {
+                        send_delta(msg.to_string()).await?;
+                    }
+                    return Ok(finish);
+                }
+                "sub_items" => {
+                    let items = args.get("items")
+                        .and_then(|items| items.as_array())
+                        .map(|items| items.iter().filter_map(|item| item.as_str()).collect::<Vec<_>>().join("\n"))
+                        .unwrap_or_default();
+                    if !items.is_empty() {
+                        send_delta(items).await?;
+                    }
+                    return Ok(finish);
+                }
+                "" => {}
+                fn_name => {
+                    called_tool = true;
+                    request.contents.push(make_fncall_result(fn_name.to_string(), args.clone()));
+                    let Some(tool) = GEMINI_BOT_TOOLS.get(fn_name) else {
+                        record_unknown_tool_call("chat");
+                        request.contents.push(make_fncall_error(fn_name.to_string(), "Function call not found".to_string()));
+                        continue;
+                    };
+                    let fn_args: HashMap<String, GeminiBotToolInputValue> = args.into_iter()
+                        .map(|(k, v)| generate_to_value(k, v))
+                        .collect();
+                    let res = (tool.action)(fn_args, user_info.clone()).await;
+                    record_tool_call("chat", fn_name, if res.is_ok() { "ok" } else { "error" });
+                    request.contents.push(match res {
+                        Ok(result) => make_fncall_result_with_value(GeminiFunctionResponse {
+                            name: fn_name.to_string(),
+                            response: Some(json!(result)),
+                            id: None,
+                            will_continue: None,
+                            scheduling: None,
+                        }),
+                        Err(e) => make_fncall_error(fn_name.to_string(), e),
+                    });
+                }
+            }
+        }
+
+        if !called_tool {
+            if text.is_empty() {
+                return Err(finish_reason.as_ref()
+                    .and_then(GeminiError::from_finish_reason)
+                    .unwrap_or(GeminiError::EmptyResponse));
+            }
+            return Ok(finish);
+        }
+    }
+    Err(GeminiError::Other("도구 호출이 너무 많아 답을 마무리하지 못했습니다.".to_string()))
+}
+
+// 허용 함수를 response_msg 하나로 좁혀 응답을 마무리하게 합니다.
+fn force_response_msg(request: &mut GenerateContentRequest) {
+    let config = request.tool_config.get_or_insert(GeminiToolConfig { function_calling_config: None });
+    let calling = config.function_calling_config.get_or_insert(GeminiFunctionCallingConfig {
+        mode: None,
+        allowed_function_names: None,
+    });
+    // 내장 도구 때문에 AUTO 였다면 함수 이름을 지정할 수 있도록 ANY 로 되돌립니다.
+    calling.mode = Some(GeminiToolConfigMode::Any);
+    calling.allowed_function_names = Some(vec!["response_msg".to_string()]);
+}
+
 // pub async fn send_query_to_cached_gemini() -> Result<GeminiResponse, String> {
 
 // } 
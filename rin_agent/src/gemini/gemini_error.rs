@@ -0,0 +1,182 @@
+use std::fmt;
+use std::time::Duration;
+
+use gemini_live_api::types::rest_api_error::GeminiApiError;
+use gemini_live_api::types::rest_api_types::FinishReason;
+
+// 채팅 생성에서 사용자에게 설명해야 하는 Gemini 오류.
+// API 오류(상태 코드)와 응답의 finish_reason 을 같은 타입으로 모읍니다.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeminiError {
+    // 429 RESOURCE_EXHAUSTED (분당 요청 수, 할당량 초과)
+    RateLimited { message: String },
+    // 5xx. 503 은 모델 과부하
+    ServerError { http_status: u16, message: String },
+    // 요청 전송/응답 수신 실패
+    Transport(String),
+    // 프롬프트 차단 또는 SAFETY, BLOCKLIST, PROHIBITED_CONTENT 등으로 응답이 중단됨
+    SafetyBlocked { reason: String },
+    // 저작물 인용(RECITATION)으로 응답이 중단됨
+    Recitation,
+    // 출력 토큰 한도에 도달해 내용이 비어 있음
+    MaxTokens,
+    // 함수 호출을 잘못 만들었거나 허용되지 않은 도구를 부름
+    MalformedFunctionCall,
+    // 후보는 있지만 보낼 내용이 없음
+    EmptyResponse,
+    Other(String),
+}
+
+impl GeminiError {
+    // 빈 응답의 원인이 될 수 있는 finish_reason 만 오류로 바꿉니다.
+    pub fn from_finish_reason(reason: &FinishReason) -> Option<Self> {
+        match reason {
+            FinishReason::Safety
+            | FinishReason::Blocklist
+            | FinishReason::ProhibitedContent
+            | FinishReason::Spii
+            | FinishReason::ImageSafety => Some(GeminiError::SafetyBlocked { reason: reason.as_str().to_string() }),
+            FinishReason::Recitation => Some(GeminiError::Recitation),
+            FinishReason::MaxTokens => Some(GeminiError::MaxTokens),
+            FinishReason::MalformedFunctionCall
+            | FinishReason::UnexpectedToolCall
+            | FinishReason::TooManyToolCalls => Some(GeminiError::MalformedFunctionCall),
+            _ => None,
+        }
+    }
+
+    // 같은 요청을 잠시 후 다시 보내면 성공할 수 있는 오류
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            GeminiError::RateLimited { .. } | GeminiError::ServerError { .. } | GeminiError::Transport(_)
+        )
+    }
+
+    // Pro 가 과부하이거나 Pro 한도를 넘었을 때는 Flash 로 다시 보낼 수 있습니다.
+    pub fn should_fallback_to_flash(&self) -> bool {
+        matches!(self, GeminiError::RateLimited { .. } | GeminiError::ServerError { .. })
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            GeminiError::RateLimited { .. } => "요청 한도 초과",
+            GeminiError::ServerError { .. } => "Gemini 서버 오류",
+            GeminiError::Transport(_) => "Gemini 연결 실패",
+            GeminiError::SafetyBlocked { .. } => "안전 정책으로 차단됨",
+            GeminiError::Recitation => "인용 제한으로 중단됨",
+            GeminiError::MaxTokens => "응답 길이 초과",
+            GeminiError::MalformedFunctionCall => "도구 호출 오류",
+            GeminiError::EmptyResponse => "빈 응답",
+            GeminiError::Other(_) => "Gemini 오류",
+        }
+    }
+
+    // 디스코드에 그대로 보여줄 설명
+    pub fn user_message(&self) -> String {
+        match self {
+            GeminiError::RateLimited { .. } => {
+                "요청이 많아 Gemini 가 잠시 응답을 거절했습니다. 잠시 후 다시 시도해 주세요.".to_string()
+            }
+            GeminiError::ServerError { http_status, .. } => format!(
+                "Gemini 서버가 혼잡하거나 오류가 발생했습니다. (HTTP {}) 잠시 후 다시 시도해 주세요.",
+                http_status
+            ),
+            GeminiError::Transport(_) => "Gemini 서버에 연결하지 못했습니다. 잠시 후 다시 시도해 주세요.".to_string(),
+            GeminiError::SafetyBlocked { reason } => format!(
+                "Gemini 의 안전 정책에 따라 이 요청에는 답할 수 없습니다. (`{}`)\n표현을 바꿔 다시 질문해 주세요.",
+                reason
+            ),
+            GeminiError::Recitation => {
+                "저작물을 그대로 옮길 우려가 있어 답변이 중단되었습니다. 요약이나 다른 표현으로 요청해 주세요.".to_string()
+            }
+            GeminiError::MaxTokens => {
+                "답변이 최대 길이에 도달해 보낼 내용이 없습니다. 질문을 나누거나 생각 예산을 줄여 주세요.".to_string()
+            }
+            GeminiError::MalformedFunctionCall => {
+                "Gemini 가 도구 호출을 잘못 만들어 답변을 끝내지 못했습니다. 다시 시도해 주세요.".to_string()
+            }
+            GeminiError::EmptyResponse => "Gemini 가 빈 응답을 보냈습니다. 다시 시도해 주세요.".to_string(),
+            GeminiError::Other(_) => {
+                "Gemini 요청을 처리하지 못했습니다. 문제가 계속되면 관리자에게 문의해 주세요.".to_string()
+            }
+        }
+    }
+}
+
+impl From<GeminiApiError> for GeminiError {
+    fn from(e: GeminiApiError) -> Self {
+        match e {
+            GeminiApiError::Api { http_status: 429, message, .. } => GeminiError::RateLimited { message },
+            GeminiApiError::Api { http_status, message, .. } if http_status >= 500 => {
+                GeminiError::ServerError { http_status, message }
+            }
+            GeminiApiError::Transport(e) => GeminiError::Transport(e),
+            GeminiApiError::Blocked { reason } => GeminiError::SafetyBlocked { reason },
+            other => GeminiError::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for GeminiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeminiError::RateLimited { message } => write!(f, "Rate limited: {}", message),
+            GeminiError::ServerError { http_status, message } => write!(f, "Server error {}: {}", http_status, message),
+            GeminiError::Transport(e) => write!(f, "Request failed: {}", e),
+            GeminiError::SafetyBlocked { reason } => write!(f, "Blocked: {}", reason),
+            GeminiError::Recitation => write!(f, "Stopped by recitation"),
+            GeminiError::MaxTokens => write!(f, "Max tokens reached without content"),
+            GeminiError::MalformedFunctionCall => write!(f, "Malformed function call"),
+            GeminiError::EmptyResponse => write!(f, "Empty response"),
+            GeminiError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for GeminiError {}
+
+// 기존 Result<_, String> 호출부와 호환
+impl From<GeminiError> for String {
+    fn from(e: GeminiError) -> Self {
+        format!("Gemini API > Error: {}", e)
+    }
+}
+
+// 일시적인 오류의 재시도 정책.
+// attempt 번째 실패 뒤 initial_backoff * 2^(attempt-1) (최대 max_backoff) 의 절반에서 전부 사이를 기다립니다.
+#[derive(Debug, Clone)]
+pub struct GeminiRetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for GeminiRetryPolicy {
+    fn default() -> Self {
+        GeminiRetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1000),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl GeminiRetryPolicy {
+    // jitter 는 0.0 ~ 1.0
+    pub fn backoff(&self, attempt: u32, jitter: f64) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let base = self.initial_backoff.saturating_mul(factor).min(self.max_backoff);
+        let millis = base.as_millis() as f64 * (0.5 + 0.5 * jitter.clamp(0.0, 1.0));
+        Duration::from_millis(millis.round() as u64)
+    }
+
+    pub fn jittered_backoff(&self, attempt: u32) -> Duration {
+        self.backoff(attempt, random_unit())
+    }
+}
+
+// [0, 1) 범위의 무작위 값
+fn random_unit() -> f64 {
+    rand::random::<f64>()
+}
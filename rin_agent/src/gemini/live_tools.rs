@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use gemini_live_api::types::live_api_types::BidiGenerateContentToolCall;
+use gemini_live_api::types::{GeminiFunctionCall, GeminiFunctionResponse};
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+
+use crate::gemini::types::{DiscordUserInfo, GeminiActionResult, GeminiBotToolInputValue, GeminiBotTools};
+use crate::gemini::utils::translate_to_gemini_param;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::libs::shutdown::IN_FLIGHT_WORK;
+use crate::service::metrics_service::{record_tool_call, record_unknown_tool_call};
+use crate::setting::gemini_setting::GEMINI_BOT_TOOLS;
+
+/// Live 세션에서 실행이 끝난 도구 결과
+#[derive(Debug, Clone)]
+pub struct LiveToolResult {
+    pub response: GeminiFunctionResponse,
+    /// 유저에게 보여줄 메시지 (알람 설정 완료 등)
+    pub show_user: Option<String>,
+}
+
+// --- Live 세션의 toolCall 을 도구 레지스트리로 실행하는 디스패처 ---
+// send_query_to_gemini 와 같은 GEMINI_BOT_TOOLS 를 사용하며, 호출마다 태스크를 띄워
+// 취소(toolCallCancellation) 가 오면 실행 중인 태스크를 중단합니다.
+pub struct LiveToolDispatcher {
+    registry: &'static HashMap<String, GeminiBotTools>,
+    user_info: Option<DiscordUserInfo>,
+    running: HashMap<String, AbortHandle>,
+    result_tx: mpsc::UnboundedSender<LiveToolResult>,
+    result_rx: mpsc::UnboundedReceiver<LiveToolResult>,
+    next_call_id: u64,
+}
+
+impl LiveToolDispatcher {
+    pub fn new(user_info: Option<DiscordUserInfo>) -> Self {
+        Self::with_registry(&GEMINI_BOT_TOOLS, user_info)
+    }
+
+    pub fn with_registry(registry: &'static HashMap<String, GeminiBotTools>, user_info: Option<DiscordUserInfo>) -> Self {
+        let (result_tx, result_rx) = mpsc::unbounded_channel();
+        LiveToolDispatcher {
+            registry,
+            user_info,
+            running: HashMap::new(),
+            result_tx,
+            result_rx,
+            next_call_id: 0,
+        }
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.running.len()
+    }
+
+    /// 서버가 요청한 함수 호출들을 실행합니다. 결과는 `next_result` 로 받습니다.
+    pub fn dispatch(&mut self, tool_call: BidiGenerateContentToolCall) {
+        for call in tool_call.function_calls {
+            self.dispatch_one(call);
+        }
+    }
+
+    fn dispatch_one(&mut self, call: GeminiFunctionCall) {
+        let id = call.id.clone().unwrap_or_else(|| {
+            self.next_call_id += 1;
+            format!("local-{}", self.next_call_id)
+        });
+        let name = call.name.clone();
+
+        let Some(tool) = self.registry.get(&name) else {
+            LOGGER.log(LogLevel::Warning, &format!("[LiveTool] 알 수 없는 함수 호출: {}", name));
+            record_unknown_tool_call("live");
+            let _ = self.result_tx.send(make_error_result(id, name, "Unknown function".to_string()));
+            return;
+        };
+
+        let args: HashMap<String, GeminiBotToolInputValue> = call
+            .args
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(k, v)| {
+                let value = GeminiBotToolInputValue { name: k.clone(), value: translate_to_gemini_param(&v) };
+                (k, value)
+            })
+            .collect();
+
+        LOGGER.log(LogLevel::Debug, &format!("[LiveTool] 함수 실행: {} ({})", name, id));
+        let action = tool.action;
+        let user_info = self.user_info.clone();
+        let result_tx = self.result_tx.clone();
+        let task_id = id.clone();
+        let in_flight = IN_FLIGHT_WORK.begin();
+        let handle = tokio::spawn(async move {
+            let _in_flight = in_flight;
+            let result = action(args, user_info).await;
+            record_tool_call("live", &name, if result.is_ok() { "ok" } else { "error" });
+            let result = match result {
+                Ok(result) => make_success_result(task_id, name, result),
+                Err(e) => make_error_result(task_id, name, e),
+            };
+            let _ = result_tx.send(result);
+        });
+        self.running.insert(id, handle.abort_handle());
+    }
+
+    /// 서버가 취소한 호출을 중단합니다. 실제로 중단한 개수를 돌려줍니다.
+    pub fn cancel(&mut self, ids: &[String]) -> usize {
+        let mut cancelled = 0;
+        for id in ids {
+            if let Some(handle) = self.running.remove(id) {
+                handle.abort();
+                cancelled += 1;
+                LOGGER.log(LogLevel::Debug, &format!("[LiveTool] 함수 호출 취소: {}", id));
+            }
+        }
+        cancelled
+    }
+
+    /// 다음으로 끝난 호출 결과를 기다립니다. 취소된 호출의 결과는 버립니다.
+    pub async fn next_result(&mut self) -> LiveToolResult {
+        loop {
+            // result_tx 를 스스로 들고 있으므로 채널은 닫히지 않습니다.
+            let result = self.result_rx.recv().await.expect("tool result channel closed");
+            let id = result.response.id.clone().unwrap_or_default();
+            // 레지스트리에 없는 함수는 running 에 등록되지 않고 바로 결과가 옵니다.
+            if self.running.remove(&id).is_some() || !self.registry.contains_key(&result.response.name) {
+                return result;
+            }
+        }
+    }
+
+    /// 세션이 끝날 때 실행 중인 호출을 모두 중단합니다.
+    pub fn abort_all(&mut self) {
+        for (_, handle) in self.running.drain() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for LiveToolDispatcher {
+    fn drop(&mut self) {
+        self.abort_all();
+    }
+}
+
+fn make_success_result(id: String, name: String, result: GeminiActionResult) -> LiveToolResult {
+    // 이미지/오디오 바이트는 Live 세션으로 돌려보내지 않습니다.
+    let response = json!({
+        "result_message": result.result_message,
+        "result": result.result,
+        "error": result.error,
+    });
+    LiveToolResult {
+        response: GeminiFunctionResponse {
+            id: Some(id),
+            name,
+            response: Some(response),
+            will_continue: None,
+            scheduling: None,
+        },
+        show_user: result.show_user,
+    }
+}
+
+fn make_error_result(id: String, name: String, error: String) -> LiveToolResult {
+    LiveToolResult {
+        response: GeminiFunctionResponse {
+            id: Some(id),
+            name,
+            response: Some(json!({ "error": { "message": error } })),
+            will_continue: None,
+            scheduling: None,
+        },
+        show_user: None,
+    }
+}
@@ -0,0 +1,315 @@
+use base64::Engine;
+use bytes::Bytes;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use gemini_live_api::service::socket_client_manager::{GeminiSocketManager, LiveSessionHandle, SocketPoolConfig};
+use gemini_live_api::types::enums::GeminiContentRole;
+use gemini_live_api::types::live_api_types::{
+    ActivityHandling, AudioTranscriptionConfig, AutomaticActivityDetection, BidiGenerateContentRealTimeInput,
+    BidiGenerateContentServerContent, BidiGenerateContentSetup, BidiGenerateContentToolResponse, ContextWindowCompression,
+    LiveEvent, RealtimeInputConfig, SessionResumptionConfig,
+};
+use gemini_live_api::types::{GeminiContents, GeminiGenerationConfig, GeminiGenerationConfigTool, GeminiParts, GeminiResponseModalities};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::discord::voice::pcm_stream::GEMINI_INPUT_SAMPLE_RATE;
+use crate::gemini::live_tools::{LiveToolDispatcher, LiveToolResult};
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::libs::shutdown::SHUTDOWN;
+use crate::setting::gemini_setting::{
+    get_gemini_bot_tools, GEMINI_LIVE_COMPRESSION_TARGET_TOKENS, GEMINI_LIVE_COMPRESSION_TRIGGER_TOKENS,
+    GEMINI_LIVE_IDLE_TIMEOUT_SECS, GEMINI_LIVE_MAX_SESSIONS, GEMINI_LIVE_URL, GEMINI_MODEL_LIVE, GEMINI_MODEL_LIVE_TEXT,
+};
+
+/// 음성 대화와 받아쓰기가 함께 쓰는 Live 세션 풀.
+/// 키는 "voice:{guild}" 처럼 용도별로 구분해서 씁니다.
+pub static LIVE_SOCKET_POOL: LazyLock<GeminiSocketManager<String>> = LazyLock::new(|| {
+    GeminiSocketManager::with_config(SocketPoolConfig {
+        max_sessions: GEMINI_LIVE_MAX_SESSIONS,
+        idle_timeout: Duration::from_secs(GEMINI_LIVE_IDLE_TIMEOUT_SECS),
+        ..Default::default()
+    })
+});
+
+/// 음성 채널 -> Live API 로 보내는 입력
+#[derive(Debug)]
+pub enum VoiceBridgeInput {
+    /// 16kHz mono s16le PCM
+    Audio(Vec<u8>),
+    Text(String),
+    /// 더 이상 음성이 들어오지 않음 (모든 화자가 조용해졌을 때)
+    AudioStreamEnd,
+}
+
+/// Live API -> 음성 채널로 내보내는 출력
+#[derive(Debug, PartialEq)]
+pub enum VoiceBridgeOutput {
+    /// 24kHz mono s16le PCM
+    Audio(Bytes),
+    TurnComplete,
+    /// 사용자가 말을 시작해 모델의 발화가 끊겼음 (barge-in)
+    Interrupted,
+    InputTranscript(String),
+    OutputTranscript(String),
+    /// 음성 세션에서 실행된 도구의 결과
+    ToolResult { name: String, show_user: Option<String> },
+    Closed,
+}
+
+pub fn get_live_api_url() -> String {
+    let key = std::env::var("GEMINI_API_KEY").unwrap_or_default();
+    format!("{}?key={}", GEMINI_LIVE_URL, key)
+}
+
+// 긴 세션을 위해 컨텍스트 압축을 켜고, 연결이 끊기면 재개 핸들로 이어받습니다.
+fn get_long_session_compression() -> ContextWindowCompression {
+    ContextWindowCompression::sliding_window(GEMINI_LIVE_COMPRESSION_TRIGGER_TOKENS, GEMINI_LIVE_COMPRESSION_TARGET_TOKENS)
+}
+
+// 음성 대화는 화자를 구분하지 않고 /join_voice 를 실행한 사용자 이름으로 도구를 실행합니다.
+// 모델이 다른 사람의 요청을 실행할 때 이를 알 수 있도록 도구 설명에 적어 둡니다.
+const VOICE_TOOL_ATTRIBUTION: &str = " (음성 대화에서는 누가 말했든 /join_voice 를 실행한 사용자 이름으로 기록됩니다. 다른 사람의 알람이나 기억이라면 실행 전에 그 사실을 알려 주세요.)";
+
+fn get_voice_tools() -> Vec<GeminiGenerationConfigTool> {
+    let mut tools = get_gemini_bot_tools();
+    for declaration in tools.iter_mut().flat_map(|tool| tool.function_declarations.iter_mut().flatten()) {
+        declaration.description.push_str(VOICE_TOOL_ATTRIBUTION);
+    }
+    tools
+}
+
+/// 음성 대화용 Live 세션 설정.
+/// 사용자가 말을 시작하면 모델 발화를 끊도록(StartOfActivityInterrupts) 설정합니다.
+pub fn get_voice_setup(system_instruction: String) -> BidiGenerateContentSetup {
+    BidiGenerateContentSetup {
+        model: format!("models/{}", GEMINI_MODEL_LIVE),
+        generation_config: Some(GeminiGenerationConfig {
+            response_modalities: Some(vec![GeminiResponseModalities::Audio]),
+            ..Default::default()
+        }),
+        system_instruction: Some(GeminiContents {
+            parts: vec![GeminiParts::new().set_text(system_instruction)],
+            role: GeminiContentRole::User,
+        }),
+        realtime_input_config: Some(RealtimeInputConfig {
+            automatic_activity_detection: Some(AutomaticActivityDetection {
+                disable: Some(false),
+                ..Default::default()
+            }),
+            activity_handling: Some(ActivityHandling::StartOfActivityInterrupts),
+            turn_coverage: None,
+        }),
+        tools: Some(get_voice_tools()),
+        session_resumption: Some(SessionResumptionConfig::default()),
+        context_window_compression: Some(get_long_session_compression()),
+        input_audio_transcription: Some(AudioTranscriptionConfig::default()),
+        output_audio_transcription: Some(AudioTranscriptionConfig::default()),
+        ..Default::default()
+    }
+}
+
+/// 받아쓰기 전용 Live 세션 설정. 입력 음성의 전사(inputTranscription)만 사용합니다.
+pub fn get_transcribe_setup() -> BidiGenerateContentSetup {
+    BidiGenerateContentSetup {
+        model: format!("models/{}", GEMINI_MODEL_LIVE_TEXT),
+        generation_config: Some(GeminiGenerationConfig {
+            response_modalities: Some(vec![GeminiResponseModalities::Text]),
+            max_output_tokens: Some(1),
+            ..Default::default()
+        }),
+        system_instruction: Some(GeminiContents {
+            parts: vec![GeminiParts::new().set_text(
+                "당신은 회의 받아쓰기 전용입니다. 들은 내용에 대답하지 말고, 항상 빈 응답만 하십시오.".to_string()
+            )],
+            role: GeminiContentRole::User,
+        }),
+        realtime_input_config: Some(RealtimeInputConfig {
+            automatic_activity_detection: Some(AutomaticActivityDetection {
+                disable: Some(false),
+                ..Default::default()
+            }),
+            activity_handling: Some(ActivityHandling::NoInterruption),
+            turn_coverage: None,
+        }),
+        session_resumption: Some(SessionResumptionConfig::default()),
+        context_window_compression: Some(get_long_session_compression()),
+        input_audio_transcription: Some(AudioTranscriptionConfig::default()),
+        ..Default::default()
+    }
+}
+
+/// 유휴 Live 세션을 주기적으로 정리합니다. 정리 태스크가 패닉하면 오류로 돌려줘 감시자가 다시 띄우게 합니다.
+/// 종료 신호를 받으면 정리 태스크를 멈춥니다. 남은 세션은 종료 절차에서 닫습니다.
+pub async fn run_live_idle_reaper(interval: Duration) -> Result<(), String> {
+    let mut reaper = LIVE_SOCKET_POOL.spawn_idle_reaper(interval);
+    tokio::select! {
+        res = &mut reaper => res.map_err(|e| format!("Live 세션 정리 태스크 오류: {}", e)),
+        _ = SHUTDOWN.cancelled() => {
+            reaper.abort();
+            Ok(())
+        }
+    }
+}
+
+/// Live 세션에 연결하고 입출력을 중계하는 태스크를 띄웁니다.
+/// 반환된 송신부가 모두 drop 되면 세션이 종료되고 `VoiceBridgeOutput::Closed` 가 전송됩니다.
+/// `tools` 가 주어지면 서버의 toolCall 을 실행하고 toolResponse 를 돌려보냅니다.
+/// 연결이 끊기면 소켓 클라이언트가 재연결과 세션 재개를 처리합니다.
+/// 세션은 `LIVE_SOCKET_POOL` 에 `key` 로 등록되며, 브리지가 끝나면 풀에서 빠집니다.
+/// 브리지가 도는 동안에는 유휴 정리 대상이 아닙니다.
+pub async fn start_live_voice_bridge(
+    key: String,
+    url: String,
+    setup: BidiGenerateContentSetup,
+    output: mpsc::Sender<VoiceBridgeOutput>,
+    tools: Option<LiveToolDispatcher>,
+) -> Result<mpsc::Sender<VoiceBridgeInput>, String> {
+    let (session, events) = LIVE_SOCKET_POOL.get_or_create_socket_client(key.clone(), url, setup).await?;
+
+    let (input_tx, input_rx) = mpsc::channel(200);
+    tokio::spawn(run_bridge(key, session, events, input_rx, output, tools));
+    Ok(input_tx)
+}
+
+async fn run_bridge(
+    id: String,
+    sender: LiveSessionHandle<String>,
+    mut events: broadcast::Receiver<LiveEvent>,
+    mut input_rx: mpsc::Receiver<VoiceBridgeInput>,
+    output: mpsc::Sender<VoiceBridgeOutput>,
+    mut tools: Option<LiveToolDispatcher>,
+) {
+    // 음성 입력은 누군가 말할 때만 들어오므로, 브리지가 살아 있는 동안은 침묵이 길어도 유휴 정리에서 빼 둡니다.
+    let _hold = sender.hold();
+    loop {
+        tokio::select! {
+            input = input_rx.recv() => {
+                let realtime_input = match input {
+                    Some(VoiceBridgeInput::Audio(pcm)) => BidiGenerateContentRealTimeInput::audio(&pcm, GEMINI_INPUT_SAMPLE_RATE),
+                    Some(VoiceBridgeInput::Text(text)) => BidiGenerateContentRealTimeInput::text(text),
+                    Some(VoiceBridgeInput::AudioStreamEnd) => BidiGenerateContentRealTimeInput {
+                        audio_stream_end: Some(true),
+                        ..Default::default()
+                    },
+                    None => break,
+                };
+                if let Err(e) = sender.send_realtime_input(realtime_input).await {
+                    LOGGER.log(LogLevel::Error, &format!("[LiveVoiceBridge:{}] 실시간 입력 전송 실패: {}", id, e));
+                    break;
+                }
+            }
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        LOGGER.log(LogLevel::Warning, &format!("[LiveVoiceBridge:{}] 이벤트 {}개를 놓쳤습니다.", id, skipped));
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                match event {
+                    LiveEvent::ServerContent(content) => {
+                        if !forward_server_content(content, &output).await {
+                            break;
+                        }
+                    }
+                    LiveEvent::ToolCall(tool_call) => match tools.as_mut() {
+                        Some(tools) => tools.dispatch(tool_call),
+                        None => LOGGER.log(LogLevel::Warning, &format!("[LiveVoiceBridge:{}] 도구가 없는 세션에 toolCall 이 왔습니다.", id)),
+                    },
+                    LiveEvent::ToolCallCancellation(cancellation) => {
+                        if let Some(tools) = tools.as_mut() {
+                            tools.cancel(&cancellation.ids);
+                        }
+                    }
+                    LiveEvent::GoAway { time_left } => {
+                        LOGGER.log(LogLevel::Info, &format!("[LiveVoiceBridge:{}] 서버가 연결 종료를 예고했습니다. ({:?} 남음)", id, time_left));
+                    }
+                    LiveEvent::Reconnecting { attempt } => {
+                        LOGGER.log(LogLevel::Warning, &format!("[LiveVoiceBridge:{}] 재연결 시도 중 ({}회)", id, attempt));
+                    }
+                    LiveEvent::Reconnected { resumed } => {
+                        let level = if resumed { LogLevel::Info } else { LogLevel::Warning };
+                        LOGGER.log(level, &format!("[LiveVoiceBridge:{}] 재연결 완료 (세션 재개: {})", id, resumed));
+                    }
+                    LiveEvent::Error(e) => {
+                        LOGGER.log(LogLevel::Warning, &format!("[LiveVoiceBridge:{}] {}", id, e));
+                    }
+                    LiveEvent::Closed { error } => {
+                        match error {
+                            Some(e) => LOGGER.log(LogLevel::Error, &format!("[LiveVoiceBridge:{}] 세션이 종료되었습니다: {}", id, e)),
+                            None => LOGGER.log(LogLevel::Info, &format!("[LiveVoiceBridge:{}] 서버 연결이 종료되었습니다.", id)),
+                        }
+                        break;
+                    }
+                    LiveEvent::SetupComplete | LiveEvent::SessionResumptionUpdate(_) => {}
+                }
+            }
+            result = next_tool_result(&mut tools) => {
+                let LiveToolResult { response, show_user } = result;
+                let name = response.name.clone();
+                let tool_response = BidiGenerateContentToolResponse {
+                    function_responses: Some(vec![response]),
+                };
+                if let Err(e) = sender.send_tool_response(tool_response).await {
+                    LOGGER.log(LogLevel::Error, &format!("[LiveVoiceBridge:{}] 도구 응답 전송 실패: {}", id, e));
+                    break;
+                }
+                if output.send(VoiceBridgeOutput::ToolResult { name, show_user }).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    if let Some(tools) = tools.as_mut() {
+        tools.abort_all();
+    }
+    LIVE_SOCKET_POOL.remove(&id).await;
+    let _ = output.send(VoiceBridgeOutput::Closed).await;
+}
+
+/// 도구가 없는 세션에서는 영원히 대기합니다.
+async fn next_tool_result(tools: &mut Option<LiveToolDispatcher>) -> LiveToolResult {
+    match tools {
+        Some(tools) => tools.next_result().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// 서버 콘텐츠를 출력 이벤트로 변환합니다. 출력 채널이 닫혔으면 false 를 돌려줍니다.
+async fn forward_server_content(content: BidiGenerateContentServerContent, output: &mpsc::Sender<VoiceBridgeOutput>) -> bool {
+    let mut events = Vec::new();
+    if content.interrupted {
+        events.push(VoiceBridgeOutput::Interrupted);
+    }
+    if let Some(transcript) = content.input_transcription {
+        events.push(VoiceBridgeOutput::InputTranscript(transcript.text));
+    }
+    if let Some(model_turn) = content.model_turn {
+        for part in model_turn.parts {
+            let Some(blob) = part.inline_data else { continue };
+            if !blob.mime_type.starts_with("audio/pcm") {
+                continue;
+            }
+            match base64::engine::general_purpose::STANDARD.decode(blob.data) {
+                Ok(pcm) => events.push(VoiceBridgeOutput::Audio(Bytes::from(pcm))),
+                Err(e) => LOGGER.log(LogLevel::Warning, &format!("[LiveVoiceBridge] 오디오 디코딩 실패: {}", e)),
+            }
+        }
+    }
+    if let Some(transcript) = content.output_transcription {
+        events.push(VoiceBridgeOutput::OutputTranscript(transcript.text));
+    }
+    if content.turn_complete {
+        events.push(VoiceBridgeOutput::TurnComplete);
+    }
+
+    for event in events {
+        if output.send(event).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
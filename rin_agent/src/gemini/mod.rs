@@ -2,4 +2,12 @@ pub mod utils;
 pub mod types;
 pub mod tools;
 pub mod gemini_client;
-pub mod unified_generation;
\ No newline at end of file
+pub mod unified_generation;
+pub mod live_voice_bridge;
+pub mod live_tools;
+pub mod model_router;
+pub mod gemini_error;
+pub mod structured_output;
+pub mod builtin_tools;
+
+pub mod receipt_ocr;
\ No newline at end of file
@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+
+use entity::tb_discord_guilds;
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use gemini_live_api::types::enums::{GeminiContentRole, GeminiSchemaType};
+use gemini_live_api::types::rest_api_types::GenerateContentRequest;
+use gemini_live_api::types::{GeminiContents, GeminiGenerationConfig, GeminiParts, GeminiSchema, ThinkingConfig};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+
+use crate::gemini::types::GeminiUsageContext;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::usage_service::spawn_record_usage;
+use crate::setting::gemini_setting::{GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO};
+
+// 점수가 이 값 이하면 가벼운 질문, 이상이면 어려운 질문으로 바로 정합니다. 그 사이는 Flash 에게 묻습니다.
+const LOW_SCORE_MAX: i32 = 0;
+const HIGH_SCORE_MIN: i32 = 4;
+const MEDIUM_THINKING_BUDGET: i32 = 2048;
+const HIGH_THINKING_BUDGET: i32 = 8192;
+// 분류 호출에 보낼 질문 길이 상한 (글자 수)
+const CLASSIFY_QUERY_MAX_CHARS: usize = 2000;
+
+const HEAVY_KEYWORDS: [&str; 16] = [
+    "증명", "분석", "설계", "리팩터", "디버그", "최적화", "알고리즘", "단계별",
+    "비교해", "proof", "analyze", "debug", "optimize", "algorithm", "step by step", "refactor",
+];
+// 한글은 어미가 붙으므로 앞부분만, 영어는 단어 전체가 같아야 합니다.
+const LIGHT_KEYWORDS: [&str; 6] = ["안녕", "고마워", "ㅋㅋ", "hello", "thanks", "hi"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteComplexity {
+    Low,
+    Medium,
+    High,
+}
+
+// 이번 요청에 쓸 모델과 생각 예산. reason 은 답장 꼬리말에 그대로 보입니다.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRoute {
+    pub use_pro: bool,
+    pub thinking_budget: Option<i32>,
+    pub reason: String,
+    // 라우터가 정했거나 도중에 모델이 바뀌었는지 (사용자가 고른 그대로면 false)
+    // true 면 답장 본문에도 꼬리말을 붙입니다.
+    pub routed: bool,
+}
+
+impl ModelRoute {
+    pub fn manual(use_pro: bool, thinking_budget: Option<i32>, reason: &str) -> Self {
+        ModelRoute {
+            use_pro,
+            thinking_budget,
+            reason: reason.to_string(),
+            routed: false,
+        }
+    }
+
+    pub fn model_name(&self) -> &'static str {
+        if self.use_pro { GEMINI_MODEL_PRO } else { GEMINI_MODEL_FLASH }
+    }
+
+    // Pro 로 보냈는데 Flash 가 응답했다면 (과부하 대체) 꼬리말에 알립니다.
+    pub fn with_response_model(self, model: &str) -> Self {
+        if !self.use_pro || model != GEMINI_MODEL_FLASH {
+            return self;
+        }
+        ModelRoute {
+            use_pro: false,
+            reason: format!("{} (Pro 과부하로 Flash)", self.reason),
+            routed: true,
+            ..self
+        }
+    }
+
+    pub fn footer(&self) -> String {
+        let budget = self.thinking_budget.map(|b| format!(" · 생각 {}", b)).unwrap_or_default();
+        format!("{}{} · {}", self.model_name(), budget, self.reason)
+    }
+}
+
+// 길드별 모델 정책. 길드 행이 없으면 기본값(자동 선택 끔, Pro 허용)을 씁니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuildModelPolicy {
+    pub auto_model_routing: bool,
+    pub allow_pro_model: bool,
+}
+
+impl Default for GuildModelPolicy {
+    fn default() -> Self {
+        GuildModelPolicy {
+            auto_model_routing: false,
+            allow_pro_model: true,
+        }
+    }
+}
+
+pub async fn load_guild_policy(guild_id: u64) -> GuildModelPolicy {
+    let Some(db) = DB_CONNECTION_POOL.get() else {
+        return GuildModelPolicy::default();
+    };
+    match tb_discord_guilds::Entity::find()
+        .filter(tb_discord_guilds::Column::GuildId.eq(guild_id as i64))
+        .one(db)
+        .await
+    {
+        Ok(Some(guild)) => GuildModelPolicy {
+            auto_model_routing: guild.auto_model_routing,
+            allow_pro_model: guild.allow_pro_model,
+        },
+        Ok(None) => GuildModelPolicy::default(),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("Router > 길드 정책 조회 실패: {}", e));
+            GuildModelPolicy::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryScore {
+    pub score: i32,
+    pub reasons: Vec<&'static str>,
+}
+
+impl QueryScore {
+    fn add(&mut self, score: i32, reason: &'static str) {
+        self.score += score;
+        self.reasons.push(reason);
+    }
+
+    pub fn complexity(&self) -> Option<RouteComplexity> {
+        if self.score <= LOW_SCORE_MAX {
+            Some(RouteComplexity::Low)
+        } else if self.score >= HIGH_SCORE_MIN {
+            Some(RouteComplexity::High)
+        } else {
+            None
+        }
+    }
+
+    pub fn reason(&self) -> String {
+        if self.reasons.is_empty() {
+            "일반 질문".to_string()
+        } else {
+            self.reasons.join(", ")
+        }
+    }
+}
+
+// 길이, 첨부, 코드 블록, 키워드로 질문의 난이도를 어림합니다.
+pub fn score_query(query: &str, has_attachment: bool) -> QueryScore {
+    let mut score = QueryScore::default();
+    let lower = query.to_lowercase();
+    let length = query.chars().count();
+    if length >= 1200 {
+        score.add(3, "긴 질문");
+    } else if length >= 400 {
+        score.add(1, "긴 질문");
+    } else if length < 40 {
+        score.add(-1, "짧은 질문");
+    }
+    if has_attachment {
+        score.add(1, "첨부 포함");
+    }
+    if query.contains("```") {
+        score.add(2, "코드 포함");
+    }
+    if HEAVY_KEYWORDS.iter().any(|k| lower.contains(k)) {
+        score.add(2, "심화 키워드");
+    }
+    let is_light_word = |word: &str| {
+        let word = word.trim_matches(|c: char| c.is_ascii_punctuation());
+        LIGHT_KEYWORDS.iter().any(|k| if k.is_ascii() { word == *k } else { word.starts_with(k) })
+    };
+    if lower.split_whitespace().any(is_light_word) {
+        score.add(-1, "가벼운 대화");
+    }
+    score
+}
+
+pub fn route_for(complexity: RouteComplexity, reason: &str, allow_pro: bool) -> ModelRoute {
+    let (use_pro, thinking_budget) = match complexity {
+        RouteComplexity::Low => (false, None),
+        RouteComplexity::Medium => (false, Some(MEDIUM_THINKING_BUDGET)),
+        RouteComplexity::High => (allow_pro, Some(HIGH_THINKING_BUDGET)),
+    };
+    let reason = if complexity == RouteComplexity::High && !allow_pro {
+        format!("자동 선택: {} (Pro 제한으로 Flash)", reason)
+    } else {
+        format!("자동 선택: {}", reason)
+    };
+    ModelRoute {
+        use_pro,
+        thinking_budget,
+        reason,
+        routed: true,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ClassifyResult {
+    complexity: RouteComplexity,
+    #[serde(default)]
+    reason: String,
+}
+
+fn classify_schema() -> GeminiSchema {
+    GeminiSchema {
+        schema_type: GeminiSchemaType::Object,
+        properties: Some(BTreeMap::from([
+            ("complexity".to_string(), GeminiSchema {
+                schema_type: GeminiSchemaType::String,
+                enum_values: Some(vec!["low".to_string(), "medium".to_string(), "high".to_string()]),
+                ..Default::default()
+            }),
+            ("reason".to_string(), GeminiSchema {
+                schema_type: GeminiSchemaType::String,
+                description: Some("한국어 10자 이내".to_string()),
+                ..Default::default()
+            }),
+        ])),
+        required: Some(vec!["complexity".to_string(), "reason".to_string()]),
+        ..Default::default()
+    }
+}
+
+// 생각 없이 Flash 에 난이도만 물어봅니다.
+pub async fn classify_with_flash(query: &str, usage_context: &GeminiUsageContext) -> Result<(RouteComplexity, String), String> {
+    let rest = GeminiRestClient::from_env()?;
+    let query: String = query.chars().take(CLASSIFY_QUERY_MAX_CHARS).collect();
+    let request = GenerateContentRequest {
+        system_instruction: Some(GeminiContents {
+            role: GeminiContentRole::User,
+            parts: vec![GeminiParts::new().set_text(
+                "사용자 질문의 난이도를 분류하세요. 인사나 간단한 사실은 low, 설명이나 요약은 medium, \
+                 코드 작성, 수학, 여러 단계의 추론이 필요하면 high 입니다.".to_string()
+            )],
+        }),
+        contents: vec![GeminiContents {
+            role: GeminiContentRole::User,
+            parts: vec![GeminiParts::new().set_text(query)],
+        }],
+        generation_config: Some(GeminiGenerationConfig {
+            response_mime_type: Some("application/json".to_string()),
+            response_schema: Some(classify_schema()),
+            max_output_tokens: Some(64),
+            temperature: Some(0.0),
+            top_p: None,
+            top_k: None,
+            presence_penalty: None,
+            frequency_penalty: None,
+            thinking_config: Some(ThinkingConfig { include_thoughts: false, thinking_budget: 0 }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let response = rest.generate_content(GEMINI_MODEL_FLASH, &request).await?;
+    spawn_record_usage(GEMINI_MODEL_FLASH, usage_context, response.usage_metadata.as_ref());
+    let text = response.text().ok_or("Empty classify response".to_string())?;
+    let result: ClassifyResult = serde_json::from_str(text.trim()).map_err(|e| format!("{} ({})", e, text))?;
+    Ok((result.complexity, result.reason))
+}
+
+// 휴리스틱으로 정할 수 없으면 Flash 분류를 쓰고, 그것도 실패하면 중간 난이도로 봅니다.
+pub async fn route_query(
+    query: &str,
+    has_attachment: bool,
+    allow_pro: bool,
+    usage_context: &GeminiUsageContext,
+) -> ModelRoute {
+    let score = score_query(query, has_attachment);
+    if let Some(complexity) = score.complexity() {
+        return route_for(complexity, &score.reason(), allow_pro);
+    }
+    let usage_context = GeminiUsageContext { source: "router".to_string(), ..usage_context.clone() };
+    match classify_with_flash(query, &usage_context).await {
+        Ok((complexity, reason)) => {
+            let reason = if reason.is_empty() { score.reason() } else { reason };
+            route_for(complexity, &reason, allow_pro)
+        }
+        Err(e) => {
+            LOGGER.log(LogLevel::Warning, &format!("Router > 분류 호출 실패, 중간 난이도로 처리합니다: {}", e));
+            route_for(RouteComplexity::Medium, &score.reason(), allow_pro)
+        }
+    }
+}
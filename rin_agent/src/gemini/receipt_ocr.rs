@@ -0,0 +1,157 @@
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use gemini_live_api::types::enums::{GeminiContentRole, GeminiSchemaType};
+use gemini_live_api::types::rest_api_types::GenerateContentRequest;
+use gemini_live_api::types::{GeminiContents, GeminiFileData, GeminiGenerationConfig, GeminiParts, GeminiSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::gemini::gemini_client::request_generate_content;
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::structured_output::{array_schema, object_schema, string_schema, validate_against_schema};
+use crate::gemini::types::{GeminiImageInputType, GeminiUsageContext};
+use crate::setting::gemini_setting::{GENERATE_CONF, SAFETY_SETTINGS};
+
+const RECEIPT_INSTRUCTION: &str = "사진 속 영수증을 읽어 JSON 으로 옮기세요. 사진이 영수증이 아니면 is_receipt 를 false 로 두고 나머지는 비워 두세요. \
+     금액은 영수증에 적힌 숫자 그대로, 통화 기호 없이 적고 currency 에는 KRW, USD, JPY 같은 ISO 4217 코드를 쓰세요. \
+     date 는 YYYY-MM-DD 형식으로, 보이지 않으면 null 로 두세요. total 은 할인과 세금, 팁을 모두 반영한 최종 결제 금액입니다.";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReceiptItem {
+    pub name: String,
+    pub quantity: Option<f64>,
+    // 수량을 곱한 뒤의 줄 금액
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReceiptData {
+    pub merchant: Option<String>,
+    pub date: Option<String>,
+    pub currency: String,
+    pub items: Vec<ReceiptItem>,
+    pub subtotal: Option<f64>,
+    pub tax: Option<f64>,
+    pub tip: Option<f64>,
+    pub total: f64,
+}
+
+impl ReceiptData {
+    // 장부는 원 단위 정수 금액만 다루므로 반올림합니다. 원화가 아니면 장부에 쓰지 않습니다.
+    pub fn total_units(&self) -> i64 {
+        self.total.round() as i64
+    }
+
+    pub fn is_krw(&self) -> bool {
+        self.currency.eq_ignore_ascii_case("KRW")
+    }
+}
+
+fn number_schema(description: &str, nullable: bool) -> GeminiSchema {
+    GeminiSchema {
+        schema_type: GeminiSchemaType::Number,
+        description: Some(description.to_string()),
+        nullable: nullable.then_some(true),
+        ..Default::default()
+    }
+}
+
+fn nullable_string_schema(description: &str) -> GeminiSchema {
+    GeminiSchema { nullable: Some(true), ..string_schema(description) }
+}
+
+pub fn receipt_schema() -> GeminiSchema {
+    // 영수증이 아닐 때는 항목이 없을 수 있어 items 의 최소 개수를 풉니다.
+    let items = GeminiSchema {
+        min_items: None,
+        ..array_schema(object_schema(
+            vec![
+                ("name", string_schema("품목 이름")),
+                ("quantity", number_schema("수량", true)),
+                ("amount", number_schema("줄 금액", false)),
+            ],
+            &["name", "amount"],
+        ))
+    };
+    object_schema(
+        vec![
+            ("is_receipt", GeminiSchema { schema_type: GeminiSchemaType::Boolean, ..Default::default() }),
+            ("merchant", nullable_string_schema("가게 이름")),
+            ("date", nullable_string_schema("결제일 (YYYY-MM-DD)")),
+            ("currency", string_schema("ISO 4217 통화 코드")),
+            ("items", items),
+            ("subtotal", number_schema("소계", true)),
+            ("tax", number_schema("세금", true)),
+            ("tip", number_schema("팁", true)),
+            ("total", number_schema("최종 결제 금액", false)),
+        ],
+        &["is_receipt", "currency", "items", "total"],
+    )
+}
+
+// 모델 응답을 검증하고 영수증으로 바꿉니다. 영수증이 아니거나 합계가 없으면 오류입니다.
+pub fn parse_receipt(value: &Value) -> Result<ReceiptData, String> {
+    validate_against_schema(value, &receipt_schema())?;
+    if value.get("is_receipt").and_then(Value::as_bool) != Some(true) {
+        return Err("영수증 사진이 아닌 것 같습니다.".to_string());
+    }
+    let mut receipt: ReceiptData = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+    receipt.currency = receipt.currency.trim().to_ascii_uppercase();
+    if receipt.currency.is_empty() {
+        receipt.currency = "KRW".to_string();
+    }
+    if receipt.total_units() <= 0 {
+        return Err("영수증에서 결제 금액을 읽지 못했습니다.".to_string());
+    }
+    Ok(receipt)
+}
+
+// 업로드한 영수증 사진을 읽습니다. image 는 upload_image_to_gemini 의 결과여야 합니다.
+pub async fn extract_receipt(
+    image: &GeminiImageInputType,
+    request: &str,
+    model: &str,
+    usage_context: &GeminiUsageContext,
+) -> Result<ReceiptData, GeminiError> {
+    let Some(file_uri) = image.file_url.clone() else {
+        return Err(GeminiError::Other("업로드한 이미지 주소가 없습니다.".to_string()));
+    };
+    let rest = GeminiRestClient::from_env()?;
+    let schema = receipt_schema();
+    let mut parts = vec![GeminiParts {
+        file_data: Some(GeminiFileData {
+            mime_type: Some(image.mime_type.clone()),
+            file_uri,
+        }),
+        ..Default::default()
+    }];
+    if !request.trim().is_empty() {
+        parts.push(GeminiParts::new().set_text(format!("사용자 메모: {}", request.trim())));
+    }
+    let request = GenerateContentRequest {
+        system_instruction: Some(GeminiContents {
+            role: GeminiContentRole::User,
+            parts: vec![GeminiParts::new().set_text(RECEIPT_INSTRUCTION.to_string())],
+        }),
+        contents: vec![GeminiContents {
+            role: GeminiContentRole::User,
+            parts,
+        }],
+        generation_config: Some(GeminiGenerationConfig {
+            response_mime_type: Some("application/json".to_string()),
+            response_schema: Some(schema),
+            ..GENERATE_CONF.clone()
+        }),
+        safety_settings: Some(SAFETY_SETTINGS.clone()),
+        ..Default::default()
+    };
+    let response = request_generate_content(&rest, model, &request, usage_context).await?;
+    let Some(text) = response.text() else {
+        return Err(response
+            .finish_reason()
+            .and_then(GeminiError::from_finish_reason)
+            .unwrap_or(GeminiError::EmptyResponse));
+    };
+    let value: Value = serde_json::from_str(text.trim())
+        .map_err(|e| GeminiError::Other(format!("영수증 JSON 파싱 실패: {}", e)))?;
+    parse_receipt(&value).map_err(GeminiError::Other)
+}
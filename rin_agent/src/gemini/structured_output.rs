@@ -0,0 +1,422 @@
+use std::collections::BTreeMap;
+
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use gemini_live_api::types::enums::{GeminiContentRole, GeminiSchemaType};
+use gemini_live_api::types::rest_api_types::GenerateContentRequest;
+use gemini_live_api::types::{GeminiContents, GeminiGenerationConfig, GeminiParts, GeminiSchema};
+use serde_json::Value;
+
+use crate::gemini::gemini_client::request_generate_content;
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::types::GeminiUsageContext;
+use crate::setting::gemini_setting::{GENERATE_CONF, SAFETY_SETTINGS};
+
+// 코드 블록 표에서 한 칸에 보여줄 최대 폭 (한글은 2칸)
+const MAX_CELL_WIDTH: usize = 24;
+
+// 미리 정해 둔 구조화 응답 형식. 이름은 슬래시 명령 선택지와 DB 의 kind 값으로 씁니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuredKind {
+    Comparison,
+    Checklist,
+    Timeline,
+}
+
+impl StructuredKind {
+    pub const ALL: [StructuredKind; 3] = [StructuredKind::Comparison, StructuredKind::Checklist, StructuredKind::Timeline];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        StructuredKind::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StructuredKind::Comparison => "comparison",
+            StructuredKind::Checklist => "checklist",
+            StructuredKind::Timeline => "timeline",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StructuredKind::Comparison => "비교표",
+            StructuredKind::Checklist => "체크리스트",
+            StructuredKind::Timeline => "타임라인",
+        }
+    }
+
+    fn instruction(&self) -> &'static str {
+        match self {
+            StructuredKind::Comparison => {
+                "사용자가 요청한 대상들을 비교표로 정리하세요. columns 는 비교 대상 이름, rows 는 비교 기준이며 \
+                 각 행의 values 는 columns 와 같은 순서, 같은 개수여야 합니다. 칸은 짧게 쓰고 summary 에 결론을 한두 문장으로 적으세요."
+            }
+            StructuredKind::Checklist => {
+                "사용자의 요청을 실행 가능한 체크리스트로 정리하세요. 항목은 순서대로 적고, 이미 끝났다고 밝힌 일만 done 을 true 로 두세요."
+            }
+            StructuredKind::Timeline => {
+                "사용자의 요청을 시간순 타임라인으로 정리하세요. date 는 가능한 한 YYYY-MM-DD 형식으로, 모르면 '미정' 이나 대략적인 시기로 쓰세요."
+            }
+        }
+    }
+
+    pub fn schema(&self) -> GeminiSchema {
+        match self {
+            StructuredKind::Comparison => object_schema(
+                vec![
+                    ("title", string_schema("표 제목")),
+                    ("columns", array_schema(string_schema("비교 대상"))),
+                    ("rows", array_schema(object_schema(
+                        vec![
+                            ("criterion", string_schema("비교 기준")),
+                            ("values", array_schema(string_schema("대상별 값"))),
+                        ],
+                        &["criterion", "values"],
+                    ))),
+                    ("summary", string_schema("결론")),
+                ],
+                &["title", "columns", "rows", "summary"],
+            ),
+            StructuredKind::Checklist => object_schema(
+                vec![
+                    ("title", string_schema("목록 제목")),
+                    ("items", array_schema(object_schema(
+                        vec![
+                            ("task", string_schema("할 일")),
+                            ("done", GeminiSchema { schema_type: GeminiSchemaType::Boolean, ..Default::default() }),
+                            ("note", GeminiSchema { nullable: Some(true), ..string_schema("메모") }),
+                        ],
+                        &["task", "done"],
+                    ))),
+                ],
+                &["title", "items"],
+            ),
+            StructuredKind::Timeline => object_schema(
+                vec![
+                    ("title", string_schema("타임라인 제목")),
+                    ("events", array_schema(object_schema(
+                        vec![
+                            ("date", string_schema("날짜 또는 시기")),
+                            ("title", string_schema("사건")),
+                            ("description", GeminiSchema { nullable: Some(true), ..string_schema("설명") }),
+                        ],
+                        &["date", "title"],
+                    ))),
+                ],
+                &["title", "events"],
+            ),
+        }
+    }
+}
+
+pub fn string_schema(description: &str) -> GeminiSchema {
+    GeminiSchema {
+        schema_type: GeminiSchemaType::String,
+        description: Some(description.to_string()),
+        ..Default::default()
+    }
+}
+
+pub fn array_schema(items: GeminiSchema) -> GeminiSchema {
+    GeminiSchema {
+        schema_type: GeminiSchemaType::Array,
+        items: Some(Box::new(items)),
+        min_items: Some("1".to_string()),
+        ..Default::default()
+    }
+}
+
+// property_ordering 을 넣어 두어야 모델이 적힌 순서대로 키를 채웁니다.
+pub fn object_schema(properties: Vec<(&str, GeminiSchema)>, required: &[&str]) -> GeminiSchema {
+    GeminiSchema {
+        schema_type: GeminiSchemaType::Object,
+        property_ordering: Some(properties.iter().map(|(name, _)| name.to_string()).collect()),
+        properties: Some(properties.into_iter().map(|(name, schema)| (name.to_string(), schema)).collect::<BTreeMap<_, _>>()),
+        required: Some(required.iter().map(|name| name.to_string()).collect()),
+        ..Default::default()
+    }
+}
+
+// 모델이 스키마를 어기는 경우가 있어 받은 JSON 을 다시 검사합니다.
+// 오류 메시지에는 $.rows[2].values 처럼 위치를 붙입니다.
+pub fn validate_against_schema(value: &Value, schema: &GeminiSchema) -> Result<(), String> {
+    validate_at(value, schema, "$")
+}
+
+fn validate_at(value: &Value, schema: &GeminiSchema, path: &str) -> Result<(), String> {
+    if value.is_null() {
+        return if schema.nullable == Some(true) || matches!(schema.schema_type, GeminiSchemaType::Null) {
+            Ok(())
+        } else {
+            Err(format!("{}: null 은 허용되지 않습니다", path))
+        };
+    }
+    let type_error = |expected: &str| format!("{}: {} 이어야 합니다", path, expected);
+    match schema.schema_type {
+        GeminiSchemaType::String => {
+            let text = value.as_str().ok_or_else(|| type_error("문자열"))?;
+            if let Some(enum_values) = &schema.enum_values {
+                if !enum_values.iter().any(|v| v == text) {
+                    return Err(format!("{}: {} 는 허용된 값이 아닙니다", path, text));
+                }
+            }
+        }
+        GeminiSchemaType::Number | GeminiSchemaType::Integer => {
+            let number = if matches!(schema.schema_type, GeminiSchemaType::Integer) {
+                value.as_i64().map(|n| n as f64).ok_or_else(|| type_error("정수"))?
+            } else {
+                value.as_f64().ok_or_else(|| type_error("숫자"))?
+            };
+            if schema.minimum.is_some_and(|min| number < min as f64) || schema.maximum.is_some_and(|max| number > max as f64) {
+                return Err(format!("{}: {} 는 허용 범위를 벗어났습니다", path, number));
+            }
+        }
+        GeminiSchemaType::Boolean => {
+            value.as_bool().ok_or_else(|| type_error("true/false"))?;
+        }
+        GeminiSchemaType::Array => {
+            let items = value.as_array().ok_or_else(|| type_error("배열"))?;
+            let bound = |b: &Option<String>| b.as_deref().and_then(|b| b.parse::<usize>().ok());
+            if bound(&schema.min_items).is_some_and(|min| items.len() < min) {
+                return Err(format!("{}: 항목이 너무 적습니다 ({}개)", path, items.len()));
+            }
+            if bound(&schema.max_items).is_some_and(|max| items.len() > max) {
+                return Err(format!("{}: 항목이 너무 많습니다 ({}개)", path, items.len()));
+            }
+            if let Some(item_schema) = &schema.items {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item, item_schema, &format!("{}[{}]", path, i))?;
+                }
+            }
+        }
+        GeminiSchemaType::Object => {
+            let object = value.as_object().ok_or_else(|| type_error("객체"))?;
+            for name in schema.required.iter().flatten() {
+                if !object.contains_key(name) {
+                    return Err(format!("{}.{}: 필수 항목이 없습니다", path, name));
+                }
+            }
+            if let Some(properties) = &schema.properties {
+                for (name, field) in object {
+                    if let Some(field_schema) = properties.get(name) {
+                        validate_at(field, field_schema, &format!("{}.{}", path, name))?;
+                    }
+                }
+            }
+        }
+        GeminiSchemaType::Null => return Err(type_error("null")),
+    }
+    Ok(())
+}
+
+// 형식마다 다른 JSON 을 같은 표 모양으로 펼칩니다. 코드 블록 표와 CSV 가 이 표를 같이 씁니다.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructuredTable {
+    pub title: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub summary: Option<String>,
+}
+
+fn text_of(value: &Value, key: &str) -> String {
+    match &value[key] {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn array_of<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value[key].as_array().map(|v| v.as_slice()).unwrap_or_default()
+}
+
+impl StructuredTable {
+    pub fn from_value(kind: StructuredKind, value: &Value) -> Self {
+        let title = text_of(value, "title");
+        match kind {
+            StructuredKind::Comparison => {
+                let columns: Vec<String> = array_of(value, "columns")
+                    .iter()
+                    .map(|c| c.as_str().unwrap_or_default().to_string())
+                    .collect();
+                let rows = array_of(value, "rows")
+                    .iter()
+                    .map(|row| {
+                        let values = array_of(row, "values");
+                        // 값 개수가 열 개수와 다르면 빈 칸으로 채우거나 잘라 냅니다.
+                        std::iter::once(text_of(row, "criterion"))
+                            .chain((0..columns.len()).map(|i| values.get(i).and_then(|v| v.as_str()).unwrap_or_default().to_string()))
+                            .collect()
+                    })
+                    .collect();
+                let summary = Some(text_of(value, "summary")).filter(|s| !s.is_empty());
+                StructuredTable {
+                    title,
+                    headers: std::iter::once("항목".to_string()).chain(columns).collect(),
+                    rows,
+                    summary,
+                }
+            }
+            StructuredKind::Checklist => StructuredTable {
+                title,
+                headers: vec!["완료".to_string(), "할 일".to_string(), "메모".to_string()],
+                rows: array_of(value, "items")
+                    .iter()
+                    .map(|item| vec![
+                        if item["done"].as_bool().unwrap_or(false) { "O" } else { "X" }.to_string(),
+                        text_of(item, "task"),
+                        text_of(item, "note"),
+                    ])
+                    .collect(),
+                summary: None,
+            },
+            StructuredKind::Timeline => StructuredTable {
+                title,
+                headers: vec!["날짜".to_string(), "사건".to_string(), "설명".to_string()],
+                rows: array_of(value, "events")
+                    .iter()
+                    .map(|event| vec![text_of(event, "date"), text_of(event, "title"), text_of(event, "description")])
+                    .collect(),
+                summary: None,
+            },
+        }
+    }
+
+    // 엑셀에서 한글이 깨지지 않도록 BOM 을 붙입니다.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("\u{feff}");
+        for line in std::iter::once(&self.headers).chain(self.rows.iter()) {
+            csv.push_str(&line.iter().map(|cell| csv_escape(cell)).collect::<Vec<_>>().join(","));
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+
+    // 고정폭 글꼴 기준으로 열을 맞춘 표. max_chars 를 넘으면 뒤쪽 행을 생략합니다.
+    pub fn to_code_block(&self, max_chars: usize) -> String {
+        let cells = |line: &Vec<String>| line.iter().map(|cell| truncate_width(cell, MAX_CELL_WIDTH)).collect::<Vec<_>>();
+        let header = cells(&self.headers);
+        let rows: Vec<Vec<String>> = self.rows.iter().map(cells).collect();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|i| {
+                std::iter::once(&header)
+                    .chain(rows.iter())
+                    .filter_map(|line| line.get(i))
+                    .map(|cell| display_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let format_line = |line: &Vec<String>| {
+            widths
+                .iter()
+                .enumerate()
+                .map(|(i, width)| {
+                    let cell = line.get(i).map(String::as_str).unwrap_or_default();
+                    format!("{}{}", cell, " ".repeat(width - display_width(cell)))
+                })
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = vec![
+            format_line(&header),
+            widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("-+-"),
+        ];
+        // 코드 블록 여닫는 부분과 생략 안내 한 줄의 여유를 남겨 둡니다.
+        let budget = max_chars.saturating_sub(40);
+        let mut used: usize = lines.iter().map(|l| l.chars().count() + 1).sum();
+        for (i, row) in rows.iter().enumerate() {
+            let line = format_line(row);
+            used += line.chars().count() + 1;
+            if used > budget {
+                lines.push(format!("... ({}행 생략)", rows.len() - i));
+                break;
+            }
+            lines.push(line);
+        }
+        format!("```\n{}\n```", lines.join("\n"))
+    }
+}
+
+// 스프레드시트가 수식으로 읽지 않도록 =, +, -, @ 등으로 시작하는 셀 앞에 ' 를 붙입니다.
+fn csv_escape(cell: &str) -> String {
+    let cell = if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", cell)
+    } else {
+        cell.to_string()
+    };
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell
+    }
+}
+
+// 한글, 한자, 전각 문자는 고정폭 글꼴에서 두 칸을 차지합니다.
+pub fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+fn truncate_width(text: &str, max_width: usize) -> String {
+    let text = text.replace('\n', " ");
+    if display_width(&text) <= max_width {
+        return text;
+    }
+    let mut result = String::new();
+    for c in text.chars() {
+        if display_width(&result) + display_width(&c.to_string()) > max_width - 1 {
+            break;
+        }
+        result.push(c);
+    }
+    result.push('…');
+    result
+}
+
+// 지정한 형식의 스키마로 응답을 받아 검증한 JSON 을 돌려줍니다.
+pub async fn generate_structured(
+    kind: StructuredKind,
+    query: &str,
+    model: &str,
+    usage_context: &GeminiUsageContext,
+) -> Result<Value, GeminiError> {
+    let rest = GeminiRestClient::from_env()?;
+    let schema = kind.schema();
+    let request = GenerateContentRequest {
+        system_instruction: Some(GeminiContents {
+            role: GeminiContentRole::User,
+            parts: vec![GeminiParts::new().set_text(kind.instruction().to_string())],
+        }),
+        contents: vec![GeminiContents {
+            role: GeminiContentRole::User,
+            parts: vec![GeminiParts::new().set_text(query.to_string())],
+        }],
+        generation_config: Some(GeminiGenerationConfig {
+            response_mime_type: Some("application/json".to_string()),
+            response_schema: Some(schema.clone()),
+            ..GENERATE_CONF.clone()
+        }),
+        safety_settings: Some(SAFETY_SETTINGS.clone()),
+        ..Default::default()
+    };
+    let response = request_generate_content(&rest, model, &request, usage_context).await?;
+    let Some(text) = response.text() else {
+        return Err(response
+            .finish_reason()
+            .and_then(GeminiError::from_finish_reason)
+            .unwrap_or(GeminiError::EmptyResponse));
+    };
+    let value: Value = serde_json::from_str(text.trim())
+        .map_err(|e| GeminiError::Other(format!("구조화 응답 JSON 파싱 실패: {}", e)))?;
+    validate_against_schema(&value, &schema).map_err(|e| GeminiError::Other(format!("스키마 검증 실패: {}", e)))?;
+    Ok(value)
+}
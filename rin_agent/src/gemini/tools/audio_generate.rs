@@ -6,7 +6,7 @@ use serde_json::json;
 use crate::gemini::{
     types::{
         DiscordUserInfo, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue,
-        GeminiBotTools, GenerationModality, UnifiedGenerationConfig,
+        GeminiBotTools, GeminiUsageContext, GenerationModality, UnifiedGenerationConfig,
     },
     unified_generation::unified_generate,
 };
@@ -29,7 +29,8 @@ pub async fn generate_audio(
         max_output_tokens: Some(2048),
     };
 
-    let result = unified_generate(prompt, config, None).await?;
+    let usage_context = GeminiUsageContext::from_user_info("audio_generate", info.as_ref());
+    let result = unified_generate(prompt, config, None, usage_context).await?;
 
     // Customize result for audio generation
     let customized_result = GeminiActionResult {
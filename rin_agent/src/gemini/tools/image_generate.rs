@@ -3,7 +3,7 @@ use std::collections::{BTreeMap, HashMap};
 use gemini_live_api::types::enums::GeminiSchemaType;
 use serde_json::json;
 
-use crate::{gemini::{types::{DiscordUserInfo, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotTools, GeminiImageInputType, GenerationModality, UnifiedGenerationConfig}, unified_generation::unified_generate}, setting::gemini_setting::GEMINI_NANO_BANANA};
+use crate::{gemini::{types::{DiscordUserInfo, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotTools, GeminiImageInputType, GeminiUsageContext, GenerationModality, UnifiedGenerationConfig}, unified_generation::unified_generate}, setting::gemini_setting::GEMINI_NANO_BANANA};
 
 
 pub async fn generate_image(params : HashMap<String,GeminiBotToolInputValue>,info:Option<DiscordUserInfo>) -> Result<GeminiActionResult, String> {
@@ -64,7 +64,8 @@ pub async fn generate_image(params : HashMap<String,GeminiBotToolInputValue>,inf
         max_output_tokens: Some(2048),
     };
 
-    let result = unified_generate(prompt, config, image_input).await?;
+    let usage_context = GeminiUsageContext::from_user_info("image_generate", info.as_ref());
+    let result = unified_generate(prompt, config, image_input, usage_context).await?;
     
     // Customize result for image generation
     let customized_result = GeminiActionResult {
@@ -3,4 +3,7 @@ pub mod discord_response;
 pub mod searching;
 pub mod web_connect;
 pub mod image_generate;
-pub mod audio_generate;
\ No newline at end of file
+pub mod audio_generate;
+pub mod query_transcripts;
+pub mod record_debt;
+pub mod query_debts;
\ No newline at end of file
@@ -0,0 +1,92 @@
+use gemini_live_api::types::enums::{GeminiSchemaFormat, GeminiSchemaType};
+use serde_json::json;
+
+use crate::gemini::types::{generate_input_to_dict, DiscordUserInfo, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotToolInputValueType, GeminiBotTools};
+use crate::service::debt_service::{get_debt_history, get_debt_summary, normalize_debtor_name};
+
+use std::collections::HashMap;
+
+const MAX_HISTORY: i64 = 50;
+
+// 사람별 잔액과 합계, 그리고 최근 영수증 기록을 함께 돌려줍니다.
+async fn query_debts(params: HashMap<String, GeminiBotToolInputValue>, info: Option<DiscordUserInfo>) -> Result<GeminiActionResult, String> {
+    let owner_id = info.map(|i| i.user_id.get()).ok_or("빚 장부는 디스코드 사용자만 쓸 수 있습니다.".to_string())?;
+    let name = params.get("name").map(|v| v.value.to_string()).filter(|n| !n.trim().is_empty());
+    let include_cleared = params.get("include_cleared").is_some_and(|v| matches!(v.value, GeminiBotToolInputValueType::Boolean(true)));
+    let limit = params
+        .get("limit")
+        .and_then(|v| v.value.to_string().parse::<f64>().ok())
+        .map(|l| l as i64)
+        .unwrap_or(20)
+        .clamp(1, MAX_HISTORY) as u64;
+
+    let mut summary = get_debt_summary(owner_id).await?;
+    if let Some(name) = &name {
+        let name = normalize_debtor_name(name)?;
+        summary.balances.retain(|b| b.name == name);
+    }
+    let history = get_debt_history(owner_id, name.as_deref(), include_cleared, limit).await?;
+
+    Ok(GeminiActionResult {
+        result_message: format!("Found {} debtors and {} receipts", summary.balances.len(), history.len()),
+        result: json!({
+            "balances": summary.balances,
+            "total_receivable": summary.total_receivable,
+            "total_payable": summary.total_payable,
+            "history": history,
+        }),
+        error: None,
+        show_user: Some(match &name {
+            Some(name) => format!("{} 님과의 빚 장부를 확인했습니다.", name),
+            None => "빚 장부를 확인했습니다.".to_string(),
+        }),
+        ..Default::default()
+    })
+}
+
+pub fn get_command() -> GeminiBotTools {
+    GeminiBotTools {
+        name: "query_debts".to_string(),
+        description: "사용자의 빚 장부에서 사람별 잔액(양수: 받을 돈, 음수: 갚을 돈), 합계, 최근 기록을 조회합니다.".to_string(),
+        parameters: vec![
+            GeminiBotToolInput {
+                name: "name".to_string(),
+                description: "특정 상대만 볼 때 이름 (없으면 전체)".to_string(),
+                input_type: GeminiSchemaType::String,
+                required: false,
+                format: None,
+                default: None,
+                enum_values: None,
+                example: Some(json!("민수")),
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "include_cleared".to_string(),
+                description: "이미 정산한 기록도 history 에 포함할지".to_string(),
+                input_type: GeminiSchemaType::Boolean,
+                required: false,
+                format: None,
+                default: Some(json!(false)),
+                enum_values: None,
+                example: None,
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "limit".to_string(),
+                description: "history 최대 건수 (1~50, 기본 20)".to_string(),
+                input_type: GeminiSchemaType::Integer,
+                required: false,
+                format: Some(GeminiSchemaFormat::Int32),
+                default: None,
+                enum_values: None,
+                example: None,
+                pattern: None,
+            },
+        ]
+        .into_iter()
+        .map(generate_input_to_dict)
+        .collect(),
+        response: None,
+        action: |params, info| Box::pin(async move { query_debts(params, info).await }),
+    }
+}
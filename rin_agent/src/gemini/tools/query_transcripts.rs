@@ -0,0 +1,137 @@
+use entity::{tb_voice_transcript, tb_voice_transcript_session};
+use gemini_live_api::types::enums::{GeminiSchemaFormat, GeminiSchemaType};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde_json::json;
+
+use crate::gemini::types::{generate_input_to_dict, DiscordUserInfo, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotToolInputValueType, GeminiBotTools};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+use std::collections::HashMap;
+
+const MAX_LINES: u64 = 300;
+
+fn get_integer(params: &HashMap<String, GeminiBotToolInputValue>, name: &str) -> Option<i64> {
+    params.get(name).and_then(|v| match &v.value {
+        GeminiBotToolInputValueType::Integer(i) => Some(*i),
+        GeminiBotToolInputValueType::Number(n) => Some(*n as i64),
+        GeminiBotToolInputValueType::String(s) => s.parse::<i64>().ok(),
+        _ => None,
+    })
+}
+
+// 다른 길드의 회의 기록이 새지 않도록 호출한 길드로만 조회합니다.
+async fn query_transcripts(params: HashMap<String, GeminiBotToolInputValue>, info: Option<DiscordUserInfo>) -> Result<GeminiActionResult, String> {
+    let guild_id = info
+        .and_then(|i| i.guild_id)
+        .map(|g| g.get() as i64)
+        .ok_or("음성 회의 기록은 길드 안에서만 조회할 수 있습니다.".to_string())?;
+    let limit = get_integer(&params, "limit").unwrap_or(3).clamp(1, 10) as u64;
+    let keyword = params.get("keyword").map(|v| v.value.to_string()).filter(|k| !k.is_empty());
+
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+
+    let mut session_query = tb_voice_transcript_session::Entity::find()
+        .filter(tb_voice_transcript_session::Column::GuildId.eq(guild_id));
+    if let Some(session_id) = get_integer(&params, "session_id") {
+        session_query = session_query.filter(tb_voice_transcript_session::Column::Id.eq(session_id));
+    }
+    let sessions = session_query
+        .order_by_desc(tb_voice_transcript_session::Column::StartedAt)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let session_ids = sessions.iter().map(|s| s.id).collect::<Vec<_>>();
+    let mut line_query = tb_voice_transcript::Entity::find()
+        .filter(tb_voice_transcript::Column::SessionId.is_in(session_ids));
+    if let Some(keyword) = &keyword {
+        line_query = line_query.filter(tb_voice_transcript::Column::Content.contains(keyword));
+    }
+    let lines = line_query
+        .order_by_asc(tb_voice_transcript::Column::CreatedAt)
+        .limit(MAX_LINES)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result = sessions
+        .iter()
+        .map(|session| {
+            json!({
+                "session_id": session.id,
+                "voice_channel_id": session.voice_channel_id.to_string(),
+                "started_at": session.started_at.to_rfc3339(),
+                "ended_at": session.ended_at.map(|t| t.to_rfc3339()),
+                "summary": session.summary,
+                "lines": lines
+                    .iter()
+                    .filter(|l| l.session_id == session.id)
+                    .map(|l| json!({
+                        "speaker": l.user_name,
+                        "speaker_id": l.user_id.to_string(),
+                        "content": l.content,
+                        "at": l.created_at.to_rfc3339(),
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(
+        GeminiActionResult {
+            result_message: format!("Found {} transcript sessions", result.len()),
+            result: json!({ "sessions": result }),
+            error: None,
+            show_user: Some("음성 회의 기록을 확인했습니다.".to_string()),
+            ..Default::default()
+        }
+    )
+}
+
+pub fn get_command() -> GeminiBotTools {
+    GeminiBotTools {
+        name: "query_transcripts".to_string(),
+        description: "`/transcribe`로 기록한 음성 회의 받아쓰기와 요약(결정 사항, 할 일, 알람 제안)을 조회합니다. 현재 길드의 최근 세션부터 돌려줍니다.".to_string(),
+        parameters: vec![
+            GeminiBotToolInput {
+                name: "keyword".to_string(),
+                description: "발화 내용에서 찾을 키워드 (없으면 전체)".to_string(),
+                input_type: GeminiSchemaType::String,
+                required: false,
+                format: None,
+                default: None,
+                enum_values: None,
+                example: None,
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "session_id".to_string(),
+                description: "특정 받아쓰기 세션 ID".to_string(),
+                input_type: GeminiSchemaType::Integer,
+                required: false,
+                format: Some(GeminiSchemaFormat::Int64),
+                default: None,
+                enum_values: None,
+                example: None,
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "limit".to_string(),
+                description: "최근 몇 개의 세션을 볼지 (1~10, 기본 3)".to_string(),
+                input_type: GeminiSchemaType::Integer,
+                required: false,
+                format: Some(GeminiSchemaFormat::Int32),
+                default: None,
+                enum_values: None,
+                example: None,
+                pattern: None,
+            },
+        ]
+        .into_iter()
+        .map(generate_input_to_dict)
+        .collect(),
+        response: None,
+        action: |params, info| Box::pin(async move { query_transcripts(params, info).await }),
+    }
+}
@@ -0,0 +1,126 @@
+use gemini_live_api::types::enums::{GeminiSchemaFormat, GeminiSchemaType};
+use serde_json::json;
+
+use crate::gemini::types::{generate_input_to_dict, DiscordUserInfo, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotToolInputValueType, GeminiBotTools};
+use crate::service::debt_service::{clear_debtor, clear_receipt, format_amount, record_debt};
+
+use std::collections::HashMap;
+
+fn get_integer(params: &HashMap<String, GeminiBotToolInputValue>, name: &str) -> Option<i64> {
+    params.get(name).and_then(|v| match &v.value {
+        GeminiBotToolInputValueType::Integer(i) => Some(*i),
+        GeminiBotToolInputValueType::Number(n) => Some(*n as i64),
+        GeminiBotToolInputValueType::String(s) => s.trim().replace(',', "").parse::<i64>().ok(),
+        _ => None,
+    })
+}
+
+// action 이 record 면 새 영수증을, clear 면 영수증 하나(receipt_id)나 한 사람(name)의 기록을 정산합니다.
+async fn record_debt_tool(params: HashMap<String, GeminiBotToolInputValue>, info: Option<DiscordUserInfo>) -> Result<GeminiActionResult, String> {
+    let owner_id = info.map(|i| i.user_id.get()).ok_or("빚 장부는 디스코드 사용자만 쓸 수 있습니다.".to_string())?;
+    let action = params.get("action").map(|v| v.value.to_string()).unwrap_or("record".to_string());
+    let name = params.get("name").map(|v| v.value.to_string()).filter(|n| !n.trim().is_empty());
+
+    if action == "clear" {
+        if let Some(receipt_id) = get_integer(&params, "receipt_id") {
+            let entry = clear_receipt(owner_id, receipt_id).await?;
+            return Ok(GeminiActionResult {
+                result_message: format!("Cleared receipt #{}", entry.id),
+                show_user: Some(format!("{} 님의 #{} 영수증({})을 정산했습니다.", entry.debtor_name, entry.id, format_amount(entry.amount))),
+                result: json!({ "cleared": [entry] }),
+                error: None,
+                ..Default::default()
+            });
+        }
+        let name = name.ok_or("Missing 'name' or 'receipt_id' parameter".to_string())?;
+        let (debtor, count, amount) = clear_debtor(owner_id, &name).await?;
+        return Ok(GeminiActionResult {
+            result_message: format!("Cleared {} receipts of {}", count, debtor.name),
+            show_user: Some(format!("{} 님의 영수증 {}건({})을 정산했습니다.", debtor.name, count, format_amount(amount))),
+            result: json!({ "debtor": debtor.name, "cleared_count": count, "cleared_amount": amount }),
+            error: None,
+            ..Default::default()
+        });
+    }
+
+    let name = name.ok_or("Missing 'name' parameter".to_string())?;
+    let amount = get_integer(&params, "amount").ok_or("Missing 'amount' parameter".to_string())?;
+    let memo = params.get("memo").map(|v| v.value.to_string());
+    let entry = record_debt(owner_id, &name, amount, memo.as_deref()).await?;
+    Ok(GeminiActionResult {
+        result_message: format!("Recorded receipt #{}", entry.id),
+        show_user: Some(format!("{} 님과의 거래 {}을 기록했습니다. (#{})", entry.debtor_name, format_amount(entry.amount), entry.id)),
+        result: json!({ "recorded": entry }),
+        error: None,
+        ..Default::default()
+    })
+}
+
+pub fn get_command() -> GeminiBotTools {
+    GeminiBotTools {
+        name: "record_debt".to_string(),
+        description: "사용자의 빚 장부에 돈을 빌려주거나 빌린 기록을 남기거나, 갚은 기록을 정산 처리합니다. 장부는 말을 건 사용자 본인의 것입니다.".to_string(),
+        parameters: vec![
+            GeminiBotToolInput {
+                name: "action".to_string(),
+                description: "record: 새 기록, clear: 정산(갚음) 처리".to_string(),
+                input_type: GeminiSchemaType::String,
+                required: true,
+                format: Some(GeminiSchemaFormat::EnumString),
+                default: Some(json!("record")),
+                enum_values: Some(vec!["record".to_string(), "clear".to_string()]),
+                example: None,
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "name".to_string(),
+                description: "상대 이름. clear 에서 receipt_id 없이 주면 그 사람의 남은 기록을 모두 정산합니다.".to_string(),
+                input_type: GeminiSchemaType::String,
+                required: false,
+                format: None,
+                default: None,
+                enum_values: None,
+                example: Some(json!("민수")),
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "amount".to_string(),
+                description: "금액(원). 사용자가 빌려줬으면 양수, 사용자가 빌렸으면 음수".to_string(),
+                input_type: GeminiSchemaType::Integer,
+                required: false,
+                format: Some(GeminiSchemaFormat::Int64),
+                default: None,
+                enum_values: None,
+                example: Some(json!(15000)),
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "memo".to_string(),
+                description: "무엇에 쓴 돈인지 메모".to_string(),
+                input_type: GeminiSchemaType::String,
+                required: false,
+                format: None,
+                default: None,
+                enum_values: None,
+                example: Some(json!("점심 식사")),
+                pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "receipt_id".to_string(),
+                description: "정산할 영수증 번호 (clear 에서만)".to_string(),
+                input_type: GeminiSchemaType::Integer,
+                required: false,
+                format: Some(GeminiSchemaFormat::Int64),
+                default: None,
+                enum_values: None,
+                example: None,
+                pattern: None,
+            },
+        ]
+        .into_iter()
+        .map(generate_input_to_dict)
+        .collect(),
+        response: None,
+        action: |params, info| Box::pin(async move { record_debt_tool(params, info).await }),
+    }
+}
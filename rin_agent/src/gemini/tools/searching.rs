@@ -2,8 +2,8 @@ use gemini_live_api::types::enums::GeminiSchemaType;
 use gemini_live_api::types::GeminiSchema;
 use serde_json::{json, Value};
 
-use crate::api::google_searching::{google_searching, GoogleSearchItem};
-use crate::gemini::types::{generate_input_to_dict, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotTools};
+use crate::api::search_provider::{search_for_guild, SearchResult};
+use crate::gemini::types::{generate_input_to_dict, DiscordUserInfo, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotTools};
 use crate::service::discord_error_msg::send_debug_error_log;
 
 use std::collections::{BTreeMap, HashMap};
@@ -13,28 +13,26 @@ fn example_result() -> Option<Value> {
     Some(
         json!(
             vec![
-                GoogleSearchItem {
-                    title: Some("Apple".to_string()),
-                    link: Some("https://www.apple.com".to_string()),
-                    snippet: Some("Apple Inc. is an American multinational technology company headquartered in Cupertino, California.".to_string()),
-                    kind: None,
-                    html_title: None,
-                    pagemap: None,
+                SearchResult {
+                    title: "Apple".to_string(),
+                    link: "https://www.apple.com".to_string(),
+                    snippet: "Apple Inc. is an American multinational technology company headquartered in Cupertino, California.".to_string(),
                 }
             ]
         )
     )
 }
 static EXAMPLE_RESULT: LazyLock<Option<Value>> = LazyLock::new(|| example_result());
-async fn searching(params: HashMap<String, GeminiBotToolInputValue>) -> Result<GeminiActionResult, String> {
+async fn searching(params: HashMap<String, GeminiBotToolInputValue>, info: Option<DiscordUserInfo>) -> Result<GeminiActionResult, String> {
     let query = params.get("query");
     if query.is_none() {
         return Err("Missing 'query' parameter".to_string());
     }
     let query = query.unwrap().value.to_string();
-    let google_searching_result = google_searching(query.clone()).await;
-    if google_searching_result.is_err() {
-        let why = google_searching_result.err().unwrap();
+    let guild_id = info.as_ref().and_then(|i| i.guild_id).map(|g| g.get());
+    let searching_result = search_for_guild(guild_id, &query).await;
+    if searching_result.is_err() {
+        let why = searching_result.err().unwrap();
         send_debug_error_log(why.to_string()).await;
         return Ok(
             GeminiActionResult{
@@ -46,12 +44,12 @@ async fn searching(params: HashMap<String, GeminiBotToolInputValue>) -> Result<G
             }
         )
     }
-    let google_searching_result = google_searching_result.unwrap();
-    let result_message = format!("Search google results for '{}':", query);
+    let (provider, searching_result) = searching_result.unwrap();
+    let result_message = format!("Search {} results for '{}':", provider.label(), query);
     Ok(
         GeminiActionResult{
             result_message,
-            result: json!(google_searching_result),
+            result: json!(searching_result),
             error: None,
             show_user: Some(format!("{} 를 검색하였습니다.", query).to_string()),
             ..Default::default()
@@ -62,7 +60,7 @@ async fn searching(params: HashMap<String, GeminiBotToolInputValue>) -> Result<G
 pub fn get_command() -> GeminiBotTools {
     GeminiBotTools {
         name: "searching".to_string(),
-        description: "query에 대해서 구글 검색을 합니다. 검색한 결과는 link들의 집합으로 나옵니다. 따라서, 답해줄 정보가 부족할 경루 이 이후에 링크를 web_connect에 넣어 호출하는걸 추천합니다. ".to_string(),
+        description: "query에 대해서 웹 검색을 합니다. 검색한 결과는 link들의 집합으로 나옵니다. 따라서, 답해줄 정보가 부족할 경루 이 이후에 링크를 web_connect에 넣어 호출하는걸 추천합니다. ".to_string(),
         parameters: vec![
             GeminiBotToolInput {
                 name:"query".to_string(),
@@ -81,8 +79,8 @@ pub fn get_command() -> GeminiBotTools {
         .collect(),
         response: Some(GeminiSchema {
             schema_type: GeminiSchemaType::Array,
-            title: Some("Google Search Results".to_string()),
-            description: Some("A list of search results from Google.".to_string()),
+            title: Some("Web Search Results".to_string()),
+            description: Some("A list of web search results.".to_string()),
             items: Some(Box::new(GeminiSchema {
                 schema_type: GeminiSchemaType::Object,
                 properties: Some(BTreeMap::from([
@@ -107,6 +105,6 @@ pub fn get_command() -> GeminiBotTools {
             example: EXAMPLE_RESULT.clone(),
             ..Default::default()
         }),
-        action: |params,info| Box::pin(async move { searching(params).await }),
+        action: |params,info| Box::pin(async move { searching(params, info).await }),
     }
 }
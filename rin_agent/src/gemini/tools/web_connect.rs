@@ -1,16 +1,27 @@
 use std::{collections::{BTreeMap, HashMap}, sync::LazyLock};
 
-use gemini_live_api::types::{enums::{GeminiSchemaFormat, GeminiSchemaType}, GeminiSchema};
+use gemini_live_api::types::{enums::GeminiSchemaType, GeminiSchema};
 use serde_json::{json, Value};
 
+use crate::api::get_web_result::{get_web_result, WebFetchOptions};
+use crate::api::html_extract::split_pages;
 use crate::gemini::types::{generate_input_to_dict, generate_to_schema, GeminiActionResult, GeminiBotToolInput, GeminiBotToolInputValue, GeminiBotTools};
 
-
+// 한 번에 모델에게 넘기는 본문 길이 (글자 수)
+const PAGE_CHARS: usize = 6000;
+// 첫 페이지에만 붙이는 링크 수
+const MAX_RESULT_LINKS: usize = 20;
 
 fn example_result() -> Option<Value> {
     Some(
       json!({
-        "html" : "<html><body><h1>Example Web Page</h1><p>This is an example web page content.</p></body></html>",
+        "url": "https://example.com/",
+        "title": "Example Domain",
+        "content": "# Example Domain\nThis domain is for use in illustrative examples in documents.",
+        "page": 1,
+        "total_pages": 1,
+        "truncated": false,
+        "links": [{ "text": "More information...", "url": "https://www.iana.org/domains/example" }],
       })
     )
 }
@@ -21,34 +32,76 @@ async fn web_connect(params: HashMap<String, GeminiBotToolInputValue>) -> Result
         return Err("Missing 'url' parameter".to_string());
     }
     let url = url_value.unwrap().value.to_string();
+    let page = params
+        .get("page")
+        .and_then(|p| p.value.to_string().parse::<f64>().ok())
+        .map(|p| p.max(1.0) as usize)
+        .unwrap_or(1);
 
-    // Perform the web request
-    let client = reqwest::Client::new();
-    let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
-    
-    if !response.status().is_success() {
-        return Err(format!("Failed to fetch URL: {}", response.status()));
-    }
+    let web_page = match get_web_result(&url, &WebFetchOptions::default()).await {
+        Ok(web_page) => web_page,
+        Err(why) => {
+            return Ok(GeminiActionResult {
+                result_message: "Failed to fetch the web page.".to_string(),
+                result: json!({}),
+                error: Some(why.clone()),
+                show_user: Some(format!("웹 페이지 {} 를 열지 못했습니다. ({})", url, why)),
+                ..Default::default()
+            });
+        }
+    };
 
-    let html_content = response.text().await.map_err(|e| e.to_string())?;
+    let pages = split_pages(&web_page.text, PAGE_CHARS);
+    let total_pages = pages.len().max(1);
+    let page = page.min(total_pages);
+    let content = pages.get(page - 1).copied().unwrap_or_default();
+    let links = if page == 1 {
+        web_page.links.iter().take(MAX_RESULT_LINKS).cloned().collect()
+    } else {
+        Vec::new()
+    };
 
     Ok(
         GeminiActionResult {
-        result_message: "Web page content fetched successfully.".to_string(),
-        result: json!({ "html": html_content }),
+        result_message: format!("Web page content fetched successfully. (page {}/{})", page, total_pages),
+        result: json!({
+            "url": web_page.url,
+            "title": web_page.title,
+            "content": content,
+            "page": page,
+            "total_pages": total_pages,
+            "truncated": web_page.truncated,
+            "links": links,
+        }),
         error: None,
-        show_user: Some(format!("웹 페이지 {} 의 HTML 내용을 가져왔습니다.", url)),
+        show_user: Some(format!("웹 페이지 {} 를 읽었습니다. ({}/{})", url, page, total_pages)),
         ..Default::default()
     })
 }
 
 static EXAMPLE_RESULT: LazyLock<Option<Value>> = LazyLock::new(|| example_result());
 
+fn field_schema(name: &str, description: &str, input_type: GeminiSchemaType) -> (String, GeminiSchema) {
+    (
+        name.to_string(),
+        generate_to_schema(&GeminiBotToolInput {
+            name: name.to_string(),
+            description: description.to_string(),
+            input_type,
+            required: true,
+            format: None,
+            default: None,
+            enum_values: None,
+            example: None,
+            pattern: None,
+        }),
+    )
+}
 
 pub fn get_command()-> GeminiBotTools {
     GeminiBotTools {
         name: "web_connect".to_string(),
-        description: "웹 페이지의 URL을 입력하면 해당 페이지의 HTML 내용을 반환합니다.".to_string(),
+        description: "웹 페이지의 URL을 입력하면 해당 페이지의 제목, 읽을 수 있는 본문, 링크를 반환합니다. 본문이 길면 page 로 나눠 읽습니다. 공개된 http/https 주소만 열 수 있습니다.".to_string(),
         parameters: vec![
             GeminiBotToolInput {
                 name: "url".to_string(),
@@ -60,6 +113,17 @@ pub fn get_command()-> GeminiBotTools {
                 enum_values: None,
                 example: Some(json!("https://example.com".to_string())),
                 pattern: None,
+            },
+            GeminiBotToolInput {
+                name: "page".to_string(),
+                description: "읽을 페이지 번호 (1부터). 이전 결과의 total_pages 보다 작거나 같아야 합니다.".to_string(),
+                input_type: GeminiSchemaType::Integer,
+                required: false,
+                format: None,
+                default: Some(json!(1)),
+                enum_values: None,
+                example: Some(json!(2)),
+                pattern: None,
             }
         ]
         .into_iter()
@@ -70,21 +134,29 @@ pub fn get_command()-> GeminiBotTools {
             title: Some("Web Connect Schema".to_string()),
             description: Some("Schema for the web connect tool".to_string()),
             properties: Some(BTreeMap::from([
-                ("html".to_string(), 
-                generate_to_schema(&GeminiBotToolInput {
-                    name: "html".to_string(),
-                    description: "HTML content of the web page".to_string(),
-                    input_type: GeminiSchemaType::String,
-                    required: true,
-                    format: None,
-                    default: None,
-                    enum_values: None,
-                    example: Some(json!("<html><body><h1>Example</h1></body></html>")),
-                    pattern: None,
-                })),
+                field_schema("url", "리다이렉트를 따라간 뒤의 최종 URL", GeminiSchemaType::String),
+                field_schema("title", "페이지 제목", GeminiSchemaType::String),
+                field_schema("content", "이번 페이지의 본문 텍스트", GeminiSchemaType::String),
+                field_schema("page", "이번 페이지 번호", GeminiSchemaType::Integer),
+                field_schema("total_pages", "전체 페이지 수", GeminiSchemaType::Integer),
+                field_schema("truncated", "크기 제한으로 문서 끝부분을 읽지 못했는지 여부", GeminiSchemaType::Boolean),
+                ("links".to_string(), GeminiSchema {
+                    schema_type: GeminiSchemaType::Array,
+                    description: Some("첫 페이지에서만 주는 페이지 안의 링크 목록".to_string()),
+                    items: Some(Box::new(GeminiSchema {
+                        schema_type: GeminiSchemaType::Object,
+                        properties: Some(BTreeMap::from([
+                            field_schema("text", "링크 글자", GeminiSchemaType::String),
+                            field_schema("url", "링크 주소", GeminiSchemaType::String),
+                        ])),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }),
             ])),
+            example: EXAMPLE_RESULT.clone(),
             ..Default::default()
         }),
         action: |params,info| Box::pin(async move { web_connect(params).await }),
     }
-}
\ No newline at end of file
+}
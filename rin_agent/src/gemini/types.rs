@@ -1,9 +1,9 @@
 use std::{collections::{hash_map, BTreeMap}, default, future::Future, pin::Pin};
 
-use gemini_live_api::types::{enums::{GeminiSchemaFormat, GeminiSchemaType}, GeminiSchema, GeminiSchemaObject};
+use gemini_live_api::types::{enums::{GeminiCodeExecutionResultOutcome, GeminiSchemaFormat, GeminiSchemaType}, GeminiSchema, GeminiSchemaObject, GroundingMetadata};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use serenity::all::{ChannelId, UserId};
+use serenity::all::{ChannelId, GuildId, UserId};
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum GenerationModality {
@@ -29,6 +29,20 @@ pub struct GeminiResponse {
     pub command_result: Vec<Result<GeminiActionResult,String>>,
     pub avg_logprobs: f64,
     pub thoughts: Option<String>,
+    // 실제로 응답한 모델 (Pro 과부하로 Flash 로 바뀌었을 수 있음)
+    pub model: String,
+    // google_search / url_context 도구를 썼을 때의 출처
+    pub grounding: Option<GroundingMetadata>,
+    pub code_executions: Vec<GeminiCodeExecution>,
+}
+
+// code_execution 도구가 실행한 코드와 결과. 결과 파트가 오기 전에는 outcome 이 None 입니다.
+#[derive(Debug, Clone)]
+pub struct GeminiCodeExecution {
+    pub language: String,
+    pub code: String,
+    pub outcome: Option<GeminiCodeExecutionResultOutcome>,
+    pub output: Option<String>,
 }
 #[derive(Debug, Clone)]
 pub struct GeminiImageInputType {
@@ -157,9 +171,49 @@ pub struct DiscordUserInfo {
     pub user_id: UserId,
     pub username: Option<String>,
     pub channel_id: ChannelId,
+    pub guild_id: Option<GuildId>,
     pub context_id: Option<i64>, // AI context ID
 }
 
 pub fn generate_input_to_dict(input: GeminiBotToolInput) -> (String, GeminiBotToolInput) {
     (input.name.clone(), input)
-}
\ No newline at end of file
+}
+// 모델별 단가 (USD / 100만 토큰). 생각 토큰은 출력 단가로 계산합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeminiModelPrice {
+    pub input: f64,
+    #[serde(default)]
+    pub cached_input: f64,
+    pub output: f64,
+}
+
+// 사용량을 기록할 때 함께 남기는 요청 출처 정보
+#[derive(Debug, Clone, Default)]
+pub struct GeminiUsageContext {
+    pub source: String,
+    pub guild_id: Option<u64>,
+    pub channel_id: Option<u64>,
+    pub user_id: Option<u64>,
+    pub context_id: Option<i64>,
+}
+
+impl GeminiUsageContext {
+    // 도구 호출처럼 DiscordUserInfo 만 있는 경우
+    pub fn from_user_info(source: &str, info: Option<&DiscordUserInfo>) -> Self {
+        GeminiUsageContext {
+            source: source.to_string(),
+            guild_id: info.and_then(|i| i.guild_id).map(|g| g.get()),
+            channel_id: info.map(|i| i.channel_id.get()),
+            user_id: info.map(|i| i.user_id.get()),
+            context_id: info.and_then(|i| i.context_id),
+        }
+    }
+}
+
+// OpenAI 호환 stream 응답에서 흘려보내는 조각. 마지막은 항상 Done 이나 Failed 입니다.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeminiStreamEvent {
+    Delta(String),
+    Done { finish_reason: String },
+    Failed(crate::gemini::gemini_error::GeminiError),
+}
@@ -1,10 +1,12 @@
-use std::env;
 use base64::{prelude::BASE64_STANDARD, Engine};
-use gemini_live_api::types::{enums::GeminiContentRole, GeminiContents, GeminiParts};
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use gemini_live_api::types::rest_api_types::GenerateContentRequest;
+use gemini_live_api::types::{enums::GeminiContentRole, GeminiContents, GeminiGenerationConfig, GeminiParts, GeminiResponseModalities};
 use serde_json::json;
 
-use crate::gemini::types::{GenerationModality, UnifiedGenerationConfig, GeminiActionResult, GeminiImageInputType};
+use crate::gemini::types::{GenerationModality, UnifiedGenerationConfig, GeminiActionResult, GeminiImageInputType, GeminiUsageContext};
 use crate::gemini::utils::upload_image_to_gemini;
+use crate::service::usage_service::spawn_record_usage;
 use crate::setting::gemini_setting::GEMINI_NANO_BANANA;
 
 /// Unified generation function that can handle text, image, and audio generation.
@@ -19,6 +21,7 @@ use crate::setting::gemini_setting::GEMINI_NANO_BANANA;
 /// * `prompt` - The text prompt for generation
 /// * `config` - Configuration specifying the model and modalities to use
 /// * `image_input` - Optional image input for image-to-image or multimodal generation
+/// * `usage_context` - Who requested the generation; recorded with the token usage
 /// 
 /// # Returns
 /// 
@@ -36,7 +39,7 @@ use crate::setting::gemini_setting::GEMINI_NANO_BANANA;
 ///     model: "gemini-2.5-flash-image".to_string(),
 ///     max_output_tokens: Some(2048),
 /// };
-/// let result = unified_generate("A beautiful sunset".to_string(), config, None).await?;
+/// let result = unified_generate("A beautiful sunset".to_string(), config, None, GeminiUsageContext::default()).await?;
 /// 
 /// // Generate audio
 /// let config = UnifiedGenerationConfig {
@@ -44,18 +47,15 @@ use crate::setting::gemini_setting::GEMINI_NANO_BANANA;
 ///     model: "gemini-2.5-flash-image".to_string(),
 ///     max_output_tokens: Some(2048),
 /// };
-/// let result = unified_generate("Hello world".to_string(), config, None).await?;
+/// let result = unified_generate("Hello world".to_string(), config, None, GeminiUsageContext::default()).await?;
 /// ```
 pub async fn unified_generate(
     prompt: String,
     config: UnifiedGenerationConfig,
     image_input: Option<GeminiImageInputType>,
+    usage_context: GeminiUsageContext,
 ) -> Result<GeminiActionResult, String> {
-    let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent",
-        config.model
-    );
+    let client = GeminiRestClient::from_env().expect("GEMINI_API_KEY must be set");
 
     // Prepare input parts
     let mut parts = vec![
@@ -110,108 +110,67 @@ pub async fn unified_generate(
     }];
 
     // Build modalities array
-    let modalities: Vec<String> = config.modalities.iter().map(|m| {
+    let modalities: Vec<GeminiResponseModalities> = config.modalities.iter().map(|m| {
         match m {
-            GenerationModality::Text => "TEXT".to_string(),
-            GenerationModality::Image => "IMAGE".to_string(),
-            GenerationModality::Audio => "AUDIO".to_string(),
+            GenerationModality::Text => GeminiResponseModalities::Text,
+            GenerationModality::Image => GeminiResponseModalities::Image,
+            GenerationModality::Audio => GeminiResponseModalities::Audio,
         }
     }).collect();
 
-    // Make API request
-    let response = reqwest::Client::new()
-        .post(&url)
-        .body(json!({
-            "contents": contents,
-            "generation_config": {
-                "response_modalities": modalities,
-                "maxOutputTokens": config.max_output_tokens.unwrap_or(2048)
-            },
-        }).to_string())
-        .header("x-goog-api-key", api_key)
-        .header("Content-Type", "application/json")
-        .send()
-        .await;
-
-    if response.is_err() {
-        let err_msg = response.err().unwrap().to_string();
-        return Ok(GeminiActionResult {
-            result_message: format!("error!! : {}", err_msg),
-            result: json!({
-                "res": format!("error!! : {}", err_msg),
-            }),
-            error: Some(err_msg),
-            show_user: Some("생성 중 오류가 발생했습니다.".to_string()),
-            image: None,
-            audio: None,
-        });
-    }
-
-    let response = response.unwrap();
-    if !response.status().is_success() {
-        let text = response.text().await.unwrap_or_default();
-        return Ok(GeminiActionResult {
-            result_message: format!("Gemini error: {}", text),
-            result: json!({ "error": text }),
-            error: Some(text.clone()),
-            show_user: Some("생성 중 오류가 발생했습니다.".to_string()),
-            image: None,
-            audio: None,
-        });
-    }
+    // GeminiGenerationConfig 의 기본값은 채팅용이므로 필요한 값만 채웁니다.
+    let request = GenerateContentRequest {
+        contents,
+        generation_config: Some(GeminiGenerationConfig {
+            response_modalities: Some(modalities),
+            max_output_tokens: Some(config.max_output_tokens.unwrap_or(2048)),
+            candidate_count: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            presence_penalty: None,
+            frequency_penalty: None,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
 
-    // Parse response
-    let value = response.text().await.map_err(|e| e.to_string())?;
-    let value: serde_json::Value = serde_json::from_str(&value).map_err(|e| e.to_string())?;
+    // Make API request
+    let response = match client.generate_content(&config.model, &request).await {
+        Ok(response) => {
+            spawn_record_usage(&config.model, &usage_context, response.usage_metadata.as_ref());
+            response
+        },
+        Err(e) => {
+            let err_msg = e.to_string();
+            return Ok(GeminiActionResult {
+                result_message: format!("error!! : {}", err_msg),
+                result: json!({
+                    "res": format!("error!! : {}", err_msg),
+                }),
+                error: Some(err_msg),
+                show_user: Some("생성 중 오류가 발생했습니다.".to_string()),
+                image: None,
+                audio: None,
+            });
+        }
+    };
 
-    let mut text_result: Option<String> = None;
+    let text_result: Option<String> = response.candidates.iter()
+        .flat_map(|c| c.parts())
+        .find_map(|p| p.text.clone());
     let mut found_image: Option<(String, Vec<u8>)> = None; // (mime, data)
     let mut found_audio: Option<(String, Vec<u8>)> = None; // (mime, data)
 
-    if let Some(cands) = value.get("candidates").and_then(|c| c.as_array()) {
-        for c in cands {
-            if let Some(parts) = c.get("content").and_then(|ct| ct.get("parts")).and_then(|p| p.as_array()) {
-                for p in parts {
-                    // Extract text
-                    if text_result.is_none() {
-                        if let Some(text) = p.get("text").and_then(|t| t.as_str()) {
-                            text_result = Some(text.to_string());
-                        }
-                    }
-
-                    // Extract image
-                    if found_image.is_none() {
-                        if let Some(inline) = p.get("inlineData").or_else(|| p.get("inline_data")) {
-                            if let (Some(mime), Some(data)) = (
-                                inline.get("mimeType").and_then(|m| m.as_str()),
-                                inline.get("data").and_then(|d| d.as_str())
-                            ) {
-                                if mime.starts_with("image/") {
-                                    let bytes = BASE64_STANDARD.decode(data.as_bytes())
-                                        .map_err(|e| format!("base64 decode error: {e}"))?;
-                                    found_image = Some((mime.to_string(), bytes));
-                                }
-                            }
-                        }
-                    }
-
-                    // Extract audio
-                    if found_audio.is_none() {
-                        if let Some(inline) = p.get("inlineData").or_else(|| p.get("inline_data")) {
-                            if let (Some(mime), Some(data)) = (
-                                inline.get("mimeType").and_then(|m| m.as_str()),
-                                inline.get("data").and_then(|d| d.as_str())
-                            ) {
-                                if mime.starts_with("audio/") {
-                                    let bytes = BASE64_STANDARD.decode(data.as_bytes())
-                                        .map_err(|e| format!("base64 decode error: {e}"))?;
-                                    found_audio = Some((mime.to_string(), bytes));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    for inline in response.candidates.iter().flat_map(|c| c.parts()).filter_map(|p| p.inline_data.as_ref()) {
+        if found_image.is_none() && inline.mime_type.starts_with("image/") {
+            let bytes = BASE64_STANDARD.decode(inline.data.as_bytes())
+                .map_err(|e| format!("base64 decode error: {e}"))?;
+            found_image = Some((inline.mime_type.clone(), bytes));
+        } else if found_audio.is_none() && inline.mime_type.starts_with("audio/") {
+            let bytes = BASE64_STANDARD.decode(inline.data.as_bytes())
+                .map_err(|e| format!("base64 decode error: {e}"))?;
+            found_audio = Some((inline.mime_type.clone(), bytes));
         }
     }
 
@@ -1,10 +1,9 @@
-use std::{collections::BTreeMap, env};
+use std::collections::BTreeMap;
 
 use crate::{gemini::types::{generate_to_schema, GeminiImageInputType}, libs::logger::LOGGER};
 use base64::Engine;
 use gemini_live_api::types::{enums::{GeminiContentRole, GeminiSchemaType}, GeminiContents, GeminiFileData, GeminiFunctionDeclaration, GeminiInlineBlob, GeminiParts, GeminiSchemaObject};
-use serde_json::json;
-use reqwest::header::{HeaderName, HeaderValue};
+use gemini_live_api::service::rest_client::GeminiRestClient;
 
 use super::types::{ GeminiBotToolInputValueType, GeminiBotTools, GeminiChatChunk};
 
@@ -80,18 +79,7 @@ pub fn generate_gemini_user_chunk(chunk: &GeminiChatChunk)->GeminiContents {
 }
 
 pub async fn upload_image_to_gemini(image: GeminiImageInputType,display_name:String) -> Result<GeminiImageInputType, String> {
-    
-// curl "https://generativelanguage.googleapis.com/upload/v1beta/files?key=${GOOGLE_API_KEY}" \
-//   -D upload-header.tmp \
-//   -H "X-Goog-Upload-Protocol: resumable" \
-//   -H "X-Goog-Upload-Command: start" \
-//   -H "X-Goog-Upload-Header-Content-Length: ${NUM_BYTES}" \
-//   -H "X-Goog-Upload-Header-Content-Type: ${MIME_TYPE}" \
-//   -H "Content-Type: application/json" \
-//   -d "{'file': {'display_name': '${DISPLAY_NAME}'}}"
-
-    let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
-    let url = format!("https://generativelanguage.googleapis.com/upload/v1beta/files?key={}", api_key);
+    let client = GeminiRestClient::from_env()?;
     let target_image: Vec<u8> = match (&image.base64_image, &image.file_url) {
         (Some(base64_image), _) => {
             // base64 이미지가 있으면 우선적으로 사용
@@ -113,78 +101,10 @@ pub async fn upload_image_to_gemini(image: GeminiImageInputType,display_name:Str
     };
     
 
-    let mut request_header = reqwest::header::HeaderMap::new();
-
-    // Example: Add a header with a valid name and value
-     request_header.insert(
-        HeaderName::from_static("x-goog-upload-header-content-length"),
-        reqwest::header::HeaderValue::from(target_image.len() as u64),
-        );
-    request_header.insert(
-        HeaderName::from_static("x-goog-upload-protocol"),
-        HeaderValue::from_static("resumable"),
-    );
-    request_header.insert(
-        HeaderName::from_static("x-goog-upload-command"),
-        HeaderValue::from_static("start"),
-    );
-    request_header.insert(
-        HeaderName::from_static("x-goog-upload-header-content-type"),
-        HeaderValue::from_str(&image.mime_type).unwrap(),
-    );
-    request_header.insert(
-        HeaderName::from_static("content-type"),
-        HeaderValue::from_static("application/json"),
-    );
-    let body_for_upload = json!({
-        "file": {
-            "display_name": display_name
-        }
-    });
-    let client = reqwest::Client::new();
-
-    let response = client
-        .post(&url)
-        .headers(request_header)
-        .body(
-            body_for_upload
-                .to_string()
-                .into_bytes()
-        ).send()
-        .await
-        .map_err(|e| e.to_string())?;
-    //upload_url=$(grep -i "x-goog-upload-url: " "${tmp_header_file}" | cut -d" " -f2 | tr -d "\r")
-    //rm "${tmp_header_file}"
-    let upload_url = response.headers().get("x-goog-upload-url").unwrap().to_str().unwrap().to_string();
-
-    //curl "${upload_url1}" \
-    //   -H "Content-Length: ${NUM1_BYTES}" \
-    //   -H "X-Goog-Upload-Offset: 0" \
-    //   -H "X-Goog-Upload-Command: upload, finalize" \
-    //   --data-binary "@${IMAGE1_PATH}" 2> /dev/null > file_info1.json
-
-    // file1_uri=$(jq ".file.uri" file_info1.json)
-    // echo file1_uri=$file1_uri
-
-    let upload_client = reqwest::Client::new();
-    let response = upload_client
-        .post(&upload_url)
-        .header("Content-Length", target_image.len())
-        .header("X-Goog-Upload-Offset", 0)
-        .header("X-Goog-Upload-Command", "upload, finalize")
-        .body(target_image)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    //file1_uri=$(jq ".file.uri" file_info1.json)
-    //echo file1_uri=$file1_uri
-    let file_info = response.text().await.map_err(|e| e.to_string())?;
-    let file_info: serde_json::Value = serde_json::from_str(&file_info).map_err(|e| e.to_string())?;
-    let file_uri = file_info["file"]["uri"].as_str().unwrap_or("").to_string();
+    let file = client.upload_file(target_image, &image.mime_type, &display_name).await?;
     Ok(GeminiImageInputType{
         base64_image: None,
-        file_url: Some(file_uri),
+        file_url: Some(file.uri),
         mime_type: image.mime_type,
     })
 }
@@ -1,5 +1,6 @@
 use lazy_static::lazy_static;
 
+use crate::libs::shutdown::PENDING_LOGS;
 use crate::service::discord_error_msg::send_debug_error_log;
 
 #[derive(PartialEq, Eq)]
@@ -44,7 +45,13 @@ lazy_static! {
     pub static ref LOGGER: Logger = Logger::new(|message, level| {
         if level == LogLevel::Error {
             // Spawn the future so it runs in the background
-            tokio::spawn(send_debug_error_log(message.to_string()));
+            // 종료할 때 다 보낼 때까지 기다릴 수 있도록 PENDING_LOGS 로 셉니다.
+            let pending = PENDING_LOGS.begin();
+            let message = message.to_string();
+            tokio::spawn(async move {
+                send_debug_error_log(message).await;
+                drop(pending);
+            });
         }
         println!("[{:?}] {}", level.to_string(), message);
     });
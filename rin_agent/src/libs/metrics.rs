@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// 초 단위 지연 시간 구간. Gemini 는 도구를 거치면 수십 초가 걸리기도 합니다.
+pub const LATENCY_BUCKETS: [f64; 11] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+// 내보낼 지표. 여기에 없는 이름으로 기록한 값은 출력하지 않습니다.
+#[derive(Debug, Clone, Copy)]
+pub struct MetricDef {
+    pub name: &'static str,
+    pub kind: MetricKind,
+    pub help: &'static str,
+}
+
+type LabelSet = Vec<(String, String)>;
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    // LATENCY_BUCKETS 각 구간 이하인 관측 수 (누적)
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+#[derive(Default)]
+struct MetricValues {
+    scalars: BTreeMap<(String, LabelSet), f64>,
+    histograms: BTreeMap<(String, LabelSet), Histogram>,
+}
+
+// Prometheus 텍스트 형식으로 내보내는 간단한 지표 저장소
+pub struct MetricsRegistry {
+    defs: &'static [MetricDef],
+    values: Mutex<MetricValues>,
+}
+
+fn to_label_set(labels: &[(&str, &str)]) -> LabelSet {
+    labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+pub fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_labels(labels: &LabelSet, extra: Option<(&str, &str)>) -> String {
+    let mut parts = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+        .collect::<Vec<_>>();
+    if let Some((k, v)) = extra {
+        parts.push(format!("{}=\"{}\"", k, v));
+    }
+    if parts.is_empty() { String::new() } else { format!("{{{}}}", parts.join(",")) }
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf".to_string() } else { "-Inf".to_string() }
+    } else {
+        value.to_string()
+    }
+}
+
+impl MetricsRegistry {
+    pub const fn new(defs: &'static [MetricDef]) -> Self {
+        MetricsRegistry {
+            defs,
+            values: Mutex::new(MetricValues { scalars: BTreeMap::new(), histograms: BTreeMap::new() }),
+        }
+    }
+
+    pub fn inc_counter(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let mut values = self.values.lock().unwrap();
+        *values.scalars.entry((name.to_string(), to_label_set(labels))).or_insert(0.0) += value;
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let mut values = self.values.lock().unwrap();
+        values.scalars.insert((name.to_string(), to_label_set(labels)), value);
+    }
+
+    // 라벨 조합이 사라질 수 있는 게이지(샤드 상태 등)를 다시 채우기 전에 비웁니다.
+    pub fn clear_gauge(&self, name: &str) {
+        let mut values = self.values.lock().unwrap();
+        values.scalars.retain(|(metric, _), _| metric != name);
+    }
+
+    pub fn observe(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let mut values = self.values.lock().unwrap();
+        let histogram = values.histograms.entry((name.to_string(), to_label_set(labels))).or_default();
+        if histogram.buckets.is_empty() {
+            histogram.buckets = vec![0; LATENCY_BUCKETS.len()];
+        }
+        for (bucket, bound) in histogram.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        histogram.count += 1;
+        histogram.sum += value;
+    }
+
+    pub fn observe_duration(&self, name: &str, labels: &[(&str, &str)], elapsed: Duration) {
+        self.observe(name, labels, elapsed.as_secs_f64());
+    }
+
+    pub fn render(&self) -> String {
+        let values = self.values.lock().unwrap();
+        let mut out = String::new();
+        for def in self.defs {
+            let _ = writeln!(out, "# HELP {} {}", def.name, def.help);
+            let _ = writeln!(out, "# TYPE {} {}", def.name, def.kind.as_str());
+            if def.kind == MetricKind::Histogram {
+                for ((_, labels), histogram) in values.histograms.iter().filter(|((name, _), _)| name == def.name) {
+                    for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                        let le = format_value(bound);
+                        let _ = writeln!(out, "{}_bucket{} {}", def.name, format_labels(labels, Some(("le", &le))), count);
+                    }
+                    let _ = writeln!(out, "{}_bucket{} {}", def.name, format_labels(labels, Some(("le", "+Inf"))), histogram.count);
+                    let _ = writeln!(out, "{}_sum{} {}", def.name, format_labels(labels, None), format_value(histogram.sum));
+                    let _ = writeln!(out, "{}_count{} {}", def.name, format_labels(labels, None), histogram.count);
+                }
+            } else {
+                for ((_, labels), value) in values.scalars.iter().filter(|((name, _), _)| name == def.name) {
+                    let _ = writeln!(out, "{}{} {}", def.name, format_labels(labels, None), format_value(*value));
+                }
+            }
+        }
+        out
+    }
+}
@@ -3,4 +3,7 @@ pub mod thread_pipelines;
 pub mod thread_message;
 pub mod redis_driver;
 pub mod voice_session;
-pub mod array_calc;
\ No newline at end of file
+pub mod array_calc;
+pub mod metrics;
+pub mod task_supervisor;
+pub mod shutdown;
\ No newline at end of file
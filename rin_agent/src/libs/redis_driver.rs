@@ -18,6 +18,19 @@ impl RedisDriver {
             pool,
         }
     }
+    // 연결에 실패해도 패닉하지 않고 오류를 돌려줍니다.
+    pub fn try_new(redis_url: &str) -> Result<Self, String> {
+        let client = redis::Client::open(redis_url).map_err(|e| e.to_string())?;
+        let pool = r2d2::Pool::builder()
+            .max_size(8)
+            .connection_timeout(std::time::Duration::from_secs(3))
+            .build(client.clone())
+            .map_err(|e| e.to_string())?;
+        Ok(RedisDriver {
+            client,
+            pool,
+        })
+    }
     pub fn get_pool(&self) -> r2d2::Pool<Client> {
         self.pool.clone()
     }
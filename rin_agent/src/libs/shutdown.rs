@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+// 모든 하위 시스템이 함께 보는 종료 신호. 취소되면 새 작업을 받지 않고 각자 루프를 빠져나옵니다.
+pub static SHUTDOWN: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
+
+// 진행 중인 Gemini 요청과 도구 호출
+pub static IN_FLIGHT_WORK: LazyLock<WorkTracker> = LazyLock::new(WorkTracker::default);
+
+// 아직 보내지 못한 웹훅 로그
+pub static PENDING_LOGS: LazyLock<WorkTracker> = LazyLock::new(WorkTracker::default);
+
+pub fn is_shutting_down() -> bool {
+    SHUTDOWN.is_cancelled()
+}
+
+// 종료가 끝나면 같은 인자로 프로세스를 다시 띄워야 하는지 (MQTT Restart 명령)
+static RESTART_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+pub fn request_restart() {
+    RESTART_REQUESTED.store(true, Ordering::Release);
+}
+
+pub fn restart_requested() -> bool {
+    RESTART_REQUESTED.load(Ordering::Acquire)
+}
+
+// 진행 중인 작업 수를 세고, 모두 끝날 때까지 기다릴 수 있게 합니다.
+#[derive(Debug, Default)]
+pub struct WorkTracker {
+    active: AtomicUsize,
+    idle: Notify,
+}
+
+// drop 될 때 작업 하나가 끝난 것으로 셉니다.
+#[must_use]
+pub struct WorkGuard<'a> {
+    tracker: &'a WorkTracker,
+}
+
+impl Drop for WorkGuard<'_> {
+    fn drop(&mut self) {
+        if self.tracker.active.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.tracker.idle.notify_waiters();
+        }
+    }
+}
+
+impl WorkTracker {
+    pub fn begin(&self) -> WorkGuard<'_> {
+        self.active.fetch_add(1, Ordering::AcqRel);
+        WorkGuard { tracker: self }
+    }
+
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    // 진행 중인 작업이 모두 끝나면 true, deadline 이 먼저 지나면 false 를 돌려줍니다.
+    pub async fn drain(&self, deadline: Duration) -> bool {
+        tokio::time::timeout(deadline, async {
+            loop {
+                // 확인하기 전에 먼저 등록해야 그 사이에 끝난 작업의 알림을 놓치지 않습니다.
+                let idle = self.idle.notified();
+                if self.active() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
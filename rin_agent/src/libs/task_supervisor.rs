@@ -0,0 +1,174 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+pub type TaskFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+type TaskFactory = Box<dyn Fn() -> TaskFuture + Send + Sync>;
+pub type SupervisorHook = Arc<dyn Fn(SupervisorEvent) + Send + Sync>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskExit {
+    // 오류 없이 끝남. 오래 도는 작업이므로 이것도 재시작합니다.
+    Finished,
+    Failed(String),
+    Panicked(String),
+}
+
+impl fmt::Display for TaskExit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskExit::Finished => write!(f, "finished"),
+            TaskExit::Failed(e) => write!(f, "failed: {}", e),
+            TaskExit::Panicked(e) => write!(f, "panicked: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupervisorEvent {
+    Started { task: String, restarts: u32 },
+    Exited { task: String, exit: TaskExit, restart_in: Duration },
+    // crash_window 안에서 crash_budget 을 넘게 죽어 더 이상 살리지 않음
+    GaveUp { task: String, exit: TaskExit, crashes: usize },
+    // 종료 중이라 다시 띄우지 않음
+    Stopped { task: String, exit: TaskExit },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub crash_budget: usize,
+    pub crash_window: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            crash_budget: 5,
+            crash_window: Duration::from_secs(600),
+        }
+    }
+}
+
+impl RestartPolicy {
+    // recent_crashes 번째 충돌 뒤에 기다릴 시간. 한 번 죽을 때마다 두 배씩 늘립니다.
+    pub fn backoff(&self, recent_crashes: usize) -> Duration {
+        let exponent = recent_crashes.saturating_sub(1).min(16) as u32;
+        self.initial_backoff.saturating_mul(2u32.pow(exponent)).min(self.max_backoff)
+    }
+}
+
+// crash_window 안에 있었던 충돌 시각
+#[derive(Debug, Default)]
+pub struct CrashHistory {
+    crashes: VecDeque<Instant>,
+}
+
+impl CrashHistory {
+    // 충돌을 기록하고 창 안의 충돌 수를 돌려줍니다.
+    pub fn record(&mut self, now: Instant, window: Duration) -> usize {
+        self.crashes.push_back(now);
+        while self.crashes.front().is_some_and(|first| now.duration_since(*first) > window) {
+            self.crashes.pop_front();
+        }
+        self.crashes.len()
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// 등록한 작업을 각각 띄우고, 끝나거나 패닉하면 백오프 뒤 다시 띄웁니다.
+pub struct TaskSupervisor {
+    policy: RestartPolicy,
+    on_event: SupervisorHook,
+    tasks: Vec<(String, TaskFactory)>,
+}
+
+impl TaskSupervisor {
+    pub fn new(policy: RestartPolicy, on_event: SupervisorHook) -> Self {
+        TaskSupervisor { policy, on_event, tasks: Vec::new() }
+    }
+
+    // factory 는 재시작할 때마다 새 작업을 만듭니다.
+    pub fn task<F, Fut>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        self.tasks.push((name.to_string(), Box::new(move || Box::pin(factory()))));
+        self
+    }
+
+    // 어느 작업이든 충돌 한도를 넘기면 나머지 감시를 멈추고 그 작업 이름과 마지막 종료 사유를 돌려줍니다.
+    // shutdown 이 취소된 뒤에 끝난 작업은 다시 띄우지 않으며, 모두 끝나면 None 을 돌려줍니다.
+    pub async fn run(self, shutdown: CancellationToken) -> Option<(String, TaskExit)> {
+        let mut set = JoinSet::new();
+        for (name, factory) in self.tasks {
+            set.spawn(supervise(name, factory, self.policy.clone(), self.on_event.clone(), shutdown.clone()));
+        }
+        while let Some(result) = set.join_next().await {
+            if let Ok(Some(gave_up)) = result {
+                set.abort_all();
+                return Some(gave_up);
+            }
+        }
+        None
+    }
+}
+
+async fn supervise(
+    name: String,
+    factory: TaskFactory,
+    policy: RestartPolicy,
+    on_event: SupervisorHook,
+    shutdown: CancellationToken,
+) -> Option<(String, TaskExit)> {
+    let mut history = CrashHistory::default();
+    let mut restarts = 0;
+    loop {
+        on_event(SupervisorEvent::Started { task: name.clone(), restarts });
+        let exit = match tokio::spawn(factory()).await {
+            Ok(Ok(())) => TaskExit::Finished,
+            Ok(Err(e)) => TaskExit::Failed(e),
+            Err(e) if e.is_panic() => TaskExit::Panicked(panic_message(e.into_panic())),
+            Err(e) => TaskExit::Failed(e.to_string()),
+        };
+        if shutdown.is_cancelled() {
+            on_event(SupervisorEvent::Stopped { task: name, exit });
+            return None;
+        }
+        let crashes = history.record(Instant::now(), policy.crash_window);
+        if crashes > policy.crash_budget {
+            on_event(SupervisorEvent::GaveUp { task: name.clone(), exit: exit.clone(), crashes });
+            return Some((name, exit));
+        }
+        let restart_in = policy.backoff(crashes);
+        on_event(SupervisorEvent::Exited { task: name.clone(), exit: exit.clone(), restart_in });
+        tokio::select! {
+            _ = tokio::time::sleep(restart_in) => {}
+            _ = shutdown.cancelled() => {
+                on_event(SupervisorEvent::Stopped { task: name, exit });
+                return None;
+            }
+        }
+        restarts += 1;
+    }
+}
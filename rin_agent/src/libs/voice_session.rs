@@ -1,5 +1,15 @@
+use tokio::sync::mpsc;
 
+use crate::gemini::live_voice_bridge::VoiceBridgeInput;
+
+/// 길드 하나에서 진행 중인 Gemini Live 음성 대화 세션
 #[derive(Default, Clone, Debug)]
 pub struct VoiceSession {
-
-}
\ No newline at end of file
+    pub guild_id: u64,
+    pub channel_id: u64,
+    // 도구 실행 결과를 알릴 텍스트 채널
+    pub text_channel_id: u64,
+    pub started_by: u64,
+    // Live 세션으로 실시간 입력을 보내는 송신부
+    pub input_sender: Option<mpsc::Sender<VoiceBridgeInput>>,
+}
@@ -10,70 +10,136 @@ mod service;
 #[cfg(test)] mod tests;
 
 use api::instances::init_rin_services;
+use api::schedule::ScheduleService;
 use contract::config::{EnvConfigBuilder, RinAgentConfig};
+use contract::{LogLevel as AlertLevel, RinAgentStatus};
 use discord::discord_bot_manager::{get_discord_service, BotManager};
+use gemini::live_voice_bridge::run_live_idle_reaper;
+use libs::shutdown::{restart_requested, PENDING_LOGS, SHUTDOWN};
+use libs::task_supervisor::{SupervisorEvent, TaskExit, TaskSupervisor};
 use service::discord_error_msg::send_additional_log;
+use service::agent_command_service::{execute_agent_command, remember_system_env, respawn_self, run_status_reporter};
+use service::mqtt_service::{init_mqtt, now_timestamp, publish_alert, publish_status, run_mqtt_event_loop, STARTED_AT};
+use service::shutdown_service::graceful_shutdown;
+use setting::mqtt_setting::{RIN_AGENT_MQTT_CLIENT_ID, RIN_AGENT_STATUS_INTERVAL};
+use setting::supervisor_setting::SUPERVISOR_POLICY;
 use web::server::server::get_rocket;
 use model::db::driver::connect_to_db;
 use libs::logger::{self, LOGGER,LogLevel};
-use tokio::task;
 use tokio::signal;
-use std::sync::Arc;
-use tokio::sync::Notify;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
-async fn fn_discord_thread() {
+async fn fn_discord_thread() -> Result<(), String> {
     let discord_service = get_discord_service().await;
-    let bot_manager_result = discord_service.call::<BotManager>();
-    
-    if let Some(bot_manager_mutex) = bot_manager_result {
-        let mut bot_manager = bot_manager_mutex.lock().await;
-        
-        LOGGER.log(LogLevel::Debug, "Discord > Starting...");
-        
-        match bot_manager.run().await {
-            Ok(_) => {
-                LOGGER.log(LogLevel::Info, "Discord > Bot finished normally");
-            },
-            Err(e) => {
-                LOGGER.log(LogLevel::Error, &format!("Discord > Bot run error: {:?}", e));
-                send_additional_log(format!("Discord bot run error: {:?}", e), None).await;
-            }
-        }
-    } else {
-        LOGGER.log(LogLevel::Error, "Discord > Failed to get BotManager");
-        send_additional_log("Failed to get Discord BotManager".to_string(), None).await;
-    }
+    let bot_manager_mutex = discord_service.call::<BotManager>()
+        .ok_or("Failed to get Discord BotManager".to_string())?;
+    let mut bot_manager = bot_manager_mutex.lock().await;
+
+    LOGGER.log(LogLevel::Debug, "Discord > Starting...");
+    bot_manager.run().await.map_err(|e| format!("Discord bot run error: {:?}", e))?;
+    LOGGER.log(LogLevel::Info, "Discord > Bot finished normally");
+    Ok(())
+}
+
+async fn fn_web_server_thread() -> Result<(), String> {
+    LOGGER.log(LogLevel::Debug, "Web server > Starting...");
+    let rocket = get_rocket().ignite().await.map_err(|e| format!("Web server error: {}", e))?;
+    // 종료 신호를 받으면 진행 중인 요청을 마무리하고 서버를 내립니다.
+    let rocket_shutdown = rocket.shutdown();
+    let stop_on_shutdown = tokio::spawn(async move {
+        SHUTDOWN.cancelled().await;
+        rocket_shutdown.notify();
+    });
+    let result = rocket.launch().await;
+    stop_on_shutdown.abort();
+    result.map_err(|e| format!("Web server error: {}", e))?;
+    LOGGER.log(LogLevel::Debug, "Web server > Stopped");
+    Ok(())
+}
+
+async fn fn_scheduler_thread() -> Result<(), String> {
+    let schedule_service = api::instances::get_rin_services().await
+        .call::<ScheduleService>()
+        .ok_or("Failed to get ScheduleService".to_string())?;
+    ScheduleService::run_alarm_loop(schedule_service).await
 }
-async fn fn_aspect_thread(threads: Vec<task::JoinHandle<()>>) {
 
-    let sigint_notify = Arc::new(Notify::new());
-    let sigint_notify_clone = sigint_notify.clone();
+async fn fn_voice_thread() -> Result<(), String> {
+    // 유휴 Live 세션을 주기적으로 정리합니다.
+    run_live_idle_reaper(Duration::from_secs(60)).await
+}
+
+fn env_name() -> &'static str {
+    if cfg!(debug_assertions) { "dev" } else { "prod" }
+}
 
+// 감시자가 알려주는 상태 변화를 로그, 디스코드 로그 채널, MQTT 로 내보냅니다.
+fn report_supervisor_event(event: SupervisorEvent) {
+    let (level, message) = match &event {
+        SupervisorEvent::Started { task, restarts: 0 } => {
+            LOGGER.log(LogLevel::Debug, &format!("Supervisor > {} started", task));
+            return;
+        }
+        SupervisorEvent::Started { task, restarts } => {
+            (LogLevel::Info, format!("{} > {} restarted (restart #{})", env_name(), task, restarts))
+        }
+        SupervisorEvent::Exited { task, exit, restart_in } => {
+            let level = match exit {
+                TaskExit::Finished => LogLevel::Warning,
+                _ => LogLevel::Error,
+            };
+            (level, format!("{} > {} {}, restarting in {}s", env_name(), task, exit, restart_in.as_secs()))
+        }
+        SupervisorEvent::GaveUp { task, exit, crashes } => {
+            (LogLevel::Error, format!("{} > {} {}, crashed {} times, giving up", env_name(), task, exit, crashes))
+        }
+        SupervisorEvent::Stopped { task, exit } => {
+            LOGGER.log(LogLevel::Debug, &format!("Supervisor > {} stopped for shutdown ({})", task, exit));
+            return;
+        }
+    };
+    LOGGER.log(level, &format!("Supervisor > {}", message));
+    match &event {
+        SupervisorEvent::Exited { exit: TaskExit::Finished, .. } => publish_alert(AlertLevel::Warn, message.clone()),
+        SupervisorEvent::Exited { .. } => publish_alert(AlertLevel::Error, message.clone()),
+        SupervisorEvent::GaveUp { .. } => {
+            publish_alert(AlertLevel::Fatal, message.clone());
+            publish_status(&RinAgentStatus::Error { error: message.clone(), timestamp: now_timestamp() });
+        }
+        _ => publish_alert(AlertLevel::Info, message.clone()),
+    }
+    let pending = PENDING_LOGS.begin();
     tokio::spawn(async move {
-        signal::ctrl_c().await.expect("Failed to listen for SIGINT");
-        LOGGER.log(LogLevel::Debug, "SIGINT received, shutting down...");
-        let is_dev = if cfg!(debug_assertions) {
-            "dev"
-        } else {
-            "prod"
-        };
-        tokio::task::spawn_blocking(move || send_additional_log(format!("{} > SIGINT received, shutting down...",is_dev).to_string(),None));
-        sigint_notify_clone.notify_one();
+        send_additional_log(message, None).await;
+        drop(pending);
     });
+}
 
-    sigint_notify.notified().await;
-    LOGGER.log(LogLevel::Debug, "Waiting for all threads to finish...");
-    for thread in threads {
-        let _ = thread.await;
+// SIGINT 또는 SIGTERM 을 기다리고 받은 신호 이름을 돌려줍니다.
+async fn wait_for_stop_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                LOGGER.log(LogLevel::Error, &format!("Failed to listen for SIGTERM: {:?}", e));
+                let _ = signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = signal::ctrl_c().await;
+        "SIGINT"
     }
-    LOGGER.log(LogLevel::Debug, "All threads have finished.");
 }
 
-async fn fn_web_server_thread() {
-    LOGGER.log(LogLevel::Debug, "Web server > Starting...");
-    get_rocket().launch().await.unwrap();
-    LOGGER.log(LogLevel::Debug, "Web server > Stopped");
-}
 #[cfg(target_os = "linux")]
 fn set_process_name(name: &str) {
     use std::ffi::CString;
@@ -91,6 +157,10 @@ async fn main() {
     set_process_name("rin_agent_main_server");
 
     // Load environment variables with CLI-specified strategy
+    // ReloadConfig 가 .env 변경분을 셀 때 시스템 환경변수를 빼도록 .env 를 읽기 전에 남겨 둡니다.
+    remember_system_env();
+    // Running 상태의 uptime 기준 시각
+    LazyLock::force(&STARTED_AT);
     let strategy = contract::config::parse_env_strategy_from_args();
     let dotenv_path = contract::config::parse_dotenv_path_from_args();
     
@@ -120,6 +190,12 @@ async fn main() {
         }
     };
 
+    // MQTT 는 브로커가 없어도 뒤에서 계속 재연결을 시도합니다.
+    if let Some((client, event_loop)) = init_mqtt(&config.common, &RIN_AGENT_MQTT_CLIENT_ID) {
+        tokio::spawn(run_mqtt_event_loop(client, event_loop, Arc::new(execute_agent_command)));
+    }
+    publish_status(&RinAgentStatus::Starting { timestamp: now_timestamp() });
+
     // Initialize database (once)
     let _db_init_ = connect_to_db().await;
     
@@ -129,17 +205,41 @@ async fn main() {
     let startup_msg = "Rin Agent Main Server started";
     LOGGER.log(LogLevel::Info, startup_msg);
     send_additional_log(startup_msg.to_string(), None).await;
-    let discord_thread = tokio::spawn(async move { fn_discord_thread().await });
-    let web_server_thread = tokio::spawn(async move { fn_web_server_thread().await });
 
+    let supervisor = TaskSupervisor::new(SUPERVISOR_POLICY.clone(), Arc::new(report_supervisor_event))
+        .task("discord", fn_discord_thread)
+        .task("web", fn_web_server_thread)
+        .task("scheduler", fn_scheduler_thread)
+        .task("voice", fn_voice_thread)
+        .task("status", || run_status_reporter(*RIN_AGENT_STATUS_INTERVAL));
 
-    // TODO : 감시자 쓰레드를 만들고, 다른 쓰레드가 종료되면 감시자가 다시시작하던 하도록 한다.
-    
-    let threads_vector = vec![
-        discord_thread,
-        web_server_thread,
-    ];
-    fn_aspect_thread(threads_vector).await;
-
-    LOGGER.log(LogLevel::Debug, "Starting Discord bot thread");
+    let mut supervisor_run = tokio::spawn(supervisor.run(SHUTDOWN.clone()));
+    tokio::select! {
+        gave_up = &mut supervisor_run => {
+            // MQTT Restart 명령으로 정리가 끝난 경우
+            if matches!(gave_up, Ok(None)) && restart_requested() {
+                if let Err(e) = respawn_self() {
+                    LOGGER.log(LogLevel::Error, &format!("Restart > {}", e));
+                    std::process::exit(1);
+                }
+                std::process::exit(0);
+            }
+            let reason = match gave_up {
+                Ok(Some((task, exit))) => format!("{} exceeded crash budget ({})", task, exit),
+                Ok(None) => "all supervised tasks stopped".to_string(),
+                Err(e) => format!("supervisor failed: {}", e),
+            };
+            LOGGER.log(LogLevel::Error, &format!("Supervisor > {}, shutting down...", reason));
+            graceful_shutdown(&reason).await;
+            std::process::exit(1);
+        }
+        signal_name = wait_for_stop_signal() => {
+            LOGGER.log(LogLevel::Info, &format!("{} received, shutting down...", signal_name));
+            graceful_shutdown(signal_name).await;
+            // 남은 작업이 종료 신호를 보고 빠져나올 시간을 줍니다.
+            if tokio::time::timeout(Duration::from_secs(10), supervisor_run).await.is_err() {
+                LOGGER.log(LogLevel::Warning, "Supervisor > tasks did not stop in time");
+            }
+        }
+    }
 }
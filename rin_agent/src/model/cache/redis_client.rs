@@ -1,3 +1,20 @@
-pub mod redis_client{
-    
-}
\ No newline at end of file
+use std::{env, sync::LazyLock};
+
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::libs::redis_driver::RedisDriver;
+
+// REDIS_URL 이 비어 있거나 연결할 수 없으면 None 입니다. 사용하는 쪽에서 Redis 없이 동작하도록 처리합니다.
+pub static REDIS_DRIVER: LazyLock<Option<RedisDriver>> = LazyLock::new(|| {
+    let redis_url = env::var("REDIS_URL").unwrap_or_default();
+    if redis_url.is_empty() {
+        LOGGER.log(LogLevel::Warning, "Redis > REDIS_URL 이 설정되지 않았습니다.");
+        return None;
+    }
+    match RedisDriver::try_new(&redis_url) {
+        Ok(driver) => Some(driver),
+        Err(e) => {
+            LOGGER.log(LogLevel::Error, &format!("Redis > 연결 실패: {}", e));
+            None
+        }
+    }
+});
@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use contract::config::{parse_dotenv_path_from_args, parse_env_strategy_from_args, EnvLoadStrategy};
+use contract::{RinAgentCommand, RinAgentStatus};
+
+use crate::api::instances::get_rin_services;
+use crate::api::schedule::ScheduleService;
+use crate::discord::discord_bot_manager::connected_guild_count;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::libs::shutdown::{is_shutting_down, request_restart, SHUTDOWN};
+use crate::libs::thread_message::GeminiFunctionAlarm;
+use crate::libs::thread_pipelines::SCHEDULE_TO_DISCORD_PIPELINE;
+use crate::service::alarm_service::find_alarm;
+use crate::service::mqtt_service::{now_timestamp, publish_status, uptime_secs, CommandFuture};
+use crate::service::shutdown_service::graceful_shutdown;
+use crate::service::voice_session_manager::VOICE_SESSION_MANAGER;
+
+// graceful 재시작에서 종료 절차가 끝난 뒤 감시 작업이 멈추기를 기다리는 최대 시간
+const RESTART_FALLBACK_DELAY: Duration = Duration::from_secs(15);
+
+static RESPAWNED: AtomicBool = AtomicBool::new(false);
+
+// .env 를 읽기 전부터 있던 환경변수. .env 와 값이 달라도 바뀐 설정으로 보지 않습니다.
+static SYSTEM_ENV_KEYS: OnceLock<HashSet<String>> = OnceLock::new();
+
+/// .env 를 읽기 전에 한 번 불러 시스템 환경변수 목록을 남겨 둡니다.
+pub fn remember_system_env() {
+    let _ = SYSTEM_ENV_KEYS.set(env::vars_os().filter_map(|(key, _)| key.into_string().ok()).collect());
+}
+
+/// MQTT 로 받은 `RinAgentCommand` 를 실행합니다. 결과 메시지는 rin_agent/alert 로 나갑니다.
+pub fn execute_agent_command(command: RinAgentCommand) -> CommandFuture {
+    Box::pin(async move {
+        match command {
+            RinAgentCommand::TriggerAlarm { alarm_id, custom_message } => trigger_alarm(alarm_id, custom_message).await,
+            RinAgentCommand::Restart { graceful } => restart(graceful),
+            RinAgentCommand::ReloadConfig => reload_config().await,
+            RinAgentCommand::BotStatus => {
+                let status = running_status().await;
+                publish_status(&status);
+                Ok(format!("{:?}", status))
+            }
+        }
+    })
+}
+
+pub async fn running_status() -> RinAgentStatus {
+    RinAgentStatus::Running {
+        uptime_secs: uptime_secs(),
+        connected_guilds: connected_guild_count(),
+        active_voice_sessions: VOICE_SESSION_MANAGER.lock().await.len(),
+        timestamp: now_timestamp(),
+    }
+}
+
+/// interval 마다 Running 상태를 보냅니다. 종료 신호를 받으면 끝납니다.
+pub async fn run_status_reporter(interval: Duration) -> Result<(), String> {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => publish_status(&running_status().await),
+            _ = SHUTDOWN.cancelled() => return Ok(()),
+        }
+    }
+}
+
+// 예약 시각과 상관없이 알람을 지금 보냅니다. 알람 자체는 그대로 남습니다.
+async fn trigger_alarm(alarm_id: i64, custom_message: Option<String>) -> Result<String, String> {
+    let mut alarm = find_alarm(alarm_id).await?.ok_or(format!("알람 {} 을(를) 찾을 수 없습니다.", alarm_id))?;
+    if let Some(message) = custom_message.filter(|m| !m.trim().is_empty()) {
+        alarm.message = message;
+    }
+    let alarm_item = GeminiFunctionAlarm {
+        message: Some(alarm.clone()),
+        sender: alarm.user_id.to_string(),
+        channel_id: alarm.channel_id.to_string(),
+        message_id: "".to_string(),
+        guild_id: "0".to_string(),
+        need_send: false,
+        context_id: 0,
+    };
+    SCHEDULE_TO_DISCORD_PIPELINE
+        .sender
+        .send(alarm_item)
+        .map_err(|e| format!("알람을 보내지 못했습니다: {}", e))?;
+    Ok(format!("알람 {} 을(를) 보냈습니다.", alarm_id))
+}
+
+// graceful 이면 진행 중인 작업을 마무리한 뒤 main 이 프로세스를 다시 띄우고,
+// 아니면 상태만 알리고 바로 다시 띄웁니다. 결과 알림이 나갈 수 있도록 실제 작업은 따로 돌립니다.
+fn restart(graceful: bool) -> Result<String, String> {
+    if is_shutting_down() {
+        return Err("이미 종료 중입니다.".to_string());
+    }
+    if graceful {
+        request_restart();
+        tokio::spawn(async {
+            graceful_shutdown("restart requested via MQTT").await;
+            // 보통은 감시 작업이 모두 멈춘 뒤 main 이 다시 띄우지만, 멈추지 않는 작업이 있으면 여기서 띄웁니다.
+            tokio::time::sleep(RESTART_FALLBACK_DELAY).await;
+            LOGGER.log(LogLevel::Warning, "Restart > 멈추지 않은 작업을 두고 다시 시작합니다.");
+            std::process::exit(if respawn_self().is_ok() { 0 } else { 1 });
+        });
+        return Ok("정리 후 다시 시작합니다.".to_string());
+    }
+    SHUTDOWN.cancel();
+    publish_status(&RinAgentStatus::ShuttingDown { reason: "immediate restart requested via MQTT".to_string(), timestamp: now_timestamp() });
+    tokio::spawn(async {
+        // MQTT 이벤트 루프가 상태와 알림을 내보낼 시간을 줍니다.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        match respawn_self() {
+            Ok(_) => std::process::exit(0),
+            Err(e) => {
+                LOGGER.log(LogLevel::Error, &format!("Restart > {}", e));
+                std::process::exit(1);
+            }
+        }
+    });
+    Ok("바로 다시 시작합니다.".to_string())
+}
+
+/// 같은 실행 파일과 인자로 새 프로세스를 띄우고 pid 를 돌려줍니다. 한 번만 띄웁니다.
+pub fn respawn_self() -> Result<u32, String> {
+    if RESPAWNED.swap(true, Ordering::AcqRel) {
+        return Err("이미 새 프로세스를 띄웠습니다.".to_string());
+    }
+    let exe = env::current_exe().map_err(|e| format!("실행 파일 경로를 찾지 못했습니다: {}", e))?;
+    let child = std::process::Command::new(exe)
+        .args(env::args_os().skip(1))
+        .spawn()
+        .map_err(|e| format!("새 프로세스를 띄우지 못했습니다: {}", e))?;
+    LOGGER.log(LogLevel::Info, &format!("Restart > 새 프로세스를 띄웠습니다 (pid {})", child.id()));
+    Ok(child.id())
+}
+
+// 알람 캐시를 새로 불러옵니다. 환경변수는 다른 스레드가 읽는 중에 바꿀 수 없으므로
+// .env 에서 바뀐 값은 개수만 알려 주고, 재시작해야 반영됩니다.
+async fn reload_config() -> Result<String, String> {
+    let pending = pending_dotenv_changes()?;
+    get_rin_services()
+        .await
+        .call::<ScheduleService>()
+        .ok_or("Failed to get ScheduleService".to_string())?
+        .lock()
+        .await
+        .reload_next_alarm()
+        .await?;
+    Ok(match pending.len() {
+        0 => "알람 캐시를 새로 불러왔습니다.".to_string(),
+        _ => format!(
+            "알람 캐시를 새로 불러왔습니다. .env 에서 바뀐 값 {}개({})는 재시작해야 반영됩니다.",
+            pending.len(),
+            pending.join(", ")
+        ),
+    })
+}
+
+/// .env 를 읽기만 해서 현재 프로세스 환경과 값이 다른 키를 돌려줍니다. 시스템 환경변수가 우선인 키는 뺍니다.
+pub fn pending_dotenv_changes() -> Result<Vec<String>, String> {
+    if parse_env_strategy_from_args() == EnvLoadStrategy::SystemOnly {
+        return Ok(Vec::new());
+    }
+    let entries = match parse_dotenv_path_from_args() {
+        Some(path) => dotenv::from_path_iter(path),
+        None => dotenv::dotenv_iter(),
+    }
+    .map_err(|e| format!(".env 를 읽지 못했습니다: {}", e))?;
+
+    let system_keys = SYSTEM_ENV_KEYS.get();
+    let mut changed = Vec::new();
+    for entry in entries {
+        let (key, value) = entry.map_err(|e| format!(".env 를 읽지 못했습니다: {}", e))?;
+        if system_keys.is_some_and(|keys| keys.contains(&key)) {
+            continue;
+        }
+        if env::var(&key).ok().as_deref() != Some(value.as_str()) {
+            changed.push(key);
+        }
+    }
+    changed.sort();
+    Ok(changed)
+}
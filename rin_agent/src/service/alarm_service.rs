@@ -0,0 +1,168 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use entity::tb_alarm_model;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use serde::Serialize;
+use serenity::all::{ChannelId, UserId};
+
+use crate::api::instances::get_rin_services;
+use crate::api::schedule::{ScheduleRequest, ScheduleService};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+pub const MAX_ALARM_MESSAGE_CHARS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlarmView {
+    pub id: i64,
+    pub time: String,
+    pub message: String,
+    pub repeat_circle: Option<String>,
+    pub repeat_end_at: Option<String>,
+    pub user_id: i64,
+    pub user_name: String,
+    pub channel_id: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<tb_alarm_model::Model> for AlarmView {
+    fn from(model: tb_alarm_model::Model) -> Self {
+        Self {
+            id: model.id,
+            time: model.time.to_rfc3339(),
+            message: model.message,
+            repeat_circle: model.repeat_circle,
+            repeat_end_at: model.repeat_end_at.map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string()),
+            user_id: model.user_id,
+            user_name: model.user_name,
+            channel_id: model.channel_id,
+            created_at: model.created_at.to_rfc3339(),
+            updated_at: model.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+// 바꿀 값만 채웁니다.
+#[derive(Debug, Clone, Default)]
+pub struct AlarmUpdate {
+    pub time: Option<DateTime<FixedOffset>>,
+    pub message: Option<String>,
+}
+
+// RFC 3339 시각을 받아 지나간 시각이면 거절합니다.
+pub fn parse_alarm_time(text: &str, now: DateTime<Utc>) -> Result<DateTime<FixedOffset>, String> {
+    let time = DateTime::parse_from_rfc3339(text.trim())
+        .map_err(|_| "시각은 RFC 3339 형식이어야 합니다. (예: 2026-01-01T09:00:00+09:00)".to_string())?;
+    if time.with_timezone(&Utc) <= now {
+        return Err("이미 지난 시각입니다.".to_string());
+    }
+    Ok(time)
+}
+
+pub fn normalize_alarm_message(message: &str) -> Result<String, String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("알람 내용을 입력하세요.".to_string());
+    }
+    if message.chars().count() > MAX_ALARM_MESSAGE_CHARS {
+        return Err(format!("알람 내용은 {}자 이하여야 합니다.", MAX_ALARM_MESSAGE_CHARS));
+    }
+    Ok(message.to_string())
+}
+
+async fn reload_schedule_cache() -> Result<(), String> {
+    get_rin_services()
+        .await
+        .call::<ScheduleService>()
+        .ok_or("Failed to get ScheduleService".to_string())?
+        .lock()
+        .await
+        .reload_next_alarm()
+        .await
+}
+
+pub async fn find_alarm(id: i64) -> Result<Option<tb_alarm_model::Model>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    tb_alarm_model::Entity::find_by_id(id).one(db).await.map_err(|e| e.to_string())
+}
+
+// user_id 가 None 이면 모든 사람의 알람입니다. 가까운 시각부터 돌려줍니다. (목록, 전체 수)
+pub async fn list_alarms(
+    user_id: Option<i64>,
+    upcoming_only: bool,
+    offset: u64,
+    limit: u64,
+) -> Result<(Vec<AlarmView>, u64), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let mut query = tb_alarm_model::Entity::find();
+    if let Some(user_id) = user_id {
+        query = query.filter(tb_alarm_model::Column::UserId.eq(user_id));
+    }
+    if upcoming_only {
+        query = query.filter(tb_alarm_model::Column::Time.gt(Utc::now()));
+    }
+    let total = query.clone().count(db).await.map_err(|e| e.to_string())?;
+    let alarms = query
+        .order_by_asc(tb_alarm_model::Column::Time)
+        .order_by_asc(tb_alarm_model::Column::Id)
+        .offset(offset)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((alarms.into_iter().map(AlarmView::from).collect(), total))
+}
+
+// 채널 확인은 호출하는 쪽에서 합니다.
+pub async fn create_alarm(
+    user_id: u64,
+    channel_id: u64,
+    time: DateTime<FixedOffset>,
+    message: &str,
+) -> Result<AlarmView, String> {
+    let message = normalize_alarm_message(message)?;
+    let request = ScheduleRequest {
+        start: time,
+        end: time,
+        name: message.clone(),
+        sender: UserId::new(user_id),
+        guild_id: None,
+        channel_id: ChannelId::new(channel_id),
+        description: Some(message),
+        repeat: None,
+        context_id: None,
+    };
+    let inserted = get_rin_services()
+        .await
+        .call::<ScheduleService>()
+        .ok_or("Failed to get ScheduleService".to_string())?
+        .lock()
+        .await
+        .add_schedule(request)
+        .await
+        .ok_or("알람을 저장하지 못했습니다.".to_string())?;
+    Ok(inserted.into())
+}
+
+pub async fn update_alarm(alarm: tb_alarm_model::Model, update: AlarmUpdate) -> Result<AlarmView, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let mut active = alarm.into_active_model();
+    if let Some(time) = update.time {
+        active.time = Set(time);
+    }
+    if let Some(message) = update.message.as_deref() {
+        active.message = Set(normalize_alarm_message(message)?);
+    }
+    active.updated_at = Set(Utc::now().fixed_offset());
+    let updated = active.update(db).await.map_err(|e| e.to_string())?;
+    reload_schedule_cache().await?;
+    Ok(updated.into())
+}
+
+pub async fn delete_alarm(id: i64) -> Result<bool, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let result = tb_alarm_model::Entity::delete_by_id(id).exec(db).await.map_err(|e| e.to_string())?;
+    if result.rows_affected > 0 {
+        reload_schedule_cache().await?;
+    }
+    Ok(result.rows_affected > 0)
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+
+use entity::{tb_ai_context, tb_context_to_msg_id, tb_discord_ai_context, tb_image_attach_file};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Alias, Expr};
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, FromQueryResult, JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    RelationTrait, Select,
+};
+use serde::Serialize;
+
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::web_auth_service::{AuthUser, WebRole};
+
+// 목록에 보여줄 첫 메시지 길이 (글자 수)
+pub const MESSAGE_PREVIEW_CHARS: usize = 120;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversationImage {
+    pub id: i64,
+    pub url: String,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversationMessage {
+    pub id: i64,
+    pub user_id: i64,
+    pub by_bot: bool,
+    pub content: String,
+    pub created_at: String,
+    pub image: Option<ConversationImage>,
+}
+
+// 대화 하나 (tb_discord_ai_context). user_id 는 대화를 시작한 사람입니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversationSummary {
+    pub id: i64,
+    pub guild_id: i64,
+    pub user_id: Option<i64>,
+    pub using_pro_model: bool,
+    pub parent_ids: Vec<i64>,
+    pub message_count: i64,
+    pub preview: String,
+    pub started_at: Option<String>,
+    pub last_message_at: Option<String>,
+}
+
+// 이어진 대화의 위(ancestors, 오래된 순)와 바로 아래(children) 대화, 이 대화의 메시지
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversationTree {
+    pub conversation: ConversationSummary,
+    pub ancestors: Vec<ConversationSummary>,
+    pub children: Vec<ConversationSummary>,
+    pub messages: Vec<ConversationMessage>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConversationFilter {
+    // 대화를 시작한 사람
+    pub user_id: Option<u64>,
+    pub guild_id: Option<u64>,
+    // 메시지 본문 검색어 (대소문자 무시)
+    pub query: Option<String>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ContextStats {
+    ai_context: i64,
+    message_count: i64,
+    last_message_at: Option<DateTimeWithTimeZone>,
+}
+
+// LIKE 패턴에서 %, _, \ 를 글자 그대로 찾도록 바꿉니다.
+pub fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub fn preview_text(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    text.chars().take(max_chars.saturating_sub(1)).collect::<String>() + "…"
+}
+
+// 길드 관리자는 길드의 모든 대화를, 나머지는 자기가 시작한 대화만 봅니다. guild_id 0 은 DM 입니다.
+pub fn can_view_conversation(user: &AuthUser, guild_id: i64, root_user_id: Option<i64>) -> bool {
+    if user.is_owner || (guild_id != 0 && user.has_role(guild_id as u64, WebRole::GuildAdmin)) {
+        return true;
+    }
+    root_user_id == Some(user.user_id as i64) && (guild_id == 0 || user.has_role(guild_id as u64, WebRole::Member))
+}
+
+fn root_message_alias() -> Alias {
+    Alias::new("root_message")
+}
+
+// can_view_conversation 과 같은 조건을 SQL 로 겁니다.
+fn visibility_condition(user: &AuthUser) -> Condition {
+    if user.is_owner {
+        return Condition::all();
+    }
+    let ids = |min_role: WebRole| {
+        user.guilds
+            .iter()
+            .filter(|g| g.role >= min_role)
+            .map(|g| g.guild_id as i64)
+            .collect::<Vec<_>>()
+    };
+    let mut member_guilds = ids(WebRole::Member);
+    member_guilds.push(0);
+    Condition::any()
+        .add(tb_discord_ai_context::Column::GuildId.is_in(ids(WebRole::GuildAdmin)))
+        .add(
+            Condition::all()
+                .add(Expr::col((root_message_alias(), tb_ai_context::Column::UserId)).eq(user.user_id as i64))
+                .add(tb_discord_ai_context::Column::GuildId.is_in(member_guilds)),
+        )
+}
+
+// 시작 메시지를 root_message 로 붙이고, 사용자가 볼 수 있는 대화만 고릅니다.
+pub fn visible_contexts(user: &AuthUser) -> Select<tb_discord_ai_context::Entity> {
+    tb_discord_ai_context::Entity::find()
+        .join_as(
+            JoinType::LeftJoin,
+            Into::<sea_orm::RelationDef>::into(
+                tb_discord_ai_context::Entity::belongs_to(tb_ai_context::Entity)
+                    .from(tb_discord_ai_context::Column::RootMsg)
+                    .to(tb_ai_context::Column::Id),
+            ),
+            root_message_alias(),
+        )
+        .filter(visibility_condition(user))
+}
+
+fn to_message(message: tb_ai_context::Model, image: Option<tb_image_attach_file::Model>) -> ConversationMessage {
+    ConversationMessage {
+        id: message.id,
+        user_id: message.user_id,
+        by_bot: message.by_bot,
+        content: message.context,
+        created_at: message.created_at.to_rfc3339(),
+        image: image.map(|image| ConversationImage {
+            id: image.image_id,
+            url: image.file_src,
+            mime_type: image.mime_type,
+        }),
+    }
+}
+
+// 시작 메시지와 메시지 수를 붙여 요약으로 바꿉니다. 입력 순서를 지킵니다.
+async fn summarize_contexts(contexts: Vec<tb_discord_ai_context::Model>) -> Result<Vec<ConversationSummary>, String> {
+    if contexts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let roots = tb_ai_context::Entity::find()
+        .filter(tb_ai_context::Column::Id.is_in(contexts.iter().map(|c| c.root_msg)))
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|m| (m.id, m))
+        .collect::<HashMap<_, _>>();
+    let stats = tb_context_to_msg_id::Entity::find()
+        .select_only()
+        .column(tb_context_to_msg_id::Column::AiContext)
+        .column_as(Expr::col(tb_context_to_msg_id::Column::AiMsg).count(), "message_count")
+        .column_as(Expr::col((tb_ai_context::Entity, tb_ai_context::Column::CreatedAt)).max(), "last_message_at")
+        .join(JoinType::InnerJoin, tb_context_to_msg_id::Relation::TbAiContext.def())
+        .filter(tb_context_to_msg_id::Column::AiContext.is_in(contexts.iter().map(|c| c.id)))
+        .group_by(tb_context_to_msg_id::Column::AiContext)
+        .into_model::<ContextStats>()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|s| (s.ai_context, s))
+        .collect::<HashMap<_, _>>();
+
+    Ok(contexts
+        .into_iter()
+        .map(|context| {
+            let root = roots.get(&context.root_msg);
+            let stat = stats.get(&context.id);
+            ConversationSummary {
+                id: context.id,
+                guild_id: context.guild_id,
+                user_id: root.map(|r| r.user_id),
+                using_pro_model: context.using_pro_model,
+                parent_ids: context.parent_context,
+                message_count: stat.map(|s| s.message_count).unwrap_or(0),
+                preview: root.map(|r| preview_text(&r.context, MESSAGE_PREVIEW_CHARS)).unwrap_or_default(),
+                started_at: root.map(|r| r.created_at.to_rfc3339()),
+                last_message_at: stat.and_then(|s| s.last_message_at).map(|t| t.to_rfc3339()),
+            }
+        })
+        .collect())
+}
+
+// 최근 대화부터 돌려줍니다. (목록, 전체 수)
+pub async fn list_conversations(
+    user: &AuthUser,
+    filter: &ConversationFilter,
+    offset: u64,
+    limit: u64,
+) -> Result<(Vec<ConversationSummary>, u64), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let mut query = visible_contexts(user);
+    if let Some(user_id) = filter.user_id {
+        query = query.filter(Expr::col((root_message_alias(), tb_ai_context::Column::UserId)).eq(user_id as i64));
+    }
+    if let Some(guild_id) = filter.guild_id {
+        query = query.filter(tb_discord_ai_context::Column::GuildId.eq(guild_id as i64));
+    }
+    if let Some(text) = filter.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        query = query.filter(Expr::cust_with_values(
+            "EXISTS (SELECT 1 FROM tb_context_to_msg_id m JOIN tb_ai_context a ON a.id = m.ai_msg \
+             WHERE m.ai_context = tb_discord_ai_context.id AND a.context ILIKE $1 ESCAPE '\\')",
+            [format!("%{}%", escape_like(text))],
+        ));
+    }
+
+    let total = query.clone().count(db).await.map_err(|e| e.to_string())?;
+    let contexts = query
+        .order_by_desc(tb_discord_ai_context::Column::Id)
+        .offset(offset)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((summarize_contexts(contexts).await?, total))
+}
+
+// 볼 수 없는 대화는 없는 것처럼 None 을 돌려줍니다.
+pub async fn get_conversation_tree(user: &AuthUser, id: i64) -> Result<Option<ConversationTree>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let Some(context) = tb_discord_ai_context::Entity::find_by_id(id).one(db).await.map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let conversation = summarize_contexts(vec![context.clone()]).await?.remove(0);
+    if !can_view_conversation(user, conversation.guild_id, conversation.user_id) {
+        return Ok(None);
+    }
+
+    let messages = tb_ai_context::Entity::find()
+        .join(JoinType::InnerJoin, tb_ai_context::Relation::TbContextToMsgId.def())
+        .filter(tb_context_to_msg_id::Column::AiContext.eq(id))
+        .order_by_asc(tb_ai_context::Column::Id)
+        .find_also_related(tb_image_attach_file::Entity)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(message, image)| to_message(message, image))
+        .collect();
+
+    // 위아래 대화도 볼 수 있는 것만 돌려줍니다.
+    let mut ancestors = visible_contexts(user)
+        .filter(tb_discord_ai_context::Column::Id.is_in(context.parent_context.clone()))
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    ancestors.sort_by_key(|a| context.parent_context.iter().position(|id| *id == a.id));
+
+    // parent_context 의 마지막 값이 이 대화인 것이 바로 아래 대화입니다.
+    let children = visible_contexts(user)
+        .filter(Expr::cust_with_values("parent_context[array_length(parent_context, 1)] = $1", [id]))
+        .order_by_asc(tb_discord_ai_context::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(ConversationTree {
+        conversation,
+        ancestors: summarize_contexts(ancestors).await?,
+        children: summarize_contexts(children).await?,
+        messages,
+    }))
+}
@@ -0,0 +1,289 @@
+use chrono::Utc;
+use entity::{tb_debt_receipt, tb_debtor};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::Serialize;
+
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+pub const MAX_DEBTOR_NAME_CHARS: usize = 50;
+pub const MAX_MEMO_CHARS: usize = 200;
+
+// 영수증 한 건. amount 가 양수면 상대가 나에게 갚을 돈, 음수면 내가 상대에게 갚을 돈입니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DebtEntry {
+    pub id: i64,
+    pub debtor_id: i32,
+    pub debtor_name: String,
+    pub amount: i64,
+    pub memo: Option<String>,
+    pub created_at: String,
+    pub is_cleared: bool,
+    pub cleared_at: Option<String>,
+    // 영수증 사진으로 만든 기록이면 tb_image_attach_file 의 id
+    pub image_id: Option<i64>,
+}
+
+// 사람별 잔액. outstanding 은 아직 정산하지 않은 영수증의 합입니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DebtorBalance {
+    pub debtor_id: i32,
+    pub name: String,
+    pub outstanding: i64,
+    pub open_count: usize,
+    pub cleared_total: i64,
+    pub total_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DebtSummary {
+    pub balances: Vec<DebtorBalance>,
+    // 받을 돈 (양수 잔액의 합)
+    pub total_receivable: i64,
+    // 갚을 돈 (음수 잔액의 합, 양수로 표시)
+    pub total_payable: i64,
+}
+
+// 앞뒤 공백을 지우고 가운데 공백은 하나로 줄입니다.
+pub fn normalize_debtor_name(name: &str) -> Result<String, String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err("이름을 입력하세요.".to_string());
+    }
+    if name.chars().count() > MAX_DEBTOR_NAME_CHARS {
+        return Err(format!("이름은 {}자 이하여야 합니다.", MAX_DEBTOR_NAME_CHARS));
+    }
+    Ok(name)
+}
+
+fn normalize_memo(memo: Option<&str>) -> Option<String> {
+    memo.map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(|m| m.chars().take(MAX_MEMO_CHARS).collect())
+}
+
+// 12300 -> "12,300원"
+pub fn format_amount(amount: i64) -> String {
+    let digits = amount.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}{}원", if amount < 0 { "-" } else { "" }, grouped)
+}
+
+fn to_entry(receipt: &tb_debt_receipt::Model, debtor_name: &str) -> DebtEntry {
+    DebtEntry {
+        id: receipt.id,
+        debtor_id: receipt.debtor_id,
+        debtor_name: debtor_name.to_string(),
+        amount: receipt.amount,
+        memo: receipt.memo.clone(),
+        created_at: receipt.created_at.to_rfc3339(),
+        is_cleared: receipt.is_cleared,
+        cleared_at: receipt.cleared_at.map(|t| t.to_rfc3339()),
+        image_id: receipt.image_id,
+    }
+}
+
+// 잔액이 큰 사람부터, 같으면 이름순으로 정렬합니다.
+pub fn summarize_balances(debtors: &[tb_debtor::Model], receipts: &[tb_debt_receipt::Model]) -> DebtSummary {
+    let mut balances = debtors
+        .iter()
+        .map(|debtor| {
+            let own = receipts.iter().filter(|r| r.debtor_id == debtor.id);
+            let (open, cleared): (Vec<_>, Vec<_>) = own.partition(|r| !r.is_cleared);
+            DebtorBalance {
+                debtor_id: debtor.id,
+                name: debtor.name.clone(),
+                outstanding: open.iter().map(|r| r.amount).sum(),
+                open_count: open.len(),
+                cleared_total: cleared.iter().map(|r| r.amount).sum(),
+                total_count: open.len() + cleared.len(),
+            }
+        })
+        .collect::<Vec<_>>();
+    balances.sort_by(|a, b| b.outstanding.cmp(&a.outstanding).then_with(|| a.name.cmp(&b.name)));
+    DebtSummary {
+        total_receivable: balances.iter().map(|b| b.outstanding.max(0)).sum(),
+        total_payable: balances.iter().map(|b| (-b.outstanding).max(0)).sum(),
+        balances,
+    }
+}
+
+pub async fn find_debtor(owner_id: u64, name: &str) -> Result<Option<tb_debtor::Model>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let name = normalize_debtor_name(name)?;
+    tb_debtor::Entity::find()
+        .filter(tb_debtor::Column::OwnerId.eq(owner_id as i64))
+        .filter(tb_debtor::Column::Name.eq(name))
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 같은 이름이 있으면 그 사람을 돌려줍니다.
+pub async fn create_debtor(owner_id: u64, name: &str) -> Result<tb_debtor::Model, String> {
+    if let Some(debtor) = find_debtor(owner_id, name).await? {
+        return Ok(debtor);
+    }
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let debtor = tb_debtor::ActiveModel {
+        owner_id: sea_orm::Set(owner_id as i64),
+        name: sea_orm::Set(normalize_debtor_name(name)?),
+        created_at: sea_orm::Set(Utc::now().naive_utc()),
+        ..Default::default()
+    };
+    tb_debtor::Entity::insert(debtor)
+        .exec_with_returning(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn list_debtors(owner_id: u64) -> Result<Vec<tb_debtor::Model>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    tb_debtor::Entity::find()
+        .filter(tb_debtor::Column::OwnerId.eq(owner_id as i64))
+        .order_by_asc(tb_debtor::Column::Name)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 처음 보는 이름이면 사람을 먼저 만듭니다.
+pub async fn record_debt(owner_id: u64, name: &str, amount: i64, memo: Option<&str>) -> Result<DebtEntry, String> {
+    record_debt_with_image(owner_id, name, amount, memo, None).await
+}
+
+// 영수증 사진에서 만든 기록은 원본 이미지를 함께 남깁니다.
+pub async fn record_debt_with_image(
+    owner_id: u64,
+    name: &str,
+    amount: i64,
+    memo: Option<&str>,
+    image_id: Option<i64>,
+) -> Result<DebtEntry, String> {
+    if amount == 0 {
+        return Err("금액은 0 이 아니어야 합니다.".to_string());
+    }
+    let debtor = create_debtor(owner_id, name).await?;
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let receipt = tb_debt_receipt::ActiveModel {
+        debtor_id: sea_orm::Set(debtor.id),
+        amount: sea_orm::Set(amount),
+        memo: sea_orm::Set(normalize_memo(memo)),
+        created_at: sea_orm::Set(Utc::now().into()),
+        is_cleared: sea_orm::Set(false),
+        cleared_at: sea_orm::Set(None),
+        image_id: sea_orm::Set(image_id),
+        ..Default::default()
+    };
+    let receipt = tb_debt_receipt::Entity::insert(receipt)
+        .exec_with_returning(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(to_entry(&receipt, &debtor.name))
+}
+
+// 다른 사람의 영수증은 없는 것처럼 다룹니다.
+pub async fn clear_receipt(owner_id: u64, receipt_id: i64) -> Result<DebtEntry, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let found = tb_debt_receipt::Entity::find_by_id(receipt_id)
+        .find_also_related(tb_debtor::Entity)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some((receipt, Some(debtor))) = found.filter(|(_, d)| d.as_ref().is_some_and(|d| d.owner_id == owner_id as i64)) else {
+        return Err(format!("#{} 영수증을 찾을 수 없습니다.", receipt_id));
+    };
+    if receipt.is_cleared {
+        return Err(format!("#{} 영수증은 이미 정산했습니다.", receipt_id));
+    }
+    let mut active: tb_debt_receipt::ActiveModel = receipt.into();
+    active.is_cleared = sea_orm::Set(true);
+    active.cleared_at = sea_orm::Set(Some(Utc::now().into()));
+    let receipt = tb_debt_receipt::Entity::update(active)
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(to_entry(&receipt, &debtor.name))
+}
+
+// 한 사람의 남은 영수증을 모두 정산하고 (정산한 건수, 금액 합) 을 돌려줍니다.
+pub async fn clear_debtor(owner_id: u64, name: &str) -> Result<(tb_debtor::Model, usize, i64), String> {
+    let debtor = find_debtor(owner_id, name)
+        .await?
+        .ok_or(format!("{} 님의 기록이 없습니다.", name.trim()))?;
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let open = tb_debt_receipt::Entity::find()
+        .filter(tb_debt_receipt::Column::DebtorId.eq(debtor.id))
+        .filter(tb_debt_receipt::Column::IsCleared.eq(false))
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if open.is_empty() {
+        return Ok((debtor, 0, 0));
+    }
+    let ids = open.iter().map(|r| r.id).collect::<Vec<_>>();
+    tb_debt_receipt::Entity::update_many()
+        .col_expr(tb_debt_receipt::Column::IsCleared, Expr::value(true))
+        .col_expr(tb_debt_receipt::Column::ClearedAt, Expr::value(Utc::now()))
+        .filter(tb_debt_receipt::Column::Id.is_in(ids))
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((debtor, open.len(), open.iter().map(|r| r.amount).sum()))
+}
+
+pub async fn get_debt_summary(owner_id: u64) -> Result<DebtSummary, String> {
+    let debtors = list_debtors(owner_id).await?;
+    if debtors.is_empty() {
+        return Ok(summarize_balances(&[], &[]));
+    }
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let receipts = tb_debt_receipt::Entity::find()
+        .filter(tb_debt_receipt::Column::DebtorId.is_in(debtors.iter().map(|d| d.id)))
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(summarize_balances(&debtors, &receipts))
+}
+
+// 최근 영수증부터 돌려줍니다. name 이 없으면 모든 사람의 기록입니다.
+pub async fn get_debt_history(
+    owner_id: u64,
+    name: Option<&str>,
+    include_cleared: bool,
+    limit: u64,
+) -> Result<Vec<DebtEntry>, String> {
+    let debtors = match name {
+        Some(name) => find_debtor(owner_id, name).await?.into_iter().collect::<Vec<_>>(),
+        None => list_debtors(owner_id).await?,
+    };
+    if debtors.is_empty() {
+        return Ok(Vec::new());
+    }
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let mut query = tb_debt_receipt::Entity::find()
+        .filter(tb_debt_receipt::Column::DebtorId.is_in(debtors.iter().map(|d| d.id)));
+    if !include_cleared {
+        query = query.filter(tb_debt_receipt::Column::IsCleared.eq(false));
+    }
+    let receipts = query
+        .order_by_desc(tb_debt_receipt::Column::CreatedAt)
+        .order_by_desc(tb_debt_receipt::Column::Id)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(receipts
+        .iter()
+        .map(|receipt| {
+            let name = debtors.iter().find(|d| d.id == receipt.debtor_id).map(|d| d.name.as_str()).unwrap_or_default();
+            to_entry(receipt, name)
+        })
+        .collect())
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::model::cache::redis_client::REDIS_DRIVER;
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+// 의존성 확인 하나에 쓰는 최대 시간
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 샤드 번호 → 연결 단계 (connected, connecting, handshake, identifying, resuming, disconnected)
+static DISCORD_SHARD_STAGES: LazyLock<Mutex<BTreeMap<u32, String>>> = LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+pub fn set_discord_shard_stage(shard: u32, stage: &str) {
+    DISCORD_SHARD_STAGES.lock().unwrap().insert(shard, stage.to_lowercase());
+}
+
+pub fn discord_shard_stages() -> BTreeMap<u32, String> {
+    DISCORD_SHARD_STAGES.lock().unwrap().clone()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Failed,
+    // REDIS_URL 이 없는 것처럼 설정으로 꺼 둔 의존성
+    Disabled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DependencyCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    // DB/Redis 의 원본 오류가 들어 있어 응답에는 싣지 않고 로그로만 남깁니다.
+    #[serde(skip_serializing)]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checks: Vec<DependencyCheck>,
+}
+
+impl DependencyCheck {
+    fn from_result(name: &'static str, result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => DependencyCheck { name, status: CheckStatus::Ok, detail: None },
+            Err(e) => DependencyCheck { name, status: CheckStatus::Failed, detail: Some(e) },
+        }
+    }
+}
+
+// 꺼 둔 의존성은 준비 상태를 막지 않습니다.
+pub fn build_readiness(checks: Vec<DependencyCheck>) -> ReadinessReport {
+    ReadinessReport { ready: checks.iter().all(|c| c.status != CheckStatus::Failed), checks }
+}
+
+pub fn check_discord_stages(stages: &BTreeMap<u32, String>) -> Result<(), String> {
+    if stages.is_empty() {
+        return Err("gateway not connected yet".to_string());
+    }
+    let down = stages
+        .iter()
+        .filter(|(_, stage)| *stage != "connected")
+        .map(|(shard, stage)| format!("shard {}: {}", shard, stage))
+        .collect::<Vec<_>>();
+    if down.is_empty() { Ok(()) } else { Err(down.join(", ")) }
+}
+
+async fn check_database() -> Result<(), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    tokio::time::timeout(HEALTH_CHECK_TIMEOUT, db.ping())
+        .await
+        .map_err(|_| "timeout".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+async fn check_redis() -> Option<Result<(), String>> {
+    REDIS_DRIVER.as_ref()?;
+    let ping = tokio::task::spawn_blocking(|| -> Result<(), String> {
+        let driver = REDIS_DRIVER.as_ref().ok_or("Redis 를 사용할 수 없습니다.".to_string())?;
+        let mut conn = driver.get_pool().get_timeout(HEALTH_CHECK_TIMEOUT).map_err(|e| e.to_string())?;
+        redis::cmd("PING").query::<String>(&mut *conn).map(|_| ()).map_err(|e| e.to_string())
+    });
+    Some(
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, ping)
+            .await
+            .map_err(|_| "timeout".to_string())
+            .and_then(|joined| joined.map_err(|e| e.to_string()))
+            .and_then(|result| result),
+    )
+}
+
+pub async fn check_readiness() -> ReadinessReport {
+    let redis = match check_redis().await {
+        Some(result) => DependencyCheck::from_result("redis", result),
+        None => DependencyCheck { name: "redis", status: CheckStatus::Disabled, detail: None },
+    };
+    build_readiness(vec![
+        DependencyCheck::from_result("database", check_database().await),
+        redis,
+        DependencyCheck::from_result("discord", check_discord_stages(&discord_shard_stages())),
+    ])
+}
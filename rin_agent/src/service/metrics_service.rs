@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use entity::tb_alarm_model;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::libs::metrics::{MetricDef, MetricKind, MetricsRegistry};
+use crate::model::cache::redis_client::REDIS_DRIVER;
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::health_service::discord_shard_stages;
+use crate::service::usage_service::UsageTokens;
+use crate::service::voice_session_manager::VOICE_SESSION_MANAGER;
+
+static METRIC_DEFS: [MetricDef; 12] = [
+    MetricDef { name: "rin_discord_commands_total", kind: MetricKind::Counter, help: "Slash commands handled, by command and result." },
+    MetricDef { name: "rin_discord_command_duration_seconds", kind: MetricKind::Histogram, help: "Slash command handling time." },
+    MetricDef { name: "rin_gemini_requests_total", kind: MetricKind::Counter, help: "Gemini generateContent calls, by model and result." },
+    MetricDef { name: "rin_gemini_request_duration_seconds", kind: MetricKind::Histogram, help: "Gemini generateContent call latency." },
+    MetricDef { name: "rin_gemini_tokens_total", kind: MetricKind::Counter, help: "Gemini tokens used, by model and kind." },
+    MetricDef { name: "rin_tool_calls_total", kind: MetricKind::Counter, help: "Gemini tool calls, by source, tool and result." },
+    MetricDef { name: "rin_alarm_queue_depth", kind: MetricKind::Gauge, help: "Alarms scheduled in the future." },
+    MetricDef { name: "rin_voice_sessions", kind: MetricKind::Gauge, help: "Active voice sessions." },
+    MetricDef { name: "rin_db_pool_connections", kind: MetricKind::Gauge, help: "Database pool connections, by state." },
+    MetricDef { name: "rin_redis_pool_connections", kind: MetricKind::Gauge, help: "Redis pool connections, by state." },
+    MetricDef { name: "rin_discord_gateway_connected", kind: MetricKind::Gauge, help: "1 if every Discord shard is connected." },
+    MetricDef { name: "rin_discord_shard_stage", kind: MetricKind::Gauge, help: "Discord shard connection stage (value is always 1)." },
+];
+
+pub static METRICS: MetricsRegistry = MetricsRegistry::new(&METRIC_DEFS);
+
+fn result_label(ok: bool) -> &'static str {
+    if ok { "ok" } else { "error" }
+}
+
+pub fn record_command(command: &str, ok: bool, elapsed: Duration) {
+    METRICS.inc_counter("rin_discord_commands_total", &[("command", command), ("result", result_label(ok))], 1.0);
+    METRICS.observe_duration("rin_discord_command_duration_seconds", &[("command", command)], elapsed);
+}
+
+pub fn record_gemini_request(model: &str, ok: bool, elapsed: Duration) {
+    METRICS.inc_counter("rin_gemini_requests_total", &[("model", model), ("result", result_label(ok))], 1.0);
+    METRICS.observe_duration("rin_gemini_request_duration_seconds", &[("model", model)], elapsed);
+}
+
+pub fn record_gemini_tokens(model: &str, tokens: &UsageTokens) {
+    for (kind, count) in [("prompt", tokens.prompt), ("cached", tokens.cached), ("thoughts", tokens.thoughts), ("output", tokens.output)] {
+        METRICS.inc_counter("rin_gemini_tokens_total", &[("model", model), ("kind", kind)], count as f64);
+    }
+}
+
+// source 는 chat(텍스트 대화) 또는 live(음성 세션), result 는 ok / error / not_found 입니다.
+pub fn record_tool_call(source: &str, tool: &str, result: &str) {
+    METRICS.inc_counter("rin_tool_calls_total", &[("source", source), ("tool", tool), ("result", result)], 1.0);
+}
+
+// 모델이 지어낸 함수 이름이 라벨로 쌓이지 않도록 없는 도구는 한 라벨로 묶습니다.
+pub fn record_unknown_tool_call(source: &str) {
+    record_tool_call(source, "unknown", "not_found");
+}
+
+// 요청 때마다 바뀌지 않는 값들은 /metrics 를 읽을 때 채웁니다.
+pub async fn collect_runtime_metrics() {
+    if let Some(db) = DB_CONNECTION_POOL.get() {
+        let pool = db.get_postgres_connection_pool();
+        let idle = pool.num_idle() as f64;
+        METRICS.set_gauge("rin_db_pool_connections", &[("state", "idle")], idle);
+        METRICS.set_gauge("rin_db_pool_connections", &[("state", "active")], pool.size() as f64 - idle);
+
+        match tb_alarm_model::Entity::find().filter(tb_alarm_model::Column::Time.gt(Utc::now())).count(db).await {
+            Ok(count) => METRICS.set_gauge("rin_alarm_queue_depth", &[], count as f64),
+            Err(e) => LOGGER.log(LogLevel::Warning, &format!("Metrics > 알람 수를 세지 못했습니다: {}", e)),
+        }
+    }
+
+    if let Some(driver) = REDIS_DRIVER.as_ref() {
+        let state = driver.get_pool().state();
+        METRICS.set_gauge("rin_redis_pool_connections", &[("state", "idle")], state.idle_connections as f64);
+        METRICS.set_gauge(
+            "rin_redis_pool_connections",
+            &[("state", "active")],
+            state.connections.saturating_sub(state.idle_connections) as f64,
+        );
+    }
+
+    METRICS.set_gauge("rin_voice_sessions", &[], VOICE_SESSION_MANAGER.lock().await.len() as f64);
+
+    let stages = discord_shard_stages();
+    METRICS.clear_gauge("rin_discord_shard_stage");
+    for (shard, stage) in &stages {
+        METRICS.set_gauge("rin_discord_shard_stage", &[("shard", &shard.to_string()), ("stage", stage)], 1.0);
+    }
+    let connected = !stages.is_empty() && stages.values().all(|stage| stage == "connected");
+    METRICS.set_gauge("rin_discord_gateway_connected", &[], if connected { 1.0 } else { 0.0 });
+}
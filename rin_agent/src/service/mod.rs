@@ -1,4 +1,19 @@
 pub mod alarm_process;
 pub mod discord_error_msg;
 pub mod voice_session_manager;
-pub mod discord_message_service;
\ No newline at end of file
+pub mod discord_message_service;
+pub mod transcribe_service;
+pub mod usage_service;
+pub mod quota_service;
+pub mod structured_output_service;
+pub mod debt_service;
+pub mod receipt_split_service;
+pub mod web_auth_service;
+pub mod conversation_service;
+pub mod alarm_service;
+pub mod openai_compat_service;
+pub mod metrics_service;
+pub mod health_service;
+pub mod mqtt_service;
+pub mod shutdown_service;
+pub mod agent_command_service;
\ No newline at end of file
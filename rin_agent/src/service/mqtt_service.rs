@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, OnceLock};
+use std::time::{Duration, Instant};
+
+use contract::config::CommonConfig;
+use contract::{topics, RinAgentAlert, RinAgentCommand, RinAgentStatus};
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Packet, Publish, QoS};
+
+use crate::libs::logger::{LogLevel, LOGGER};
+
+pub type CommandFuture = Pin<Box<dyn Future<Output = Result<String, String>> + Send>>;
+// rin_agent/command 로 들어온 명령을 실행하고 결과 메시지를 돌려줍니다.
+pub type CommandHandler = Arc<dyn Fn(RinAgentCommand) -> CommandFuture + Send + Sync>;
+
+static MQTT_CLIENT: OnceLock<AsyncClient> = OnceLock::new();
+
+pub static STARTED_AT: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+pub fn now_timestamp() -> u64 {
+    chrono::Utc::now().timestamp() as u64
+}
+
+pub fn uptime_secs() -> u64 {
+    STARTED_AT.elapsed().as_secs()
+}
+
+pub fn connect_mqtt(config: &CommonConfig, client_id: &str) -> (AsyncClient, EventLoop) {
+    let mut options = MqttOptions::new(client_id, &config.mqtt_host, config.mqtt_port);
+    options.set_keep_alive(Duration::from_secs(30));
+    AsyncClient::new(options, 64)
+}
+
+// 전역 클라이언트를 만듭니다. 두 번째 호출부터는 None 입니다.
+pub fn init_mqtt(config: &CommonConfig, client_id: &str) -> Option<(AsyncClient, EventLoop)> {
+    let (client, event_loop) = connect_mqtt(config, client_id);
+    MQTT_CLIENT.set(client.clone()).ok()?;
+    Some((client, event_loop))
+}
+
+// 브로커가 없거나 끊겨도 죽지 않고 다시 연결을 시도합니다. 연결될 때마다 명령 토픽을 다시 구독합니다.
+pub async fn run_mqtt_event_loop(client: AsyncClient, mut event_loop: EventLoop, handler: CommandHandler) {
+    let mut connected = false;
+    loop {
+        match event_loop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                LOGGER.log(LogLevel::Info, "MQTT > 연결되었습니다.");
+                connected = true;
+                // poll 안에서 기다리면 요청 큐가 찼을 때 멈추므로 try_ 로 넣습니다.
+                if let Err(e) = client.try_subscribe(topics::RIN_AGENT_COMMAND, QoS::AtLeastOnce) {
+                    LOGGER.log(LogLevel::Error, &format!("MQTT > 명령 토픽을 구독하지 못했습니다: {}", e));
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                handle_publish(&client, &handler, publish);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                if connected {
+                    LOGGER.log(LogLevel::Warning, &format!("MQTT > 연결이 끊겼습니다: {}", e));
+                    connected = false;
+                }
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+fn handle_publish(client: &AsyncClient, handler: &CommandHandler, publish: Publish) {
+    if publish.topic != topics::RIN_AGENT_COMMAND {
+        return;
+    }
+    let command = match serde_json::from_slice::<RinAgentCommand>(&publish.payload) {
+        Ok(command) => command,
+        Err(e) => {
+            let message = format!("잘못된 명령입니다: {}", e);
+            LOGGER.log(LogLevel::Warning, &format!("MQTT > {}", message));
+            publish_alert_to(client, &RinAgentAlert::new(contract::LogLevel::Error, message));
+            return;
+        }
+    };
+    LOGGER.log(LogLevel::Info, &format!("MQTT > 명령 수신: {:?}", command));
+    // 재시작처럼 오래 걸리는 명령이 이벤트 루프를 막지 않도록 따로 실행합니다.
+    let client = client.clone();
+    let future = handler(command.clone());
+    tokio::spawn(async move {
+        let alert = match future.await {
+            Ok(message) => RinAgentAlert::new(contract::LogLevel::Info, format!("{:?}: {}", command, message)),
+            Err(e) => {
+                LOGGER.log(LogLevel::Warning, &format!("MQTT > 명령 실패 {:?}: {}", command, e));
+                RinAgentAlert::new(contract::LogLevel::Error, format!("{:?}: {}", command, e))
+            }
+        };
+        publish_alert_to(&client, &alert);
+    });
+}
+
+// 보내지 못해도 호출한 쪽을 막지 않도록 큐에만 넣습니다.
+pub fn publish_json_to<T: serde::Serialize>(client: &AsyncClient, topic: &str, payload: &T) -> Result<(), String> {
+    let payload = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    client.try_publish(topic, QoS::AtLeastOnce, false, payload).map_err(|e| e.to_string())
+}
+
+pub fn publish_status_to(client: &AsyncClient, status: &RinAgentStatus) {
+    if let Err(e) = publish_json_to(client, topics::RIN_AGENT_STATUS, status) {
+        LOGGER.log(LogLevel::Debug, &format!("MQTT > 상태를 보내지 못했습니다: {}", e));
+    }
+}
+
+pub fn publish_alert_to(client: &AsyncClient, alert: &RinAgentAlert) {
+    if let Err(e) = publish_json_to(client, topics::RIN_AGENT_ALERT, alert) {
+        LOGGER.log(LogLevel::Debug, &format!("MQTT > 알림을 보내지 못했습니다: {}", e));
+    }
+}
+
+pub fn publish_status(status: &RinAgentStatus) {
+    if let Some(client) = MQTT_CLIENT.get() {
+        publish_status_to(client, status);
+    }
+}
+
+pub fn publish_alert(level: contract::LogLevel, message: impl Into<String>) {
+    if let Some(client) = MQTT_CLIENT.get() {
+        publish_alert_to(client, &RinAgentAlert::new(level, message));
+    }
+}
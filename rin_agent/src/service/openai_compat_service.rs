@@ -0,0 +1,319 @@
+use base64::Engine;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use serenity::all::{ChannelId, UserId};
+use tokio::sync::mpsc;
+
+use crate::gemini::gemini_client::{stream_query_to_gemini, GeminiClient, GeminiClientTrait};
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::types::{DiscordUserInfo, GeminiChatChunk, GeminiImageInputType, GeminiResponse, GeminiStreamEvent};
+use crate::libs::shutdown::IN_FLIGHT_WORK;
+use crate::service::web_auth_service::AuthUser;
+use crate::setting::gemini_setting::{get_begin_query, GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO};
+
+// /v1/models 에 보이는 이름. Gemini 모델 이름을 그대로 보내도 됩니다.
+pub const OPENAI_MODEL_FLASH: &str = "canarin";
+pub const OPENAI_MODEL_PRO: &str = "canarin-pro";
+pub const OPENAI_MODEL_OWNER: &str = "canarin";
+// 스트림 이벤트를 쌓아둘 수 있는 개수. 클라이언트가 느리면 Gemini 쪽 읽기도 멈춥니다.
+const STREAM_EVENT_BUFFER: usize = 32;
+
+// OpenAI 요청 형식. 여기에 없는 필드(temperature 등)는 무시합니다.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub stream: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<ChatContent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ChatContent {
+    Text(String),
+    Parts(Vec<ChatContentPart>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ChatImageUrl },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatImageUrl {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChatCompletion {
+    pub id: String,
+    pub object: &'static str,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChatChoice {
+    pub index: u32,
+    pub message: ChatResponseMessage,
+    pub finish_reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChatResponseMessage {
+    pub role: &'static str,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: &'static str,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<ChatChunkChoice>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChatChunkChoice {
+    pub index: u32,
+    pub delta: ChatDelta,
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChatDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelInfo {
+    pub id: &'static str,
+    pub object: &'static str,
+    pub created: i64,
+    pub owned_by: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelList {
+    pub object: &'static str,
+    pub data: Vec<ModelInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenAiError {
+    pub error: OpenAiErrorBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenAiErrorBody {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub error_type: &'static str,
+}
+
+impl OpenAiError {
+    pub fn new(error_type: &'static str, message: impl Into<String>) -> Self {
+        OpenAiError { error: OpenAiErrorBody { message: message.into(), error_type } }
+    }
+}
+
+pub fn list_models() -> ModelList {
+    let model = |id| ModelInfo { id, object: "model", created: 0, owned_by: OPENAI_MODEL_OWNER };
+    ModelList { object: "list", data: vec![model(OPENAI_MODEL_FLASH), model(OPENAI_MODEL_PRO)] }
+}
+
+// 요청한 모델이 Pro 인지. 모르는 모델이면 None 입니다.
+pub fn resolve_model(model: &str) -> Option<bool> {
+    let model = model.strip_prefix("models/").unwrap_or(model);
+    match model {
+        OPENAI_MODEL_FLASH | GEMINI_MODEL_FLASH => Some(false),
+        OPENAI_MODEL_PRO | GEMINI_MODEL_PRO => Some(true),
+        _ => None,
+    }
+}
+
+// data:image/png;base64,.... 만 받습니다. 외부 URL 은 서버가 대신 받아오지 않습니다.
+pub fn parse_data_url(url: &str) -> Result<GeminiImageInputType, String> {
+    let rest = url.strip_prefix("data:").ok_or("image_url 은 data: URL 이어야 합니다.".to_string())?;
+    let (meta, data) = rest.split_once(',').ok_or("잘못된 data URL 입니다.".to_string())?;
+    let mime_type = meta.strip_suffix(";base64").ok_or("data URL 은 base64 로 인코딩되어야 합니다.".to_string())?;
+    if !mime_type.starts_with("image/") {
+        return Err(format!("이미지가 아닙니다: {}", mime_type));
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|_| "base64 를 읽을 수 없습니다.".to_string())?;
+    Ok(GeminiImageInputType { base64_image: Some(data.to_string()), file_url: None, mime_type: mime_type.to_string() })
+}
+
+// OpenAI 메시지를 GeminiChatChunk 로 바꿉니다. 이미지가 여러 장이면 장마다 조각을 나눕니다.
+// system 메시지는 페르소나를 바꾸지 않도록 사용자 발화로 넣고, tool 메시지는 버립니다. (도구는 서버가 직접 실행합니다)
+pub fn messages_to_chunks(messages: &[ChatMessage], user_id: u64) -> Result<Vec<GeminiChatChunk>, String> {
+    let timestamp = Utc::now().to_string();
+    let mut chunks = Vec::new();
+    for message in messages {
+        let is_bot = match message.role.as_str() {
+            "user" | "system" | "developer" => false,
+            "assistant" => true,
+            "tool" | "function" => continue,
+            role => return Err(format!("알 수 없는 role 입니다: {}", role)),
+        };
+        let (text, images) = match &message.content {
+            None => (String::new(), Vec::new()),
+            Some(ChatContent::Text(text)) => (text.clone(), Vec::new()),
+            Some(ChatContent::Parts(parts)) => {
+                let mut texts = Vec::new();
+                let mut images = Vec::new();
+                for part in parts {
+                    match part {
+                        ChatContentPart::Text { text } => texts.push(text.as_str()),
+                        ChatContentPart::ImageUrl { image_url } => images.push(parse_data_url(&image_url.url)?),
+                        ChatContentPart::Unsupported => {}
+                    }
+                }
+                (texts.join("\n"), images)
+            }
+        };
+        let text = if message.role == "user" || is_bot { text } else { format!("[{}] {}", message.role, text) };
+        if text.trim().is_empty() && images.is_empty() {
+            continue;
+        }
+        let mut images = images.into_iter();
+        let chunk = |query: String, image: Option<GeminiImageInputType>| GeminiChatChunk {
+            query,
+            image,
+            is_bot,
+            timestamp: timestamp.clone(),
+            user_id: Some(user_id.to_string()),
+            guild_id: None,
+            channel_id: None,
+        };
+        chunks.push(chunk(text, images.next()));
+        chunks.extend(images.map(|image| chunk(String::new(), Some(image))));
+    }
+    if !chunks.last().is_some_and(|c| !c.is_bot) {
+        return Err("마지막 메시지는 user 메시지여야 합니다.".to_string());
+    }
+    Ok(chunks)
+}
+
+// 답장이 비어 있으면 도구가 사용자에게 보여주려던 문구를 대신 씁니다.
+pub fn response_text(response: &GeminiResponse) -> String {
+    let text = response.discord_msg.trim();
+    if !text.is_empty() {
+        return text.to_string();
+    }
+    response
+        .command_result
+        .iter()
+        .filter_map(|r| r.as_ref().ok())
+        .map(|r| r.show_user.clone().unwrap_or_else(|| r.result_message.clone()))
+        .filter(|s| !s.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn map_finish_reason(reason: &str) -> &'static str {
+    match reason {
+        "MAX_TOKENS" => "length",
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY" => "content_filter",
+        _ => "stop",
+    }
+}
+
+pub fn new_completion_id() -> String {
+    format!("chatcmpl-{:016x}{:016x}", rand::random::<u64>(), rand::random::<u64>())
+}
+
+pub fn build_completion(id: &str, model: &str, created: i64, response: &GeminiResponse) -> ChatCompletion {
+    ChatCompletion {
+        id: id.to_string(),
+        object: "chat.completion",
+        created,
+        model: model.to_string(),
+        choices: vec![ChatChoice {
+            index: 0,
+            message: ChatResponseMessage { role: "assistant", content: response_text(response) },
+            finish_reason: map_finish_reason(&response.finish_reason).to_string(),
+        }],
+    }
+}
+
+pub fn build_chunk(id: &str, model: &str, created: i64, delta: ChatDelta, finish_reason: Option<&str>) -> ChatCompletionChunk {
+    ChatCompletionChunk {
+        id: id.to_string(),
+        object: "chat.completion.chunk",
+        created,
+        model: model.to_string(),
+        choices: vec![ChatChunkChoice { index: 0, delta, finish_reason: finish_reason.map(str::to_string) }],
+    }
+}
+
+// 디스코드와 같은 페르소나와 도구로 답합니다. 알람처럼 채널이 필요한 도구는 유저의 DM 으로 보냅니다.
+// DM 을 열 수 없으면 그런 도구는 실패로 돌아갑니다.
+pub async fn run_chat_completion(
+    user: &AuthUser,
+    chunks: Vec<GeminiChatChunk>,
+    use_pro: bool,
+    dm_channel_id: Option<u64>,
+) -> Result<GeminiResponse, GeminiError> {
+    let begin_query = get_begin_query("ko".to_string(), user.user_id.to_string(), None, None);
+    let user_info = dm_user_info(user, dm_channel_id);
+    let _in_flight = IN_FLIGHT_WORK.begin();
+    GeminiClient::new()
+        .send_query_to_gemini(chunks, &begin_query, use_pro, None, None, user_info, 0)
+        .await
+}
+
+// run_chat_completion 의 스트리밍판. Gemini 가 보내는 대로 조각을 넘기고, 마지막은 Done 이나 Failed 입니다.
+// 받는 쪽을 버리면 다음 조각을 보낼 때 생성을 멈춥니다.
+pub fn stream_chat_completion(
+    user: &AuthUser,
+    chunks: Vec<GeminiChatChunk>,
+    use_pro: bool,
+    dm_channel_id: Option<u64>,
+) -> mpsc::Receiver<GeminiStreamEvent> {
+    let (tx, rx) = mpsc::channel(STREAM_EVENT_BUFFER);
+    let begin_query = get_begin_query("ko".to_string(), user.user_id.to_string(), None, None);
+    let user_info = dm_user_info(user, dm_channel_id);
+    let in_flight = IN_FLIGHT_WORK.begin();
+    tokio::spawn(async move {
+        let _in_flight = in_flight;
+        let rest = match GeminiRestClient::from_env() {
+            Ok(rest) => rest,
+            Err(e) => {
+                let _ = tx.send(GeminiStreamEvent::Failed(GeminiError::from(e))).await;
+                return;
+            }
+        };
+        stream_query_to_gemini(&rest, chunks, &begin_query, use_pro, user_info, tx).await;
+    });
+    rx
+}
+
+fn dm_user_info(user: &AuthUser, dm_channel_id: Option<u64>) -> Option<DiscordUserInfo> {
+    dm_channel_id.map(|channel_id| DiscordUserInfo {
+        user_id: UserId::new(user.user_id),
+        username: user.user_name.clone(),
+        channel_id: ChannelId::new(channel_id),
+        guild_id: None,
+        context_id: None,
+    })
+}
@@ -0,0 +1,344 @@
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+
+use crate::gemini::types::GeminiUsageContext;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::cache::redis_client::REDIS_DRIVER;
+use crate::setting::gemini_setting::MANAGER_ID;
+use crate::setting::quota_setting::{QuotaConfig, QuotaLimits, QUOTA_CONFIG};
+
+const KEY_PREFIX: &str = "rin:quota";
+// 일일 카운터는 날짜가 바뀐 뒤에도 조회할 수 있도록 이틀 동안 남겨 둡니다.
+const DAILY_KEY_TTL_SECS: i64 = 60 * 60 * 48;
+const COST_SCALE: f64 = 1_000_000.0;
+
+// 모든 범위를 먼저 검사하고, 전부 통과했을 때만 버킷을 한 칸씩 씁니다.
+// burst 나 refill_per_ms 가 0 인 범위는 버킷을 건너뛰고 남은 요청을 -1 로 돌려줍니다.
+// KEYS: 범위마다 [bucket, daily]
+// ARGV: now_ms, 범위마다 [burst, refill_per_ms, daily_tokens, daily_cost_micro]
+// 반환: {code, 실패한 범위 index(1부터), 대기 ms, 범위마다 [남은 요청, 사용 토큰, 사용 비용(micro)]}
+const CHECK_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local scopes = #KEYS / 2
+local states = {}
+for i = 1, scopes do
+    local base = 1 + (i - 1) * 4
+    local burst = tonumber(ARGV[base + 1])
+    local rate = tonumber(ARGV[base + 2])
+    local token_limit = tonumber(ARGV[base + 3])
+    local cost_limit = tonumber(ARGV[base + 4])
+    local limited = burst > 0 and rate > 0
+    local tokens = -1
+    if limited then
+        local bucket = redis.call('HMGET', KEYS[i * 2 - 1], 'tokens', 'ts')
+        tokens = tonumber(bucket[1]) or burst
+        local ts = tonumber(bucket[2]) or now
+        tokens = math.min(burst, tokens + math.max(0, now - ts) * rate)
+    end
+    local daily = redis.call('HMGET', KEYS[i * 2], 'tokens', 'cost')
+    local used_tokens = tonumber(daily[1]) or 0
+    local used_cost = tonumber(daily[2]) or 0
+    states[i] = {tokens, used_tokens, used_cost, burst, rate, limited}
+    local code = 0
+    local wait = 0
+    if token_limit > 0 and used_tokens >= token_limit then
+        code = 2
+    elseif cost_limit > 0 and used_cost >= cost_limit then
+        code = 3
+    elseif limited and tokens < 1 then
+        code = 1
+        wait = math.ceil((1 - tokens) / rate)
+    end
+    if code ~= 0 then
+        local result = {code, i, wait}
+        for j = 1, i do
+            table.insert(result, math.floor(states[j][1]))
+            table.insert(result, states[j][2])
+            table.insert(result, states[j][3])
+        end
+        return result
+    end
+end
+local result = {0, 0, 0}
+for i = 1, scopes do
+    local s = states[i]
+    local left = -1
+    if s[6] then
+        left = s[1] - 1
+        redis.call('HSET', KEYS[i * 2 - 1], 'tokens', tostring(left), 'ts', now)
+        redis.call('PEXPIRE', KEYS[i * 2 - 1], math.ceil(s[4] / s[5]) + 60000)
+    end
+    table.insert(result, math.floor(left))
+    table.insert(result, s[2])
+    table.insert(result, s[3])
+end
+return result
+"#;
+
+static CHECK: LazyLock<redis::Script> = LazyLock::new(|| redis::Script::new(CHECK_SCRIPT));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaScope {
+    User,
+    Guild,
+}
+
+impl QuotaScope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QuotaScope::User => "user",
+            QuotaScope::Guild => "guild",
+        }
+    }
+}
+
+// 할당량을 검사할 요청자. role_ids 는 길드 안에서의 역할입니다.
+#[derive(Debug, Clone, Default)]
+pub struct QuotaSubject {
+    pub user_id: u64,
+    pub guild_id: Option<u64>,
+    pub role_ids: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaDeniedReason {
+    // Pro 모델 사용 권한 없음
+    ProModelNotAllowed,
+    // 토큰 버킷이 비어 있음
+    RateLimited { retry_after_ms: i64 },
+    DailyTokensExceeded,
+    DailyCostExceeded,
+}
+
+// 범위 하나의 현재 상태
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaUsage {
+    pub scope: QuotaScope,
+    pub remaining_requests: i64,
+    pub used_tokens: i64,
+    pub used_cost_usd: f64,
+    pub limits: QuotaLimits,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaDenied {
+    pub reason: QuotaDeniedReason,
+    // 막힌 범위. Pro 권한 문제일 때는 None
+    pub usage: Option<QuotaUsage>,
+    pub reset_at: DateTime<Utc>,
+}
+
+// 다음 UTC 자정
+pub fn next_daily_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now.date_naive() + Duration::days(1)).and_time(NaiveTime::MIN).and_utc()
+}
+
+fn bucket_key(scope: QuotaScope, id: u64) -> String {
+    format!("{}:bucket:{}:{}", KEY_PREFIX, scope.as_str(), id)
+}
+
+pub fn daily_key(scope: QuotaScope, id: u64, now: DateTime<Utc>) -> String {
+    format!("{}:daily:{}:{}:{}", KEY_PREFIX, scope.as_str(), id, now.format("%Y%m%d"))
+}
+
+// 역할에 맞는 사용자 제한을 고릅니다. 설정된 역할이 여러 개면 일일 비용 한도가 큰 쪽(0 은 무제한)이 이깁니다.
+pub fn resolve_user_limits(config: &QuotaConfig, role_ids: &[u64]) -> QuotaLimits {
+    let generosity = |limits: &QuotaLimits| {
+        if limits.daily_cost_usd <= 0.0 { f64::INFINITY } else { limits.daily_cost_usd }
+    };
+    role_ids
+        .iter()
+        .filter_map(|role| config.roles.get(role))
+        .max_by(|a, b| generosity(a).total_cmp(&generosity(b)))
+        .cloned()
+        .unwrap_or_else(|| config.user.clone())
+}
+
+pub fn can_use_pro(config: &QuotaConfig, subject: &QuotaSubject) -> bool {
+    config.pro_roles.is_empty()
+        || subject.user_id as i64 == *MANAGER_ID
+        || subject.role_ids.iter().any(|role| config.pro_roles.contains(role))
+}
+
+// thinking_bought 를 사용자 제한 안으로 줄입니다.
+pub fn clamp_thinking_budget(limits: &QuotaLimits, requested: Option<i32>) -> Option<i32> {
+    match (requested, limits.max_thinking_budget) {
+        (Some(requested), Some(max)) => Some(requested.min(max)),
+        (requested, _) => requested,
+    }
+}
+
+fn scopes_for(config: &QuotaConfig, subject: &QuotaSubject) -> Vec<(QuotaScope, u64, QuotaLimits)> {
+    let mut scopes = vec![(QuotaScope::User, subject.user_id, resolve_user_limits(config, &subject.role_ids))];
+    if let Some(guild_id) = subject.guild_id {
+        scopes.push((QuotaScope::Guild, guild_id, config.guild.clone()));
+    }
+    scopes
+}
+
+// CHECK_SCRIPT 의 반환값을 해석합니다.
+pub fn parse_check_result(
+    raw: &[i64],
+    scopes: &[(QuotaScope, QuotaLimits)],
+    now: DateTime<Utc>,
+) -> Result<Vec<QuotaUsage>, QuotaDenied> {
+    let usages = raw
+        .get(3..)
+        .unwrap_or_default()
+        .chunks(3)
+        .zip(scopes.iter())
+        .filter(|(chunk, _)| chunk.len() == 3)
+        .map(|(chunk, (scope, limits))| QuotaUsage {
+            scope: *scope,
+            remaining_requests: chunk[0].max(0),
+            used_tokens: chunk[1],
+            used_cost_usd: chunk[2] as f64 / COST_SCALE,
+            limits: limits.clone(),
+        })
+        .collect::<Vec<_>>();
+    let code = raw.first().copied().unwrap_or(0);
+    if code == 0 {
+        return Ok(usages);
+    }
+
+    let failed = raw.get(1).copied().unwrap_or(1).max(1) as usize - 1;
+    let retry_after_ms = raw.get(2).copied().unwrap_or(0);
+    let (reason, reset_at) = match code {
+        1 => (
+            QuotaDeniedReason::RateLimited { retry_after_ms },
+            if retry_after_ms >= 0 { now + Duration::milliseconds(retry_after_ms) } else { next_daily_reset(now) },
+        ),
+        2 => (QuotaDeniedReason::DailyTokensExceeded, next_daily_reset(now)),
+        _ => (QuotaDeniedReason::DailyCostExceeded, next_daily_reset(now)),
+    };
+    Err(QuotaDenied {
+        reason,
+        usage: usages.get(failed).cloned(),
+        reset_at,
+    })
+}
+
+// 요청 한 번을 할당량에서 차감합니다. Redis 를 쓸 수 없으면 막지 않고 통과시킵니다.
+pub async fn check_quota(subject: &QuotaSubject, use_pro: bool) -> Result<Vec<QuotaUsage>, QuotaDenied> {
+    let config: &QuotaConfig = &QUOTA_CONFIG;
+    let now = Utc::now();
+    if use_pro && !can_use_pro(config, subject) {
+        return Err(QuotaDenied {
+            reason: QuotaDeniedReason::ProModelNotAllowed,
+            usage: None,
+            reset_at: now,
+        });
+    }
+    if subject.user_id as i64 == *MANAGER_ID {
+        return Ok(Vec::new());
+    }
+
+    let scopes = scopes_for(config, subject);
+    let scope_limits = scopes.iter().map(|(scope, _, limits)| (*scope, limits.clone())).collect::<Vec<_>>();
+    let raw = tokio::task::spawn_blocking(move || -> Result<Vec<i64>, String> {
+        let driver = REDIS_DRIVER.as_ref().ok_or("Redis 를 사용할 수 없습니다.".to_string())?;
+        let mut conn = driver.get_pool().get().map_err(|e| e.to_string())?;
+        let mut invocation = CHECK.prepare_invoke();
+        for (scope, id, _) in &scopes {
+            invocation.key(bucket_key(*scope, *id)).key(daily_key(*scope, *id, now));
+        }
+        invocation.arg(now.timestamp_millis());
+        for (_, _, limits) in &scopes {
+            let (burst, refill_per_ms) = if limits.limits_requests() {
+                (limits.burst, limits.refill_per_minute / 60_000.0)
+            } else {
+                (0, 0.0)
+            };
+            invocation
+                .arg(burst)
+                .arg(refill_per_ms)
+                .arg(limits.daily_tokens)
+                .arg((limits.daily_cost_usd * COST_SCALE).round() as i64);
+        }
+        invocation.invoke::<Vec<i64>>(&mut *conn).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    match raw {
+        Ok(raw) => parse_check_result(&raw, &scope_limits, now),
+        Err(e) => {
+            LOGGER.log(LogLevel::Warning, &format!("Quota > 할당량을 확인하지 못해 통과시킵니다: {}", e));
+            Ok(Vec::new())
+        }
+    }
+}
+
+// 응답을 받은 뒤 실제 토큰 수와 비용을 일일 카운터에 더합니다.
+pub async fn add_quota_usage(context: &GeminiUsageContext, total_tokens: i64, cost_usd: f64) -> Result<(), String> {
+    let now = Utc::now();
+    let mut keys = Vec::new();
+    if let Some(user_id) = context.user_id {
+        keys.push(daily_key(QuotaScope::User, user_id, now));
+    }
+    if let Some(guild_id) = context.guild_id {
+        keys.push(daily_key(QuotaScope::Guild, guild_id, now));
+    }
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let cost_micro = (cost_usd * COST_SCALE).round() as i64;
+    tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let Some(driver) = REDIS_DRIVER.as_ref() else {
+            return Ok(());
+        };
+        let mut conn = driver.get_pool().get().map_err(|e| e.to_string())?;
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for key in &keys {
+            pipe.hincr(key, "tokens", total_tokens).ignore()
+                .hincr(key, "cost", cost_micro).ignore()
+                .expire(key, DAILY_KEY_TTL_SECS).ignore();
+        }
+        pipe.query::<()>(&mut *conn).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn format_limit(used: String, limit: String, unlimited: bool) -> String {
+    if unlimited { format!("{} / 제한 없음", used) } else { format!("{} / {}", used, limit) }
+}
+
+impl QuotaDenied {
+    // 디스코드로 보낼 안내 문구
+    pub fn to_message(&self) -> String {
+        let reset = format!("<t:{}:R>", self.reset_at.timestamp());
+        let scope_name = match self.usage.as_ref().map(|u| u.scope) {
+            Some(QuotaScope::Guild) => "이 서버의",
+            _ => "내",
+        };
+        let mut lines = vec![match self.reason {
+            QuotaDeniedReason::ProModelNotAllowed => {
+                "Pro 모델은 지정된 역할만 사용할 수 있습니다. `use_pro` 없이 다시 요청해 주세요.".to_string()
+            }
+            QuotaDeniedReason::RateLimited { .. } => format!("요청이 너무 잦습니다. {} 다시 시도해 주세요.", reset),
+            QuotaDeniedReason::DailyTokensExceeded => format!("오늘 {} 토큰 한도를 모두 썼습니다. {} 초기화됩니다.", scope_name, reset),
+            QuotaDeniedReason::DailyCostExceeded => format!("오늘 {} 비용 한도를 모두 썼습니다. {} 초기화됩니다.", scope_name, reset),
+        }];
+        if let Some(usage) = &self.usage {
+            lines.push(format!(
+                "남은 요청 {} · 토큰 {} · 비용 {}",
+                if usage.limits.limits_requests() {
+                    format!("{} / {}", usage.remaining_requests, usage.limits.burst)
+                } else {
+                    "제한 없음".to_string()
+                },
+                format_limit(usage.used_tokens.to_string(), usage.limits.daily_tokens.to_string(), usage.limits.daily_tokens <= 0),
+                format_limit(
+                    format!("${:.2}", usage.used_cost_usd),
+                    format!("${:.2}", usage.limits.daily_cost_usd),
+                    usage.limits.daily_cost_usd <= 0.0
+                ),
+            ));
+        }
+        lines.join("\n")
+    }
+}
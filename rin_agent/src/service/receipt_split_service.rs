@@ -0,0 +1,149 @@
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use dashmap::DashMap;
+use serde::Serialize;
+
+use crate::gemini::receipt_ocr::ReceiptData;
+use crate::service::debt_service::{record_debt_with_image, DebtEntry};
+
+// 확인 버튼을 누르지 않은 제안은 이 시간이 지나면 버립니다.
+pub const SPLIT_CONFIRM_TTL: Duration = Duration::from_secs(15 * 60);
+// 원본 사진을 DB 에 그대로 남기므로 크기를 제한합니다.
+pub const MAX_RECEIPT_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// 영수증 원본을 base64 로 인코딩합니다. 디스코드 첨부 주소는 만료되므로 감사용 사본은 내용 자체로 남깁니다.
+pub fn encode_receipt_image(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// tb_image_attach_file.file_src 에 넣을 data URL
+pub fn receipt_image_src(mime_type: &str, base64_image: &str) -> String {
+    format!("data:{};base64,{}", mime_type, base64_image)
+}
+
+// 나눠 낼 한 사람의 몫. 장부에는 name 으로 기록합니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SplitShare {
+    pub user_id: u64,
+    pub name: String,
+    pub amount: i64,
+}
+
+// 영수증을 읽은 뒤 확인 버튼을 기다리는 제안. 결제한 사람(owner)의 몫은 shares 에 넣지 않습니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReceiptSplitProposal {
+    pub owner_id: u64,
+    pub image_id: Option<i64>,
+    pub receipt: ReceiptData,
+    pub owner_share: i64,
+    pub shares: Vec<SplitShare>,
+}
+
+struct PendingSplit {
+    created_at: Instant,
+    proposal: ReceiptSplitProposal,
+}
+
+// 키는 /receipt 명령의 interaction id 입니다.
+static PENDING_SPLITS: LazyLock<DashMap<u64, PendingSplit>> = LazyLock::new(DashMap::new);
+
+// 나머지는 앞사람부터 1씩 더 냅니다. 첫 번째가 결제한 사람이면 나머지를 떠안습니다.
+pub fn split_evenly(total: i64, people: usize) -> Vec<i64> {
+    if people == 0 {
+        return Vec::new();
+    }
+    let base = total / people as i64;
+    let remainder = (total % people as i64) as usize;
+    (0..people).map(|i| base + if i < remainder { 1 } else { 0 }).collect()
+}
+
+// "<@123>" 또는 "<@!123>" 형태의 사용자 멘션을 나온 순서대로, 중복 없이 찾습니다.
+pub fn parse_user_mentions(text: &str) -> Vec<u64> {
+    let mut ids = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("<@") {
+        rest = &rest[start + 2..];
+        let digits = rest.strip_prefix('!').unwrap_or(rest);
+        let Some(end) = digits.find('>') else {
+            break;
+        };
+        if let Ok(id) = digits[..end].parse::<u64>() {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+// participants 는 결제한 사람을 뺀 (user_id, 이름) 목록입니다.
+// 장부 금액은 원 단위이므로 원화가 아닌 영수증은 나누지 않고 읽은 내용만 돌려줍니다.
+pub fn propose_split(owner_id: u64, image_id: Option<i64>, receipt: ReceiptData, participants: &[(u64, String)]) -> ReceiptSplitProposal {
+    if !receipt.is_krw() {
+        return ReceiptSplitProposal { owner_id, image_id, receipt, owner_share: 0, shares: Vec::new() };
+    }
+    let participants = participants.iter().filter(|(id, _)| *id != owner_id).collect::<Vec<_>>();
+    let amounts = split_evenly(receipt.total_units(), participants.len() + 1);
+    let shares = participants
+        .iter()
+        .zip(amounts.iter().skip(1))
+        .map(|((user_id, name), amount)| SplitShare { user_id: *user_id, name: name.clone(), amount: *amount })
+        .collect();
+    ReceiptSplitProposal {
+        owner_id,
+        image_id,
+        owner_share: amounts.first().copied().unwrap_or_default(),
+        receipt,
+        shares,
+    }
+}
+
+// 장부 메모
+pub fn split_memo(receipt: &ReceiptData) -> String {
+    let mut parts = vec![receipt.merchant.clone().unwrap_or("영수증".to_string())];
+    if let Some(date) = &receipt.date {
+        parts.push(date.clone());
+    }
+    format!("{} 나눠 내기", parts.join(" "))
+}
+
+pub fn store_split_proposal(key: u64, proposal: ReceiptSplitProposal) {
+    PENDING_SPLITS.retain(|_, pending| pending.created_at.elapsed() < SPLIT_CONFIRM_TTL);
+    PENDING_SPLITS.insert(key, PendingSplit { created_at: Instant::now(), proposal });
+}
+
+// 결제한 사람만 확인하거나 취소할 수 있습니다. 다른 사람이 누르면 제안은 그대로 둡니다.
+pub fn take_split_proposal(key: u64, user_id: u64) -> Result<ReceiptSplitProposal, String> {
+    let Some(pending) = PENDING_SPLITS.get(&key) else {
+        return Err("만료되었거나 이미 처리한 요청입니다.".to_string());
+    };
+    if pending.proposal.owner_id != user_id {
+        return Err("영수증을 올린 사람만 확인할 수 있습니다.".to_string());
+    }
+    let expired = pending.created_at.elapsed() >= SPLIT_CONFIRM_TTL;
+    drop(pending);
+    let (_, pending) = PENDING_SPLITS.remove(&key).ok_or("만료되었거나 이미 처리한 요청입니다.".to_string())?;
+    if expired {
+        return Err("확인 시간이 지났습니다. 다시 /receipt 를 실행해 주세요.".to_string());
+    }
+    Ok(pending.proposal)
+}
+
+// 몫이 0 인 사람은 건너뜁니다. 일부만 기록된 경우에도 기록된 것까지 돌려줍니다.
+pub async fn confirm_split(proposal: &ReceiptSplitProposal) -> (Vec<DebtEntry>, Vec<String>) {
+    if !proposal.receipt.is_krw() {
+        return (Vec::new(), vec!["원화(KRW) 영수증만 장부에 기록할 수 있습니다.".to_string()]);
+    }
+    let memo = split_memo(&proposal.receipt);
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for share in proposal.shares.iter().filter(|s| s.amount > 0) {
+        match record_debt_with_image(proposal.owner_id, &share.name, share.amount, Some(&memo), proposal.image_id).await {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(format!("{}: {}", share.name, e)),
+        }
+    }
+    (entries, errors)
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use contract::RinAgentStatus;
+use entity::tb_discord_ai_context;
+use sea_orm::{prelude::Expr, ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::discord::discord_bot_manager::shutdown_discord_shards;
+use crate::gemini::gemini_client::{GeminiClient, GeminiClientTrait};
+use crate::gemini::live_voice_bridge::LIVE_SOCKET_POOL;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::libs::shutdown::{IN_FLIGHT_WORK, PENDING_LOGS, SHUTDOWN};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::discord_error_msg::send_additional_log;
+use crate::service::mqtt_service::{now_timestamp, publish_status};
+use crate::service::voice_session_manager::close_all_voice_sessions;
+use crate::setting::supervisor_setting::SHUTDOWN_DRAIN_TIMEOUT;
+
+// 웹훅 로그를 보내고 기다리는 최대 시간
+const LOG_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 모든 하위 시스템을 순서대로 정리합니다. 여러 번 불려도 한 번만 실행됩니다.
+/// 새 작업을 막고, 진행 중인 작업을 기다린 뒤 음성/Live 세션, Gemini 캐시, Discord 샤드를 닫고 로그를 비웁니다.
+pub async fn graceful_shutdown(reason: &str) {
+    if SHUTDOWN.is_cancelled() {
+        return;
+    }
+    SHUTDOWN.cancel();
+    LOGGER.log(LogLevel::Info, &format!("Shutdown > 종료를 시작합니다: {}", reason));
+    // MQTT 이벤트 루프가 정리하는 동안 내보낼 수 있도록 가장 먼저 알립니다.
+    publish_status(&RinAgentStatus::ShuttingDown { reason: reason.to_string(), timestamp: now_timestamp() });
+
+    let in_flight = IN_FLIGHT_WORK.active();
+    if in_flight > 0 {
+        LOGGER.log(LogLevel::Info, &format!("Shutdown > 진행 중인 작업 {}개를 기다립니다.", in_flight));
+    }
+    if !IN_FLIGHT_WORK.drain(*SHUTDOWN_DRAIN_TIMEOUT).await {
+        LOGGER.log(LogLevel::Warning, &format!(
+            "Shutdown > {}초 안에 끝나지 않은 작업 {}개를 두고 종료합니다.",
+            SHUTDOWN_DRAIN_TIMEOUT.as_secs(), IN_FLIGHT_WORK.active()
+        ));
+    }
+
+    close_all_voice_sessions().await;
+    LIVE_SOCKET_POOL.shutdown_all().await;
+    if let Err(e) = drop_gemini_caches().await {
+        LOGGER.log(LogLevel::Warning, &format!("Shutdown > Gemini 캐시 정리 실패: {}", e));
+    }
+    shutdown_discord_shards().await;
+
+    let is_dev = if cfg!(debug_assertions) { "dev" } else { "prod" };
+    send_additional_log(format!("{} > shutting down: {}", is_dev, reason), None).await;
+    if !PENDING_LOGS.drain(LOG_FLUSH_TIMEOUT).await {
+        LOGGER.log(LogLevel::Warning, "Shutdown > 보내지 못한 로그가 남아 있습니다.");
+    }
+    LOGGER.log(LogLevel::Info, "Shutdown > 정리를 마쳤습니다.");
+}
+
+// 아직 살아 있는 컨텍스트 캐시를 지우고 만료된 것으로 표시합니다. 다음 대화는 캐시 없이 이어집니다.
+async fn drop_gemini_caches() -> Result<(), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let now = chrono::Utc::now();
+    let live_caches = tb_discord_ai_context::Entity::find()
+        .filter(tb_discord_ai_context::Column::CacheKey.is_not_null())
+        .filter(tb_discord_ai_context::Column::CacheExpiresAt.gt(now))
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if live_caches.is_empty() {
+        return Ok(());
+    }
+
+    let mut client = GeminiClient::new();
+    let mut dropped = Vec::new();
+    for context in live_caches {
+        let Some(cache_key) = context.cache_key else { continue };
+        if client.drop_cache(&cache_key).await.is_ok() {
+            dropped.push(context.id);
+        }
+    }
+    tb_discord_ai_context::Entity::update_many()
+        .col_expr(tb_discord_ai_context::Column::CacheKey, Expr::value(Option::<String>::None))
+        .col_expr(tb_discord_ai_context::Column::CacheExpiresAt, Expr::value(now))
+        .filter(tb_discord_ai_context::Column::Id.is_in(dropped.clone()))
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    LOGGER.log(LogLevel::Info, &format!("Shutdown > Gemini 캐시 {}개를 정리했습니다.", dropped.len()));
+    Ok(())
+}
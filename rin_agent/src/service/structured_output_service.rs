@@ -0,0 +1,49 @@
+use chrono::Utc;
+use entity::tb_structured_output;
+use sea_orm::EntityTrait;
+use serde_json::Value;
+
+use crate::gemini::structured_output::{StructuredKind, StructuredTable};
+use crate::gemini::types::GeminiUsageContext;
+use crate::model::db::driver::DB_CONNECTION_POOL;
+
+// 검증을 마친 구조화 응답을 저장하고 ID 를 돌려줍니다. CSV 내보내기는 이 ID 로 찾습니다.
+pub async fn save_structured_output(
+    kind: StructuredKind,
+    query: &str,
+    model: &str,
+    data: &Value,
+    context: &GeminiUsageContext,
+) -> Result<i64, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let row = tb_structured_output::ActiveModel {
+        kind: sea_orm::Set(kind.as_str().to_string()),
+        query: sea_orm::Set(query.to_string()),
+        data: sea_orm::Set(data.clone()),
+        model: sea_orm::Set(model.to_string()),
+        guild_id: sea_orm::Set(context.guild_id.map(|id| id as i64)),
+        channel_id: sea_orm::Set(context.channel_id.map(|id| id as i64)),
+        user_id: sea_orm::Set(context.user_id.unwrap_or_default() as i64),
+        created_at: sea_orm::Set(Utc::now().into()),
+        ..Default::default()
+    };
+    tb_structured_output::Entity::insert(row)
+        .exec(db)
+        .await
+        .map(|inserted| inserted.last_insert_id)
+        .map_err(|e| e.to_string())
+}
+
+pub async fn find_structured_output(id: i64) -> Result<Option<tb_structured_output::Model>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    tb_structured_output::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 저장된 행을 CSV 로 바꿉니다. 알 수 없는 kind 면 오류입니다.
+pub fn structured_output_to_csv(row: &tb_structured_output::Model) -> Result<String, String> {
+    let kind = StructuredKind::parse(&row.kind).ok_or(format!("Unknown structured kind: {}", row.kind))?;
+    Ok(StructuredTable::from_value(kind, &row.data).to_csv())
+}
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use entity::{tb_voice_transcript, tb_voice_transcript_session};
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use gemini_live_api::types::enums::GeminiContentRole;
+use gemini_live_api::types::rest_api_types::GenerateContentRequest;
+use gemini_live_api::types::{GeminiContents, GeminiGenerationConfig, GeminiParts, GeminiSchema};
+use sea_orm::{ActiveModelTrait, EntityTrait};
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, ChannelType, Context, CreateEmbed, CreateMessage, CreateThread, EditThread, GuildId, Http, UserId};
+use songbird::CoreEvent;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::discord::voice::voice_receiver::{SpeakerAudio, SpeakerReceiver};
+use crate::discord::voice::voice_thread_manager::VOICE_MANAGER;
+use crate::gemini::gemini_client::request_generate_content;
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::structured_output::{array_schema, object_schema, string_schema};
+use crate::gemini::types::GeminiUsageContext;
+use crate::gemini::live_voice_bridge::{get_live_api_url, get_transcribe_setup, start_live_voice_bridge, VoiceBridgeInput, VoiceBridgeOutput};
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::voice_session_manager::get_session;
+use crate::setting::gemini_setting::{GEMINI_MODEL_FLASH, GENERATE_CONF, SAFETY_SETTINGS};
+
+/// 진행 중인 받아쓰기 세션 (길드당 하나)
+pub static TRANSCRIBE_SESSIONS: LazyLock<DashMap<GuildId, Arc<TranscribeSession>>> = LazyLock::new(DashMap::new);
+
+// 시작 절차(스레드 생성, 채널 참여)가 진행 중인 길드. 같은 길드에서 두 번 시작하지 못하게 먼저 자리를 잡습니다.
+static STARTING_TRANSCRIPTIONS: LazyLock<DashMap<GuildId, ()>> = LazyLock::new(DashMap::new);
+
+// 시작이 끝나거나 실패하면 잡아 둔 자리를 돌려줍니다.
+struct StartReservation(GuildId);
+
+impl StartReservation {
+    fn acquire(guild_id: GuildId) -> Option<Self> {
+        match STARTING_TRANSCRIPTIONS.entry(guild_id) {
+            dashmap::Entry::Occupied(_) => None,
+            dashmap::Entry::Vacant(entry) => {
+                entry.insert(());
+                Some(StartReservation(guild_id))
+            }
+        }
+    }
+}
+
+impl Drop for StartReservation {
+    fn drop(&mut self) {
+        STARTING_TRANSCRIPTIONS.remove(&self.0);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscriptLine {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub content: String,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptActionItem {
+    pub owner: String,
+    pub task: String,
+    #[serde(default)]
+    pub due: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptProposedAlarm {
+    /// "YYYY-MM-DD HH:MM:SS+09:00" 형식
+    pub time: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptSummary {
+    #[serde(default)]
+    pub overview: String,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    #[serde(default)]
+    pub action_items: Vec<TranscriptActionItem>,
+    #[serde(default)]
+    pub proposed_alarms: Vec<TranscriptProposedAlarm>,
+}
+
+pub struct TranscribeSession {
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub voice_channel_id: ChannelId,
+    pub thread_id: ChannelId,
+    http: Arc<Http>,
+    input_sender: Mutex<Option<mpsc::Sender<SpeakerAudio>>>,
+    user_names: DashMap<UserId, String>,
+    lines: Mutex<Vec<TranscriptLine>>,
+    collectors: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl TranscribeSession {
+    async fn user_name(&self, user_id: UserId) -> String {
+        if let Some(name) = self.user_names.get(&user_id) {
+            return name.clone();
+        }
+        let name = match self.http.get_user(user_id).await {
+            Ok(user) => user.global_name.unwrap_or(user.name),
+            Err(_) => user_id.to_string(),
+        };
+        self.user_names.insert(user_id, name.clone());
+        name
+    }
+
+    /// 한 화자의 발화 한 줄을 스레드에 올리고 DB 에 저장합니다.
+    async fn record_line(&self, user_id: UserId, content: String) {
+        let user_name = self.user_name(user_id).await;
+        let line = TranscriptLine { user_id, user_name, content, at: chrono::Utc::now() };
+
+        if let Err(e) = self.thread_id.say(&self.http, format_transcript_line(&line)).await {
+            LOGGER.log(LogLevel::Warning, &format!("[Transcribe:{}] 스레드 전송 실패: {}", self.guild_id, e));
+        }
+
+        if let Some(db) = DB_CONNECTION_POOL.get() {
+            let model = tb_voice_transcript::ActiveModel {
+                session_id: sea_orm::Set(self.id),
+                user_id: sea_orm::Set(user_id.get() as i64),
+                user_name: sea_orm::Set(line.user_name.clone()),
+                content: sea_orm::Set(line.content.clone()),
+                created_at: sea_orm::Set(line.at.into()),
+                ..Default::default()
+            };
+            if let Err(e) = tb_voice_transcript::Entity::insert(model).exec(db).await {
+                LOGGER.log(LogLevel::Error, &format!("[Transcribe:{}] 받아쓰기 저장 실패: {}", self.guild_id, e));
+            }
+        }
+
+        self.lines.lock().await.push(line);
+    }
+}
+
+pub fn format_transcript_line(line: &TranscriptLine) -> String {
+    format!("**{}** : {}", line.user_name, line.content)
+}
+
+/// 요약을 디스코드 메시지 본문으로 만듭니다.
+pub fn format_summary(summary: &TranscriptSummary) -> String {
+    let mut text = String::new();
+    if !summary.overview.is_empty() {
+        text.push_str(&format!("{}\n\n", summary.overview));
+    }
+
+    text.push_str("**결정 사항**\n");
+    if summary.decisions.is_empty() {
+        text.push_str("- 없음\n");
+    }
+    for decision in &summary.decisions {
+        text.push_str(&format!("- {}\n", decision));
+    }
+
+    text.push_str("\n**할 일**\n");
+    if summary.action_items.is_empty() {
+        text.push_str("- 없음\n");
+    }
+    for item in &summary.action_items {
+        match &item.due {
+            Some(due) => text.push_str(&format!("- [{}] {} (기한: {})\n", item.owner, item.task, due)),
+            None => text.push_str(&format!("- [{}] {}\n", item.owner, item.task)),
+        }
+    }
+
+    if !summary.proposed_alarms.is_empty() {
+        text.push_str("\n**제안하는 알람** (필요하면 `/gemini_query`로 설정을 부탁해 주세요)\n");
+        for alarm in &summary.proposed_alarms {
+            text.push_str(&format!("- {} : {}\n", alarm.time, alarm.message));
+        }
+    }
+    text
+}
+
+/// 유저가 있는 음성 채널에서 받아쓰기를 시작하고, `text_channel_id` 아래에 스레드를 만듭니다.
+pub async fn start_transcription(
+    ctx: &Context,
+    guild_id: GuildId,
+    voice_channel_id: ChannelId,
+    text_channel_id: ChannelId,
+    started_by: UserId,
+) -> Result<Arc<TranscribeSession>, String> {
+    // 자리를 먼저 잡은 뒤 진행 중인 세션을 확인합니다. 시작을 마친 쪽은 세션을 등록한 다음에 자리를 돌려주므로 둘 다 통과할 수 없습니다.
+    let Some(_reservation) = StartReservation::acquire(guild_id) else {
+        return Err("이미 이 서버에서 받아쓰기를 시작하는 중입니다.".to_string());
+    };
+    if TRANSCRIBE_SESSIONS.contains_key(&guild_id) {
+        return Err("이미 이 서버에서 받아쓰기가 진행 중입니다.".to_string());
+    }
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+
+    let now = chrono::Utc::now();
+    let thread = text_channel_id
+        .create_thread(
+            &ctx.http,
+            CreateThread::new(format!("회의록 {}", now.with_timezone(&chrono::FixedOffset::east_opt(9 * 3600).unwrap()).format("%Y-%m-%d %H:%M")))
+                .kind(ChannelType::PublicThread),
+        )
+        .await
+        .map_err(|e| format!("스레드 생성 실패: {}", e))?;
+
+    let inserted = tb_voice_transcript_session::ActiveModel {
+        guild_id: sea_orm::Set(guild_id.get() as i64),
+        voice_channel_id: sea_orm::Set(voice_channel_id.get() as i64),
+        thread_id: sea_orm::Set(thread.id.get() as i64),
+        started_by: sea_orm::Set(started_by.get() as i64),
+        started_at: sea_orm::Set(now.into()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await;
+    let inserted = match inserted {
+        Ok(inserted) => inserted,
+        Err(e) => {
+            archive_thread(ctx, thread.id).await;
+            return Err(format!("받아쓰기 세션 저장 실패: {}", e));
+        }
+    };
+
+    let state = match VOICE_MANAGER.join(ctx, guild_id, voice_channel_id).await {
+        Ok(state) => state,
+        Err(e) => {
+            // 시작하지 못한 세션이 진행 중으로 남지 않도록 끝난 것으로 저장하고 스레드를 닫습니다.
+            let update = tb_voice_transcript_session::ActiveModel {
+                id: sea_orm::Set(inserted.id),
+                ended_at: sea_orm::Set(Some(chrono::Utc::now().into())),
+                ..Default::default()
+            };
+            if let Err(e) = update.update(db).await {
+                LOGGER.log(LogLevel::Error, &format!("[Transcribe:{}] 종료 시각 저장 실패: {}", guild_id, e));
+            }
+            archive_thread(ctx, thread.id).await;
+            return Err(e);
+        }
+    };
+
+    let (input_tx, input_rx) = mpsc::channel(500);
+    let session = Arc::new(TranscribeSession {
+        id: inserted.id,
+        guild_id,
+        voice_channel_id,
+        thread_id: thread.id,
+        http: ctx.http.clone(),
+        input_sender: Mutex::new(Some(input_tx.clone())),
+        user_names: DashMap::new(),
+        lines: Mutex::new(Vec::new()),
+        collectors: Mutex::new(Vec::new()),
+    });
+
+    let receiver = SpeakerReceiver::new(&input_tx);
+    {
+        let mut call = state.call.lock().await;
+        call.add_global_event(CoreEvent::SpeakingStateUpdate.into(), receiver.clone());
+        call.add_global_event(CoreEvent::VoiceTick.into(), receiver);
+    }
+    drop(input_tx);
+
+    tokio::spawn(route_speaker_audio(session.clone(), input_rx));
+    TRANSCRIBE_SESSIONS.insert(guild_id, session.clone());
+    LOGGER.log(LogLevel::Info, &format!("[Transcribe] 길드 {} 받아쓰기 시작 (세션 {})", guild_id, inserted.id));
+    Ok(session)
+}
+
+async fn archive_thread(ctx: &Context, thread_id: ChannelId) {
+    if let Err(e) = thread_id.edit_thread(&ctx.http, EditThread::new().archived(true)).await {
+        LOGGER.log(LogLevel::Warning, &format!("[Transcribe] 스레드 {} 보관 실패: {}", thread_id, e));
+    }
+}
+
+/// 받아쓰기를 끝내고 요약을 만들어 스레드에 올립니다.
+pub async fn stop_transcription(ctx: &Context, guild_id: GuildId, requested_by: UserId) -> Result<TranscriptSummary, String> {
+    let (_, session) = TRANSCRIBE_SESSIONS
+        .remove(&guild_id)
+        .ok_or("이 서버에서 진행 중인 받아쓰기가 없습니다.".to_string())?;
+
+    // 송신부를 닫으면 화자별 Live 세션이 닫히고, 남은 발화가 모두 기록됩니다.
+    session.input_sender.lock().await.take();
+    let collectors = std::mem::take(&mut *session.collectors.lock().await);
+    for collector in collectors {
+        let _ = tokio::time::timeout(Duration::from_secs(10), collector).await;
+    }
+
+    let lines = session.lines.lock().await.clone();
+    let summary = if lines.is_empty() {
+        TranscriptSummary {
+            overview: "기록된 발화가 없습니다.".to_string(),
+            ..Default::default()
+        }
+    } else {
+        // 요약을 요청한 사용자 몫으로 사용량과 할당량을 기록합니다.
+        let usage_context = GeminiUsageContext {
+            source: "transcribe".to_string(),
+            guild_id: Some(guild_id.get()),
+            channel_id: Some(session.thread_id.get()),
+            user_id: Some(requested_by.get()),
+            context_id: None,
+        };
+        summarize_transcript(&lines, &usage_context).await.unwrap_or_else(|e| {
+            LOGGER.log(LogLevel::Error, &format!("[Transcribe:{}] 요약 실패: {}", guild_id, e));
+            TranscriptSummary {
+                overview: "요약을 만드는 데 실패했습니다.".to_string(),
+                ..Default::default()
+            }
+        })
+    };
+
+    if let Some(db) = DB_CONNECTION_POOL.get() {
+        let update = tb_voice_transcript_session::ActiveModel {
+            id: sea_orm::Set(session.id),
+            ended_at: sea_orm::Set(Some(chrono::Utc::now().into())),
+            summary: sea_orm::Set(serde_json::to_value(&summary).ok()),
+            ..Default::default()
+        };
+        if let Err(e) = update.update(db).await {
+            LOGGER.log(LogLevel::Error, &format!("[Transcribe:{}] 요약 저장 실패: {}", guild_id, e));
+        }
+    }
+
+    let embed = CreateEmbed::new()
+        .title("회의 요약")
+        .description(format_summary(&summary))
+        .color(0x00AAFF);
+    let _ = session.thread_id.send_message(&ctx.http, CreateMessage::new().embed(embed)).await;
+
+    // 음성 대화 세션이 없으면 채널에서 나갑니다.
+    if get_session(guild_id.get() as i64).await.is_none() {
+        let _ = VOICE_MANAGER.leave(ctx, guild_id).await;
+    }
+    Ok(summary)
+}
+
+/// 종료할 때 진행 중인 받아쓰기를 요약 없이 끝냅니다. 남은 발화는 기록하고 세션을 끝난 것으로 저장합니다.
+pub async fn close_all_transcriptions() {
+    let guild_ids: Vec<GuildId> = TRANSCRIBE_SESSIONS.iter().map(|entry| *entry.key()).collect();
+    for guild_id in guild_ids {
+        let Some((_, session)) = TRANSCRIBE_SESSIONS.remove(&guild_id) else { continue };
+        session.input_sender.lock().await.take();
+        let collectors = std::mem::take(&mut *session.collectors.lock().await);
+        for collector in collectors {
+            let _ = tokio::time::timeout(Duration::from_secs(5), collector).await;
+        }
+
+        if let Some(db) = DB_CONNECTION_POOL.get() {
+            let update = tb_voice_transcript_session::ActiveModel {
+                id: sea_orm::Set(session.id),
+                ended_at: sea_orm::Set(Some(chrono::Utc::now().into())),
+                ..Default::default()
+            };
+            if let Err(e) = update.update(db).await {
+                LOGGER.log(LogLevel::Error, &format!("[Transcribe:{}] 종료 시각 저장 실패: {}", guild_id, e));
+            }
+        }
+    }
+}
+
+// 화자별로 Live 세션을 하나씩 열어 음성을 넘깁니다.
+async fn route_speaker_audio(session: Arc<TranscribeSession>, mut input_rx: mpsc::Receiver<SpeakerAudio>) {
+    let mut bridges: HashMap<UserId, mpsc::Sender<VoiceBridgeInput>> = HashMap::new();
+    while let Some(audio) = input_rx.recv().await {
+        let (user_id, input) = match audio {
+            SpeakerAudio::Audio(user_id, pcm) => (user_id, VoiceBridgeInput::Audio(pcm)),
+            SpeakerAudio::End(user_id) => (user_id, VoiceBridgeInput::AudioStreamEnd),
+        };
+
+        if !bridges.contains_key(&user_id) {
+            let (output_tx, output_rx) = mpsc::channel(100);
+            match start_live_voice_bridge(format!("transcribe:{}:{}", session.guild_id, user_id), get_live_api_url(), get_transcribe_setup(), output_tx, None).await {
+                Ok(sender) => {
+                    let handle = tokio::spawn(collect_transcript(session.clone(), user_id, output_rx));
+                    session.collectors.lock().await.push(handle);
+                    bridges.insert(user_id, sender);
+                }
+                Err(e) => {
+                    LOGGER.log(LogLevel::Error, &format!("[Transcribe:{}] 화자 {} Live 세션 연결 실패: {}", session.guild_id, user_id, e));
+                    continue;
+                }
+            }
+        }
+        if let Some(bridge) = bridges.get(&user_id) {
+            if bridge.send(input).await.is_err() {
+                bridges.remove(&user_id);
+            }
+        }
+    }
+    // 여기서 bridges 가 drop 되며 화자별 세션이 모두 닫힙니다.
+}
+
+// 한 화자의 전사 조각을 모아 발화가 끝날 때마다 한 줄로 기록합니다.
+async fn collect_transcript(session: Arc<TranscribeSession>, user_id: UserId, mut output_rx: mpsc::Receiver<VoiceBridgeOutput>) {
+    let mut buffer = String::new();
+    while let Some(output) = output_rx.recv().await {
+        match output {
+            VoiceBridgeOutput::InputTranscript(text) => buffer.push_str(&text),
+            VoiceBridgeOutput::TurnComplete | VoiceBridgeOutput::Closed => {
+                let line = buffer.trim().to_string();
+                buffer.clear();
+                if !line.is_empty() {
+                    session.record_line(user_id, line).await;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn summary_schema() -> GeminiSchema {
+    object_schema(
+        vec![
+            ("overview", string_schema("회의 요약")),
+            ("decisions", array_schema(string_schema("결정 사항"))),
+            ("action_items", array_schema(object_schema(
+                vec![
+                    ("owner", string_schema("담당자")),
+                    ("task", string_schema("할 일")),
+                    ("due", GeminiSchema { nullable: Some(true), ..string_schema("기한") }),
+                ],
+                &["owner", "task"],
+            ))),
+            ("proposed_alarms", array_schema(object_schema(
+                vec![
+                    ("time", string_schema("YYYY-MM-DD HH:MM:SS+09:00")),
+                    ("message", string_schema("알람 내용")),
+                ],
+                &["time", "message"],
+            ))),
+        ],
+        &["overview", "decisions", "action_items", "proposed_alarms"],
+    )
+}
+
+/// 전체 받아쓰기를 Gemini 에 보내 결정 사항/할 일/알람 제안을 JSON 으로 받습니다.
+pub async fn summarize_transcript(lines: &[TranscriptLine], usage_context: &GeminiUsageContext) -> Result<TranscriptSummary, GeminiError> {
+    let rest = GeminiRestClient::from_env()?;
+    let transcript = lines
+        .iter()
+        .map(|l| format!("[{}] {} : {}", l.at.to_rfc3339(), l.user_name, l.content))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let prompt = format!(
+        "다음은 디스코드 음성 회의 받아쓰기입니다. 한국어로 요약하고, 결정 사항, 할 일(담당자/기한), \
+        그리고 만들면 좋을 알람(시간은 UTC+9 기준 'YYYY-MM-DD HH:MM:SS+09:00')을 뽑아 주세요.\n\n{}",
+        transcript
+    );
+
+    let request = GenerateContentRequest {
+        contents: vec![GeminiContents {
+            role: GeminiContentRole::User,
+            parts: vec![GeminiParts::new().set_text(prompt)],
+        }],
+        generation_config: Some(GeminiGenerationConfig {
+            response_mime_type: Some("application/json".to_string()),
+            response_schema: Some(summary_schema()),
+            ..GENERATE_CONF.clone()
+        }),
+        safety_settings: Some(SAFETY_SETTINGS.clone()),
+        ..Default::default()
+    };
+    let response = request_generate_content(&rest, GEMINI_MODEL_FLASH, &request, usage_context).await?;
+    let Some(text) = response.text() else {
+        return Err(response
+            .finish_reason()
+            .and_then(GeminiError::from_finish_reason)
+            .unwrap_or(GeminiError::EmptyResponse));
+    };
+    serde_json::from_str(text.trim()).map_err(|e| GeminiError::Other(format!("요약 JSON 파싱 실패: {}", e)))
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use entity::tb_gemini_usage;
+use gemini_live_api::types::GeminiUsageMetadata;
+use sea_orm::sea_query::{Alias, Expr};
+use sea_orm::{ColumnTrait, EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select};
+use serde::Serialize;
+
+use crate::gemini::types::{GeminiModelPrice, GeminiUsageContext};
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::metrics_service::record_gemini_tokens;
+use crate::service::quota_service::add_quota_usage;
+use crate::setting::gemini_setting::GEMINI_PRICE_TABLE;
+
+// 응답 한 번에 쓰인 토큰 수. prompt 에는 cached 가 포함되어 있습니다.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageTokens {
+    pub prompt: i64,
+    pub cached: i64,
+    pub thoughts: i64,
+    pub output: i64,
+    pub tool_use_prompt: i64,
+    pub total: i64,
+}
+
+impl From<&GeminiUsageMetadata> for UsageTokens {
+    fn from(metadata: &GeminiUsageMetadata) -> Self {
+        UsageTokens {
+            prompt: metadata.prompt_token_count as i64,
+            cached: metadata.cached_content_token_count as i64,
+            thoughts: metadata.thoughts_token_count as i64,
+            output: metadata.candidates_token_count as i64,
+            tool_use_prompt: metadata.tool_use_prompt_token_count as i64,
+            total: metadata.total_token_count as i64,
+        }
+    }
+}
+
+// 모델 이름이 단가표에 그대로 없으면 가장 긴 접두사가 맞는 항목을 씁니다.
+// ("models/" 접두사와 "gemini-2.5-flash-preview-05-20" 같은 버전 꼬리를 허용)
+pub fn find_model_price(table: &HashMap<String, GeminiModelPrice>, model: &str) -> Option<GeminiModelPrice> {
+    let model = model.strip_prefix("models/").unwrap_or(model);
+    if let Some(price) = table.get(model) {
+        return Some(*price);
+    }
+    table
+        .iter()
+        .filter(|(name, _)| model.starts_with(name.as_str()))
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, price)| *price)
+}
+
+// 캐시된 입력은 캐시 단가로, 나머지 입력과 도구 사용 프롬프트는 입력 단가로,
+// 출력과 생각 토큰은 출력 단가로 계산합니다.
+pub fn compute_cost(price: &GeminiModelPrice, tokens: &UsageTokens) -> f64 {
+    let uncached_input = (tokens.prompt - tokens.cached).max(0) + tokens.tool_use_prompt;
+    let cost = uncached_input as f64 * price.input
+        + tokens.cached as f64 * price.cached_input
+        + (tokens.output + tokens.thoughts) as f64 * price.output;
+    cost / 1_000_000.0
+}
+
+pub fn estimate_cost(model: &str, tokens: &UsageTokens) -> f64 {
+    match find_model_price(&GEMINI_PRICE_TABLE, model) {
+        Some(price) => compute_cost(&price, tokens),
+        None => {
+            LOGGER.log(LogLevel::Warning, &format!("Usage > 단가표에 없는 모델입니다: {}", model));
+            0.0
+        }
+    }
+}
+
+// 사용량 기록은 응답 처리를 막지 않도록 백그라운드에서 저장합니다.
+// 같은 값을 일일 할당량 카운터에도 더합니다.
+pub fn spawn_record_usage(model: &str, context: &GeminiUsageContext, metadata: Option<&GeminiUsageMetadata>) {
+    let Some(metadata) = metadata else {
+        return;
+    };
+    let model = model.to_string();
+    let context = context.clone();
+    let tokens = UsageTokens::from(metadata);
+    record_gemini_tokens(&model, &tokens);
+    tokio::spawn(async move {
+        let cost_usd = estimate_cost(&model, &tokens);
+        if let Err(e) = add_quota_usage(&context, tokens.total, cost_usd).await {
+            LOGGER.log(LogLevel::Warning, &format!("Usage > 할당량 카운터 갱신 실패: {}", e));
+        }
+        if let Err(e) = record_usage(&model, &context, tokens, cost_usd).await {
+            LOGGER.log(LogLevel::Error, &format!("Usage > 사용량 저장 실패: {}", e));
+        }
+    });
+}
+
+pub async fn record_usage(model: &str, context: &GeminiUsageContext, tokens: UsageTokens, cost_usd: f64) -> Result<(), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let model_row = tb_gemini_usage::ActiveModel {
+        model: sea_orm::Set(model.to_string()),
+        source: sea_orm::Set(context.source.clone()),
+        guild_id: sea_orm::Set(context.guild_id.map(|id| id as i64)),
+        channel_id: sea_orm::Set(context.channel_id.map(|id| id as i64)),
+        user_id: sea_orm::Set(context.user_id.map(|id| id as i64)),
+        context_id: sea_orm::Set(context.context_id),
+        prompt_tokens: sea_orm::Set(tokens.prompt),
+        cached_tokens: sea_orm::Set(tokens.cached),
+        thoughts_tokens: sea_orm::Set(tokens.thoughts),
+        output_tokens: sea_orm::Set(tokens.output),
+        tool_use_prompt_tokens: sea_orm::Set(tokens.tool_use_prompt),
+        total_tokens: sea_orm::Set(tokens.total),
+        cost_usd: sea_orm::Set(cost_usd),
+        created_at: sea_orm::Set(Utc::now().into()),
+        ..Default::default()
+    };
+    tb_gemini_usage::Entity::insert(model_row)
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGroupBy {
+    Guild,
+    User,
+    Model,
+}
+
+impl UsageGroupBy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "guild" => Some(UsageGroupBy::Guild),
+            "user" => Some(UsageGroupBy::User),
+            "model" => Some(UsageGroupBy::Model),
+            _ => None,
+        }
+    }
+
+    fn column(&self) -> tb_gemini_usage::Column {
+        match self {
+            UsageGroupBy::Guild => tb_gemini_usage::Column::GuildId,
+            UsageGroupBy::User => tb_gemini_usage::Column::UserId,
+            UsageGroupBy::Model => tb_gemini_usage::Column::Model,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageFilter {
+    pub guild_id: Option<u64>,
+    pub user_id: Option<u64>,
+    pub days: i64,
+}
+
+// key 는 묶은 컬럼 값(길드/유저 ID 또는 모델 이름)이며, DM 처럼 값이 없으면 None 입니다.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, FromQueryResult)]
+pub struct UsageSummary {
+    pub key: Option<String>,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub cached_tokens: i64,
+    pub thoughts_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+}
+
+impl UsageSummary {
+    // 여러 묶음을 하나의 합계로 합칩니다.
+    pub fn total(rows: &[UsageSummary]) -> UsageSummary {
+        rows.iter().fold(UsageSummary::default(), |mut acc, row| {
+            acc.requests += row.requests;
+            acc.prompt_tokens += row.prompt_tokens;
+            acc.cached_tokens += row.cached_tokens;
+            acc.thoughts_tokens += row.thoughts_tokens;
+            acc.output_tokens += row.output_tokens;
+            acc.total_tokens += row.total_tokens;
+            acc.cost_usd += row.cost_usd;
+            acc
+        })
+    }
+}
+
+// 기간, 길드, 사용자 조건을 건 조회
+fn filtered_usage(filter: &UsageFilter) -> Select<tb_gemini_usage::Entity> {
+    let since = Utc::now() - Duration::days(filter.days.max(1));
+    let mut query = tb_gemini_usage::Entity::find().filter(tb_gemini_usage::Column::CreatedAt.gte(since));
+    if let Some(guild_id) = filter.guild_id {
+        query = query.filter(tb_gemini_usage::Column::GuildId.eq(guild_id as i64));
+    }
+    if let Some(user_id) = filter.user_id {
+        query = query.filter(tb_gemini_usage::Column::UserId.eq(user_id as i64));
+    }
+    query
+}
+
+// 기간 안의 사용량을 묶어서 비용이 큰 순으로 돌려줍니다.
+pub async fn summarize_usage(
+    filter: &UsageFilter,
+    group_by: UsageGroupBy,
+    limit: u64,
+) -> Result<Vec<UsageSummary>, String> {
+    summarize_usage_page(filter, group_by, 0, limit).await
+}
+
+pub async fn summarize_usage_page(
+    filter: &UsageFilter,
+    group_by: UsageGroupBy,
+    offset: u64,
+    limit: u64,
+) -> Result<Vec<UsageSummary>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let sum = |column: tb_gemini_usage::Column| Expr::col(column).sum().cast_as(Alias::new("bigint"));
+
+    filtered_usage(filter)
+        .select_only()
+        .column_as(Expr::col(group_by.column()).cast_as(Alias::new("text")), "key")
+        .column_as(Expr::col(tb_gemini_usage::Column::Id).count(), "requests")
+        .column_as(sum(tb_gemini_usage::Column::PromptTokens), "prompt_tokens")
+        .column_as(sum(tb_gemini_usage::Column::CachedTokens), "cached_tokens")
+        .column_as(sum(tb_gemini_usage::Column::ThoughtsTokens), "thoughts_tokens")
+        .column_as(sum(tb_gemini_usage::Column::OutputTokens), "output_tokens")
+        .column_as(sum(tb_gemini_usage::Column::TotalTokens), "total_tokens")
+        .column_as(Expr::col(tb_gemini_usage::Column::CostUsd).sum(), "cost_usd")
+        .group_by(group_by.column())
+        .order_by_desc(Expr::col(tb_gemini_usage::Column::CostUsd).sum())
+        .offset(offset)
+        .limit(limit)
+        .into_model::<UsageSummary>()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 묶음(길드/유저/모델) 수
+pub async fn count_usage_groups(filter: &UsageFilter, group_by: UsageGroupBy) -> Result<u64, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    filtered_usage(filter)
+        .select_only()
+        .column(group_by.column())
+        .group_by(group_by.column())
+        .count(db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 하루 단위 사용량. date 는 UTC 기준 YYYY-MM-DD 입니다.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, FromQueryResult)]
+pub struct DailyUsage {
+    pub date: String,
+    pub requests: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+}
+
+pub async fn summarize_daily_usage(filter: &UsageFilter) -> Result<Vec<DailyUsage>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let day = || Expr::cust("to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD')");
+    filtered_usage(filter)
+        .select_only()
+        .column_as(day(), "date")
+        .column_as(Expr::col(tb_gemini_usage::Column::Id).count(), "requests")
+        .column_as(Expr::col(tb_gemini_usage::Column::TotalTokens).sum().cast_as(Alias::new("bigint")), "total_tokens")
+        .column_as(Expr::col(tb_gemini_usage::Column::CostUsd).sum(), "cost_usd")
+        .group_by(day())
+        .order_by_asc(day())
+        .into_model::<DailyUsage>()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,14 +1,185 @@
 use std::{collections::BTreeMap, sync::{Arc, LazyLock}};
-use tokio::sync::{Mutex, MutexGuard};
+use dashmap::DashMap;
+use serenity::all::{ChannelId, Context, CreateMessage, GuildId, UserId};
+use songbird::CoreEvent;
+use tokio::sync::{mpsc, Mutex};
 
+use crate::discord::voice::voice_receiver::VoiceReceiver;
+use crate::discord::voice::voice_thread_manager::VOICE_MANAGER;
+use crate::gemini::live_tools::LiveToolDispatcher;
+use crate::gemini::live_voice_bridge::{get_live_api_url, get_voice_setup, start_live_voice_bridge, VoiceBridgeOutput};
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::gemini::types::DiscordUserInfo;
 use crate::libs::voice_session::VoiceSession;
+use crate::service::discord_message_service::send_discord_message;
+use crate::service::transcribe_service::{close_all_transcriptions, TRANSCRIBE_SESSIONS};
+use crate::setting::gemini_setting::get_begin_query;
 
 pub static VOICE_SESSION_MANAGER: LazyLock<Arc<Mutex<BTreeMap<i64, VoiceSession>>>> = LazyLock::new(|| {
     Arc::new(Mutex::new(BTreeMap::new()))
 });
 
+// 채널 참여와 Live 연결이 진행 중인 길드. 같은 길드에서 두 번 시작하지 못하게 먼저 자리를 잡습니다.
+static STARTING_SESSIONS: LazyLock<DashMap<i64, ()>> = LazyLock::new(DashMap::new);
+
+// 시작이 끝나거나 실패하면 잡아 둔 자리를 돌려줍니다.
+struct StartReservation(i64);
+
+impl Drop for StartReservation {
+    fn drop(&mut self) {
+        STARTING_SESSIONS.remove(&self.0);
+    }
+}
+
+fn reserve_start(guild_id: i64) -> Option<StartReservation> {
+    match STARTING_SESSIONS.entry(guild_id) {
+        dashmap::Entry::Occupied(_) => None,
+        dashmap::Entry::Vacant(entry) => {
+            entry.insert(());
+            Some(StartReservation(guild_id))
+        }
+    }
+}
+
 pub async fn add_session(guild_id: i64, session: VoiceSession) {
     let mut map = VOICE_SESSION_MANAGER.lock().await;
     map.insert(guild_id, session);
     drop(map);
-}
\ No newline at end of file
+}
+
+pub async fn get_session(guild_id: i64) -> Option<VoiceSession> {
+    VOICE_SESSION_MANAGER.lock().await.get(&guild_id).cloned()
+}
+
+pub async fn remove_session(guild_id: i64) -> Option<VoiceSession> {
+    VOICE_SESSION_MANAGER.lock().await.remove(&guild_id)
+}
+
+/// 음성 채널에 참여하고, 길드 전용 Gemini Live 세션을 열어 음성 입출력을 연결합니다.
+/// 음성으로 호출한 도구(알람, 검색 등)의 결과는 `text_channel_id` 로 보냅니다.
+/// 화자를 구분하지 않으므로 누가 말했든 도구는 `user_id` 로 실행됩니다.
+pub async fn start_voice_session(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    text_channel_id: ChannelId,
+    user_id: UserId,
+    user_name: String,
+    locale: String,
+) -> Result<(), String> {
+    let key = guild_id.get() as i64;
+    // 자리를 먼저 잡은 뒤 진행 중인 세션을 확인합니다. 시작을 마친 쪽은 세션을 등록한 다음에 자리를 돌려줍니다.
+    let Some(_reservation) = reserve_start(key) else {
+        return Err("이미 이 서버에서 음성 세션을 시작하는 중입니다.".to_string());
+    };
+    if get_session(key).await.is_some() {
+        return Err("이미 이 서버에서 음성 세션이 진행 중입니다.".to_string());
+    }
+
+    let state = VOICE_MANAGER.join(ctx, guild_id, channel_id).await?;
+
+    let begin_query = get_begin_query(locale, user_id.to_string(), Some(guild_id.get()), Some(channel_id.get()));
+    let (output_tx, output_rx) = mpsc::channel(200);
+    let tools = LiveToolDispatcher::new(Some(DiscordUserInfo {
+        user_id,
+        username: Some(user_name),
+        channel_id: text_channel_id,
+        guild_id: Some(guild_id),
+        context_id: None,
+    }));
+    let input_sender = match start_live_voice_bridge(format!("voice:{}", guild_id), get_live_api_url(), get_voice_setup(begin_query.query), output_tx, Some(tools)).await {
+        Ok(sender) => sender,
+        Err(e) => {
+            let _ = VOICE_MANAGER.leave(ctx, guild_id).await;
+            return Err(format!("Live 세션 연결 실패: {}", e));
+        }
+    };
+
+    state
+        .call
+        .lock()
+        .await
+        .add_global_event(CoreEvent::VoiceTick.into(), VoiceReceiver::new(&input_sender));
+
+    tokio::spawn(forward_bridge_output(ctx.clone(), guild_id, text_channel_id, output_rx));
+
+    add_session(key, VoiceSession {
+        guild_id: guild_id.get(),
+        channel_id: channel_id.get(),
+        text_channel_id: text_channel_id.get(),
+        started_by: user_id.get(),
+        input_sender: Some(input_sender),
+    }).await;
+    LOGGER.log(LogLevel::Info, &format!("[VoiceSession] 길드 {} 음성 세션 시작", guild_id));
+    Ok(())
+}
+
+/// Live 세션을 닫고 음성 채널에서 나갑니다.
+pub async fn stop_voice_session(ctx: &Context, guild_id: GuildId) -> Result<(), String> {
+    // 세션의 송신부가 drop 되면 Live 세션이 닫히고, 수신 핸들러도 스스로 해제됩니다.
+    remove_session(guild_id.get() as i64).await;
+    // 받아쓰기가 진행 중이면 채널에는 남아 있습니다.
+    if TRANSCRIBE_SESSIONS.contains_key(&guild_id) {
+        return VOICE_MANAGER.interrupt(guild_id).await;
+    }
+    VOICE_MANAGER.leave(ctx, guild_id).await
+}
+
+/// 종료할 때 모든 음성 대화와 받아쓰기를 끝내고 음성 채널에서 나갑니다.
+pub async fn close_all_voice_sessions() {
+    // 송신부가 drop 되면서 길드별 Live 세션이 닫힙니다.
+    std::mem::take(&mut *VOICE_SESSION_MANAGER.lock().await);
+    close_all_transcriptions().await;
+    VOICE_MANAGER.leave_all().await;
+}
+
+// Live 세션이 먼저 끊겼을 때 음성 채널에서 나가고 텍스트 채널에 알립니다.
+async fn end_closed_session(ctx: &Context, guild_id: GuildId, text_channel_id: ChannelId) {
+    let result = if TRANSCRIBE_SESSIONS.contains_key(&guild_id) {
+        VOICE_MANAGER.interrupt(guild_id).await
+    } else {
+        VOICE_MANAGER.leave(ctx, guild_id).await
+    };
+    if let Err(e) = result {
+        LOGGER.log(LogLevel::Warning, &format!("[VoiceSession:{}] 음성 채널 정리 실패: {}", guild_id, e));
+    }
+    let notice = CreateMessage::new().content("음성 대화 연결이 끊겨 세션을 종료했습니다. 다시 시작하려면 /join_voice 를 사용하세요.");
+    if let Err(e) = send_discord_message(text_channel_id, notice).await {
+        LOGGER.log(LogLevel::Warning, &format!("[VoiceSession:{}] 종료 알림 전송 실패: {}", guild_id, e));
+    }
+}
+
+// Live 세션의 출력을 길드 재생 큐로 넘기고, 도구 결과는 텍스트 채널에 알립니다.
+async fn forward_bridge_output(ctx: Context, guild_id: GuildId, text_channel_id: ChannelId, mut output_rx: mpsc::Receiver<VoiceBridgeOutput>) {
+    while let Some(output) = output_rx.recv().await {
+        let result = match output {
+            VoiceBridgeOutput::Audio(chunk) => VOICE_MANAGER.play(guild_id, chunk).await,
+            VoiceBridgeOutput::TurnComplete => VOICE_MANAGER.finish_clip(guild_id).await,
+            VoiceBridgeOutput::Interrupted => VOICE_MANAGER.interrupt(guild_id).await,
+            VoiceBridgeOutput::InputTranscript(text) | VoiceBridgeOutput::OutputTranscript(text) => {
+                LOGGER.log(LogLevel::Debug, &format!("[VoiceSession:{}] {}", guild_id, text));
+                Ok(())
+            }
+            VoiceBridgeOutput::ToolResult { name, show_user } => {
+                LOGGER.log(LogLevel::Debug, &format!("[VoiceSession:{}] 도구 실행 완료: {}", guild_id, name));
+                match show_user {
+                    Some(message) => send_discord_message(text_channel_id, CreateMessage::new().content(message))
+                        .await
+                        .map(|_| ()),
+                    None => Ok(()),
+                }
+            }
+            VoiceBridgeOutput::Closed => {
+                // stop_voice_session 으로 끝낸 경우에는 세션이 이미 빠져 있어 따로 정리하지 않습니다.
+                if remove_session(guild_id.get() as i64).await.is_some() {
+                    end_closed_session(&ctx, guild_id, text_channel_id).await;
+                }
+                break;
+            }
+        };
+        if let Err(e) = result {
+            LOGGER.log(LogLevel::Warning, &format!("[VoiceSession:{}] 출력 처리 실패: {}", guild_id, e));
+        }
+    }
+    LOGGER.log(LogLevel::Info, &format!("[VoiceSession] 길드 {} 음성 세션 종료", guild_id));
+}
@@ -0,0 +1,346 @@
+use chrono::{Duration, Utc};
+use entity::{tb_discord_guilds, tb_web_auth_token};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::setting::gemini_setting::MANAGER_ID;
+use crate::setting::web_auth_setting::{
+    DiscordOAuthConfig, API_TOKEN_DEFAULT_DAYS, API_TOKEN_MAX_DAYS, DISCORD_API_URL, DISCORD_AUTHORIZE_URL,
+    WEB_SESSION_TTL_HOURS,
+};
+
+// Discord 권한 비트
+const PERMISSION_ADMINISTRATOR: u64 = 1 << 3;
+const PERMISSION_MANAGE_GUILD: u64 = 1 << 5;
+
+pub const TOKEN_KIND_SESSION: &str = "session";
+pub const TOKEN_KIND_API: &str = "api";
+
+// 길드 안에서의 권한. Owner 는 봇 관리자(MANAGER_ID)로 모든 길드에 접근합니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebRole {
+    Member,
+    GuildAdmin,
+    Owner,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuildScope {
+    pub guild_id: u64,
+    pub name: String,
+    pub role: WebRole,
+}
+
+// 요청을 보낸 사람. 세션 쿠키나 API 토큰에서 만듭니다.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthUser {
+    pub user_id: u64,
+    pub user_name: Option<String>,
+    pub token_id: i64,
+    pub kind: String,
+    pub is_owner: bool,
+    pub guilds: Vec<GuildScope>,
+}
+
+impl AuthUser {
+    pub fn role_in(&self, guild_id: u64) -> Option<WebRole> {
+        if self.is_owner {
+            return Some(WebRole::Owner);
+        }
+        self.guilds.iter().find(|g| g.guild_id == guild_id).map(|g| g.role)
+    }
+
+    pub fn has_role(&self, guild_id: u64, min_role: WebRole) -> bool {
+        self.role_in(guild_id).is_some_and(|role| role >= min_role)
+    }
+
+    // 본인 데이터이거나 봇 관리자일 때
+    pub fn is_self_or_owner(&self, user_id: u64) -> bool {
+        self.is_owner || self.user_id == user_id
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordOAuthUser {
+    pub id: String,
+    pub username: String,
+    pub global_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordOAuthGuild {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub owner: bool,
+    #[serde(default)]
+    pub permissions: Option<String>,
+}
+
+// 발급한 API 토큰 목록에 보여줄 정보. 토큰 원문은 발급할 때 한 번만 돌려줍니다.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTokenInfo {
+    pub id: i64,
+    pub name: Option<String>,
+    pub guild_ids: Vec<u64>,
+    pub created_at: String,
+    pub expires_at: String,
+    pub last_used_at: Option<String>,
+}
+
+// "rin_" 뒤에 무작위 32바이트를 16진수로 붙입니다.
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    format!("rin_{}", to_hex(&bytes))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// DB 에는 토큰 원문 대신 SHA-256 해시만 남깁니다.
+pub fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+// "Bearer <token>" 에서 토큰을 꺼냅니다.
+pub fn parse_bearer(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+pub fn guild_role_from_permissions(owner: bool, permissions: Option<&str>) -> WebRole {
+    let permissions = permissions.and_then(|p| p.parse::<u64>().ok()).unwrap_or(0);
+    if owner || permissions & (PERMISSION_ADMINISTRATOR | PERMISSION_MANAGE_GUILD) != 0 {
+        WebRole::GuildAdmin
+    } else {
+        WebRole::Member
+    }
+}
+
+// 로그인한 사람의 길드 중 봇이 들어가 있는 길드만 남깁니다.
+pub fn build_guild_scopes(guilds: &[DiscordOAuthGuild], bot_guild_ids: &[u64]) -> Vec<GuildScope> {
+    guilds
+        .iter()
+        .filter_map(|guild| {
+            let guild_id = guild.id.parse::<u64>().ok()?;
+            bot_guild_ids.contains(&guild_id).then(|| GuildScope {
+                guild_id,
+                name: guild.name.clone(),
+                role: guild_role_from_permissions(guild.owner, guild.permissions.as_deref()),
+            })
+        })
+        .collect()
+}
+
+pub fn authorize_url(config: &DiscordOAuthConfig, state: &str) -> Result<String, String> {
+    reqwest::Url::parse_with_params(
+        DISCORD_AUTHORIZE_URL,
+        &[
+            ("client_id", config.client_id.as_str()),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", "identify guilds"),
+            ("state", state),
+            ("prompt", "none"),
+        ],
+    )
+    .map(|url| url.to_string())
+    .map_err(|e| e.to_string())
+}
+
+async fn discord_json(request: reqwest::RequestBuilder) -> Result<Value, String> {
+    let response = request.send().await.map_err(|e| format!("Discord 요청 실패: {}", e))?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| format!("Discord 응답을 읽지 못했습니다: {}", e))?;
+    if !status.is_success() {
+        let body: String = text.chars().take(200).collect();
+        return Err(format!("Discord 요청 실패 ({}): {}", status.as_u16(), body));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("Discord 응답 JSON 파싱 실패: {}", e))
+}
+
+// 인가 코드를 액세스 토큰으로 바꿉니다.
+pub async fn exchange_code(config: &DiscordOAuthConfig, code: &str) -> Result<String, String> {
+    // reqwest 의 form 기능 없이 application/x-www-form-urlencoded 본문을 만듭니다.
+    let form = reqwest::Url::parse_with_params(
+        "http://localhost/",
+        &[
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", config.redirect_uri.as_str()),
+        ],
+    )
+    .map_err(|e| e.to_string())?
+    .query()
+    .unwrap_or_default()
+    .to_string();
+    let json = discord_json(
+        reqwest::Client::new()
+            .post(format!("{}/oauth2/token", DISCORD_API_URL))
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(form),
+    )
+    .await?;
+    json.get("access_token")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or("Discord 액세스 토큰이 없습니다.".to_string())
+}
+
+pub async fn fetch_discord_user(access_token: &str) -> Result<DiscordOAuthUser, String> {
+    let json = discord_json(reqwest::Client::new().get(format!("{}/users/@me", DISCORD_API_URL)).bearer_auth(access_token)).await?;
+    serde_json::from_value(json).map_err(|e| e.to_string())
+}
+
+pub async fn fetch_discord_guilds(access_token: &str) -> Result<Vec<DiscordOAuthGuild>, String> {
+    let json = discord_json(reqwest::Client::new().get(format!("{}/users/@me/guilds", DISCORD_API_URL)).bearer_auth(access_token)).await?;
+    serde_json::from_value(json).map_err(|e| e.to_string())
+}
+
+pub async fn load_bot_guild_ids() -> Result<Vec<u64>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let guilds = tb_discord_guilds::Entity::find().all(db).await.map_err(|e| e.to_string())?;
+    Ok(guilds.into_iter().map(|g| g.guild_id as u64).collect())
+}
+
+async fn insert_token(
+    kind: &str,
+    user_id: u64,
+    user_name: Option<String>,
+    name: Option<String>,
+    guilds: &[GuildScope],
+    ttl: Duration,
+) -> Result<(String, tb_web_auth_token::Model), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let token = generate_token();
+    let now = Utc::now();
+    let row = tb_web_auth_token::ActiveModel {
+        token_hash: sea_orm::Set(hash_token(&token)),
+        kind: sea_orm::Set(kind.to_string()),
+        user_id: sea_orm::Set(user_id as i64),
+        user_name: sea_orm::Set(user_name),
+        name: sea_orm::Set(name),
+        guild_scopes: sea_orm::Set(serde_json::to_value(guilds).map_err(|e| e.to_string())?),
+        created_at: sea_orm::Set(now.into()),
+        expires_at: sea_orm::Set((now + ttl).into()),
+        last_used_at: sea_orm::Set(None),
+        ..Default::default()
+    };
+    let row = tb_web_auth_token::Entity::insert(row)
+        .exec_with_returning(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((token, row))
+}
+
+// OAuth 로그인을 마치고 세션 토큰을 만듭니다.
+pub async fn create_session(user: &DiscordOAuthUser, guilds: &[GuildScope]) -> Result<String, String> {
+    let user_id = user.id.parse::<u64>().map_err(|e| e.to_string())?;
+    let user_name = Some(user.global_name.clone().unwrap_or(user.username.clone()));
+    let (token, _) = insert_token(TOKEN_KIND_SESSION, user_id, user_name, None, guilds, Duration::hours(*WEB_SESSION_TTL_HOURS)).await?;
+    Ok(token)
+}
+
+// 스크립트용 토큰. guild_ids 를 주면 그 길드로만 범위를 좁힙니다. 발급한 사람의 권한을 넘지 않습니다.
+pub async fn issue_api_token(
+    user: &AuthUser,
+    name: Option<String>,
+    guild_ids: Option<&[u64]>,
+    days: Option<i64>,
+) -> Result<(String, ApiTokenInfo), String> {
+    let guilds = match guild_ids {
+        Some(ids) => {
+            if let Some(missing) = ids.iter().find(|id| !user.guilds.iter().any(|g| g.guild_id == **id)) {
+                return Err(format!("guild {} 에 접근할 수 없습니다.", missing));
+            }
+            user.guilds.iter().filter(|g| ids.contains(&g.guild_id)).cloned().collect::<Vec<_>>()
+        }
+        None => user.guilds.clone(),
+    };
+    let days = days.unwrap_or(API_TOKEN_DEFAULT_DAYS).clamp(1, API_TOKEN_MAX_DAYS);
+    let name = name.map(|n| n.trim().chars().take(100).collect::<String>()).filter(|n| !n.is_empty());
+    let (token, row) = insert_token(TOKEN_KIND_API, user.user_id, user.user_name.clone(), name, &guilds, Duration::days(days)).await?;
+    Ok((token, to_token_info(&row)))
+}
+
+fn to_token_info(row: &tb_web_auth_token::Model) -> ApiTokenInfo {
+    let guilds: Vec<GuildScope> = serde_json::from_value(row.guild_scopes.clone()).unwrap_or_default();
+    ApiTokenInfo {
+        id: row.id,
+        name: row.name.clone(),
+        guild_ids: guilds.iter().map(|g| g.guild_id).collect(),
+        created_at: row.created_at.to_rfc3339(),
+        expires_at: row.expires_at.to_rfc3339(),
+        last_used_at: row.last_used_at.map(|t| t.to_rfc3339()),
+    }
+}
+
+// 토큰 원문으로 사용자를 찾습니다. 만료된 토큰은 지웁니다.
+pub async fn find_auth_user(token: &str) -> Result<Option<AuthUser>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let Some(row) = tb_web_auth_token::Entity::find()
+        .filter(tb_web_auth_token::Column::TokenHash.eq(hash_token(token)))
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+    let now = Utc::now();
+    if row.expires_at < now {
+        tb_web_auth_token::Entity::delete_by_id(row.id).exec(db).await.map_err(|e| e.to_string())?;
+        return Ok(None);
+    }
+    if row.kind == TOKEN_KIND_API {
+        let mut active: tb_web_auth_token::ActiveModel = row.clone().into();
+        active.last_used_at = sea_orm::Set(Some(now.into()));
+        tb_web_auth_token::Entity::update(active).exec(db).await.map_err(|e| e.to_string())?;
+    }
+    Ok(Some(AuthUser {
+        user_id: row.user_id as u64,
+        user_name: row.user_name.clone(),
+        token_id: row.id,
+        kind: row.kind.clone(),
+        is_owner: *MANAGER_ID != 0 && row.user_id == *MANAGER_ID,
+        guilds: serde_json::from_value(row.guild_scopes).unwrap_or_default(),
+    }))
+}
+
+pub async fn delete_token(token_id: i64) -> Result<(), String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    tb_web_auth_token::Entity::delete_by_id(token_id).exec(db).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub async fn list_api_tokens(user_id: u64) -> Result<Vec<ApiTokenInfo>, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let rows = tb_web_auth_token::Entity::find()
+        .filter(tb_web_auth_token::Column::UserId.eq(user_id as i64))
+        .filter(tb_web_auth_token::Column::Kind.eq(TOKEN_KIND_API))
+        .order_by_desc(tb_web_auth_token::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.iter().map(to_token_info).collect())
+}
+
+// 본인이 발급한 API 토큰만 지울 수 있습니다. 지운 것이 없으면 false.
+pub async fn revoke_api_token(user_id: u64, token_id: i64) -> Result<bool, String> {
+    let db = DB_CONNECTION_POOL.get().ok_or("DB Connection Error".to_string())?;
+    let result = tb_web_auth_token::Entity::delete_many()
+        .filter(tb_web_auth_token::Column::Id.eq(token_id))
+        .filter(tb_web_auth_token::Column::UserId.eq(user_id as i64))
+        .filter(tb_web_auth_token::Column::Kind.eq(TOKEN_KIND_API))
+        .exec(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected > 0)
+}
@@ -2,7 +2,6 @@ use std::collections::HashMap;
 use std::{collections::hash_map, env};
 use std::sync::LazyLock;
 
-use serde_json::json;
 use serenity::all::User;
 use serenity::model::user;
 use sqlx::types::chrono;
@@ -11,11 +10,20 @@ use sqlx::types::chrono;
 use gemini_live_api::types::{
     GeminiCodeExecutionTool, GeminiGenerationConfig, GeminiGenerationConfigTool, GeminiGoogleSearchTool, HarmBlockThreshold, SafetySetting, ThinkingConfig, UrlContext
 };
-use crate::{gemini::{types::{GeminiBotTools, GeminiChatChunk}, utils::generate_fns_to_gemini}, libs::logger::LOGGER};
+use crate::{gemini::{types::{GeminiBotTools, GeminiChatChunk, GeminiModelPrice}, utils::generate_fns_to_gemini}, libs::logger::{LogLevel, LOGGER}};
 
 pub const GEMINI_MODEL_PRO : &str = "gemini-3-pro-preview";
 pub const GEMINI_MODEL_FLASH: &str = "gemini-flash-latest"; 
 pub const GEMINI_NANO_BANANA: &str = "gemini-2.5-flash-image";
+pub const GEMINI_MODEL_LIVE: &str = "gemini-2.5-flash-native-audio-preview-09-2025";
+pub const GEMINI_MODEL_LIVE_TEXT: &str = "gemini-live-2.5-flash-preview";
+pub const GEMINI_LIVE_URL: &str = "wss://generativelanguage.googleapis.com/ws/google.ai.generativelanguage.v1beta.GenerativeService.BidiGenerateContent";
+// Live 세션 컨텍스트가 이만큼 쌓이면 앞부분을 잘라 목표 크기로 줄입니다.
+pub const GEMINI_LIVE_COMPRESSION_TRIGGER_TOKENS: i64 = 25600;
+pub const GEMINI_LIVE_COMPRESSION_TARGET_TOKENS: i64 = 12800;
+// 동시에 유지할 Live 세션 수와, 송수신이 없을 때 세션을 닫기까지의 시간
+pub const GEMINI_LIVE_MAX_SESSIONS: usize = 16;
+pub const GEMINI_LIVE_IDLE_TIMEOUT_SECS: u64 = 600;
 
 pub static MANAGER_ID: LazyLock<i64> = LazyLock::new(|| {
     env::var("MANAGER_ID").unwrap_or_default().parse::<i64>().unwrap_or(0)
@@ -256,7 +264,10 @@ pub static GEMINI_BOT_TOOLS: LazyLock<hash_map::HashMap<String, GeminiBotTools>>
         searching,
         web_connect,
         image_generate,
-        audio_generate
+        audio_generate,
+        query_transcripts,
+        record_debt,
+        query_debts
     )
     .into_iter()
     .map(|tool| (tool.name.clone(), tool))
@@ -276,41 +287,60 @@ pub fn get_gemini_bot_tools()-> Vec<GeminiGenerationConfigTool> {
             function_declarations,
             ..Default::default()
         },
-        // GeminiGenerationConfigTool {
-        //     url_context:Some(UrlContext{}),
-        //     ..Default::default()
-        // },
-        // GeminiGenerationConfigTool {
-        //     google_search:Some(GeminiGoogleSearchTool{}),
-        //     ..Default::default()
-        // },
-        // GeminiGenerationConfigTool {
-        //     code_execution:Some(GeminiCodeExecutionTool{}),
-        //     ..Default::default()
-        // }
+        // url_context, google_search, code_execution 은 길드 설정에 따라
+        // gemini::builtin_tools::GuildBuiltinTools::config_tools 에서 붙입니다.
     ]
 }
 
 use gemini_live_api::types::{HarmCategory};
 
-fn generate_safety_settings_for_gemini() -> serde_json::Value {
-    json!(
-        vec![
-            SafetySetting{
-                category:HarmCategory::HarmCategorySexuallyExplicit,
-                threshold: HarmBlockThreshold::BlockNone
-            },
-        ]
-    )
+fn generate_safety_settings_for_gemini() -> Vec<SafetySetting> {
+    vec![
+        SafetySetting{
+            category:HarmCategory::HarmCategorySexuallyExplicit,
+            threshold: HarmBlockThreshold::BlockNone
+        },
+    ]
 }
 
 
 pub static GEMINI_BOT_TOOLS_JSON: LazyLock<Vec<GeminiGenerationConfigTool>> = LazyLock::new(|| {
     get_gemini_bot_tools()
 });
-pub static SAFETY_SETTINGS: LazyLock<serde_json::Value> = LazyLock::new(|| {
+pub static SAFETY_SETTINGS: LazyLock<Vec<SafetySetting>> = LazyLock::new(|| {
     generate_safety_settings_for_gemini()
 });
 pub static GENERATE_CONF: LazyLock<GeminiGenerationConfig> = LazyLock::new(|| {
     get_gemini_generate_config()
-});
\ No newline at end of file
+});
+// 기본 단가표 (USD / 100만 토큰). GEMINI_PRICE_TABLE 에 JSON 파일 경로를 주면 같은 모델 항목을 덮어씁니다.
+// 예: {"gemini-flash-latest": {"input": 0.3, "cached_input": 0.03, "output": 2.5}}
+fn default_gemini_price_table() -> HashMap<String, GeminiModelPrice> {
+    HashMap::from([
+        (GEMINI_MODEL_PRO.to_string(), GeminiModelPrice { input: 2.0, cached_input: 0.2, output: 12.0 }),
+        ("gemini-2.5-pro".to_string(), GeminiModelPrice { input: 1.25, cached_input: 0.125, output: 10.0 }),
+        (GEMINI_MODEL_FLASH.to_string(), GeminiModelPrice { input: 0.3, cached_input: 0.03, output: 2.5 }),
+        ("gemini-2.5-flash".to_string(), GeminiModelPrice { input: 0.3, cached_input: 0.03, output: 2.5 }),
+        ("gemini-2.5-flash-lite".to_string(), GeminiModelPrice { input: 0.1, cached_input: 0.01, output: 0.4 }),
+        (GEMINI_NANO_BANANA.to_string(), GeminiModelPrice { input: 0.3, cached_input: 0.03, output: 30.0 }),
+    ])
+}
+
+fn load_gemini_price_table() -> HashMap<String, GeminiModelPrice> {
+    let mut table = default_gemini_price_table();
+    let Ok(path) = env::var("GEMINI_PRICE_TABLE") else {
+        return table;
+    };
+    let loaded = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| serde_json::from_str::<HashMap<String, GeminiModelPrice>>(&raw).map_err(|e| e.to_string()));
+    match loaded {
+        Ok(overrides) => table.extend(overrides),
+        Err(e) => LOGGER.log(LogLevel::Warning, &format!("Gemini 단가표({})를 읽지 못해 기본값을 씁니다: {}", path, e)),
+    }
+    table
+}
+
+pub static GEMINI_PRICE_TABLE: LazyLock<HashMap<String, GeminiModelPrice>> = LazyLock::new(|| {
+    load_gemini_price_table()
+});
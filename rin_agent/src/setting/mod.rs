@@ -1 +1,6 @@
-pub mod gemini_setting;
\ No newline at end of file
+pub mod gemini_setting;
+pub mod quota_setting;
+pub mod search_setting;
+pub mod web_auth_setting;
+pub mod supervisor_setting;
+pub mod mqtt_setting;
\ No newline at end of file
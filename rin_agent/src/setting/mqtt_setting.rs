@@ -0,0 +1,19 @@
+use std::{env, sync::LazyLock, time::Duration};
+
+// 매니저와 같은 .env 를 쓸 수 있도록 MQTT_CLIENT_ID 대신 따로 읽습니다.
+pub static RIN_AGENT_MQTT_CLIENT_ID: LazyLock<String> = LazyLock::new(|| {
+    env::var("RIN_AGENT_MQTT_CLIENT_ID")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "rin_agent".to_string())
+});
+
+// rin_agent/status 로 Running 상태를 보내는 주기
+pub static RIN_AGENT_STATUS_INTERVAL: LazyLock<Duration> = LazyLock::new(|| {
+    env::var("RIN_AGENT_STATUS_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(30))
+});
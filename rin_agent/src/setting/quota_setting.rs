@@ -0,0 +1,97 @@
+use std::{collections::HashMap, env, sync::LazyLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::libs::logger::{LogLevel, LOGGER};
+
+// 한 범위(사용자/길드)에 적용되는 제한. 0 은 제한 없음입니다.
+// burst 나 refill_per_minute 중 하나라도 0 이면 요청 횟수는 제한하지 않습니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuotaLimits {
+    // 토큰 버킷 크기: 연속으로 보낼 수 있는 요청 수
+    pub burst: u32,
+    // 버킷이 분당 다시 채워지는 요청 수
+    pub refill_per_minute: f64,
+    // 하루(UTC) 동안 쓸 수 있는 총 토큰 수
+    #[serde(default)]
+    pub daily_tokens: i64,
+    // 하루(UTC) 동안 쓸 수 있는 예상 비용 (USD)
+    #[serde(default)]
+    pub daily_cost_usd: f64,
+    // thinking_bought 상한
+    #[serde(default)]
+    pub max_thinking_budget: Option<i32>,
+}
+
+impl QuotaLimits {
+    // 토큰 버킷으로 요청 횟수를 제한하는지
+    pub fn limits_requests(&self) -> bool {
+        self.burst > 0 && self.refill_per_minute > 0.0
+    }
+}
+
+// RIN_QUOTA_CONFIG 에 JSON 파일 경로를 주면 전체를 덮어씁니다.
+// roles 는 역할 ID -> 사용자 제한이며, 여러 역할이 있으면 일일 비용 한도가 가장 큰 것을 씁니다.
+// pro_roles 가 비어 있으면 누구나 Pro 모델을 쓸 수 있습니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    #[serde(default = "default_user_limits")]
+    pub user: QuotaLimits,
+    #[serde(default = "default_guild_limits")]
+    pub guild: QuotaLimits,
+    #[serde(default)]
+    pub roles: HashMap<u64, QuotaLimits>,
+    #[serde(default)]
+    pub pro_roles: Vec<u64>,
+}
+
+fn default_user_limits() -> QuotaLimits {
+    QuotaLimits {
+        burst: 5,
+        refill_per_minute: 3.0,
+        daily_tokens: 2_000_000,
+        daily_cost_usd: 1.0,
+        max_thinking_budget: Some(8192),
+    }
+}
+
+fn default_guild_limits() -> QuotaLimits {
+    QuotaLimits {
+        burst: 30,
+        refill_per_minute: 20.0,
+        daily_tokens: 20_000_000,
+        daily_cost_usd: 10.0,
+        max_thinking_budget: None,
+    }
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        QuotaConfig {
+            user: default_user_limits(),
+            guild: default_guild_limits(),
+            roles: HashMap::new(),
+            pro_roles: Vec::new(),
+        }
+    }
+}
+
+fn load_quota_config() -> QuotaConfig {
+    let Ok(path) = env::var("RIN_QUOTA_CONFIG") else {
+        return QuotaConfig::default();
+    };
+    let loaded = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| serde_json::from_str::<QuotaConfig>(&raw).map_err(|e| e.to_string()));
+    match loaded {
+        Ok(config) => config,
+        Err(e) => {
+            LOGGER.log(LogLevel::Warning, &format!("Quota > 설정({})을 읽지 못해 기본값을 씁니다: {}", path, e));
+            QuotaConfig::default()
+        }
+    }
+}
+
+pub static QUOTA_CONFIG: LazyLock<QuotaConfig> = LazyLock::new(|| {
+    load_quota_config()
+});
@@ -0,0 +1,27 @@
+use std::{env, sync::LazyLock};
+
+use crate::api::search_provider::SearchProviderKind;
+use crate::libs::logger::{LogLevel, LOGGER};
+
+// 길드에 따로 정한 검색 제공자가 없을 때 쓰는 기본값 (SEARCH_PROVIDER)
+pub static DEFAULT_SEARCH_PROVIDER: LazyLock<SearchProviderKind> = LazyLock::new(|| {
+    let value = env::var("SEARCH_PROVIDER").unwrap_or_default();
+    if value.trim().is_empty() {
+        return SearchProviderKind::Google;
+    }
+    SearchProviderKind::parse(&value).unwrap_or_else(|| {
+        LOGGER.log(LogLevel::Warning, &format!("Search > 알 수 없는 SEARCH_PROVIDER: {}, google 을 씁니다.", value));
+        SearchProviderKind::Google
+    })
+});
+
+// 검색 결과를 Redis 에 보관하는 시간 (초). 0 이면 캐시하지 않습니다.
+pub static SEARCH_CACHE_TTL_SECS: LazyLock<u64> = LazyLock::new(|| {
+    env::var("SEARCH_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(3600)
+});
+
+// 한 번에 돌려줄 검색 결과 수
+pub const SEARCH_RESULT_COUNT: usize = 8;
@@ -0,0 +1,24 @@
+use std::{env, sync::LazyLock, time::Duration};
+
+use crate::libs::task_supervisor::RestartPolicy;
+
+// SUPERVISOR_CRASH_WINDOW_SECS 동안 SUPERVISOR_CRASH_BUDGET 번을 넘게 죽은 작업이 있으면 프로세스를 끝냅니다.
+pub static SUPERVISOR_POLICY: LazyLock<RestartPolicy> = LazyLock::new(|| {
+    let read = |key: &str| env::var(key).ok().and_then(|v| v.trim().parse::<u64>().ok()).filter(|v| *v > 0);
+    let defaults = RestartPolicy::default();
+    RestartPolicy {
+        initial_backoff: read("SUPERVISOR_INITIAL_BACKOFF_SECS").map(Duration::from_secs).unwrap_or(defaults.initial_backoff),
+        max_backoff: read("SUPERVISOR_MAX_BACKOFF_SECS").map(Duration::from_secs).unwrap_or(defaults.max_backoff),
+        crash_budget: read("SUPERVISOR_CRASH_BUDGET").map(|v| v as usize).unwrap_or(defaults.crash_budget),
+        crash_window: read("SUPERVISOR_CRASH_WINDOW_SECS").map(Duration::from_secs).unwrap_or(defaults.crash_window),
+    }
+});
+
+// 종료할 때 진행 중인 Gemini 요청과 도구 호출을 기다리는 최대 시간
+pub static SHUTDOWN_DRAIN_TIMEOUT: LazyLock<Duration> = LazyLock::new(|| {
+    env::var("SHUTDOWN_DRAIN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(30))
+});
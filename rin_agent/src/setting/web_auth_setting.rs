@@ -0,0 +1,55 @@
+use std::{env, sync::LazyLock};
+
+// Discord OAuth2 앱 설정. 봇과 같은 애플리케이션을 씁니다.
+#[derive(Debug, Clone)]
+pub struct DiscordOAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    // 예: https://rin.example.org/api/auth/callback (Discord 개발자 포털에 등록한 주소와 같아야 합니다)
+    pub redirect_uri: String,
+}
+
+impl DiscordOAuthConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let read = |key: &str| {
+            env::var(key)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .ok_or(format!("{} is not set", key))
+        };
+        Ok(DiscordOAuthConfig {
+            client_id: read("DISCORD_CLIENT_ID")?,
+            client_secret: read("DISCORD_CLIENT_SECRET")?,
+            redirect_uri: read("DISCORD_OAUTH_REDIRECT_URI")?,
+        })
+    }
+}
+
+pub const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+pub const DISCORD_AUTHORIZE_URL: &str = "https://discord.com/oauth2/authorize";
+pub const SESSION_COOKIE: &str = "rin_session";
+pub const OAUTH_STATE_COOKIE: &str = "rin_oauth_state";
+
+// 로그인 세션 유지 시간 (시간)
+pub static WEB_SESSION_TTL_HOURS: LazyLock<i64> = LazyLock::new(|| {
+    env::var("WEB_SESSION_TTL_HOURS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(24 * 7)
+});
+
+// 스크립트용 API 토큰의 기본/최대 유효 기간 (일)
+pub const API_TOKEN_DEFAULT_DAYS: i64 = 90;
+pub const API_TOKEN_MAX_DAYS: i64 = 365;
+
+// https 로 서비스할 때만 Secure 쿠키를 씁니다. 비우면 리다이렉트 주소로 판단합니다.
+pub static WEB_COOKIE_SECURE: LazyLock<bool> = LazyLock::new(|| match env::var("WEB_COOKIE_SECURE") {
+    Ok(v) if !v.trim().is_empty() => v.trim().eq_ignore_ascii_case("true") || v.trim() == "1",
+    _ => env::var("DISCORD_OAUTH_REDIRECT_URI").unwrap_or_default().starts_with("https://"),
+});
+
+// /metrics 를 읽을 때 필요한 Bearer 토큰. 비우면 /metrics 를 열지 않습니다.
+pub static METRICS_TOKEN: LazyLock<Option<String>> = LazyLock::new(|| {
+    env::var("METRICS_TOKEN").ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+});
@@ -0,0 +1,67 @@
+#[cfg(test)]
+use gemini_live_api::types::enums::GeminiCodeExecutionResultOutcome;
+use gemini_live_api::types::{GeminiToolConfigMode, GroundingMetadata};
+use serde_json::json;
+
+use crate::gemini::builtin_tools::{add_citation_markers, format_code_execution, format_sources, render_grounded_text, GuildBuiltinTools};
+use crate::gemini::types::GeminiCodeExecution;
+use crate::setting::gemini_setting::GEMINI_BOT_TOOLS_JSON;
+
+fn sample_metadata() -> GroundingMetadata {
+    serde_json::from_value(json!({
+        "groundingChunks": [
+            {"web": {"uri": "https://example.com/a", "title": "example.com"}},
+            {"web": {"uri": "https://news.example.org/b", "title": "news.example.org"}}
+        ],
+        "groundingSupports": [
+            {"segment": {"startIndex": 0, "endIndex": 16, "text": "서울은 수도입니다."}, "groundingChunkIndices": [0]},
+            {"segment": {"text": "인구는 약 940만 명입니다."}, "groundingChunkIndices": [0, 1]},
+            {"segment": {"text": "본문에 없는 문장"}, "groundingChunkIndices": [1]}
+        ],
+        "webSearchQueries": ["서울 인구"]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn citation_markers_follow_segments() {
+    let text = "서울은 수도입니다. 인구는 약 940만 명입니다.";
+    assert_eq!(
+        add_citation_markers(text, &sample_metadata()),
+        "서울은 수도입니다.[1] 인구는 약 940만 명입니다.[1][2]"
+    );
+}
+
+#[test]
+fn sources_are_numbered_links() {
+    assert_eq!(
+        format_sources(&sample_metadata()).unwrap(),
+        "-# 출처\n-# [1] [example.com](<https://example.com/a>)\n-# [2] [news.example.org](<https://news.example.org/b>)\n-# 검색어: 서울 인구"
+    );
+    assert_eq!(render_grounded_text("그대로", None), "그대로");
+}
+
+#[test]
+fn builtin_tools_switch_calling_mode() {
+    let tools = GuildBuiltinTools { google_search: true, url_context: false, code_execution: true };
+    assert!(tools.any());
+    assert_eq!(tools.config_tools().len(), GEMINI_BOT_TOOLS_JSON.len() + 2);
+    let config = tools.function_calling_config();
+    assert!(matches!(config.mode, Some(GeminiToolConfigMode::Auto)));
+    assert!(config.allowed_function_names.is_none());
+    assert!(!GuildBuiltinTools::default().any());
+}
+
+#[test]
+fn code_execution_hides_code_behind_spoiler() {
+    let execution = GeminiCodeExecution {
+        language: "python".to_string(),
+        code: "print(1 + 1)\n".to_string(),
+        outcome: Some(GeminiCodeExecutionResultOutcome::OutcomeOk),
+        output: Some("2\n".to_string()),
+    };
+    assert_eq!(
+        format_code_execution(&execution),
+        "**코드** (눌러서 펼치기)\n||```python\nprint(1 + 1)\n```||\n**출력**\n```\n2\n```"
+    );
+}
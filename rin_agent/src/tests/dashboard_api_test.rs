@@ -0,0 +1,118 @@
+#[cfg(test)]
+use chrono::{TimeZone, Utc};
+use entity::tb_alarm_model;
+use sea_orm::{DbBackend, QueryTrait};
+
+use crate::service::alarm_service::{normalize_alarm_message, parse_alarm_time, AlarmView, MAX_ALARM_MESSAGE_CHARS};
+use crate::service::conversation_service::{can_view_conversation, escape_like, preview_text, visible_contexts};
+use crate::tests::test_support::auth_user;
+use crate::web::api::usage::authorize_usage_filter;
+use crate::web::api::v1::pagination::{Page, PageParams, DEFAULT_PER_PAGE, MAX_PAGE, MAX_PER_PAGE};
+
+#[test]
+fn clamps_page_params() {
+    assert_eq!(PageParams::new(None, None), PageParams { page: 1, per_page: DEFAULT_PER_PAGE });
+    assert_eq!(PageParams::new(Some(0), Some(0)), PageParams { page: 1, per_page: 1 });
+    assert_eq!(PageParams::new(Some(3), Some(1000)).per_page, MAX_PER_PAGE);
+    let last = PageParams::new(Some(u64::MAX), Some(MAX_PER_PAGE));
+    assert_eq!(last.page, MAX_PAGE);
+    assert!(last.offset() <= i64::MAX as u64);
+    assert_eq!(PageParams::new(Some(3), Some(25)).offset(), 50);
+}
+
+#[test]
+fn counts_total_pages() {
+    let params = PageParams::new(Some(2), Some(20));
+    let page = Page::new(vec![1, 2, 3], params, 41);
+    assert_eq!(page.total_pages, 3);
+    assert_eq!(page.page, 2);
+    assert_eq!(Page::<i32>::new(vec![], params, 0).total_pages, 0);
+    assert_eq!(Page::<i32>::new(vec![], params, 40).total_pages, 2);
+}
+
+#[test]
+fn escapes_like_wildcards() {
+    assert_eq!(escape_like("50%_off"), "50\\%\\_off");
+    assert_eq!(escape_like("a\\b"), "a\\\\b");
+    assert_eq!(escape_like("날씨"), "날씨");
+}
+
+#[test]
+fn truncates_preview_by_chars() {
+    assert_eq!(preview_text("  안녕\n  하세요 ", 10), "안녕 하세요");
+    assert_eq!(preview_text("가나다라마바", 4), "가나다…");
+}
+
+#[test]
+fn checks_conversation_visibility() {
+    let user = auth_user(false);
+    // 관리하는 길드는 누구의 대화든 볼 수 있다.
+    assert!(can_view_conversation(&user, 100, Some(1)));
+    // 멤버인 길드는 본인 대화만
+    assert!(can_view_conversation(&user, 200, Some(7)));
+    assert!(!can_view_conversation(&user, 200, Some(1)));
+    // 나간 길드의 대화는 본인 것이어도 숨긴다.
+    assert!(!can_view_conversation(&user, 300, Some(7)));
+    // DM
+    assert!(can_view_conversation(&user, 0, Some(7)));
+    assert!(!can_view_conversation(&user, 0, Some(1)));
+    assert!(!can_view_conversation(&user, 0, None));
+    assert!(can_view_conversation(&auth_user(true), 300, Some(1)));
+}
+
+#[test]
+fn tree_queries_apply_visibility() {
+    // 위아래 대화 조회도 목록과 같은 가시성 조건을 건다.
+    let sql = visible_contexts(&auth_user(false)).build(DbBackend::Postgres).to_string();
+    assert!(sql.contains(r#""root_message"."user_id" = 7"#), "{}", sql);
+    assert!(sql.contains(r#""tb_discord_ai_context"."guild_id" IN (100)"#), "{}", sql);
+}
+
+#[test]
+fn validates_alarm_input() {
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    let time = parse_alarm_time("2026-01-01T10:00:00+09:00", now).unwrap();
+    assert_eq!(time.with_timezone(&Utc), Utc.with_ymd_and_hms(2026, 1, 1, 1, 0, 0).unwrap());
+    assert!(parse_alarm_time("2026-01-01T09:00:00+09:00", now).is_err());
+    assert!(parse_alarm_time("내일 아침", now).is_err());
+
+    assert_eq!(normalize_alarm_message("  물 마시기 ").unwrap(), "물 마시기");
+    assert!(normalize_alarm_message("   ").is_err());
+    assert!(normalize_alarm_message(&"a".repeat(MAX_ALARM_MESSAGE_CHARS + 1)).is_err());
+}
+
+#[test]
+fn converts_alarm_model_to_view() {
+    let time = Utc.with_ymd_and_hms(2026, 1, 1, 1, 0, 0).unwrap().fixed_offset();
+    let view = AlarmView::from(tb_alarm_model::Model {
+        id: 3,
+        time,
+        message: "회의".to_string(),
+        repeat_circle: None,
+        repeat_end_at: None,
+        created_at: time,
+        updated_at: time,
+        user_id: 7,
+        user_name: "rin".to_string(),
+        channel_id: 42,
+    });
+    assert_eq!(view.id, 3);
+    assert_eq!(view.time, "2026-01-01T01:00:00+00:00");
+    assert_eq!(view.channel_id, 42);
+}
+
+#[test]
+fn authorizes_usage_filters() {
+    let user = auth_user(false);
+    assert!(authorize_usage_filter(&user, Some(100), None).is_ok());
+    assert!(authorize_usage_filter(&user, Some(100), Some(8)).is_ok());
+    // 일반 멤버인 길드의 사용량에는 다른 멤버 사용량이 들어 있다.
+    assert!(authorize_usage_filter(&user, Some(200), None).is_err());
+    assert!(authorize_usage_filter(&user, None, Some(7)).is_ok());
+    assert!(authorize_usage_filter(&user, None, Some(8)).is_err());
+    assert!(authorize_usage_filter(&user, None, None).is_err());
+
+    let owner = auth_user(true);
+    assert!(authorize_usage_filter(&owner, Some(300), None).is_ok());
+    assert!(authorize_usage_filter(&owner, None, None).is_ok());
+}
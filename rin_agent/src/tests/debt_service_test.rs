@@ -0,0 +1,73 @@
+#[cfg(test)]
+use chrono::{TimeZone, Utc};
+use entity::{tb_debt_receipt, tb_debtor};
+
+use crate::service::debt_service::{format_amount, normalize_debtor_name, summarize_balances};
+
+fn debtor(id: i32, name: &str) -> tb_debtor::Model {
+    tb_debtor::Model {
+        id,
+        name: name.to_string(),
+        created_at: Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap().naive_utc(),
+        owner_id: 1,
+    }
+}
+
+fn receipt(id: i64, debtor_id: i32, amount: i64, is_cleared: bool) -> tb_debt_receipt::Model {
+    tb_debt_receipt::Model {
+        id,
+        debtor_id,
+        amount,
+        created_at: Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap().into(),
+        is_cleared,
+        cleared_at: None,
+        memo: None,
+        image_id: None,
+    }
+}
+
+#[test]
+fn formats_amounts_with_separators() {
+    assert_eq!(format_amount(0), "0원");
+    assert_eq!(format_amount(999), "999원");
+    assert_eq!(format_amount(12300), "12,300원");
+    assert_eq!(format_amount(-1234567), "-1,234,567원");
+}
+
+#[test]
+fn normalizes_debtor_names() {
+    assert_eq!(normalize_debtor_name("  김   민수 ").unwrap(), "김 민수");
+    assert!(normalize_debtor_name("   ").is_err());
+    assert!(normalize_debtor_name(&"가".repeat(51)).is_err());
+    assert!(normalize_debtor_name(&"가".repeat(50)).is_ok());
+}
+
+#[test]
+fn summarizes_outstanding_balances() {
+    let debtors = vec![debtor(1, "민수"), debtor(2, "지연"), debtor(3, "철수"), debtor(4, "영희")];
+    let receipts = vec![
+        receipt(1, 1, 10000, false),
+        receipt(2, 1, 5000, false),
+        receipt(3, 1, 3000, true),
+        receipt(4, 2, -7000, false),
+        receipt(5, 3, 2000, true),
+        receipt(6, 4, 15000, false),
+    ];
+
+    let summary = summarize_balances(&debtors, &receipts);
+
+    let names = summary.balances.iter().map(|b| b.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["민수", "영희", "철수", "지연"]);
+    let minsu = &summary.balances[0];
+    assert_eq!((minsu.outstanding, minsu.open_count, minsu.cleared_total, minsu.total_count), (15000, 2, 3000, 3));
+    assert_eq!(summary.balances[2].outstanding, 0);
+    assert_eq!(summary.total_receivable, 30000);
+    assert_eq!(summary.total_payable, 7000);
+}
+
+#[test]
+fn empty_ledger_has_no_totals() {
+    let summary = summarize_balances(&[], &[]);
+    assert!(summary.balances.is_empty());
+    assert_eq!((summary.total_receivable, summary.total_payable), (0, 0));
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+use std::time::Duration;
+
+use gemini_live_api::types::rest_api_error::GeminiApiError;
+use gemini_live_api::types::rest_api_types::FinishReason;
+
+use crate::gemini::gemini_error::{GeminiError, GeminiRetryPolicy};
+use crate::gemini::model_router::ModelRoute;
+use crate::setting::gemini_setting::{GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO};
+
+#[test]
+fn api_errors_are_classified() {
+    let rate_limited = GeminiError::from(GeminiApiError::from_response(
+        429,
+        r#"{"error": {"code": 429, "message": "Resource has been exhausted", "status": "RESOURCE_EXHAUSTED"}}"#,
+    ));
+    assert_eq!(rate_limited, GeminiError::RateLimited { message: "Resource has been exhausted".to_string() });
+    assert!(rate_limited.is_transient());
+
+    let overloaded = GeminiError::from(GeminiApiError::Api {
+        http_status: 503,
+        status: Some("UNAVAILABLE".to_string()),
+        message: "The model is overloaded.".to_string(),
+    });
+    assert!(matches!(overloaded, GeminiError::ServerError { http_status: 503, .. }));
+    assert!(overloaded.is_transient());
+    assert!(overloaded.should_fallback_to_flash());
+
+    let invalid = GeminiError::from(GeminiApiError::Api {
+        http_status: 400,
+        status: Some("INVALID_ARGUMENT".to_string()),
+        message: "bad request".to_string(),
+    });
+    assert!(matches!(invalid, GeminiError::Other(_)));
+    assert!(!invalid.is_transient());
+    assert!(!invalid.should_fallback_to_flash());
+
+    let blocked = GeminiError::from(GeminiApiError::Blocked { reason: "Safety".to_string() });
+    assert_eq!(blocked, GeminiError::SafetyBlocked { reason: "Safety".to_string() });
+    assert!(!blocked.is_transient());
+}
+
+#[test]
+fn finish_reasons_are_classified() {
+    assert_eq!(
+        GeminiError::from_finish_reason(&FinishReason::ProhibitedContent),
+        Some(GeminiError::SafetyBlocked { reason: "PROHIBITED_CONTENT".to_string() })
+    );
+    assert_eq!(GeminiError::from_finish_reason(&FinishReason::Recitation), Some(GeminiError::Recitation));
+    assert_eq!(GeminiError::from_finish_reason(&FinishReason::MaxTokens), Some(GeminiError::MaxTokens));
+    assert_eq!(
+        GeminiError::from_finish_reason(&FinishReason::MalformedFunctionCall),
+        Some(GeminiError::MalformedFunctionCall)
+    );
+    assert_eq!(GeminiError::from_finish_reason(&FinishReason::Stop), None);
+    assert_eq!(GeminiError::from_finish_reason(&FinishReason::Unknown), None);
+}
+
+#[test]
+fn user_message_explains_the_block() {
+    let blocked = GeminiError::SafetyBlocked { reason: "SAFETY".to_string() };
+    assert!(blocked.user_message().contains("`SAFETY`"));
+    assert!(!blocked.user_message().contains("administrator"));
+    let server = GeminiError::ServerError { http_status: 503, message: "overloaded".to_string() };
+    assert!(server.user_message().contains("HTTP 503"));
+    // 기존 문자열 오류 형식과 맞춰 둡니다.
+    assert_eq!(String::from(GeminiError::EmptyResponse), "Gemini API > Error: Empty response");
+}
+
+#[test]
+fn backoff_is_jittered_and_capped() {
+    let policy = GeminiRetryPolicy {
+        max_attempts: 5,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(500),
+    };
+    assert_eq!(policy.backoff(1, 0.0), Duration::from_millis(50));
+    assert_eq!(policy.backoff(1, 1.0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(3, 1.0), Duration::from_millis(400));
+    assert_eq!(policy.backoff(8, 1.0), Duration::from_millis(500));
+    assert_eq!(policy.backoff(8, 7.0), Duration::from_millis(500));
+    for attempt in 1..=5 {
+        let wait = policy.jittered_backoff(attempt);
+        assert!(wait >= policy.backoff(attempt, 0.0) && wait <= policy.backoff(attempt, 1.0));
+    }
+}
+
+#[test]
+fn fallback_is_shown_in_route_footer() {
+    let route = ModelRoute::manual(true, None, "직접 선택");
+    assert_eq!(route.clone().with_response_model(GEMINI_MODEL_PRO), route);
+
+    let fell_back = route.with_response_model(GEMINI_MODEL_FLASH);
+    assert!(!fell_back.use_pro);
+    assert!(fell_back.routed);
+    assert_eq!(fell_back.footer(), format!("{} · 직접 선택 (Pro 과부하로 Flash)", GEMINI_MODEL_FLASH));
+}
@@ -0,0 +1,451 @@
+#[cfg(test)]
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use gemini_live_api::service::rest_client::GeminiRestClient;
+use gemini_live_api::types::enums::GeminiContentRole;
+use gemini_live_api::types::rest_api_error::GeminiApiError;
+use gemini_live_api::types::rest_api_types::{
+    BlockReason, CachedContentUpdate, CountTokensRequest, EmbedContentRequest, EmbeddingTaskType, FileState,
+    FinishReason, GenerateContentRequest, HarmProbability,
+};
+use gemini_live_api::types::{GeminiContents, GeminiParts, HarmCategory};
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::gemini::gemini_client::stream_query_to_gemini;
+use crate::gemini::types::{GeminiChatChunk, GeminiStreamEvent};
+use crate::setting::gemini_setting::get_begin_query;
+
+// 실제 API 응답을 녹화해 둔 본문들
+const GENERATE_CONTENT: &str = include_str!("fixtures/gemini_rest/generate_content.json");
+const GENERATE_FUNCTION_CALL: &str = include_str!("fixtures/gemini_rest/generate_content_function_call.json");
+const GENERATE_BLOCKED: &str = include_str!("fixtures/gemini_rest/generate_content_blocked.json");
+const STREAM_GENERATE: &str = include_str!("fixtures/gemini_rest/stream_generate_content.sse");
+const COUNT_TOKENS: &str = include_str!("fixtures/gemini_rest/count_tokens.json");
+const EMBED_CONTENT: &str = include_str!("fixtures/gemini_rest/embed_content.json");
+const CACHED_CONTENT: &str = include_str!("fixtures/gemini_rest/cached_content.json");
+const LIST_CACHED_CONTENTS: &str = include_str!("fixtures/gemini_rest/list_cached_contents.json");
+const FILE: &str = include_str!("fixtures/gemini_rest/file.json");
+const FILE_GET: &str = include_str!("fixtures/gemini_rest/file_get.json");
+const LIST_FILES: &str = include_str!("fixtures/gemini_rest/list_files.json");
+const ERROR_INVALID_ARGUMENT: &str = include_str!("fixtures/gemini_rest/error_invalid_argument.json");
+
+#[derive(Debug)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+struct FixtureResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    // 나눠 보낼 본문 조각. 스트리밍 테스트에서 청크 경계를 흉내 냅니다.
+    chunks: Vec<Vec<u8>>,
+}
+
+impl FixtureResponse {
+    fn json(status: u16, body: &str) -> Self {
+        FixtureResponse {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            chunks: vec![body.as_bytes().to_vec()],
+        }
+    }
+}
+
+// 연결마다 준비된 응답을 순서대로 하나씩 돌려주고, 받은 요청을 기록하는 서버 대역.
+async fn spawn_fixture_server(
+    responses: Vec<FixtureResponse>,
+) -> (String, mpsc::UnboundedReceiver<RecordedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut stream).await;
+            let _ = tx.send(request);
+            write_response(&mut stream, response).await;
+        }
+    });
+    (base_url, rx)
+}
+
+async fn read_request(stream: &mut TcpStream) -> RecordedRequest {
+    let mut buf = Vec::new();
+    let header_end = loop {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await.unwrap();
+        assert!(n > 0, "connection closed before headers");
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+    };
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap().split_whitespace();
+    let method = request_line.next().unwrap().to_string();
+    let path = request_line.next().unwrap().to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect::<HashMap<_, _>>();
+    let length = headers.get("content-length").and_then(|v| v.parse::<usize>().ok()).unwrap_or(0);
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < length {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await.unwrap();
+        assert!(n > 0, "connection closed before body");
+        body.extend_from_slice(&chunk[..n]);
+    }
+    RecordedRequest { method, path, headers, body }
+}
+
+async fn write_response(stream: &mut TcpStream, response: FixtureResponse) {
+    let mut head = format!("HTTP/1.1 {} Fixture\r\nConnection: close\r\n", response.status);
+    for (k, v) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await.unwrap();
+    for chunk in response.chunks {
+        stream.write_all(&chunk).await.unwrap();
+        stream.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    stream.shutdown().await.ok();
+}
+
+fn test_client(base_url: &str) -> GeminiRestClient {
+    GeminiRestClient::new("test-key".to_string()).with_base_url(base_url)
+}
+
+fn user_text(text: &str) -> GeminiContents {
+    GeminiContents { role: GeminiContentRole::User, parts: vec![GeminiParts::new().set_text(text.to_string())] }
+}
+
+#[tokio::test]
+async fn generate_content_parses_recorded_response() {
+    let (base_url, mut requests) = spawn_fixture_server(vec![FixtureResponse::json(200, GENERATE_CONTENT)]).await;
+    let request = GenerateContentRequest {
+        contents: vec![user_text("프랑스의 수도는?")],
+        cached_content: Some("cachedContents/abc".to_string()),
+        ..Default::default()
+    };
+
+    let response = test_client(&base_url).generate_content("gemini-flash-latest", &request).await.unwrap();
+
+    let recorded = requests.recv().await.unwrap();
+    assert_eq!(recorded.method, "POST");
+    assert_eq!(recorded.path, "/v1beta/models/gemini-flash-latest:generateContent");
+    assert_eq!(recorded.headers.get("x-goog-api-key").map(String::as_str), Some("test-key"));
+    let body = recorded.json();
+    assert_eq!(body["contents"][0]["parts"][0]["text"], "프랑스의 수도는?");
+    assert_eq!(body["cachedContent"], "cachedContents/abc");
+    assert!(body.get("systemInstruction").is_none());
+
+    assert_eq!(response.text().as_deref(), Some("프랑스의 수도는 파리입니다."));
+    assert_eq!(response.thoughts().as_deref(), Some("수도를 묻는 질문이다."));
+    assert_eq!(response.finish_reason(), Some(&FinishReason::Stop));
+    assert_eq!(response.response_id.as_deref(), Some("resp-1"));
+
+    let candidate = response.first_candidate().unwrap();
+    assert_eq!(candidate.safety_ratings.len(), 3);
+    assert_eq!(candidate.safety_ratings[0].probability, HarmProbability::Negligible);
+    // 모르는 카테고리는 Unspecified 로 받습니다.
+    assert!(matches!(candidate.safety_ratings[2].category, HarmCategory::HarmCategoryUnspecified));
+    assert_eq!(candidate.citation_metadata.as_ref().unwrap().citation_sources[0].end_index, 12);
+    let grounding = candidate.grounding_metadata.as_ref().unwrap();
+    assert_eq!(grounding.web_search_queries, vec!["프랑스 수도".to_string()]);
+    assert_eq!(grounding.grounding_supports[0].segment.as_ref().unwrap().start_index, 0);
+
+    let usage = response.usage_metadata.unwrap();
+    assert_eq!(usage.prompt_token_count, 9);
+    assert_eq!(usage.candidates_token_count, 8);
+    assert_eq!(usage.thoughts_token_count, 24);
+    assert_eq!(usage.total_token_count, 41);
+}
+
+#[tokio::test]
+async fn generate_content_returns_function_calls_and_blocks() {
+    let (base_url, _requests) = spawn_fixture_server(vec![
+        FixtureResponse::json(200, GENERATE_FUNCTION_CALL),
+        FixtureResponse::json(200, GENERATE_BLOCKED),
+    ])
+    .await;
+    let client = test_client(&base_url);
+    let request = GenerateContentRequest { contents: vec![user_text("안녕")], ..Default::default() };
+
+    let response = client.generate_content("models/gemini-flash-latest", &request).await.unwrap();
+    let calls = response.function_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].name, "response_msg");
+    assert_eq!(calls[0].args.as_ref().unwrap()["msg"], "안녕하세요");
+    assert_eq!(response.usage_metadata.unwrap().cached_content_token_count, 100);
+
+    let blocked = client.generate_content("gemini-flash-latest", &request).await;
+    let reason = format!("{:?}", BlockReason::ProhibitedContent);
+    assert_eq!(blocked.err(), Some(GeminiApiError::Blocked { reason }));
+}
+
+#[tokio::test]
+async fn api_errors_are_typed() {
+    let (base_url, _requests) =
+        spawn_fixture_server(vec![FixtureResponse::json(400, ERROR_INVALID_ARGUMENT), FixtureResponse::json(200, "{")])
+            .await;
+    let client = test_client(&base_url);
+    let request = GenerateContentRequest { contents: vec![user_text("안녕")], ..Default::default() };
+
+    let error = client.generate_content("gemini-flash-latest", &request).await.unwrap_err();
+    assert_eq!(
+        error,
+        GeminiApiError::Api {
+            http_status: 400,
+            status: Some("INVALID_ARGUMENT".to_string()),
+            message: "API key not valid. Please pass a valid API key.".to_string(),
+        }
+    );
+    assert_eq!(error.http_status(), Some(400));
+
+    let error = client.count_tokens("gemini-flash-latest", &CountTokensRequest::default()).await.unwrap_err();
+    assert!(matches!(error, GeminiApiError::Parse { .. }));
+}
+
+// 청크 경계가 한글 한가운데에 걸려도 이벤트를 온전히 복원하는지 확인합니다.
+#[tokio::test]
+async fn stream_generate_content_reassembles_sse_chunks() {
+    let bytes = STREAM_GENERATE.as_bytes();
+    let chunks = bytes.chunks(7).map(|c| c.to_vec()).collect::<Vec<_>>();
+    let (base_url, mut requests) = spawn_fixture_server(vec![FixtureResponse {
+        status: 200,
+        headers: vec![("Content-Type".to_string(), "text/event-stream".to_string())],
+        chunks,
+    }])
+    .await;
+    let request = GenerateContentRequest { contents: vec![user_text("인사해줘")], ..Default::default() };
+
+    let stream = test_client(&base_url).stream_generate_content("gemini-flash-latest", &request).await.unwrap();
+    let responses = stream.collect::<Vec<_>>().await.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+    let recorded = requests.recv().await.unwrap();
+    assert_eq!(recorded.path, "/v1beta/models/gemini-flash-latest:streamGenerateContent?alt=sse");
+    assert_eq!(responses.len(), 3);
+    let text = responses.iter().filter_map(|r| r.text()).collect::<String>();
+    assert_eq!(text, "안녕하세요, 린입니다.");
+    assert_eq!(responses[2].finish_reason(), Some(&FinishReason::MaxTokens));
+    assert_eq!(responses[2].usage_metadata.as_ref().unwrap().total_token_count, 15);
+}
+
+fn sse_response(body: &str) -> FixtureResponse {
+    FixtureResponse {
+        status: 200,
+        headers: vec![("Content-Type".to_string(), "text/event-stream".to_string())],
+        chunks: body.as_bytes().chunks(7).map(|c| c.to_vec()).collect(),
+    }
+}
+
+fn stream_query(text: &str) -> GeminiChatChunk {
+    GeminiChatChunk {
+        image: None,
+        is_bot: false,
+        user_id: Some("1".to_string()),
+        guild_id: None,
+        channel_id: None,
+        timestamp: chrono::Utc::now().to_string(),
+        query: text.to_string(),
+    }
+}
+
+async fn collect_stream_events(base_url: &str) -> Vec<GeminiStreamEvent> {
+    let begin_query = get_begin_query("ko".to_string(), "1".to_string(), None, None);
+    let (tx, mut rx) = mpsc::channel(8);
+    stream_query_to_gemini(&test_client(base_url), vec![stream_query("인사해줘")], &begin_query, false, None, tx).await;
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    events
+}
+
+#[tokio::test]
+async fn stream_query_forwards_sse_text_as_it_arrives() {
+    let (base_url, mut requests) = spawn_fixture_server(vec![sse_response(STREAM_GENERATE)]).await;
+
+    let events = collect_stream_events(&base_url).await;
+
+    assert_eq!(
+        events,
+        vec![
+            GeminiStreamEvent::Delta("안녕".to_string()),
+            GeminiStreamEvent::Delta("하세요, 린입니다.".to_string()),
+            GeminiStreamEvent::Done { finish_reason: "MAX_TOKENS".to_string() },
+        ]
+    );
+    let recorded = requests.recv().await.unwrap();
+    assert!(recorded.path.ends_with(":streamGenerateContent?alt=sse"));
+    assert_eq!(recorded.json()["toolConfig"]["functionCallingConfig"]["mode"], "AUTO");
+}
+
+#[tokio::test]
+async fn stream_query_sends_response_msg_call_as_one_delta() {
+    let body = r#"data: {"candidates": [{"content": {"parts": [{"functionCall": {"name": "response_msg","args": {"msg": "린입니다."}}}],"role": "model"},"finishReason": "STOP","index": 0}]}
+
+"#;
+    let (base_url, _requests) = spawn_fixture_server(vec![sse_response(body)]).await;
+
+    let events = collect_stream_events(&base_url).await;
+
+    assert_eq!(
+        events,
+        vec![
+            GeminiStreamEvent::Delta("린입니다.".to_string()),
+            GeminiStreamEvent::Done { finish_reason: "STOP".to_string() },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn count_tokens_and_embed_content() {
+    let (base_url, mut requests) = spawn_fixture_server(vec![
+        FixtureResponse::json(200, COUNT_TOKENS),
+        FixtureResponse::json(200, EMBED_CONTENT),
+    ])
+    .await;
+    let client = test_client(&base_url);
+
+    let count = client
+        .count_tokens("gemini-flash-latest", &CountTokensRequest { contents: Some(vec![user_text("안녕")]), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(count.total_tokens, 31);
+    let recorded = requests.recv().await.unwrap();
+    assert_eq!(recorded.path, "/v1beta/models/gemini-flash-latest:countTokens");
+    assert_eq!(recorded.json()["contents"][0]["role"], "user");
+
+    let embedding = client
+        .embed_content(
+            "text-embedding-004",
+            &EmbedContentRequest {
+                content: user_text("린은 디스코드 봇입니다."),
+                task_type: Some(EmbeddingTaskType::RetrievalDocument),
+                title: None,
+                output_dimensionality: Some(4),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(embedding.embedding.values.len(), 4);
+    let recorded = requests.recv().await.unwrap();
+    assert_eq!(recorded.path, "/v1beta/models/text-embedding-004:embedContent");
+    assert_eq!(recorded.json()["taskType"], "RETRIEVAL_DOCUMENT");
+    assert_eq!(recorded.json()["outputDimensionality"], 4);
+}
+
+#[tokio::test]
+async fn cached_contents_crud() {
+    let (base_url, mut requests) = spawn_fixture_server(vec![
+        FixtureResponse::json(200, CACHED_CONTENT),
+        FixtureResponse::json(200, LIST_CACHED_CONTENTS),
+        FixtureResponse::json(200, CACHED_CONTENT),
+        FixtureResponse::json(200, "{}"),
+    ])
+    .await;
+    let client = test_client(&base_url);
+    let name = "cachedContents/bzjcqjn5zf9b8ev2a2ig76ewucihbabkqawxbigw";
+
+    let cached = client.get_cached_content(name).await.unwrap();
+    assert_eq!(cached.name, name);
+    assert_eq!(cached.usage_metadata.unwrap().total_token_count, 1170);
+    assert_eq!(requests.recv().await.unwrap().path, format!("/v1beta/{}", name));
+
+    let list = client.list_cached_contents(Some(10), Some("page-1")).await.unwrap();
+    assert_eq!(list.cached_contents.len(), 1);
+    assert_eq!(list.next_page_token.as_deref(), Some("page-2"));
+    assert_eq!(requests.recv().await.unwrap().path, "/v1beta/cachedContents?pageSize=10&pageToken=page-1");
+
+    let update = CachedContentUpdate { ttl: Some("600s".to_string()), ..Default::default() };
+    client.update_cached_content(name, &update).await.unwrap();
+    let recorded = requests.recv().await.unwrap();
+    assert_eq!(recorded.method, "PATCH");
+    assert_eq!(recorded.path, format!("/v1beta/{}?updateMask=ttl", name));
+    assert_eq!(recorded.json()["ttl"], "600s");
+
+    client.delete_cached_content(name).await.unwrap();
+    assert_eq!(requests.recv().await.unwrap().method, "DELETE");
+}
+
+#[tokio::test]
+async fn files_get_list_delete() {
+    let (base_url, mut requests) = spawn_fixture_server(vec![
+        // 업로드 URL 헤더가 빠진 시작 응답
+        FixtureResponse { status: 200, headers: vec![], chunks: vec![] },
+        FixtureResponse::json(200, FILE_GET),
+        FixtureResponse::json(200, LIST_FILES),
+        FixtureResponse::json(200, "{}"),
+    ])
+    .await;
+    let client = test_client(&base_url);
+
+    let missing = client.upload_file(vec![1, 2, 3, 4], "image/png", "image_test.png").await;
+    assert_eq!(missing.err(), Some(GeminiApiError::UploadUrlMissing));
+    let start = requests.recv().await.unwrap();
+    assert_eq!(start.path, "/upload/v1beta/files");
+    assert_eq!(start.headers.get("x-goog-upload-command").map(String::as_str), Some("start"));
+    assert_eq!(start.headers.get("x-goog-upload-header-content-length").map(String::as_str), Some("4"));
+    assert_eq!(start.json()["file"]["display_name"], "image_test.png");
+
+    let file = client.get_file("files/abc-123").await.unwrap();
+    assert_eq!(file.size_bytes.as_deref(), Some("4"));
+    assert_eq!(requests.recv().await.unwrap().path, "/v1beta/files/abc-123");
+
+    let list = client.list_files(None, None).await.unwrap();
+    assert_eq!(list.files[0].state, Some(FileState::Processing));
+    assert_eq!(requests.recv().await.unwrap().path, "/v1beta/files");
+
+    client.delete_file("files/abc-123").await.unwrap();
+    let recorded = requests.recv().await.unwrap();
+    assert_eq!((recorded.method.as_str(), recorded.path.as_str()), ("DELETE", "/v1beta/files/abc-123"));
+}
+
+#[tokio::test]
+async fn files_resumable_upload() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let upload_url = format!("{}/upload-session/1", base_url);
+    let (tx, mut requests) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let responses = vec![
+            FixtureResponse { status: 200, headers: vec![("x-goog-upload-url".to_string(), upload_url)], chunks: vec![] },
+            FixtureResponse::json(200, FILE),
+        ];
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = tx.send(read_request(&mut stream).await);
+            write_response(&mut stream, response).await;
+        }
+    });
+
+    let file = test_client(&base_url).upload_file(vec![1, 2, 3, 4], "image/png", "image_test.png").await.unwrap();
+    assert_eq!(file.name, "files/abc-123");
+    assert_eq!(file.state, Some(FileState::Active));
+    assert_eq!(file.uri, "https://generativelanguage.googleapis.com/v1beta/files/abc-123");
+
+    requests.recv().await.unwrap();
+    let upload = requests.recv().await.unwrap();
+    assert_eq!(upload.path, "/upload-session/1");
+    assert_eq!(upload.headers.get("x-goog-upload-command").map(String::as_str), Some("upload, finalize"));
+    assert_eq!(upload.body, vec![1, 2, 3, 4]);
+}
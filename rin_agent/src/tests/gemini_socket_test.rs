@@ -1,5 +1,5 @@
 use gemini_live_api::types::enums::GeminiContentRole;
-use gemini_live_api::types::live_api_types::{BidiGenerateContentClientContent, BidiGenerateContentSetup, ContextWindowCompression, GeminiLiveApiTool, SessionResumptionConfig};
+use gemini_live_api::types::live_api_types::{BidiGenerateContentClientContent, BidiGenerateContentSetup, ContextWindowCompression, GeminiLiveApiTool, LiveEvent, SessionResumptionConfig};
 use gemini_live_api::types::{GeminiContents, GeminiFunctionDeclaration, GeminiGenerationConfig, GeminiGenerationConfigTool, GeminiGoogleSearchTool, GeminiParts};
 use crate::gemini::types::GeminiBotTools;
 #[cfg(test)]
@@ -8,6 +8,7 @@ use gemini_live_api::service::socket_client::GeminiSocketClient;
 use gemini_live_api::service::socket_client_manager::GeminiSocketManager;
 use crate::setting::gemini_setting::GEMINI_MODEL_PRO;
 use dotenv::dotenv;
+use futures_util::StreamExt;
 use tokio_tungstenite::tungstenite::Message;
 use std::{env, thread};
 
@@ -64,7 +65,7 @@ async fn make_client() {
             session_resumption, 
             context_window_compression, 
             input_audio_transcription: audio_conf.clone(), 
-            output_audio_generation: audio_conf.clone()
+            output_audio_transcription: audio_conf.clone()
         }
     );
     let connection_result = client.connect().await;
@@ -89,11 +90,13 @@ async fn make_client() {
         msgcontent
     ).await
         .expect("Failed to send message");
-    client.listen(
-        |msg| {
-            LOGGER.log(LogLevel::Debug, format!("Received message: {:?}", msg).as_str());
+    let (_sender, mut events) = client.into_event_stream();
+    while let Some(event) = events.next().await {
+        LOGGER.log(LogLevel::Debug, format!("Received event: {:?}", event).as_str());
+        if matches!(event, LiveEvent::Closed { .. }) {
+            break;
         }
-    ).await;
+    }
 }
 
 #[test]
@@ -0,0 +1,147 @@
+#[cfg(test)]
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use gemini_live_api::service::socket_client::ReconnectPolicy;
+use gemini_live_api::service::socket_client_manager::{GeminiSocketManager, SocketPoolConfig};
+use gemini_live_api::types::live_api_error::LiveApiError;
+use gemini_live_api::types::live_api_types::{BidiGenerateContentRealTimeInput, BidiGenerateContentSetup, LiveEvent};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+fn test_config(max_sessions: usize, idle_timeout: Duration) -> SocketPoolConfig {
+    SocketPoolConfig {
+        max_sessions,
+        idle_timeout,
+        connect_retries: 3,
+        connect_backoff: Duration::from_millis(20),
+        reconnect_policy: ReconnectPolicy::disabled(),
+    }
+}
+
+// 연결마다 setupComplete 를 보내고, 받은 텍스트 입력에는 turnComplete 로 답하는 서버 대역.
+// 연결이 닫히면 closed 채널로 알립니다.
+async fn spawn_server(listener: TcpListener) -> (mpsc::UnboundedReceiver<()>, mpsc::UnboundedReceiver<()>) {
+    let (accepted_tx, accepted_rx) = mpsc::unbounded_channel();
+    let (closed_tx, closed_rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let accepted_tx = accepted_tx.clone();
+            let closed_tx = closed_tx.clone();
+            tokio::spawn(async move {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                let _ = accepted_tx.send(());
+                ws.next().await;
+                ws.send(Message::Text(json!({"setupComplete": {}}).to_string().into())).await.unwrap();
+                while let Some(Ok(msg)) = ws.next().await {
+                    if msg.is_close() {
+                        break;
+                    }
+                    let reply = json!({"serverContent": {"turnComplete": true}});
+                    if ws.send(Message::Text(reply.to_string().into())).await.is_err() {
+                        break;
+                    }
+                }
+                let _ = closed_tx.send(());
+            });
+        }
+    });
+    (accepted_rx, closed_rx)
+}
+
+async fn next_event(events: &mut tokio::sync::broadcast::Receiver<LiveEvent>) -> LiveEvent {
+    tokio::time::timeout(Duration::from_secs(5), events.recv())
+        .await
+        .expect("event timeout")
+        .expect("event channel closed")
+}
+
+#[tokio::test]
+async fn pool_shares_sessions_and_caps_count() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (mut accepted, _closed) = spawn_server(listener).await;
+    let pool = GeminiSocketManager::<String>::with_config(test_config(1, Duration::from_secs(60)));
+
+    let (handle, mut events) = pool
+        .get_or_create_socket_client("a".to_string(), url.clone(), BidiGenerateContentSetup::default())
+        .await
+        .unwrap();
+    assert!(matches!(next_event(&mut events).await, LiveEvent::SetupComplete));
+
+    // 같은 키는 같은 세션을 돌려주고, 새 연결을 만들지 않습니다.
+    let (shared, _) = pool
+        .get_or_create_socket_client("a".to_string(), url.clone(), BidiGenerateContentSetup::default())
+        .await
+        .unwrap();
+    accepted.recv().await.unwrap();
+    assert!(accepted.try_recv().is_err());
+
+    let result = pool
+        .get_or_create_socket_client("b".to_string(), url, BidiGenerateContentSetup::default())
+        .await;
+    assert_eq!(result.err(), Some(LiveApiError::PoolExhausted { max_sessions: 1 }));
+
+    // 복제한 핸들을 다른 태스크에서 써도 같은 세션으로 갑니다.
+    tokio::spawn(async move {
+        shared.send_realtime_input(BidiGenerateContentRealTimeInput::text("안녕".to_string())).await.unwrap();
+    })
+    .await
+    .unwrap();
+    match next_event(&mut events).await {
+        LiveEvent::ServerContent(content) => assert!(content.turn_complete),
+        other => panic!("unexpected event: {:?}", other),
+    }
+
+    let stats = handle.stats();
+    assert_eq!(stats.turns, 1);
+    assert!(stats.bytes_out > 0);
+    assert!(stats.bytes_in > 0);
+    assert_eq!(pool.stats().await.len(), 1);
+}
+
+#[tokio::test]
+async fn pool_evicts_idle_sessions() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (_accepted, mut closed) = spawn_server(listener).await;
+    let pool = GeminiSocketManager::<String>::with_config(test_config(4, Duration::from_millis(50)));
+
+    let (handle, _events) = pool
+        .get_or_create_socket_client("idle".to_string(), url, BidiGenerateContentSetup::default())
+        .await
+        .unwrap();
+    assert!(pool.evict_idle().await.is_empty());
+
+    // 잡혀 있는 동안은 조용해도 정리하지 않습니다.
+    let hold = handle.hold();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(pool.evict_idle().await.is_empty());
+    drop(hold);
+    assert_eq!(pool.evict_idle().await, vec!["idle".to_string()]);
+    tokio::time::timeout(Duration::from_secs(5), closed.recv()).await.unwrap().unwrap();
+    assert!(pool.get(&"idle".to_string()).await.is_none());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(handle.is_closed());
+}
+
+// 첫 연결이 실패해도 정해진 횟수만큼 다시 시도합니다.
+#[tokio::test]
+async fn pool_retries_failed_connects() {
+    let addr = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let listener = TcpListener::bind(addr).await.unwrap();
+        spawn_server(listener).await;
+        std::future::pending::<()>().await;
+    });
+    let pool = GeminiSocketManager::<String>::with_config(test_config(4, Duration::from_secs(60)));
+
+    let result = pool
+        .get_or_create_socket_client("retry".to_string(), format!("ws://{}", addr), BidiGenerateContentSetup::default())
+        .await;
+    assert!(result.is_ok());
+    assert_eq!(pool.open_sessions().await, 1);
+}
@@ -0,0 +1,169 @@
+#[cfg(test)]
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use gemini_live_api::service::socket_client::{GeminiSocketClient, LiveEventStream, ReconnectPolicy};
+use gemini_live_api::types::live_api_error::LiveApiError;
+use gemini_live_api::types::live_api_types::{
+    BidiGenerateContentRealTimeInput, BidiGenerateContentServerMessage, BidiGenerateContentSetup, LiveEvent,
+    SessionResumptionConfig,
+};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+fn fast_policy(max_attempts: u32) -> ReconnectPolicy {
+    ReconnectPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(50),
+    }
+}
+
+async fn accept(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+    let (stream, _) = listener.accept().await.unwrap();
+    tokio_tungstenite::accept_async(stream).await.unwrap()
+}
+
+async fn next_json(ws: &mut WebSocketStream<TcpStream>) -> Value {
+    loop {
+        match ws.next().await.expect("stream closed").expect("ws error") {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            Message::Binary(bytes) => return serde_json::from_slice(&bytes).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+async fn next_event(events: &mut LiveEventStream) -> LiveEvent {
+    tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("event timeout")
+        .expect("stream ended")
+}
+
+#[test]
+fn go_away_parses_proto_duration() {
+    let msg: BidiGenerateContentServerMessage = serde_json::from_str(r#"{"goAway": {"timeLeft": "1.5s"}}"#).unwrap();
+    match msg.into_events().as_slice() {
+        [LiveEvent::GoAway { time_left }] => assert_eq!(*time_left, Some(Duration::from_millis(1500))),
+        other => panic!("unexpected events: {:?}", other),
+    }
+    assert!(serde_json::from_str::<BidiGenerateContentServerMessage>(r#"{"goAway": {"timeLeft": "soon"}}"#).is_err());
+}
+
+#[test]
+fn reconnect_backoff_is_capped() {
+    let policy = ReconnectPolicy {
+        max_attempts: 10,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(500),
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(8), Duration::from_millis(500));
+}
+
+// 해석할 수 없는 프레임은 스트림을 끝내지 않고 Error 이벤트로 전달됩니다.
+#[tokio::test]
+async fn unparseable_frame_becomes_error_event() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        next_json(&mut ws).await;
+        ws.send(Message::Text("not json".into())).await.unwrap();
+        ws.send(Message::Text(json!({"setupComplete": {}}).to_string().into())).await.unwrap();
+        ws.close(None).await.ok();
+    });
+
+    let mut client = GeminiSocketClient::new(1i64, format!("ws://{}", addr), BidiGenerateContentSetup::default());
+    client.connect().await.unwrap();
+    let (_sender, mut events) = client.into_event_stream();
+
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Error(LiveApiError::Parse { .. })));
+    assert!(matches!(next_event(&mut events).await, LiveEvent::SetupComplete));
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Closed { error: None }));
+    assert!(events.next().await.is_none());
+    server.await.unwrap();
+}
+
+// 연결이 끊기거나 GoAway 를 받으면 마지막 재개 핸들로 다시 연결합니다.
+#[tokio::test]
+async fn reconnects_with_resumption_handle() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        // 1번 연결: 핸들을 알려준 뒤 닫는 절차 없이 끊습니다.
+        let mut ws = accept(&listener).await;
+        let setup = next_json(&mut ws).await;
+        assert!(setup["setup"]["sessionResumption"].get("handle").is_none());
+        let update = json!({"sessionResumptionUpdate": {"newHandle": "handle-1", "resumable": true}});
+        ws.send(Message::Text(update.to_string().into())).await.unwrap();
+        drop(ws);
+
+        // 2번 연결: 이어받은 뒤 GoAway 를 보냅니다.
+        let mut ws = accept(&listener).await;
+        let setup = next_json(&mut ws).await;
+        assert_eq!(setup["setup"]["sessionResumption"]["handle"], "handle-1");
+        let update = json!({"sessionResumptionUpdate": {"newHandle": "handle-2", "resumable": true}});
+        ws.send(Message::Text(update.to_string().into())).await.unwrap();
+        ws.send(Message::Text(json!({"goAway": {"timeLeft": "5s"}}).to_string().into())).await.unwrap();
+
+        // 3번 연결: 재연결 후 보낸 입력을 받고 정상 종료합니다.
+        let mut ws = accept(&listener).await;
+        let setup = next_json(&mut ws).await;
+        assert_eq!(setup["setup"]["sessionResumption"]["handle"], "handle-2");
+        let input = next_json(&mut ws).await;
+        assert_eq!(input["realtimeInput"]["text"], "다시 왔어요");
+        ws.close(None).await.ok();
+    });
+
+    let setup = BidiGenerateContentSetup {
+        session_resumption: Some(SessionResumptionConfig::default()),
+        ..Default::default()
+    };
+    let mut client = GeminiSocketClient::new(2i64, format!("ws://{}", addr), setup).with_reconnect_policy(fast_policy(3));
+    client.connect().await.unwrap();
+    let (sender, mut events) = client.into_event_stream();
+
+    assert!(matches!(next_event(&mut events).await, LiveEvent::SessionResumptionUpdate(_)));
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Reconnecting { attempt: 1 }));
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Reconnected { resumed: true }));
+    assert!(matches!(next_event(&mut events).await, LiveEvent::SessionResumptionUpdate(_)));
+    match next_event(&mut events).await {
+        LiveEvent::GoAway { time_left } => assert_eq!(time_left, Some(Duration::from_secs(5))),
+        other => panic!("unexpected event: {:?}", other),
+    }
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Reconnecting { attempt: 1 }));
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Reconnected { resumed: true }));
+
+    sender.send_realtime_input(BidiGenerateContentRealTimeInput::text("다시 왔어요".to_string())).await.unwrap();
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Closed { error: None }));
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        next_json(&mut ws).await;
+        // 리스너까지 닫아서 재연결이 모두 실패하게 합니다.
+    });
+
+    let mut client = GeminiSocketClient::new(3i64, format!("ws://{}", addr), BidiGenerateContentSetup::default())
+        .with_reconnect_policy(fast_policy(2));
+    client.connect().await.unwrap();
+    let (_sender, mut events) = client.into_event_stream();
+    server.await.unwrap();
+
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Reconnecting { attempt: 1 }));
+    assert!(matches!(next_event(&mut events).await, LiveEvent::Reconnecting { attempt: 2 }));
+    assert!(matches!(
+        next_event(&mut events).await,
+        LiveEvent::Closed { error: Some(LiveApiError::ReconnectExhausted { attempts: 2 }) }
+    ));
+}
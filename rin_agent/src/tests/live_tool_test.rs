@@ -0,0 +1,158 @@
+#[cfg(test)]
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use gemini_live_api::types::live_api_types::BidiGenerateContentToolCall;
+use gemini_live_api::types::GeminiFunctionCall;
+use serde_json::{json, Value};
+use serenity::all::{ChannelId, UserId};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::gemini::live_tools::LiveToolDispatcher;
+use crate::gemini::live_voice_bridge::{get_voice_setup, start_live_voice_bridge, VoiceBridgeOutput};
+use crate::gemini::types::{DiscordUserInfo, GeminiActionResult, GeminiBotTools};
+
+static TEST_TOOLS: LazyLock<HashMap<String, GeminiBotTools>> = LazyLock::new(|| {
+    let echo = GeminiBotTools {
+        name: "echo".to_string(),
+        action: |params, info| Box::pin(async move {
+            let text = params.get("text").map(|v| v.value.to_string()).unwrap_or_default();
+            Ok(GeminiActionResult {
+                result_message: format!("echo by {}", info.map(|i| i.user_id.get()).unwrap_or_default()),
+                result: json!({ "text": text }),
+                show_user: Some(format!("echo: {}", text)),
+                ..Default::default()
+            })
+        }),
+        ..Default::default()
+    };
+    let slow = GeminiBotTools {
+        name: "slow".to_string(),
+        action: |_params, _info| Box::pin(async move {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok(GeminiActionResult::default())
+        }),
+        ..Default::default()
+    };
+    HashMap::from([("echo".to_string(), echo), ("slow".to_string(), slow)])
+});
+
+fn test_user() -> Option<DiscordUserInfo> {
+    Some(DiscordUserInfo {
+        user_id: UserId::new(7),
+        username: Some("rin".to_string()),
+        channel_id: ChannelId::new(1),
+        guild_id: None,
+        context_id: None,
+    })
+}
+
+fn make_call(id: &str, name: &str, args: Value) -> GeminiFunctionCall {
+    GeminiFunctionCall {
+        id: Some(id.to_string()),
+        name: name.to_string(),
+        args: serde_json::from_value(args).ok(),
+    }
+}
+
+#[tokio::test]
+async fn dispatcher_runs_registered_tool() {
+    let mut tools = LiveToolDispatcher::with_registry(&TEST_TOOLS, test_user());
+    tools.dispatch(BidiGenerateContentToolCall {
+        function_calls: vec![make_call("call-1", "echo", json!({ "text": "안녕" }))],
+    });
+
+    let result = tools.next_result().await;
+    assert_eq!(result.response.id.as_deref(), Some("call-1"));
+    assert_eq!(result.response.name, "echo");
+    let response = result.response.response.unwrap();
+    assert_eq!(response["result"]["text"], "안녕");
+    assert_eq!(response["result_message"], "echo by 7");
+    assert_eq!(result.show_user.as_deref(), Some("echo: 안녕"));
+    assert_eq!(tools.running_count(), 0);
+}
+
+#[tokio::test]
+async fn dispatcher_reports_unknown_tool_and_cancels() {
+    let mut tools = LiveToolDispatcher::with_registry(&TEST_TOOLS, test_user());
+    tools.dispatch(BidiGenerateContentToolCall {
+        function_calls: vec![
+            make_call("call-slow", "slow", json!({})),
+            make_call("call-missing", "missing", json!({})),
+        ],
+    });
+    assert_eq!(tools.running_count(), 1);
+
+    let result = tools.next_result().await;
+    assert_eq!(result.response.id.as_deref(), Some("call-missing"));
+    assert!(result.response.response.unwrap()["error"]["message"].is_string());
+
+    assert_eq!(tools.cancel(&["call-slow".to_string(), "call-none".to_string()]), 1);
+    assert_eq!(tools.running_count(), 0);
+    let pending = tokio::time::timeout(Duration::from_millis(100), tools.next_result()).await;
+    assert!(pending.is_err());
+}
+
+// 서버의 toolCall 을 실행해 같은 id 로 toolResponse 를 돌려보내는지 확인합니다.
+#[tokio::test]
+async fn live_bridge_answers_tool_call() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        let setup = ws.next().await.unwrap().unwrap();
+        let setup: Value = serde_json::from_slice(&setup.into_data()).unwrap();
+        assert!(setup["setup"]["tools"].is_array());
+        ws.send(Message::Text(json!({"setupComplete": {}}).to_string().into())).await.unwrap();
+
+        let tool_call = json!({"toolCall": {"functionCalls": [{"id": "call-9", "name": "echo", "args": {"text": "알람"}}]}});
+        ws.send(Message::Text(tool_call.to_string().into())).await.unwrap();
+
+        let response = loop {
+            match ws.next().await.expect("stream closed").expect("ws error") {
+                Message::Text(text) => break serde_json::from_str::<Value>(&text).unwrap(),
+                Message::Binary(bytes) => break serde_json::from_slice::<Value>(&bytes).unwrap(),
+                _ => continue,
+            }
+        };
+        let function_response = &response["toolResponse"]["functionResponses"][0];
+        assert_eq!(function_response["id"], "call-9");
+        assert_eq!(function_response["name"], "echo");
+        assert_eq!(function_response["response"]["result"]["text"], "알람");
+        ws.close(None).await.ok();
+    });
+
+    let (output_tx, mut output_rx) = mpsc::channel(16);
+    let _input = start_live_voice_bridge(
+        "test:live-tool".to_string(),
+        format!("ws://{}", addr),
+        get_voice_setup("test".to_string()),
+        output_tx,
+        Some(LiveToolDispatcher::with_registry(&TEST_TOOLS, test_user())),
+    )
+    .await
+    .expect("bridge connect");
+
+    assert_eq!(
+        output_rx.recv().await,
+        Some(VoiceBridgeOutput::ToolResult { name: "echo".to_string(), show_user: Some("echo: 알람".to_string()) })
+    );
+    assert_eq!(output_rx.recv().await, Some(VoiceBridgeOutput::Closed));
+    server.await.unwrap();
+}
+
+// 음성 대화의 도구는 /join_voice 를 실행한 사용자로 기록된다는 사실을 설명에 담습니다.
+#[test]
+fn voice_setup_tools_mention_session_owner() {
+    let tools = get_voice_setup("test".to_string()).tools.unwrap();
+    let declarations = tools.iter().flat_map(|tool| tool.function_declarations.iter().flatten()).collect::<Vec<_>>();
+    assert!(!declarations.is_empty());
+    assert!(declarations.iter().all(|d| d.description.contains("/join_voice")));
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+use base64::Engine;
+use bytes::Bytes;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::discord::voice::pcm_stream::{downmix_to_gemini_input, mix_voices};
+use crate::gemini::live_voice_bridge::{get_voice_setup, start_live_voice_bridge, VoiceBridgeInput, VoiceBridgeOutput};
+
+async fn next_json<S>(ws: &mut S) -> Value
+where
+    S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        match ws.next().await.expect("stream closed").expect("ws error") {
+            Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            Message::Binary(bytes) => return serde_json::from_slice(&bytes).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+// 로컬 WebSocket 서버를 Live API 대역으로 띄워 브리지의 입출력을 확인합니다.
+#[tokio::test]
+async fn live_voice_bridge_round_trip() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        let setup = next_json(&mut ws).await;
+        assert_eq!(
+            setup["setup"]["realtimeInputConfig"]["activityHandling"],
+            "START_OF_ACTIVITY_INTERRUPTS"
+        );
+        // Live API 필드 이름은 outputAudioTranscription
+        assert!(setup["setup"].get("outputAudioTranscription").is_some());
+        assert!(setup["setup"].get("inputAudioTranscription").is_some());
+        ws.send(Message::Text(json!({"setupComplete": {}}).to_string().into())).await.unwrap();
+
+        let input = next_json(&mut ws).await;
+        assert_eq!(input["realtimeInput"]["audio"]["mimeType"], "audio/pcm;rate=16000");
+        assert!(input["realtimeInput"].get("text").is_none());
+
+        let end = next_json(&mut ws).await;
+        assert_eq!(end["realtimeInput"]["audioStreamEnd"], true);
+
+        let audio = base64::engine::general_purpose::STANDARD.encode([1u8, 2, 3, 4]);
+        let frames = [
+            json!({"serverContent": {"modelTurn": {"role": "model", "parts": [{"inlineData": {"mimeType": "audio/pcm;rate=24000", "data": audio}}]}}}),
+            json!({"serverContent": {"interrupted": true}}),
+            json!({"serverContent": {"turnComplete": true}}),
+        ];
+        for frame in frames {
+            // 실제 Live API 처럼 바이너리 프레임으로 보냅니다.
+            ws.send(Message::Binary(frame.to_string().into_bytes().into())).await.unwrap();
+        }
+        ws.close(None).await.ok();
+    });
+
+    let (output_tx, mut output_rx) = mpsc::channel(16);
+    let input = start_live_voice_bridge(
+        "test:live-voice-bridge".to_string(),
+        format!("ws://{}", addr),
+        get_voice_setup("test".to_string()),
+        output_tx,
+        None,
+    )
+    .await
+    .expect("bridge connect");
+
+    let voice = vec![1000i16; 960 * 2];
+    input.send(VoiceBridgeInput::Audio(downmix_to_gemini_input(&voice))).await.unwrap();
+    input.send(VoiceBridgeInput::AudioStreamEnd).await.unwrap();
+
+    assert_eq!(output_rx.recv().await, Some(VoiceBridgeOutput::Audio(Bytes::from_static(&[1, 2, 3, 4]))));
+    assert_eq!(output_rx.recv().await, Some(VoiceBridgeOutput::Interrupted));
+    assert_eq!(output_rx.recv().await, Some(VoiceBridgeOutput::TurnComplete));
+    assert_eq!(output_rx.recv().await, Some(VoiceBridgeOutput::Closed));
+
+    server.await.unwrap();
+}
+
+#[test]
+fn downmix_and_mix_for_live_input() {
+    // 48kHz stereo 20ms = 960 프레임 -> 16kHz mono 320 샘플
+    let a = vec![1000i16; 960 * 2];
+    let b = vec![i16::MAX; 960 * 2];
+    let mixed = mix_voices([a.as_slice(), b.as_slice()]);
+    assert!(mixed.iter().all(|s| *s == i16::MAX));
+
+    let pcm = downmix_to_gemini_input(&a);
+    assert_eq!(pcm.len(), 320 * 2);
+    assert_eq!(i16::from_le_bytes([pcm[0], pcm[1]]), 1000);
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+use std::collections::BTreeMap;
+
+use crate::libs::metrics::{escape_label_value, MetricDef, MetricKind, MetricsRegistry};
+use crate::service::health_service::{build_readiness, check_discord_stages, CheckStatus, DependencyCheck};
+
+static TEST_DEFS: [MetricDef; 3] = [
+    MetricDef { name: "test_requests_total", kind: MetricKind::Counter, help: "Requests." },
+    MetricDef { name: "test_stage", kind: MetricKind::Gauge, help: "Stage." },
+    MetricDef { name: "test_duration_seconds", kind: MetricKind::Histogram, help: "Duration." },
+];
+
+fn check(name: &'static str, status: CheckStatus) -> DependencyCheck {
+    DependencyCheck { name, status, detail: None }
+}
+
+#[test]
+fn renders_counters_and_gauges() {
+    let registry = MetricsRegistry::new(&TEST_DEFS);
+    registry.inc_counter("test_requests_total", &[("command", "lping"), ("result", "ok")], 1.0);
+    registry.inc_counter("test_requests_total", &[("command", "lping"), ("result", "ok")], 2.0);
+    registry.set_gauge("test_stage", &[("shard", "0")], 1.0);
+    registry.set_gauge("unknown_metric", &[], 5.0);
+    let text = registry.render();
+    assert!(text.contains("# TYPE test_requests_total counter\n"));
+    assert!(text.contains("test_requests_total{command=\"lping\",result=\"ok\"} 3\n"));
+    assert!(text.contains("test_stage{shard=\"0\"} 1\n"));
+    assert!(!text.contains("unknown_metric"));
+
+    registry.clear_gauge("test_stage");
+    assert!(!registry.render().contains("test_stage{"));
+}
+
+#[test]
+fn renders_cumulative_histogram() {
+    let registry = MetricsRegistry::new(&TEST_DEFS);
+    registry.observe("test_duration_seconds", &[("model", "flash")], 0.3);
+    registry.observe("test_duration_seconds", &[("model", "flash")], 7.0);
+    let text = registry.render();
+    assert!(text.contains("test_duration_seconds_bucket{model=\"flash\",le=\"0.25\"} 0\n"));
+    assert!(text.contains("test_duration_seconds_bucket{model=\"flash\",le=\"0.5\"} 1\n"));
+    assert!(text.contains("test_duration_seconds_bucket{model=\"flash\",le=\"10\"} 2\n"));
+    assert!(text.contains("test_duration_seconds_bucket{model=\"flash\",le=\"+Inf\"} 2\n"));
+    assert!(text.contains("test_duration_seconds_sum{model=\"flash\"} 7.3\n"));
+    assert!(text.contains("test_duration_seconds_count{model=\"flash\"} 2\n"));
+}
+
+#[test]
+fn escapes_label_values() {
+    assert_eq!(escape_label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+}
+
+#[test]
+fn reports_readiness() {
+    assert!(build_readiness(vec![check("database", CheckStatus::Ok), check("redis", CheckStatus::Disabled)]).ready);
+    assert!(!build_readiness(vec![check("database", CheckStatus::Failed), check("redis", CheckStatus::Ok)]).ready);
+
+    // 원본 오류는 응답에 싣지 않습니다.
+    let failed = DependencyCheck { detail: Some("password authentication failed".to_string()), ..check("database", CheckStatus::Failed) };
+    let body = serde_json::to_value(build_readiness(vec![failed])).unwrap();
+    assert_eq!(body, serde_json::json!({ "ready": false, "checks": [{ "name": "database", "status": "failed" }] }));
+}
+
+#[test]
+fn checks_discord_shards() {
+    assert!(check_discord_stages(&BTreeMap::new()).is_err());
+    let mut stages = BTreeMap::from([(0, "connected".to_string())]);
+    assert!(check_discord_stages(&stages).is_ok());
+    stages.insert(1, "resuming".to_string());
+    assert_eq!(check_discord_stages(&stages), Err("shard 1: resuming".to_string()));
+}
@@ -2,4 +2,30 @@ pub mod test;
 pub mod gemini_socket_test;
 pub mod searching_test;
 pub mod test_gemini_cache;
-pub mod test_unified_generation;
\ No newline at end of file
+pub mod test_unified_generation;
+pub mod voice_playback_test;
+pub mod live_voice_bridge_test;
+pub mod transcribe_test;
+pub mod live_tool_test;
+pub mod live_socket_test;
+pub mod live_socket_pool_test;
+pub mod gemini_rest_client_test;
+pub mod usage_service_test;
+pub mod quota_service_test;
+pub mod model_router_test;
+pub mod gemini_error_test;
+pub mod structured_output_test;
+pub mod builtin_tools_test;
+
+pub mod search_provider_test;
+pub mod web_connect_test;
+pub mod debt_service_test;
+pub mod receipt_split_test;
+pub mod web_auth_test;
+pub mod dashboard_api_test;
+pub mod openai_compat_test;
+pub mod metrics_test;
+pub mod task_supervisor_test;
+pub mod shutdown_test;
+pub mod mqtt_control_test;
+pub mod test_support;
\ No newline at end of file
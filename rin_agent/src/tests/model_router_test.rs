@@ -0,0 +1,51 @@
+#[cfg(test)]
+use crate::gemini::model_router::{route_for, score_query, ModelRoute, RouteComplexity};
+use crate::setting::gemini_setting::{GEMINI_MODEL_FLASH, GEMINI_MODEL_PRO};
+
+#[test]
+fn greeting_is_low() {
+    let score = score_query("안녕하세요!", false);
+    assert_eq!(score.complexity(), Some(RouteComplexity::Low));
+    assert!(score.reason().contains("가벼운 대화"));
+    // "hi" 는 단어 전체가 같을 때만 가벼운 대화로 봅니다.
+    assert!(!score_query("this is a high level question about taxes", false).reasons.contains(&"가벼운 대화"));
+}
+
+#[test]
+fn code_and_analysis_is_high() {
+    let query = format!("이 코드의 성능을 분석하고 최적화해 줘\n```rust\n{}\n```", "let x = 1;\n".repeat(40));
+    let score = score_query(&query, false);
+    assert_eq!(score.complexity(), Some(RouteComplexity::High));
+    assert_eq!(score.reason(), "긴 질문, 코드 포함, 심화 키워드");
+}
+
+#[test]
+fn middle_scores_need_classification() {
+    let score = score_query("이 사진 속 식물의 이름이 무엇인지 알려주고, 키우는 방법도 간단히 설명해 줄래?", true);
+    assert_eq!(score.complexity(), None);
+}
+
+#[test]
+fn high_route_respects_pro_permission() {
+    let route = route_for(RouteComplexity::High, "코드 포함", true);
+    assert!(route.use_pro && route.routed);
+    assert_eq!(route.thinking_budget, Some(8192));
+    assert_eq!(route.footer(), format!("{} · 생각 8192 · 자동 선택: 코드 포함", GEMINI_MODEL_PRO));
+
+    let route = route_for(RouteComplexity::High, "코드 포함", false);
+    assert!(!route.use_pro);
+    assert_eq!(route.model_name(), GEMINI_MODEL_FLASH);
+    assert!(route.reason.ends_with("(Pro 제한으로 Flash)"));
+
+    let route = route_for(RouteComplexity::Low, "짧은 질문", true);
+    assert_eq!((route.use_pro, route.thinking_budget), (false, None));
+}
+
+#[test]
+fn manual_route_keeps_user_choice() {
+    let route = ModelRoute::manual(true, Some(1024), "직접 선택");
+    assert!(!route.routed);
+    assert_eq!(route.footer(), format!("{} · 생각 1024 · 직접 선택", GEMINI_MODEL_PRO));
+    let complexity: RouteComplexity = serde_json::from_str("\"medium\"").unwrap();
+    assert_eq!(complexity, RouteComplexity::Medium);
+}
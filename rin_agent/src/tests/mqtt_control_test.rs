@@ -0,0 +1,239 @@
+#[cfg(test)]
+use std::sync::Arc;
+use std::time::Duration;
+
+use contract::config::CommonConfig;
+use contract::{topics, LogLevel, RinAgentAlert, RinAgentCommand, RinAgentStatus};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::service::mqtt_service::{connect_mqtt, publish_status_to, run_mqtt_event_loop, CommandFuture, CommandHandler};
+
+// 테스트용 MQTT 3.1.1 브로커. CONNECT/SUBSCRIBE/PUBLISH/PINGREQ 만 처리하고,
+// 클라이언트가 보낸 PUBLISH 는 published 로 흘려보내며 토픽이 정확히 같은 구독자에게 QoS 0 으로 전달합니다.
+struct FakeBroker {
+    port: u16,
+    subscribers: Arc<Mutex<Vec<(String, Arc<Mutex<OwnedWriteHalf>>)>>>,
+    published: mpsc::UnboundedReceiver<(String, Vec<u8>)>,
+}
+
+fn encode_packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    let mut length = body.len();
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+fn encode_publish(topic: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = (topic.len() as u16).to_be_bytes().to_vec();
+    body.extend_from_slice(topic.as_bytes());
+    body.extend_from_slice(payload);
+    encode_packet(0x30, &body)
+}
+
+fn read_string(body: &[u8], at: usize) -> (String, usize) {
+    let length = u16::from_be_bytes([body[at], body[at + 1]]) as usize;
+    (String::from_utf8_lossy(&body[at + 2..at + 2 + length]).into_owned(), at + 2 + length)
+}
+
+async fn read_packet(reader: &mut OwnedReadHalf) -> std::io::Result<(u8, Vec<u8>)> {
+    let header = reader.read_u8().await?;
+    let mut length = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte = reader.read_u8().await?;
+        length |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok((header, body))
+}
+
+impl FakeBroker {
+    async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        let (published_tx, published) = mpsc::unbounded_channel();
+        let accept_subscribers = subscribers.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (reader, writer) = stream.into_split();
+                tokio::spawn(serve_connection(reader, Arc::new(Mutex::new(writer)), accept_subscribers.clone(), published_tx.clone()));
+            }
+        });
+        FakeBroker { port, subscribers, published }
+    }
+
+    fn config(&self) -> CommonConfig {
+        CommonConfig {
+            redis_url: String::new(),
+            database_url: String::new(),
+            mqtt_host: "127.0.0.1".to_string(),
+            mqtt_port: self.port,
+        }
+    }
+
+    async fn wait_for_subscriber(&self, topic: &str) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !self.subscribers.lock().await.iter().any(|(t, _)| t == topic) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("subscribe timeout");
+    }
+
+    // 브로커 쪽에서 바로 구독자에게 보냅니다. (다른 클라이언트가 발행한 것처럼)
+    async fn inject(&self, topic: &str, payload: &[u8]) {
+        forward(&self.subscribers, topic, payload).await;
+    }
+
+    async fn next_publish(&mut self, topic: &str) -> Vec<u8> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let (received, payload) = self.published.recv().await.expect("broker closed");
+                if received == topic {
+                    return payload;
+                }
+            }
+        })
+        .await
+        .expect("publish timeout")
+    }
+}
+
+async fn forward(subscribers: &Mutex<Vec<(String, Arc<Mutex<OwnedWriteHalf>>)>>, topic: &str, payload: &[u8]) {
+    let targets: Vec<_> = subscribers.lock().await.iter().filter(|(t, _)| t == topic).map(|(_, w)| w.clone()).collect();
+    for writer in targets {
+        let _ = writer.lock().await.write_all(&encode_publish(topic, payload)).await;
+    }
+}
+
+async fn serve_connection(
+    mut reader: OwnedReadHalf,
+    writer: Arc<Mutex<OwnedWriteHalf>>,
+    subscribers: Arc<Mutex<Vec<(String, Arc<Mutex<OwnedWriteHalf>>)>>>,
+    published: mpsc::UnboundedSender<(String, Vec<u8>)>,
+) {
+    while let Ok((header, body)) = read_packet(&mut reader).await {
+        match header >> 4 {
+            // CONNECT -> CONNACK
+            1 => {
+                let _ = writer.lock().await.write_all(&encode_packet(0x20, &[0, 0])).await;
+            }
+            // PUBLISH
+            3 => {
+                let qos = (header >> 1) & 0x03;
+                let (topic, mut at) = read_string(&body, 0);
+                if qos > 0 {
+                    let _ = writer.lock().await.write_all(&encode_packet(0x40, &body[at..at + 2])).await;
+                    at += 2;
+                }
+                let payload = body[at..].to_vec();
+                forward(&subscribers, &topic, &payload).await;
+                let _ = published.send((topic, payload));
+            }
+            // SUBSCRIBE -> SUBACK
+            8 => {
+                let mut ack = body[0..2].to_vec();
+                let mut at = 2;
+                while at < body.len() {
+                    let (topic, next) = read_string(&body, at);
+                    ack.push(body[next].min(1));
+                    at = next + 1;
+                    subscribers.lock().await.push((topic, writer.clone()));
+                }
+                let _ = writer.lock().await.write_all(&encode_packet(0x90, &ack)).await;
+            }
+            // PINGREQ -> PINGRESP
+            12 => {
+                let _ = writer.lock().await.write_all(&encode_packet(0xD0, &[])).await;
+            }
+            // DISCONNECT
+            14 => break,
+            _ => {}
+        }
+    }
+}
+
+fn test_handler() -> CommandHandler {
+    Arc::new(|command: RinAgentCommand| -> CommandFuture {
+        Box::pin(async move {
+            match command {
+                RinAgentCommand::BotStatus => Ok("bot ok".to_string()),
+                RinAgentCommand::TriggerAlarm { alarm_id, .. } => Err(format!("알람 {} 을(를) 찾을 수 없습니다.", alarm_id)),
+                _ => Ok("done".to_string()),
+            }
+        })
+    })
+}
+
+#[tokio::test]
+async fn executes_commands_and_reports_alerts() {
+    let mut broker = FakeBroker::start().await;
+    let (client, event_loop) = connect_mqtt(&broker.config(), "rin_agent_test");
+    tokio::spawn(run_mqtt_event_loop(client, event_loop, test_handler()));
+    broker.wait_for_subscriber(topics::RIN_AGENT_COMMAND).await;
+
+    broker.inject(topics::RIN_AGENT_COMMAND, br#"{"type": "bot_status"}"#).await;
+    let alert: RinAgentAlert = serde_json::from_slice(&broker.next_publish(topics::RIN_AGENT_ALERT).await).unwrap();
+    assert_eq!(alert.level, LogLevel::Info);
+    assert!(alert.message.contains("bot ok"), "{}", alert.message);
+
+    broker.inject(topics::RIN_AGENT_COMMAND, br#"{"type": "trigger_alarm", "alarm_id": 404}"#).await;
+    let alert: RinAgentAlert = serde_json::from_slice(&broker.next_publish(topics::RIN_AGENT_ALERT).await).unwrap();
+    assert_eq!(alert.level, LogLevel::Error);
+    assert!(alert.message.contains("404"), "{}", alert.message);
+}
+
+#[tokio::test]
+async fn rejects_malformed_commands() {
+    let mut broker = FakeBroker::start().await;
+    let (client, event_loop) = connect_mqtt(&broker.config(), "rin_agent_test");
+    tokio::spawn(run_mqtt_event_loop(client, event_loop, test_handler()));
+    broker.wait_for_subscriber(topics::RIN_AGENT_COMMAND).await;
+
+    broker.inject(topics::RIN_AGENT_COMMAND, br#"{"type": "self_destruct"}"#).await;
+    let alert: RinAgentAlert = serde_json::from_slice(&broker.next_publish(topics::RIN_AGENT_ALERT).await).unwrap();
+    assert_eq!(alert.level, LogLevel::Error);
+}
+
+#[tokio::test]
+async fn publishes_running_status() {
+    let mut broker = FakeBroker::start().await;
+    let (client, event_loop) = connect_mqtt(&broker.config(), "rin_agent_test");
+    tokio::spawn(run_mqtt_event_loop(client.clone(), event_loop, test_handler()));
+    broker.wait_for_subscriber(topics::RIN_AGENT_COMMAND).await;
+
+    publish_status_to(&client, &RinAgentStatus::Running {
+        uptime_secs: 42,
+        connected_guilds: 3,
+        active_voice_sessions: 1,
+        timestamp: 1_700_000_000,
+    });
+    let status: RinAgentStatus = serde_json::from_slice(&broker.next_publish(topics::RIN_AGENT_STATUS).await).unwrap();
+    match status {
+        RinAgentStatus::Running { uptime_secs, connected_guilds, active_voice_sessions, .. } => {
+            assert_eq!((uptime_secs, connected_guilds, active_voice_sessions), (42, 3, 1));
+        }
+        other => panic!("unexpected status: {:?}", other),
+    }
+}
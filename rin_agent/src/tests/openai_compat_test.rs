@@ -0,0 +1,110 @@
+#[cfg(test)]
+use serde_json::json;
+
+use crate::gemini::types::{GeminiActionResult, GeminiResponse};
+use crate::service::openai_compat_service::{
+    build_chunk, map_finish_reason, messages_to_chunks, parse_data_url, resolve_model, response_text,
+    ChatCompletionRequest, ChatDelta, OPENAI_MODEL_FLASH, OPENAI_MODEL_PRO,
+};
+use crate::setting::gemini_setting::GEMINI_MODEL_PRO;
+
+fn gemini_response(msg: &str, command_result: Vec<Result<GeminiActionResult, String>>) -> GeminiResponse {
+    GeminiResponse {
+        discord_msg: msg.to_string(),
+        sub_items: None,
+        finish_reason: "STOP".to_string(),
+        command_result,
+        avg_logprobs: 0.0,
+        thoughts: None,
+        model: "gemini-flash-latest".to_string(),
+        grounding: None,
+        code_executions: Vec::new(),
+    }
+}
+
+fn request(messages: serde_json::Value) -> ChatCompletionRequest {
+    serde_json::from_value(json!({ "model": OPENAI_MODEL_FLASH, "messages": messages })).unwrap()
+}
+
+#[test]
+fn resolves_model_names() {
+    assert_eq!(resolve_model(OPENAI_MODEL_FLASH), Some(false));
+    assert_eq!(resolve_model(OPENAI_MODEL_PRO), Some(true));
+    assert_eq!(resolve_model(&format!("models/{}", GEMINI_MODEL_PRO)), Some(true));
+    assert_eq!(resolve_model("gpt-4o"), None);
+}
+
+#[test]
+fn converts_messages_to_chunks() {
+    let request = request(json!([
+        { "role": "system", "content": "짧게 답해" },
+        { "role": "user", "content": "안녕" },
+        { "role": "assistant", "content": "안녕하세요, 주인님." },
+        { "role": "tool", "content": "{}" },
+        { "role": "user", "content": [
+            { "type": "text", "text": "이거 뭐야?" },
+            { "type": "image_url", "image_url": { "url": "data:image/png;base64,iVBORw0KGgo=" } },
+            { "type": "image_url", "image_url": { "url": "data:image/jpeg;base64,/9j/4AAQ" } },
+            { "type": "input_audio" }
+        ]}
+    ]));
+    assert!(!request.stream);
+    let chunks = messages_to_chunks(&request.messages, 7).unwrap();
+    assert_eq!(chunks.len(), 5);
+    assert_eq!(chunks[0].query, "[system] 짧게 답해");
+    assert!(!chunks[0].is_bot);
+    assert!(chunks[2].is_bot);
+    assert_eq!(chunks[3].query, "이거 뭐야?");
+    assert_eq!(chunks[3].image.as_ref().unwrap().mime_type, "image/png");
+    assert_eq!(chunks[4].query, "");
+    assert_eq!(chunks[4].image.as_ref().unwrap().mime_type, "image/jpeg");
+    assert!(chunks.iter().all(|c| c.user_id.as_deref() == Some("7") && c.channel_id.is_none()));
+}
+
+#[test]
+fn rejects_invalid_conversations() {
+    assert!(messages_to_chunks(&request(json!([{ "role": "assistant", "content": "hi" }])).messages, 7).is_err());
+    assert!(messages_to_chunks(&request(json!([{ "role": "robot", "content": "hi" }])).messages, 7).is_err());
+    assert!(messages_to_chunks(&request(json!([])).messages, 7).is_err());
+}
+
+#[test]
+fn parses_only_image_data_urls() {
+    let image = parse_data_url("data:image/webp;base64,UklGRg==").unwrap();
+    assert_eq!(image.mime_type, "image/webp");
+    assert_eq!(image.base64_image.as_deref(), Some("UklGRg=="));
+    assert!(parse_data_url("https://example.com/a.png").is_err());
+    assert!(parse_data_url("data:text/plain;base64,aGk=").is_err());
+    assert!(parse_data_url("data:image/png,raw").is_err());
+    assert!(parse_data_url("data:image/png;base64,@@@").is_err());
+}
+
+#[test]
+fn falls_back_to_tool_messages() {
+    assert_eq!(response_text(&gemini_response("  답장 ", Vec::new())), "답장");
+    let alarm = GeminiActionResult {
+        result_message: "알람 설정됨".to_string(),
+        show_user: Some("9시에 알려드릴게요".to_string()),
+        ..Default::default()
+    };
+    let search = GeminiActionResult { result_message: "검색 완료".to_string(), ..Default::default() };
+    let response = gemini_response("", vec![Ok(alarm), Err("실패".to_string()), Ok(search)]);
+    assert_eq!(response_text(&response), "9시에 알려드릴게요\n검색 완료");
+}
+
+#[test]
+fn maps_finish_reasons() {
+    assert_eq!(map_finish_reason("STOP"), "stop");
+    assert_eq!(map_finish_reason("MAX_TOKENS"), "length");
+    assert_eq!(map_finish_reason("SAFETY"), "content_filter");
+    assert_eq!(map_finish_reason("unknown"), "stop");
+}
+
+#[test]
+fn serializes_stream_chunks() {
+    let chunk = build_chunk("chatcmpl-1", OPENAI_MODEL_FLASH, 10, ChatDelta { role: Some("assistant"), content: None }, None);
+    let value = serde_json::to_value(&chunk).unwrap();
+    assert_eq!(value["object"], "chat.completion.chunk");
+    assert_eq!(value["choices"][0]["delta"], json!({ "role": "assistant" }));
+    assert!(value["choices"][0]["finish_reason"].is_null());
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+
+use crate::service::quota_service::{
+    can_use_pro, clamp_thinking_budget, daily_key, next_daily_reset, parse_check_result, resolve_user_limits,
+    QuotaDeniedReason, QuotaScope, QuotaSubject,
+};
+use crate::setting::quota_setting::{QuotaConfig, QuotaLimits};
+
+fn limits(daily_cost_usd: f64, max_thinking_budget: Option<i32>) -> QuotaLimits {
+    QuotaLimits {
+        burst: 5,
+        refill_per_minute: 3.0,
+        daily_tokens: 1000,
+        daily_cost_usd,
+        max_thinking_budget,
+    }
+}
+
+#[test]
+fn config_parses_role_ids_and_defaults() {
+    let config: QuotaConfig = serde_json::from_str(r#"{
+        "roles": {"111": {"burst": 10, "refill_per_minute": 6, "daily_cost_usd": 5}},
+        "pro_roles": [111]
+    }"#).unwrap();
+    assert_eq!(config.user, QuotaConfig::default().user);
+    assert_eq!(config.roles.get(&111).unwrap().burst, 10);
+    assert_eq!(config.roles.get(&111).unwrap().daily_tokens, 0);
+    assert_eq!(config.pro_roles, vec![111]);
+}
+
+#[test]
+fn most_generous_role_wins() {
+    let config = QuotaConfig {
+        user: limits(1.0, Some(1024)),
+        roles: HashMap::from([(1, limits(3.0, None)), (2, limits(0.0, Some(4096))), (3, limits(2.0, None))]),
+        ..Default::default()
+    };
+    assert_eq!(resolve_user_limits(&config, &[]), config.user);
+    assert_eq!(resolve_user_limits(&config, &[9]), config.user);
+    assert_eq!(resolve_user_limits(&config, &[1, 3]).daily_cost_usd, 3.0);
+    // 0 은 무제한이므로 가장 너그럽습니다.
+    assert_eq!(resolve_user_limits(&config, &[1, 2]).max_thinking_budget, Some(4096));
+}
+
+#[test]
+fn pro_requires_configured_role() {
+    let mut config = QuotaConfig::default();
+    let subject = QuotaSubject { user_id: 42, guild_id: Some(7), role_ids: vec![1] };
+    assert!(can_use_pro(&config, &subject));
+    config.pro_roles = vec![2];
+    assert!(!can_use_pro(&config, &subject));
+    config.pro_roles = vec![1, 2];
+    assert!(can_use_pro(&config, &subject));
+}
+
+#[test]
+fn thinking_budget_is_clamped() {
+    assert_eq!(clamp_thinking_budget(&limits(1.0, Some(1024)), Some(24576)), Some(1024));
+    assert_eq!(clamp_thinking_budget(&limits(1.0, Some(1024)), Some(512)), Some(512));
+    assert_eq!(clamp_thinking_budget(&limits(1.0, Some(1024)), None), None);
+    assert_eq!(clamp_thinking_budget(&limits(1.0, None), Some(24576)), Some(24576));
+}
+
+#[test]
+fn daily_reset_and_keys_use_utc_date() {
+    let now = Utc.with_ymd_and_hms(2026, 2, 14, 23, 59, 0).unwrap();
+    assert_eq!(next_daily_reset(now), Utc.with_ymd_and_hms(2026, 2, 15, 0, 0, 0).unwrap());
+    assert_eq!(daily_key(QuotaScope::Guild, 7, now), "rin:quota:daily:guild:7:20260214");
+}
+
+#[test]
+fn check_result_allows_and_reports_usage() {
+    let now = Utc.with_ymd_and_hms(2026, 2, 14, 12, 0, 0).unwrap();
+    let scopes = vec![(QuotaScope::User, limits(1.0, None)), (QuotaScope::Guild, limits(10.0, None))];
+    let usages = parse_check_result(&[0, 0, 0, 4, 100, 250_000, 29, 900, 1_500_000], &scopes, now).unwrap();
+    assert_eq!(usages.len(), 2);
+    assert_eq!(usages[0].remaining_requests, 4);
+    assert_eq!(usages[0].used_tokens, 100);
+    assert!((usages[0].used_cost_usd - 0.25).abs() < 1e-9);
+    assert_eq!(usages[1].scope, QuotaScope::Guild);
+}
+
+#[test]
+fn zero_limits_do_not_block() {
+    let now = Utc.with_ymd_and_hms(2026, 2, 14, 12, 0, 0).unwrap();
+    let unlimited = QuotaLimits {
+        burst: 0,
+        refill_per_minute: 0.0,
+        daily_tokens: 0,
+        daily_cost_usd: 0.0,
+        max_thinking_budget: None,
+    };
+    assert!(!unlimited.limits_requests());
+    assert!(!QuotaLimits { burst: 5, ..unlimited.clone() }.limits_requests());
+    assert!(!QuotaLimits { refill_per_minute: 3.0, ..unlimited.clone() }.limits_requests());
+    assert!(limits(1.0, None).limits_requests());
+
+    // 버킷을 건너뛴 범위는 남은 요청이 -1 로 옵니다.
+    let scopes = vec![(QuotaScope::User, unlimited.clone()), (QuotaScope::Guild, limits(10.0, None))];
+    let usages = parse_check_result(&[0, 0, 0, -1, 5_000, 0, 29, 900, 0], &scopes, now).unwrap();
+    assert_eq!(usages[0].remaining_requests, 0);
+
+    let scopes = vec![
+        (QuotaScope::User, limits(1.0, None)),
+        (QuotaScope::Guild, QuotaLimits { daily_cost_usd: 1.0, ..unlimited }),
+    ];
+    let denied = parse_check_result(&[3, 2, 0, 4, 100, 0, -1, 10, 2_000_000], &scopes, now).unwrap_err();
+    assert_eq!(denied.reason, QuotaDeniedReason::DailyCostExceeded);
+    assert!(denied.to_message().contains("남은 요청 제한 없음"));
+    assert!(denied.to_message().contains("토큰 10 / 제한 없음"));
+}
+
+#[test]
+fn check_result_denials_explain_reset() {
+    let now = Utc.with_ymd_and_hms(2026, 2, 14, 12, 0, 0).unwrap();
+    let scopes = vec![(QuotaScope::User, limits(1.0, None)), (QuotaScope::Guild, limits(10.0, None))];
+
+    let denied = parse_check_result(&[1, 1, 20_000, 0, 100, 0], &scopes, now).unwrap_err();
+    assert_eq!(denied.reason, QuotaDeniedReason::RateLimited { retry_after_ms: 20_000 });
+    assert_eq!(denied.reset_at, Utc.with_ymd_and_hms(2026, 2, 14, 12, 0, 20).unwrap());
+    assert!(denied.to_message().contains(&format!("<t:{}:R>", denied.reset_at.timestamp())));
+    assert!(denied.to_message().contains("남은 요청 0 / 5"));
+
+    let denied = parse_check_result(&[2, 2, 0, 3, 10, 0, 20, 1000, 0], &scopes, now).unwrap_err();
+    assert_eq!(denied.reason, QuotaDeniedReason::DailyTokensExceeded);
+    assert_eq!(denied.usage.as_ref().unwrap().scope, QuotaScope::Guild);
+    assert_eq!(denied.reset_at, Utc.with_ymd_and_hms(2026, 2, 15, 0, 0, 0).unwrap());
+    assert!(denied.to_message().contains("이 서버의"));
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+use serde_json::json;
+
+use crate::gemini::receipt_ocr::{parse_receipt, ReceiptData, ReceiptItem};
+use crate::service::receipt_split_service::{
+    encode_receipt_image, parse_user_mentions, propose_split, receipt_image_src, split_evenly, split_memo,
+    store_split_proposal, take_split_proposal,
+};
+
+fn sample_receipt(total: f64, currency: &str) -> ReceiptData {
+    ReceiptData {
+        merchant: Some("김밥천국".to_string()),
+        date: Some("2026-03-20".to_string()),
+        currency: currency.to_string(),
+        items: vec![ReceiptItem { name: "라면".to_string(), quantity: Some(2.0), amount: total }],
+        subtotal: None,
+        tax: None,
+        tip: None,
+        total,
+    }
+}
+
+#[test]
+fn splits_evenly_with_payer_taking_remainder() {
+    assert_eq!(split_evenly(10000, 3), vec![3334, 3333, 3333]);
+    assert_eq!(split_evenly(9000, 3), vec![3000, 3000, 3000]);
+    assert_eq!(split_evenly(5, 1), vec![5]);
+    assert!(split_evenly(100, 0).is_empty());
+}
+
+#[test]
+fn parses_user_mentions_in_order() {
+    assert_eq!(parse_user_mentions("<@12> 랑 <@!34> 그리고 <@12> 와 나눠줘"), vec![12, 34]);
+    assert_eq!(parse_user_mentions("<@&56> 역할, <#78> 채널, <@abc>"), Vec::<u64>::new());
+    assert!(parse_user_mentions("멘션 없음").is_empty());
+}
+
+#[test]
+fn proposes_split_without_payer_share() {
+    let participants = vec![(1, "나".to_string()), (2, "민수".to_string()), (3, "지연".to_string())];
+    let proposal = propose_split(1, Some(9), sample_receipt(10000.4, "KRW"), &participants);
+
+    assert_eq!(proposal.owner_share, 3334);
+    assert_eq!(proposal.shares.iter().map(|s| (s.user_id, s.amount)).collect::<Vec<_>>(), vec![(2, 3333), (3, 3333)]);
+    assert_eq!(proposal.image_id, Some(9));
+}
+
+#[test]
+fn does_not_split_foreign_currency() {
+    let participants = vec![(2, "민수".to_string()), (3, "지연".to_string())];
+    let proposal = propose_split(1, None, sample_receipt(12.5, "USD"), &participants);
+
+    assert!(proposal.shares.is_empty());
+    assert_eq!(proposal.owner_share, 0);
+    assert_eq!(split_memo(&sample_receipt(10000.0, "KRW")), "김밥천국 2026-03-20 나눠 내기");
+}
+
+#[test]
+fn parses_and_validates_receipt_json() {
+    let value = json!({
+        "is_receipt": true,
+        "merchant": "카페",
+        "date": null,
+        "currency": "krw",
+        "items": [{ "name": "아메리카노", "quantity": 2, "amount": 9000 }],
+        "subtotal": null,
+        "tax": null,
+        "tip": null,
+        "total": 9000
+    });
+    let receipt = parse_receipt(&value).unwrap();
+    assert_eq!(receipt.currency, "KRW");
+    assert_eq!(receipt.total_units(), 9000);
+    assert_eq!(receipt.items[0].quantity, Some(2.0));
+
+    let not_receipt = json!({ "is_receipt": false, "currency": "KRW", "items": [], "total": 0 });
+    assert!(parse_receipt(&not_receipt).unwrap_err().contains("영수증"));
+
+    let missing_total = json!({ "is_receipt": true, "currency": "KRW", "items": [] });
+    assert!(parse_receipt(&missing_total).unwrap_err().contains("total"));
+}
+
+#[test]
+fn only_payer_can_take_proposal_once() {
+    let proposal = propose_split(1, None, sample_receipt(6000.0, "KRW"), &[(2, "민수".to_string())]);
+    store_split_proposal(4242, proposal.clone());
+
+    assert!(take_split_proposal(4242, 2).is_err());
+    assert_eq!(take_split_proposal(4242, 1).unwrap(), proposal);
+    assert!(take_split_proposal(4242, 1).is_err());
+}
+
+#[test]
+fn keeps_receipt_image_as_data_url() {
+    let encoded = encode_receipt_image(&[0xff, 0xd8, 0xff]);
+    assert_eq!(encoded, "/9j/");
+    assert_eq!(receipt_image_src("image/jpeg", &encoded), "data:image/jpeg;base64,/9j/");
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+use serde_json::json;
+
+use crate::api::search_provider::{
+    search_cache_key, strip_html_tags, BingProvider, BraveProvider, GoogleCseProvider, SearchProvider,
+    SearchProviderKind, SearchResult, SearxngProvider,
+};
+use crate::tests::test_support::{spawn_stub_server, StubResponse};
+
+#[tokio::test]
+async fn google_cse_encodes_query_and_normalizes_items() {
+    let body = json!({
+        "items": [
+            {"kind": "customsearch#result", "title": "Rust", "link": "https://www.rust-lang.org", "snippet": "A language"},
+            {"title": "링크 없음"}
+        ]
+    });
+    let (base_url, mut requests) = spawn_stub_server(vec![StubResponse::json(200, body.to_string())]).await;
+    let provider = GoogleCseProvider { base_url, token: "key".to_string(), cx: "cx".to_string() };
+
+    let results = provider.search("C++ & Rust", 5).await.unwrap();
+
+    let request = requests.recv().await.unwrap();
+    assert!(request.path.starts_with("/customsearch/v1?"), "{}", request.path);
+    assert!(request.path.contains("q=C%2B%2B+%26+Rust"), "{}", request.path);
+    assert!(request.path.contains("num=5"), "{}", request.path);
+    assert_eq!(
+        results,
+        vec![SearchResult {
+            title: "Rust".to_string(),
+            link: "https://www.rust-lang.org".to_string(),
+            snippet: "A language".to_string(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn google_cse_without_items_is_empty() {
+    let (base_url, _requests) =
+        spawn_stub_server(vec![StubResponse::json(200, json!({"kind": "customsearch#search"}).to_string())]).await;
+    let provider = GoogleCseProvider { base_url, token: "key".to_string(), cx: "cx".to_string() };
+
+    assert!(provider.search("없는 검색어", 5).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn searxng_requests_json_format_and_limits_count() {
+    let body = json!({
+        "results": [
+            {"title": "One", "url": "https://one.example", "content": "first"},
+            {"title": "Two", "url": "https://two.example", "content": "second"},
+            {"title": "Three", "url": "https://three.example", "content": "third"}
+        ]
+    });
+    let (base_url, mut requests) = spawn_stub_server(vec![StubResponse::json(200, body.to_string())]).await;
+    let provider = SearxngProvider { base_url: format!("{}/", base_url) };
+
+    let results = provider.search("러스트 언어", 2).await.unwrap();
+
+    let request = requests.recv().await.unwrap();
+    assert!(request.path.starts_with("/search?"), "{}", request.path);
+    assert!(request.path.contains("format=json"), "{}", request.path);
+    assert!(!request.path.contains(' '), "{}", request.path);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1].link, "https://two.example");
+    assert_eq!(results[1].snippet, "second");
+}
+
+#[tokio::test]
+async fn brave_sends_token_and_strips_markup() {
+    let body = json!({
+        "web": {
+            "results": [
+                {"title": "Rust <strong>Book</strong>", "url": "https://doc.rust-lang.org/book", "description": "The <strong>Rust</strong> book"}
+            ]
+        }
+    });
+    let (base_url, mut requests) = spawn_stub_server(vec![StubResponse::json(200, body.to_string())]).await;
+    let provider = BraveProvider { base_url, token: "brave-token".to_string() };
+
+    let results = provider.search("rust book", 3).await.unwrap();
+
+    let request = requests.recv().await.unwrap();
+    assert!(request.path.starts_with("/res/v1/web/search?"), "{}", request.path);
+    assert_eq!(request.headers.get("x-subscription-token").map(String::as_str), Some("brave-token"));
+    assert_eq!(results[0].title, "Rust Book");
+    assert_eq!(results[0].snippet, "The Rust book");
+}
+
+#[tokio::test]
+async fn bing_maps_web_pages() {
+    let body = json!({
+        "webPages": {
+            "value": [
+                {"name": "Tokio", "url": "https://tokio.rs", "snippet": "An asynchronous runtime"}
+            ]
+        }
+    });
+    let (base_url, mut requests) = spawn_stub_server(vec![StubResponse::json(200, body.to_string())]).await;
+    let provider = BingProvider { base_url, key: "bing-key".to_string() };
+
+    let results = provider.search("tokio", 3).await.unwrap();
+
+    let request = requests.recv().await.unwrap();
+    assert!(request.path.starts_with("/v7.0/search?"), "{}", request.path);
+    assert_eq!(request.headers.get("ocp-apim-subscription-key").map(String::as_str), Some("bing-key"));
+    assert_eq!(results[0].title, "Tokio");
+    assert_eq!(results[0].link, "https://tokio.rs");
+}
+
+#[tokio::test]
+async fn error_status_is_reported() {
+    let (base_url, _requests) =
+        spawn_stub_server(vec![StubResponse::json(403, json!({"error": {"message": "quota"}}).to_string())]).await;
+    let provider = BingProvider { base_url, key: "bing-key".to_string() };
+
+    let error = provider.search("tokio", 3).await.unwrap_err();
+    assert!(error.contains("403"), "{}", error);
+}
+
+#[test]
+fn cache_key_ignores_case_and_spacing() {
+    assert_eq!(
+        search_cache_key(SearchProviderKind::Brave, "  Rust   Book ", 8),
+        search_cache_key(SearchProviderKind::Brave, "rust book", 8)
+    );
+    assert_ne!(
+        search_cache_key(SearchProviderKind::Brave, "rust book", 8),
+        search_cache_key(SearchProviderKind::Bing, "rust book", 8)
+    );
+    assert_eq!(SearchProviderKind::parse(" SearxNG "), Some(SearchProviderKind::Searxng));
+    assert_eq!(strip_html_tags("<b>Rust</b> 1.80 &amp; up"), "Rust 1.80 &amp; up");
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+use std::time::Duration;
+
+use crate::libs::shutdown::WorkTracker;
+
+#[tokio::test]
+async fn drain_returns_immediately_when_idle() {
+    let tracker = WorkTracker::default();
+    assert!(tracker.drain(Duration::from_millis(10)).await);
+}
+
+#[tokio::test]
+async fn drain_waits_for_guards() {
+    let tracker: &'static WorkTracker = Box::leak(Box::new(WorkTracker::default()));
+    let first = tracker.begin();
+    let second = tracker.begin();
+    assert_eq!(tracker.active(), 2);
+
+    tokio::spawn(async move {
+        let _first = first;
+        let _second = second;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    });
+    assert!(tracker.drain(Duration::from_secs(5)).await);
+    assert_eq!(tracker.active(), 0);
+}
+
+#[tokio::test]
+async fn drain_gives_up_after_deadline() {
+    let tracker = WorkTracker::default();
+    let _stuck = tracker.begin();
+    assert!(!tracker.drain(Duration::from_millis(20)).await);
+    assert_eq!(tracker.active(), 1);
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+use serde_json::json;
+
+use crate::gemini::structured_output::{display_width, validate_against_schema, StructuredKind, StructuredTable};
+
+#[test]
+fn kinds_round_trip() {
+    for kind in StructuredKind::ALL {
+        assert_eq!(StructuredKind::parse(kind.as_str()), Some(kind));
+    }
+    assert_eq!(StructuredKind::parse("poem"), None);
+}
+
+#[test]
+fn schema_validation_reports_path() {
+    let schema = StructuredKind::Checklist.schema();
+    let valid = json!({"title": "이사 준비", "items": [{"task": "짐 싸기", "done": false, "note": null}]});
+    assert_eq!(validate_against_schema(&valid, &schema), Ok(()));
+
+    let wrong_type = json!({"title": "이사 준비", "items": [{"task": "짐 싸기", "done": "no"}]});
+    assert_eq!(
+        validate_against_schema(&wrong_type, &schema),
+        Err("$.items[0].done: true/false 이어야 합니다".to_string())
+    );
+
+    let missing = json!({"title": "이사 준비", "items": [{"done": true}]});
+    assert_eq!(
+        validate_against_schema(&missing, &schema),
+        Err("$.items[0].task: 필수 항목이 없습니다".to_string())
+    );
+
+    let empty = json!({"title": "이사 준비", "items": []});
+    assert!(validate_against_schema(&empty, &schema).unwrap_err().starts_with("$.items: 항목이 너무 적습니다"));
+}
+
+#[test]
+fn comparison_table_pads_missing_values() {
+    let data = json!({
+        "title": "언어 비교",
+        "columns": ["Rust", "Go"],
+        "rows": [
+            {"criterion": "GC", "values": ["없음", "있음"]},
+            {"criterion": "제네릭", "values": ["있음"]}
+        ],
+        "summary": "둘 다 좋습니다."
+    });
+    let table = StructuredTable::from_value(StructuredKind::Comparison, &data);
+    assert_eq!(table.headers, vec!["항목", "Rust", "Go"]);
+    assert_eq!(table.rows[1], vec!["제네릭", "있음", ""]);
+    assert_eq!(table.summary.as_deref(), Some("둘 다 좋습니다."));
+    assert_eq!(
+        table.to_code_block(4000),
+        "```\n항목   | Rust | Go\n-------+------+-----\nGC     | 없음 | 있음\n제네릭 | 있음\n```"
+    );
+}
+
+#[test]
+fn csv_escapes_cells() {
+    let table = StructuredTable {
+        title: "t".to_string(),
+        headers: vec!["날짜".to_string(), "사건".to_string(), "설명".to_string()],
+        rows: vec![vec!["2026-01-01".to_string(), "출시, 1차".to_string(), "\"베타\"\n종료".to_string()]],
+        summary: None,
+    };
+    assert_eq!(table.to_csv(), "\u{feff}날짜,사건,설명\r\n2026-01-01,\"출시, 1차\",\"\"\"베타\"\"\n종료\"\r\n");
+}
+
+#[test]
+fn csv_neutralizes_formula_cells() {
+    let table = StructuredTable {
+        title: "t".to_string(),
+        headers: vec!["식".to_string(), "값".to_string()],
+        rows: vec![
+            vec!["=HYPERLINK(\"x\")".to_string(), "+1".to_string()],
+            vec!["@SUM(A1,A2)".to_string(), "-3".to_string()],
+        ],
+        summary: None,
+    };
+    assert_eq!(
+        table.to_csv(),
+        "\u{feff}식,값\r\n\"'=HYPERLINK(\"\"x\"\")\",'+1\r\n\"'@SUM(A1,A2)\",'-3\r\n"
+    );
+}
+
+#[test]
+fn code_block_omits_rows_over_budget() {
+    let table = StructuredTable {
+        headers: vec!["a".to_string()],
+        rows: (0..100).map(|i| vec![format!("row {}", i)]).collect(),
+        ..Default::default()
+    };
+    let block = table.to_code_block(200);
+    assert!(block.chars().count() <= 200);
+    assert!(block.contains("행 생략)"));
+    assert_eq!(display_width("가a"), 3);
+}
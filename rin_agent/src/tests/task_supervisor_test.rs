@@ -0,0 +1,108 @@
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::libs::task_supervisor::{CrashHistory, RestartPolicy, SupervisorEvent, TaskExit, TaskSupervisor};
+
+fn fast_policy(crash_budget: usize) -> RestartPolicy {
+    RestartPolicy {
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(4),
+        crash_budget,
+        crash_window: Duration::from_secs(60),
+    }
+}
+
+#[test]
+fn backoff_doubles_until_max() {
+    let policy = RestartPolicy::default();
+    assert_eq!(policy.backoff(1), Duration::from_secs(1));
+    assert_eq!(policy.backoff(2), Duration::from_secs(2));
+    assert_eq!(policy.backoff(4), Duration::from_secs(8));
+    assert_eq!(policy.backoff(7), Duration::from_secs(60));
+    assert_eq!(policy.backoff(1000), Duration::from_secs(60));
+}
+
+#[test]
+fn crash_history_forgets_old_crashes() {
+    let window = Duration::from_secs(10);
+    let start = Instant::now();
+    let mut history = CrashHistory::default();
+    assert_eq!(history.record(start, window), 1);
+    assert_eq!(history.record(start + Duration::from_secs(5), window), 2);
+    assert_eq!(history.record(start + Duration::from_secs(12), window), 2);
+    assert_eq!(history.record(start + Duration::from_secs(30), window), 1);
+}
+
+#[tokio::test]
+async fn restarts_panicking_task_until_budget() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorder = events.clone();
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = runs.clone();
+
+    let supervisor = TaskSupervisor::new(fast_policy(2), Arc::new(move |event| recorder.lock().unwrap().push(event)))
+        .task("flaky", move || {
+            let counter = counter.clone();
+            async move {
+                if counter.fetch_add(1, Ordering::SeqCst) < 10 {
+                    panic!("boom");
+                }
+                Ok(())
+            }
+        })
+        .task("steady", std::future::pending::<Result<(), String>>);
+
+    let gave_up = tokio::time::timeout(Duration::from_secs(5), supervisor.run(CancellationToken::new())).await.unwrap();
+    assert_eq!(gave_up, Some(("flaky".to_string(), TaskExit::Panicked("boom".to_string()))));
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+    let events = events.lock().unwrap();
+    let flaky: Vec<_> = events.iter().filter(|e| !matches!(e, SupervisorEvent::Started { task, .. } if task == "steady")).collect();
+    assert_eq!(flaky.len(), 6);
+    assert!(matches!(flaky[3], SupervisorEvent::Exited { restart_in, .. } if *restart_in == Duration::from_millis(2)));
+    assert!(matches!(flaky[5], SupervisorEvent::GaveUp { crashes: 3, .. }));
+}
+
+#[tokio::test]
+async fn failed_task_reports_error() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorder = events.clone();
+    let supervisor = TaskSupervisor::new(fast_policy(0), Arc::new(move |event| recorder.lock().unwrap().push(event)))
+        .task("web", || async { Err::<(), _>("bind failed".to_string()) });
+
+    let gave_up = supervisor.run(CancellationToken::new()).await;
+    assert_eq!(gave_up, Some(("web".to_string(), TaskExit::Failed("bind failed".to_string()))));
+    assert_eq!(
+        events.lock().unwrap().last(),
+        Some(&SupervisorEvent::GaveUp { task: "web".to_string(), exit: TaskExit::Failed("bind failed".to_string()), crashes: 1 })
+    );
+}
+
+#[tokio::test]
+async fn shutdown_stops_restarts() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorder = events.clone();
+    let shutdown = CancellationToken::new();
+    let token = shutdown.clone();
+    let supervisor = TaskSupervisor::new(fast_policy(100), Arc::new(move |event| recorder.lock().unwrap().push(event)))
+        .task("scheduler", move || {
+            let token = token.clone();
+            async move {
+                token.cancelled().await;
+                Ok(())
+            }
+        });
+
+    let run = tokio::spawn(supervisor.run(shutdown.clone()));
+    shutdown.cancel();
+    let gave_up = tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap();
+    assert_eq!(gave_up, None);
+    assert_eq!(
+        events.lock().unwrap().last(),
+        Some(&SupervisorEvent::Stopped { task: "scheduler".to_string(), exit: TaskExit::Finished })
+    );
+}
@@ -22,7 +22,7 @@ async fn test_gemini_cache() {
     let use_pro = false; // Set to true if you want to use the pro version
     let ttl:f32 = 12.0; // Time to live in seconds\
     let v_q = vec![chunk_for_query];
-    let setting = generate_gemini_cache_setting(v_q.clone(), &begin_query, use_pro, ttl);
+    let setting = generate_gemini_cache_setting(v_q.clone(), &begin_query, use_pro, ttl, &Default::default());
     let response = gemini_client.start_gemini_cache(
       v_q, &begin_query, use_pro, ttl)
       .await;
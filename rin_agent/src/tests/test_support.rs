@@ -0,0 +1,84 @@
+#[cfg(test)]
+use std::collections::HashMap;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+use crate::service::web_auth_service::{AuthUser, GuildScope, WebRole, TOKEN_KIND_SESSION};
+
+// 길드 100 은 관리자, 200 은 일반 멤버인 웹 사용자.
+pub fn auth_user(is_owner: bool) -> AuthUser {
+    AuthUser {
+        user_id: 7,
+        user_name: Some("rin".to_string()),
+        token_id: 1,
+        kind: TOKEN_KIND_SESSION.to_string(),
+        is_owner,
+        guilds: vec![
+            GuildScope { guild_id: 100, name: "a".to_string(), role: WebRole::GuildAdmin },
+            GuildScope { guild_id: 200, name: "b".to_string(), role: WebRole::Member },
+        ],
+    }
+}
+
+#[derive(Debug)]
+pub struct RecordedRequest {
+    pub path: String,
+    pub headers: HashMap<String, String>,
+}
+
+pub struct StubResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl StubResponse {
+    pub fn json(status: u16, body: String) -> Self {
+        StubResponse { status, headers: vec![("Content-Type", "application/json".to_string())], body }
+    }
+}
+
+// 연결마다 준비된 응답을 순서대로 하나씩 돌려주고, 받은 요청의 경로와 헤더를 기록하는 HTTP 서버 대역.
+pub async fn spawn_stub_server(responses: Vec<StubResponse>) -> (String, mpsc::UnboundedReceiver<RecordedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                let mut chunk = [0u8; 4096];
+                let n = stream.read(&mut chunk).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                buf.extend_from_slice(&chunk[..n]);
+            }
+            let head = String::from_utf8_lossy(&buf).to_string();
+            let mut lines = head.lines();
+            let path = lines.next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or_default().to_string();
+            let headers = lines
+                .filter_map(|line| line.split_once(':'))
+                .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+                .collect();
+            let _ = tx.send(RecordedRequest { path, headers });
+
+            let mut head = format!(
+                "HTTP/1.1 {} Stub\r\nConnection: close\r\nContent-Length: {}\r\n",
+                response.status,
+                response.body.len()
+            );
+            for (k, v) in &response.headers {
+                head.push_str(&format!("{}: {}\r\n", k, v));
+            }
+            head.push_str("\r\n");
+            stream.write_all(head.as_bytes()).await.unwrap();
+            stream.write_all(response.body.as_bytes()).await.ok();
+            stream.shutdown().await.ok();
+        }
+    });
+    (base_url, rx)
+}
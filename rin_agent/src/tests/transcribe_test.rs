@@ -0,0 +1,76 @@
+#[cfg(test)]
+use serenity::all::{ChannelId, UserId};
+
+use crate::gemini::tools::query_transcripts;
+use crate::gemini::types::DiscordUserInfo;
+use crate::gemini::structured_output::validate_against_schema;
+use crate::service::transcribe_service::{format_summary, format_transcript_line, summary_schema, TranscriptActionItem, TranscriptLine, TranscriptSummary};
+
+#[test]
+fn summary_parses_model_json() {
+    let text = r#"{
+        "overview": "배포 일정 회의",
+        "decisions": ["금요일에 배포"],
+        "action_items": [{"owner": "rin", "task": "릴리즈 노트 작성", "due": "목요일"}],
+        "proposed_alarms": [{"time": "2026-02-06 10:00:00+09:00", "message": "배포 시작"}]
+    }"#;
+    let value: serde_json::Value = serde_json::from_str(text).unwrap();
+    validate_against_schema(&value, &summary_schema()).unwrap();
+    let summary: TranscriptSummary = serde_json::from_value(value).unwrap();
+    assert_eq!(summary.decisions, vec!["금요일에 배포".to_string()]);
+    assert_eq!(summary.action_items[0], TranscriptActionItem {
+        owner: "rin".to_string(),
+        task: "릴리즈 노트 작성".to_string(),
+        due: Some("목요일".to_string()),
+    });
+
+    let rendered = format_summary(&summary);
+    assert!(rendered.starts_with("배포 일정 회의"));
+    assert!(rendered.contains("- 금요일에 배포"));
+    assert!(rendered.contains("- [rin] 릴리즈 노트 작성 (기한: 목요일)"));
+    assert!(rendered.contains("- 2026-02-06 10:00:00+09:00 : 배포 시작"));
+}
+
+#[test]
+fn empty_summary_renders_placeholders() {
+    let summary: TranscriptSummary = serde_json::from_str("{}").unwrap();
+    let rendered = format_summary(&summary);
+    assert_eq!(rendered.matches("- 없음").count(), 2);
+    assert!(!rendered.contains("제안하는 알람"));
+}
+
+#[test]
+fn transcript_line_names_the_speaker() {
+    let line = TranscriptLine {
+        user_id: UserId::new(1),
+        user_name: "canard".to_string(),
+        content: "안녕하세요".to_string(),
+        at: chrono::Utc::now(),
+    };
+    assert_eq!(format_transcript_line(&line), "**canard** : 안녕하세요");
+}
+
+#[test]
+fn query_transcripts_has_no_guild_parameter() {
+    let tool = query_transcripts::get_command();
+    assert!(!tool.parameters.contains_key("guild_id"));
+}
+
+#[tokio::test]
+async fn query_transcripts_requires_guild_context() {
+    let tool = query_transcripts::get_command();
+
+    let err = (tool.action)(Default::default(), None).await.unwrap_err();
+    assert!(err.contains("길드"));
+
+    // DM 처럼 guild_id 가 없으면 DB 를 보기 전에 거절
+    let dm_user = DiscordUserInfo {
+        user_id: UserId::new(7),
+        username: None,
+        channel_id: ChannelId::new(1),
+        guild_id: None,
+        context_id: None,
+    };
+    let err = (tool.action)(Default::default(), Some(dm_user)).await.unwrap_err();
+    assert!(err.contains("길드"));
+}
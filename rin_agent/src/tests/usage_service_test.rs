@@ -0,0 +1,89 @@
+#[cfg(test)]
+use std::collections::HashMap;
+
+use gemini_live_api::types::GeminiUsageMetadata;
+use serenity::all::Permissions;
+
+use crate::discord::commands::usage::can_view_guild_usage;
+use crate::gemini::types::GeminiModelPrice;
+use crate::service::usage_service::{compute_cost, find_model_price, UsageGroupBy, UsageSummary, UsageTokens};
+
+fn price_table() -> HashMap<String, GeminiModelPrice> {
+    HashMap::from([
+        ("gemini-2.5-flash".to_string(), GeminiModelPrice { input: 0.3, cached_input: 0.03, output: 2.5 }),
+        ("gemini-2.5-flash-lite".to_string(), GeminiModelPrice { input: 0.1, cached_input: 0.01, output: 0.4 }),
+    ])
+}
+
+#[test]
+fn tokens_from_usage_metadata() {
+    let metadata: GeminiUsageMetadata = serde_json::from_str(r#"{
+        "promptTokenCount": 1200,
+        "cachedContentTokenCount": 1000,
+        "candidatesTokenCount": 80,
+        "thoughtsTokenCount": 40,
+        "totalTokenCount": 1320
+    }"#).unwrap();
+    let tokens = UsageTokens::from(&metadata);
+    assert_eq!(tokens, UsageTokens {
+        prompt: 1200,
+        cached: 1000,
+        thoughts: 40,
+        output: 80,
+        tool_use_prompt: 0,
+        total: 1320,
+    });
+}
+
+#[test]
+fn price_lookup_prefers_exact_then_longest_prefix() {
+    let table = price_table();
+    assert_eq!(find_model_price(&table, "gemini-2.5-flash").unwrap().input, 0.3);
+    assert_eq!(find_model_price(&table, "models/gemini-2.5-flash-lite").unwrap().input, 0.1);
+    assert_eq!(find_model_price(&table, "gemini-2.5-flash-lite-preview-06-17").unwrap().input, 0.1);
+    assert_eq!(find_model_price(&table, "gemini-2.5-flash-preview-05-20").unwrap().input, 0.3);
+    assert!(find_model_price(&table, "gemini-1.0-pro").is_none());
+}
+
+#[test]
+fn cost_splits_cached_input_and_bills_thoughts_as_output() {
+    let price = GeminiModelPrice { input: 1.0, cached_input: 0.1, output: 10.0 };
+    let tokens = UsageTokens {
+        prompt: 1_000_000,
+        cached: 500_000,
+        thoughts: 100_000,
+        output: 100_000,
+        tool_use_prompt: 0,
+        total: 1_200_000,
+    };
+    // 입력 50만 * 1.0 + 캐시 50만 * 0.1 + (출력+생각) 20만 * 10.0
+    let cost = compute_cost(&price, &tokens);
+    assert!((cost - (0.5 + 0.05 + 2.0)).abs() < 1e-9);
+}
+
+#[test]
+fn summary_total_and_group_by_parse() {
+    let rows = vec![
+        UsageSummary { key: Some("1".to_string()), requests: 2, prompt_tokens: 10, output_tokens: 5, total_tokens: 15, cost_usd: 0.5, ..Default::default() },
+        UsageSummary { key: None, requests: 1, prompt_tokens: 4, cached_tokens: 2, total_tokens: 4, cost_usd: 0.25, ..Default::default() },
+    ];
+    let total = UsageSummary::total(&rows);
+    assert_eq!(total.key, None);
+    assert_eq!(total.requests, 3);
+    assert_eq!(total.prompt_tokens, 14);
+    assert_eq!(total.cached_tokens, 2);
+    assert_eq!(total.total_tokens, 19);
+    assert!((total.cost_usd - 0.75).abs() < 1e-9);
+
+    assert_eq!(UsageGroupBy::parse("guild"), Some(UsageGroupBy::Guild));
+    assert_eq!(UsageGroupBy::parse("model"), Some(UsageGroupBy::Model));
+    assert_eq!(UsageGroupBy::parse("channel"), None);
+}
+
+#[test]
+fn guild_usage_requires_manage_guild() {
+    assert!(!can_view_guild_usage(1, None));
+    assert!(!can_view_guild_usage(1, Some(Permissions::SEND_MESSAGES | Permissions::VIEW_CHANNEL)));
+    assert!(can_view_guild_usage(1, Some(Permissions::MANAGE_GUILD)));
+    assert!(can_view_guild_usage(1, Some(Permissions::ADMINISTRATOR)));
+}
@@ -0,0 +1,157 @@
+#[cfg(test)]
+use std::io::Read;
+
+use songbird::constants::{DEFAULT_BITRATE, MONO_FRAME_SIZE, SAMPLE_RATE, STEREO_FRAME_SIZE, VOICE_PACKET_MAX};
+use songbird::driver::opus::coder::{Decoder as OpusDecoder, Encoder as OpusEncoder};
+use songbird::driver::opus::{Application, Channels};
+use songbird::input::codecs::{get_codec_registry, get_probe};
+use songbird::input::{Input, LiveInput, RawAdapter};
+use symphonia_core::audio::SampleBuffer;
+
+use crate::discord::voice::pcm_stream::{PcmBuffer, PcmResampler, PcmStreamSource, DISCORD_CHANNELS, DISCORD_SAMPLE_RATE};
+
+fn to_s16le(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+fn to_f32(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[test]
+fn resample_24k_mono_to_48k_stereo() {
+    let mut resampler = PcmResampler::gemini_to_discord();
+    let out = resampler.process(&to_s16le(&[16384, -16384]));
+
+    // 입력 샘플 1개당 출력 프레임 2개, 프레임당 채널 2개
+    assert_eq!(out.len(), 2 * 2 * 2);
+    let expected = [0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.0, 0.0];
+    for (got, want) in out.iter().zip(expected.iter()) {
+        assert!((got - want).abs() < 1e-6, "got {} want {}", got, want);
+    }
+}
+
+#[test]
+fn resample_handles_split_samples_across_chunks() {
+    let pcm = to_s16le(&[1000, 2000, -3000, 4000, 32767]);
+
+    let mut whole = PcmResampler::gemini_to_discord();
+    let expected = whole.process(&pcm);
+
+    let mut split = PcmResampler::gemini_to_discord();
+    let mut out = split.process(&pcm[..3]);
+    out.extend(split.process(&pcm[3..7]));
+    out.extend(split.process(&pcm[7..]));
+
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn resampler_reset_clears_interpolation_state() {
+    let mut resampler = PcmResampler::gemini_to_discord();
+    let first = resampler.process(&to_s16le(&[8192, 8192]));
+    resampler.reset();
+    let second = resampler.process(&to_s16le(&[8192, 8192]));
+    assert_eq!(first, second);
+}
+
+#[test]
+fn stream_source_reads_frames_then_silence_then_eof() {
+    let mut resampler = PcmResampler::gemini_to_discord();
+    let pcm = resampler.process_to_bytes(&to_s16le(&[16384; 10]));
+    let buffer = PcmBuffer::new();
+    buffer.push(&pcm);
+
+    let mut source = PcmStreamSource::new(buffer.clone());
+    let mut read_buf = vec![0u8; 4096];
+    let n = source.read(&mut read_buf).unwrap();
+    assert_eq!(n, pcm.len());
+    assert_eq!(to_f32(&read_buf[..n]), to_f32(&pcm));
+
+    // 아직 닫히지 않은 버퍼는 무음을 돌려줍니다.
+    read_buf.fill(1);
+    let n = source.read(&mut read_buf).unwrap();
+    assert!(n > 0 && n % 8 == 0);
+    assert!(read_buf[..n].iter().all(|b| *b == 0));
+
+    buffer.close();
+    assert_eq!(source.read(&mut read_buf).unwrap(), 0);
+}
+
+#[test]
+fn stream_source_drains_before_eof_and_stops_on_interrupt() {
+    let buffer = PcmBuffer::new();
+    buffer.push(&[1u8; 64]);
+    buffer.close();
+
+    let mut source = PcmStreamSource::new(buffer.clone());
+    let mut read_buf = vec![0u8; 32];
+    assert_eq!(source.read(&mut read_buf).unwrap(), 32);
+    assert_eq!(source.read(&mut read_buf).unwrap(), 32);
+    assert_eq!(source.read(&mut read_buf).unwrap(), 0);
+
+    let interrupted = PcmBuffer::new();
+    interrupted.push(&[1u8; 64]);
+    interrupted.interrupt();
+    assert_eq!(interrupted.buffered_len(), 0);
+    let mut source = PcmStreamSource::new(interrupted.clone());
+    assert_eq!(source.read(&mut read_buf).unwrap(), 0);
+
+    // 중단된 버퍼에는 더 이상 데이터가 쌓이지 않습니다.
+    interrupted.push(&[1u8; 16]);
+    assert_eq!(interrupted.buffered_len(), 0);
+}
+
+// 재생할 때와 같은 RawAdapter 로 감싸 songbird 가 읽는 대로 디코딩한 interleaved f32 샘플
+async fn decode_through_songbird(buffer: std::sync::Arc<PcmBuffer>) -> Vec<Vec<f32>> {
+    let input: Input = RawAdapter::new(PcmStreamSource::new(buffer), DISCORD_SAMPLE_RATE, DISCORD_CHANNELS).into();
+    let input = input.make_playable_async(get_codec_registry(), get_probe()).await.unwrap();
+    let Input::Live(LiveInput::Parsed(mut parsed), _) = input else {
+        panic!("RawAdapter input should be parsed");
+    };
+
+    let mut packets = Vec::new();
+    while let Ok(packet) = parsed.format.next_packet() {
+        let decoded = parsed.decoder.decode(&packet).unwrap();
+        let mut samples = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+        samples.copy_interleaved_ref(decoded);
+        packets.push(samples.samples().to_vec());
+    }
+    packets
+}
+
+#[tokio::test]
+async fn stream_source_encodes_to_discord_opus_frames() {
+    // 24kHz 440Hz 사인파 100ms -> 48kHz stereo 20ms 프레임 5개
+    let gemini_pcm = (0..2400)
+        .map(|i| ((i as f32 * 440.0 * std::f32::consts::TAU / 24_000.0).sin() * 12_000.0) as i16)
+        .collect::<Vec<_>>();
+    let mut resampler = PcmResampler::gemini_to_discord();
+    let buffer = PcmBuffer::new();
+    buffer.push(&resampler.process_to_bytes(&to_s16le(&gemini_pcm)));
+    buffer.close();
+
+    let frames = decode_through_songbird(buffer).await;
+
+    assert_eq!(frames.len(), 5);
+    assert!(frames.iter().all(|frame| frame.len() == STEREO_FRAME_SIZE));
+
+    let mut encoder = OpusEncoder::new(SAMPLE_RATE, Channels::Stereo, Application::Audio).unwrap();
+    encoder.set_bitrate(DEFAULT_BITRATE).unwrap();
+    let mut decoder = OpusDecoder::new(SAMPLE_RATE, Channels::Stereo).unwrap();
+    for frame in &frames {
+        let mut packet = vec![0u8; VOICE_PACKET_MAX];
+        let len = encoder.encode_float(frame, &mut packet).unwrap();
+        // 무음이 아니므로 DTX 용 3바이트 프레임보다 커야 합니다.
+        assert!(len > 3 && len <= VOICE_PACKET_MAX, "opus packet size {}", len);
+
+        let mut pcm = vec![0f32; STEREO_FRAME_SIZE];
+        let samples_per_channel = decoder
+            .decode_float(Some((&packet[..len]).try_into().unwrap()), (&mut pcm).try_into().unwrap(), false)
+            .unwrap();
+        assert_eq!(samples_per_channel, MONO_FRAME_SIZE);
+    }
+}
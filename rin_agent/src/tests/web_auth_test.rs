@@ -0,0 +1,80 @@
+#[cfg(test)]
+use crate::service::web_auth_service::{
+    authorize_url, build_guild_scopes, generate_token, guild_role_from_permissions, hash_token, parse_bearer,
+    DiscordOAuthGuild, WebRole,
+};
+use crate::setting::web_auth_setting::DiscordOAuthConfig;
+use crate::tests::test_support::auth_user;
+
+fn oauth_guild(id: &str, owner: bool, permissions: &str) -> DiscordOAuthGuild {
+    DiscordOAuthGuild {
+        id: id.to_string(),
+        name: format!("guild-{}", id),
+        owner,
+        permissions: Some(permissions.to_string()),
+    }
+}
+
+#[test]
+fn hashes_tokens_with_sha256() {
+    assert_eq!(hash_token("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    let token = generate_token();
+    assert!(token.starts_with("rin_"));
+    assert_eq!(token.len(), 4 + 64);
+    assert_ne!(token, generate_token());
+}
+
+#[test]
+fn parses_bearer_header() {
+    assert_eq!(parse_bearer("Bearer rin_abc"), Some("rin_abc"));
+    assert_eq!(parse_bearer("bearer   rin_abc "), Some("rin_abc"));
+    assert_eq!(parse_bearer("Basic dXNlcjpwdw=="), None);
+    assert_eq!(parse_bearer("Bearer "), None);
+}
+
+#[test]
+fn maps_discord_permissions_to_roles() {
+    assert_eq!(guild_role_from_permissions(false, Some("8")), WebRole::GuildAdmin);
+    assert_eq!(guild_role_from_permissions(false, Some("32")), WebRole::GuildAdmin);
+    assert_eq!(guild_role_from_permissions(true, Some("0")), WebRole::GuildAdmin);
+    assert_eq!(guild_role_from_permissions(false, Some("1024")), WebRole::Member);
+    assert_eq!(guild_role_from_permissions(false, None), WebRole::Member);
+}
+
+#[test]
+fn keeps_only_guilds_with_the_bot() {
+    let guilds = vec![oauth_guild("100", false, "40"), oauth_guild("200", false, "0"), oauth_guild("300", true, "8")];
+    let scopes = build_guild_scopes(&guilds, &[100, 200]);
+    assert_eq!(scopes.iter().map(|s| (s.guild_id, s.role)).collect::<Vec<_>>(), vec![(100, WebRole::GuildAdmin), (200, WebRole::Member)]);
+}
+
+#[test]
+fn checks_guild_roles() {
+    let user = auth_user(false);
+    assert!(user.has_role(100, WebRole::GuildAdmin));
+    assert!(user.has_role(200, WebRole::Member));
+    assert!(!user.has_role(200, WebRole::GuildAdmin));
+    assert!(!user.has_role(300, WebRole::Member));
+    assert!(user.is_self_or_owner(7));
+    assert!(!user.is_self_or_owner(8));
+
+    let owner = auth_user(true);
+    assert_eq!(owner.role_in(300), Some(WebRole::Owner));
+    assert!(owner.is_self_or_owner(8));
+}
+
+#[test]
+fn builds_authorize_url() {
+    let config = DiscordOAuthConfig {
+        client_id: "123".to_string(),
+        client_secret: "secret".to_string(),
+        redirect_uri: "https://rin.example.org/api/auth/callback".to_string(),
+    };
+    let url = authorize_url(&config, "state_1").unwrap();
+    assert!(url.starts_with("https://discord.com/oauth2/authorize?"));
+    assert!(url.contains("client_id=123"));
+    assert!(url.contains("redirect_uri=https%3A%2F%2Frin.example.org%2Fapi%2Fauth%2Fcallback"));
+    assert!(url.contains("scope=identify+guilds"));
+    assert!(url.contains("state=state_1"));
+    assert!(!url.contains("secret"));
+}
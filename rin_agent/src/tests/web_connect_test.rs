@@ -0,0 +1,156 @@
+#[cfg(test)]
+use std::net::IpAddr;
+
+use crate::api::get_web_result::{fetch_web, get_web_result, is_public_ip, WebFetchOptions};
+use crate::api::html_extract::{attr_value, decode_entities, extract_readable, split_pages};
+use crate::tests::test_support::{spawn_stub_server, StubResponse};
+
+const ARTICLE_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Rust &amp; Tokio 소개</title>
+  <style>body { color: red; }</style>
+  <script>var secret = "<p>not text</p>";</script>
+</head>
+<body>
+  <nav><a href="/">홈</a> <a href="/about">소개</a></nav>
+  <main>
+    <h1>비동기 Rust</h1>
+    <p>Tokio 는   비동기 런타임입니다.
+    이벤트 루프와 작업 스케줄러를 제공합니다.</p>
+    <ul><li>작업</li><li>채널</li></ul>
+    <p>자세한 내용은 <a href="/docs#intro">문서</a>를 보세요. 이 문단은 본문이 충분히 길도록 덧붙인 설명입니다. 이 문단은 본문이 충분히 길도록 덧붙인 설명입니다.</p>
+    <p>Tokio 의 작업은 가벼운 그린 스레드처럼 동작하며, 런타임은 여러 작업을 적은 수의 OS 스레드 위에서 번갈아 실행합니다. 채널을 쓰면 작업 사이에 값을 안전하게 주고받을 수 있습니다.</p>
+    <!-- <p>주석</p> -->
+  </main>
+  <div class="ad">광고 영역</div>
+  <footer>Copyright</footer>
+</body>
+</html>"#;
+
+fn html(status: u16, body: &str) -> StubResponse {
+    StubResponse {
+        status,
+        headers: vec![("Content-Type", "text/html; charset=utf-8".to_string())],
+        body: body.to_string(),
+    }
+}
+
+fn local_options() -> WebFetchOptions {
+    WebFetchOptions { allow_private: true, ..Default::default() }
+}
+
+#[test]
+fn blocks_private_and_special_addresses() {
+    for blocked in [
+        "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.0.10", "169.254.169.254", "100.64.0.1", "0.0.0.0",
+        "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1", "64:ff9b::a00:1",
+        // 6to4 로 감싼 127.0.0.1, 169.254.169.254 와 클라이언트가 127.0.0.1 인 Teredo
+        "2002:7f00:1::1", "2002:a9fe:a9fe::", "2001:0:808:808::80ff:fffe",
+    ] {
+        assert!(!is_public_ip(blocked.parse::<IpAddr>().unwrap()), "{} should be blocked", blocked);
+    }
+    for allowed in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111", "::ffff:8.8.8.8", "2002:808:808::1", "2001:0:808:808::fefe:fefe"] {
+        assert!(is_public_ip(allowed.parse::<IpAddr>().unwrap()), "{} should be allowed", allowed);
+    }
+}
+
+#[tokio::test]
+async fn rejects_loopback_and_non_http_urls() {
+    let options = WebFetchOptions::default();
+    let error = fetch_web("http://127.0.0.1:9/", &options).await.unwrap_err();
+    assert!(error.contains("내부 네트워크"), "{}", error);
+    let error = fetch_web("http://[::1]:9/", &options).await.unwrap_err();
+    assert!(error.contains("내부 네트워크"), "{}", error);
+    assert!(fetch_web("file:///etc/passwd", &options).await.is_err());
+    assert!(fetch_web("http://user:pw@example.com/", &options).await.is_err());
+}
+
+#[test]
+fn extracts_title_main_text_and_links() {
+    let base = reqwest::Url::parse("https://example.com/blog/post").unwrap();
+    let extracted = extract_readable(ARTICLE_HTML, Some(&base));
+
+    assert_eq!(extracted.title.as_deref(), Some("Rust & Tokio 소개"));
+    assert!(extracted.text.starts_with("# 비동기 Rust\nTokio 는 비동기 런타임입니다. 이벤트 루프와 작업 스케줄러를 제공합니다.\n- 작업\n- 채널\n"), "{}", extracted.text);
+    assert!(!extracted.text.contains("not text"));
+    assert!(!extracted.text.contains("Copyright"));
+    assert!(!extracted.text.contains("홈"));
+    assert!(!extracted.text.contains("주석"));
+    assert!(!extracted.text.contains("광고"));
+    assert_eq!(extracted.links.len(), 1);
+    assert_eq!(extracted.links[0].text, "문서");
+    assert_eq!(extracted.links[0].url, "https://example.com/docs");
+}
+
+#[test]
+fn falls_back_to_body_when_main_is_short() {
+    let extracted = extract_readable("<body><main>짧음</main><div>본문 <b>굵게</b></div></body>", None);
+    assert_eq!(extracted.text, "짧음\n본문 굵게");
+}
+
+#[test]
+fn decodes_entities_and_attributes() {
+    assert_eq!(decode_entities("a &lt;b&gt; &#44032;&#x41; &unknown; & c"), "a <b> 가A &unknown; & c");
+    assert_eq!(attr_value(r#" class="x" HREF='/a b' data-x=1"#, "href").as_deref(), Some("/a b"));
+    assert_eq!(attr_value(" href=/plain target=_blank", "href").as_deref(), Some("/plain"));
+    assert_eq!(attr_value(" disabled", "href"), None);
+}
+
+#[test]
+fn splits_pages_on_line_breaks() {
+    let text = "첫째 줄입니다\n둘째 줄입니다\n셋째";
+    let pages = split_pages(text, 10);
+    assert_eq!(pages, vec!["첫째 줄입니다", "둘째 줄입니다\n셋째"]);
+    assert_eq!(split_pages("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+    assert!(split_pages("   ", 4).is_empty());
+}
+
+#[tokio::test]
+async fn follows_redirects_and_reads_page() {
+    let (base_url, _requests) = spawn_stub_server(vec![
+        StubResponse { status: 302, headers: vec![("Location", "/final".to_string())], body: String::new() },
+        html(200, ARTICLE_HTML),
+    ])
+    .await;
+
+    let page = get_web_result(&format!("{}/start", base_url), &local_options()).await.unwrap();
+
+    assert_eq!(page.url, format!("{}/final", base_url));
+    assert_eq!(page.title.as_deref(), Some("Rust & Tokio 소개"));
+    assert!(page.text.contains("Tokio 는 비동기 런타임입니다."));
+    assert!(!page.truncated);
+}
+
+#[tokio::test]
+async fn stops_after_max_redirects() {
+    let redirect = || StubResponse { status: 301, headers: vec![("Location", "/loop".to_string())], body: String::new() };
+    let (base_url, _requests) = spawn_stub_server(vec![redirect(), redirect(), redirect()]).await;
+    let options = WebFetchOptions { max_redirects: 2, ..local_options() };
+
+    let error = fetch_web(&format!("{}/loop", base_url), &options).await.unwrap_err();
+    assert!(error.contains("리다이렉트"), "{}", error);
+}
+
+#[tokio::test]
+async fn rejects_binary_content_type() {
+    let (base_url, _requests) = spawn_stub_server(vec![StubResponse {
+        status: 200,
+        headers: vec![("Content-Type", "image/png".to_string())],
+        body: "PNG".to_string(),
+    }])
+    .await;
+
+    let error = fetch_web(&format!("{}/image.png", base_url), &local_options()).await.unwrap_err();
+    assert!(error.contains("image/png"), "{}", error);
+}
+
+#[tokio::test]
+async fn truncates_large_bodies() {
+    let (base_url, _requests) = spawn_stub_server(vec![html(200, &"a".repeat(5000))]).await;
+    let options = WebFetchOptions { max_bytes: 1000, ..local_options() };
+
+    let fetched = fetch_web(&base_url, &options).await.unwrap();
+    assert_eq!(fetched.body.len(), 1000);
+    assert!(fetched.truncated);
+}
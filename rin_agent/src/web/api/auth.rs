@@ -0,0 +1,129 @@
+use rocket::http::{Cookie, CookieJar, SameSite, Status};
+use rocket::response::status::Custom;
+use rocket::response::Redirect;
+use rocket::serde::json::Json;
+use rocket::serde::Deserialize;
+use rocket::{delete, get, post};
+use serde_json::{json, Value};
+
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::service::web_auth_service::{
+    authorize_url, build_guild_scopes, create_session, delete_token, exchange_code, fetch_discord_guilds, fetch_discord_user,
+    generate_token, issue_api_token, list_api_tokens, load_bot_guild_ids, revoke_api_token, ApiTokenInfo, AuthUser,
+    TOKEN_KIND_SESSION,
+};
+use crate::setting::web_auth_setting::{
+    DiscordOAuthConfig, OAUTH_STATE_COOKIE, SESSION_COOKIE, WEB_COOKIE_SECURE, WEB_SESSION_TTL_HOURS,
+};
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct CreateApiTokenRequest {
+    name: Option<String>,
+    // 비우면 발급하는 사람이 접근할 수 있는 모든 길드
+    guild_ids: Option<Vec<u64>>,
+    days: Option<i64>,
+}
+
+fn oauth_config() -> Result<DiscordOAuthConfig, Custom<String>> {
+    DiscordOAuthConfig::from_env().map_err(|e| {
+        LOGGER.log(LogLevel::Error, &format!("Web > OAuth 설정 오류: {}", e));
+        Custom(Status::ServiceUnavailable, "discord login is not configured".to_string())
+    })
+}
+
+// Discord 로그인 화면으로 보냅니다. state 는 쿠키에 두고 콜백에서 비교합니다.
+#[get("/auth/login")]
+pub fn login(jar: &CookieJar<'_>) -> Result<Redirect, Custom<String>> {
+    let config = oauth_config()?;
+    let state = generate_token();
+    let url = authorize_url(&config, &state).map_err(|e| Custom(Status::InternalServerError, e))?;
+    jar.add(
+        Cookie::build((OAUTH_STATE_COOKIE, state))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .secure(*WEB_COOKIE_SECURE)
+            .max_age(rocket::time::Duration::minutes(10)),
+    );
+    Ok(Redirect::to(url))
+}
+
+#[get("/auth/callback?<code>&<state>")]
+pub async fn login_callback(code: Option<&str>, state: Option<&str>, jar: &CookieJar<'_>) -> Result<Redirect, Custom<String>> {
+    let expected_state = jar.get(OAUTH_STATE_COOKIE).map(|c| c.value().to_string());
+    jar.remove(Cookie::from(OAUTH_STATE_COOKIE));
+    let (Some(code), Some(state)) = (code, state) else {
+        return Err(Custom(Status::BadRequest, "login was cancelled".to_string()));
+    };
+    if expected_state.as_deref() != Some(state) {
+        return Err(Custom(Status::BadRequest, "invalid oauth state".to_string()));
+    }
+
+    let config = oauth_config()?;
+    let bad_gateway = |e: String| {
+        LOGGER.log(LogLevel::Error, &format!("Web > Discord 로그인 실패: {}", e));
+        Custom(Status::BadGateway, "discord login failed".to_string())
+    };
+    let access_token = exchange_code(&config, code).await.map_err(bad_gateway)?;
+    let user = fetch_discord_user(&access_token).await.map_err(bad_gateway)?;
+    let guilds = fetch_discord_guilds(&access_token).await.map_err(bad_gateway)?;
+    let bot_guild_ids = load_bot_guild_ids().await.map_err(|e| Custom(Status::InternalServerError, e))?;
+    let scopes = build_guild_scopes(&guilds, &bot_guild_ids);
+    let session = create_session(&user, &scopes).await.map_err(|e| Custom(Status::InternalServerError, e))?;
+
+    LOGGER.log(LogLevel::Info, &format!("Web > 로그인: {} ({}개 길드)", user.id, scopes.len()));
+    jar.add(
+        Cookie::build((SESSION_COOKIE, session))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .secure(*WEB_COOKIE_SECURE)
+            .max_age(rocket::time::Duration::hours(*WEB_SESSION_TTL_HOURS)),
+    );
+    Ok(Redirect::to("/"))
+}
+
+#[post("/auth/logout")]
+pub async fn logout(user: AuthUser, jar: &CookieJar<'_>) -> Result<Status, Custom<String>> {
+    if user.kind == TOKEN_KIND_SESSION {
+        delete_token(user.token_id).await.map_err(|e| Custom(Status::InternalServerError, e))?;
+    }
+    jar.remove(Cookie::from(SESSION_COOKIE));
+    Ok(Status::NoContent)
+}
+
+#[get("/auth/me")]
+pub fn get_me(user: AuthUser) -> Json<AuthUser> {
+    Json(user)
+}
+
+#[get("/auth/tokens")]
+pub async fn get_api_tokens(user: AuthUser) -> Result<Json<Vec<ApiTokenInfo>>, Custom<String>> {
+    list_api_tokens(user.user_id)
+        .await
+        .map(Json)
+        .map_err(|e| Custom(Status::InternalServerError, e))
+}
+
+// 토큰 원문은 이 응답에서만 볼 수 있습니다. API 토큰으로 새 토큰을 만들 수는 없습니다.
+#[post("/auth/tokens", data = "<request>")]
+pub async fn create_api_token(user: AuthUser, request: Json<CreateApiTokenRequest>) -> Result<Custom<Json<Value>>, Custom<String>> {
+    if user.kind != TOKEN_KIND_SESSION {
+        return Err(Custom(Status::Forbidden, "api tokens can only be created from a login session".to_string()));
+    }
+    let request = request.into_inner();
+    let (token, info) = issue_api_token(&user, request.name, request.guild_ids.as_deref(), request.days)
+        .await
+        .map_err(|e| Custom(Status::BadRequest, e))?;
+    Ok(Custom(Status::Created, Json(json!({ "token": token, "info": info }))))
+}
+
+#[delete("/auth/tokens/<token_id>")]
+pub async fn delete_api_token(user: AuthUser, token_id: i64) -> Result<Status, Custom<String>> {
+    match revoke_api_token(user.user_id, token_id).await {
+        Ok(true) => Ok(Status::NoContent),
+        Ok(false) => Err(Custom(Status::NotFound, format!("token {} not found", token_id))),
+        Err(e) => Err(Custom(Status::InternalServerError, e)),
+    }
+}
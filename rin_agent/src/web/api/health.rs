@@ -0,0 +1,54 @@
+use rocket::get;
+use rocket::http::{ContentType, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::Request;
+
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::service::health_service::{check_readiness, CheckStatus, ReadinessReport};
+use crate::service::metrics_service::{collect_runtime_metrics, METRICS};
+use crate::service::web_auth_service::parse_bearer;
+use crate::setting::web_auth_setting::METRICS_TOKEN;
+
+// METRICS_TOKEN 과 같은 값의 Bearer 헤더를 요구합니다. 토큰을 설정하지 않으면 /metrics 는 닫혀 있습니다.
+pub struct MetricsAccess;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for MetricsAccess {
+    type Error = String;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(expected) = METRICS_TOKEN.as_deref() else {
+            return Outcome::Error((Status::NotFound, "metrics disabled".to_string()));
+        };
+        match req.headers().get_one("Authorization").and_then(parse_bearer) {
+            Some(token) if token == expected => Outcome::Success(MetricsAccess),
+            _ => Outcome::Error((Status::Unauthorized, "metrics token required".to_string())),
+        }
+    }
+}
+
+// 프로세스가 요청을 받을 수 있는지만 봅니다.
+#[get("/healthz")]
+pub fn get_healthz() -> &'static str {
+    "ok"
+}
+
+// DB, Redis, 디스코드 연결까지 확인합니다. 하나라도 실패하면 503 입니다.
+// 응답에는 항목별 상태만 싣고, 실패 원인은 로그로 남깁니다.
+#[get("/readyz")]
+pub async fn get_readyz() -> Custom<Json<ReadinessReport>> {
+    let report = check_readiness().await;
+    for check in report.checks.iter().filter(|c| c.status == CheckStatus::Failed) {
+        LOGGER.log(LogLevel::Warning, &format!("readyz > {} 확인 실패: {}", check.name, check.detail.as_deref().unwrap_or("-")));
+    }
+    let status = if report.ready { Status::Ok } else { Status::ServiceUnavailable };
+    Custom(status, Json(report))
+}
+
+#[get("/metrics")]
+pub async fn get_metrics(_access: MetricsAccess) -> (ContentType, String) {
+    collect_runtime_metrics().await;
+    (ContentType::new("text", "plain").with_params(("version", "0.0.4")), METRICS.render())
+}
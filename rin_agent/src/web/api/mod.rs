@@ -1 +1,7 @@
-pub mod status;
\ No newline at end of file
+pub mod status;
+pub mod usage;
+pub mod structured;
+pub mod auth;
+pub mod v1;
+pub mod openai;
+pub mod health;
\ No newline at end of file
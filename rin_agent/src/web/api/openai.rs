@@ -0,0 +1,99 @@
+use chrono::Utc;
+use rocket::futures::stream::{self, BoxStream, StreamExt};
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::{get, post, Responder};
+
+use crate::discord::utils::open_dm_channel;
+use crate::gemini::gemini_error::GeminiError;
+use crate::gemini::types::GeminiStreamEvent;
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::libs::shutdown::is_shutting_down;
+use crate::service::openai_compat_service::{
+    build_chunk, build_completion, list_models, map_finish_reason, messages_to_chunks, new_completion_id, resolve_model,
+    run_chat_completion, stream_chat_completion, ChatCompletion, ChatCompletionRequest, ChatDelta, ModelList, OpenAiError,
+};
+use crate::service::quota_service::{check_quota, QuotaSubject};
+use crate::service::web_auth_service::AuthUser;
+
+type OpenAiResult<T> = Result<T, Custom<Json<OpenAiError>>>;
+
+#[derive(Responder)]
+pub enum ChatCompletionResponse {
+    Completion(Json<ChatCompletion>),
+    Stream(EventStream<BoxStream<'static, Event>>),
+}
+
+fn openai_error(status: Status, error_type: &'static str, message: impl Into<String>) -> Custom<Json<OpenAiError>> {
+    Custom(status, Json(OpenAiError::new(error_type, message)))
+}
+
+fn gemini_error(e: GeminiError) -> Custom<Json<OpenAiError>> {
+    openai_error(Status::BadGateway, "api_error", e.to_string())
+}
+
+// OpenAI SDK 의 base_url 을 http://<host>/v1 로 두고 API 토큰을 api_key 로 넣으면 됩니다.
+#[get("/models")]
+pub fn get_openai_models(_user: AuthUser) -> Json<ModelList> {
+    Json(list_models())
+}
+
+#[post("/chat/completions", data = "<body>")]
+pub async fn create_chat_completion(user: AuthUser, body: Json<ChatCompletionRequest>) -> OpenAiResult<ChatCompletionResponse> {
+    if is_shutting_down() {
+        return Err(openai_error(Status::ServiceUnavailable, "api_error", "server is shutting down".to_string()));
+    }
+    let request = body.into_inner();
+    let use_pro = resolve_model(&request.model).ok_or_else(|| {
+        openai_error(Status::NotFound, "invalid_request_error", format!("unknown model: {}", request.model))
+    })?;
+    let chunks = messages_to_chunks(&request.messages, user.user_id)
+        .map_err(|e| openai_error(Status::BadRequest, "invalid_request_error", e))?;
+    let subject = QuotaSubject { user_id: user.user_id, guild_id: None, role_ids: Vec::new() };
+    if let Err(denied) = check_quota(&subject, use_pro).await {
+        return Err(openai_error(Status::TooManyRequests, "rate_limit_exceeded", format!("quota exceeded: {:?}", denied.reason)));
+    }
+    let dm_channel_id = match open_dm_channel(user.user_id).await {
+        Ok(channel_id) => Some(channel_id),
+        Err(e) => {
+            LOGGER.log(LogLevel::Warning, &format!("OpenAI API > {}", e));
+            None
+        }
+    };
+
+    let id = new_completion_id();
+    let created = Utc::now().timestamp();
+    let model = request.model;
+    if !request.stream {
+        let response = run_chat_completion(&user, chunks, use_pro, dm_channel_id).await.map_err(gemini_error)?;
+        return Ok(ChatCompletionResponse::Completion(Json(build_completion(&id, &model, created, &response))));
+    }
+
+    // role 을 먼저 보내고, Gemini 가 보내는 조각을 그대로 delta 로 흘려보냅니다.
+    let head = Event::json(&build_chunk(&id, &model, created, ChatDelta { role: Some("assistant"), content: None }, None));
+    let receiver = stream_chat_completion(&user, chunks, use_pro, dm_channel_id);
+    let body = stream::unfold(receiver, move |mut receiver| {
+        let id = id.clone();
+        let model = model.clone();
+        async move {
+            let event = match receiver.recv().await? {
+                GeminiStreamEvent::Delta(content) => {
+                    Event::json(&build_chunk(&id, &model, created, ChatDelta { role: None, content: Some(content) }, None))
+                }
+                GeminiStreamEvent::Done { finish_reason } => Event::json(&build_chunk(
+                    &id,
+                    &model,
+                    created,
+                    ChatDelta::default(),
+                    Some(map_finish_reason(&finish_reason)),
+                )),
+                GeminiStreamEvent::Failed(e) => Event::json(&OpenAiError::new("api_error", e.to_string())),
+            };
+            Some((event, receiver))
+        }
+    });
+    let events = stream::iter(vec![head]).chain(body).chain(stream::iter(vec![Event::data("[DONE]")])).boxed();
+    Ok(ChatCompletionResponse::Stream(EventStream::from(events)))
+}
@@ -1,26 +1,30 @@
 use entity::tb_ai_context;
 use rocket::get;
+use rocket::http::Status;
+use rocket::response::status::Custom;
 use sea_orm::{EntityTrait, QueryOrder, QuerySelect};
 
 use crate::model::db::driver::DB_CONNECTION_POOL;
+use crate::service::web_auth_service::AuthUser;
 
 
+// 대화 원문이 그대로 나오므로 봇 관리자만 볼 수 있습니다.
 #[get("/status")]
-pub async fn get_status() -> String {
+pub async fn get_status(user: AuthUser) -> Result<String, Custom<String>> {
+    user.require_owner()?;
 
-    let db = DB_CONNECTION_POOL.get();
-    let conn = db.unwrap();
+    let conn = DB_CONNECTION_POOL.get().ok_or(Custom(Status::InternalServerError, "DB Connection Error".to_string()))?;
 
     let a = tb_ai_context::Entity::find()
         .order_by_desc(tb_ai_context::Column::Id)
         .limit(10)
         .all(conn)
         .await
-        .unwrap();
+        .map_err(|e| Custom(Status::InternalServerError, e.to_string()))?;
     let mut result = String::new();
     for i in &a {
         result.push_str(&format!("{}: {}\n", i.id, i.context));
     }
     result.push_str(&format!("{}: {}\n", "count", a.len()));
-    result
+    Ok(result)
 }
\ No newline at end of file
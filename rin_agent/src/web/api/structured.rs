@@ -0,0 +1,25 @@
+use rocket::get;
+use rocket::http::{ContentType, Status};
+use rocket::response::status::Custom;
+
+use crate::service::structured_output_service::{find_structured_output, structured_output_to_csv};
+use crate::service::web_auth_service::{AuthUser, WebRole};
+
+// 예: /api/structured/42/csv
+// 만든 사람과 같은 길드 사람만 받을 수 있습니다.
+#[get("/structured/<id>/csv")]
+pub async fn get_structured_csv(user: AuthUser, id: i64) -> Result<(ContentType, String), Custom<String>> {
+    let row = find_structured_output(id)
+        .await
+        .map_err(|e| Custom(Status::InternalServerError, e))?
+        .ok_or_else(|| Custom(Status::NotFound, format!("structured output {} not found", id)))?;
+    let allowed = user.is_self_or_owner(row.user_id as u64)
+        || row.guild_id.is_some_and(|guild_id| user.has_role(guild_id as u64, WebRole::Member));
+    if !allowed {
+        // 다른 길드의 기록은 있는지도 알리지 않습니다.
+        return Err(Custom(Status::NotFound, format!("structured output {} not found", id)));
+    }
+    structured_output_to_csv(&row)
+        .map(|csv| (ContentType::CSV, csv))
+        .map_err(|e| Custom(Status::InternalServerError, e))
+}
@@ -0,0 +1,45 @@
+use rocket::get;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+
+use crate::service::usage_service::{summarize_usage, UsageFilter, UsageGroupBy, UsageSummary};
+use crate::service::web_auth_service::{AuthUser, WebRole};
+
+// 길드 사용량은 그 길드 관리자, 본인 사용량은 본인, 나머지는 봇 관리자만 볼 수 있습니다.
+// /api/v1/usage 도 같은 조건으로 확인합니다.
+pub fn authorize_usage_filter(
+    user: &AuthUser,
+    guild_id: Option<u64>,
+    user_id: Option<u64>,
+) -> Result<(), Custom<String>> {
+    match (guild_id, user_id) {
+        (Some(guild_id), _) => user.require_guild(guild_id, WebRole::GuildAdmin),
+        (None, Some(user_id)) => user.require_self_or_owner(user_id),
+        (None, None) => user.require_owner(),
+    }
+}
+
+// 예: /api/usage?group_by=guild&days=7, /api/usage?group_by=user&guild_id=123
+#[get("/usage?<group_by>&<days>&<guild_id>&<user_id>&<limit>")]
+pub async fn get_usage(
+    user: AuthUser,
+    group_by: Option<&str>,
+    days: Option<i64>,
+    guild_id: Option<u64>,
+    user_id: Option<u64>,
+    limit: Option<u64>,
+) -> Result<Json<Vec<UsageSummary>>, Custom<String>> {
+    let group_by = match group_by {
+        None => UsageGroupBy::Model,
+        Some(value) => UsageGroupBy::parse(value).ok_or_else(|| {
+            Custom(Status::BadRequest, format!("group_by must be one of guild, user, model: {}", value))
+        })?,
+    };
+    authorize_usage_filter(&user, guild_id, user_id)?;
+    let filter = UsageFilter { guild_id, user_id, days: days.unwrap_or(30) };
+    summarize_usage(&filter, group_by, limit.unwrap_or(50).min(500))
+        .await
+        .map(Json)
+        .map_err(|e| Custom(Status::InternalServerError, e))
+}
@@ -0,0 +1,97 @@
+use chrono::Utc;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::serde::Deserialize;
+use rocket::{delete, get, patch, post};
+
+use crate::discord::utils::{fetch_channel_scope, ChannelScope};
+use crate::service::alarm_service::{
+    create_alarm, delete_alarm, find_alarm, list_alarms, parse_alarm_time, update_alarm, AlarmUpdate, AlarmView,
+};
+use crate::service::web_auth_service::{AuthUser, WebRole};
+use crate::web::api::v1::pagination::{Page, PageParams};
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct CreateAlarmRequest {
+    pub channel_id: u64,
+    // RFC 3339, 예: 2026-01-01T09:00:00+09:00
+    pub time: String,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct UpdateAlarmRequest {
+    pub time: Option<String>,
+    pub message: Option<String>,
+}
+
+// 남의 알람은 없는 것처럼 404 로 답합니다.
+async fn find_own_alarm(user: &AuthUser, id: i64) -> Result<entity::tb_alarm_model::Model, Custom<String>> {
+    find_alarm(id)
+        .await
+        .map_err(|e| Custom(Status::InternalServerError, e))?
+        .filter(|alarm| user.is_self_or_owner(alarm.user_id as u64))
+        .ok_or_else(|| Custom(Status::NotFound, format!("alarm not found: {}", id)))
+}
+
+// 예: GET /api/v1/alarms?upcoming=true&page=1
+// 봇 관리자는 all=true 로 모든 사람의 알람을 봅니다.
+#[get("/alarms?<upcoming>&<all>&<page>&<per_page>")]
+pub async fn get_alarms(
+    user: AuthUser,
+    upcoming: Option<bool>,
+    all: Option<bool>,
+    page: Option<u64>,
+    per_page: Option<u64>,
+) -> Result<Json<Page<AlarmView>>, Custom<String>> {
+    let owner_filter = if all.unwrap_or(false) {
+        user.require_owner()?;
+        None
+    } else {
+        Some(user.user_id as i64)
+    };
+    let params = PageParams::new(page, per_page);
+    let (items, total) = list_alarms(owner_filter, upcoming.unwrap_or(true), params.offset(), params.per_page)
+        .await
+        .map_err(|e| Custom(Status::InternalServerError, e))?;
+    Ok(Json(Page::new(items, params, total)))
+}
+
+// 알람은 본인이 속한 길드의 채널이나 본인과의 DM 에만 만들 수 있습니다.
+#[post("/alarms", data = "<body>")]
+pub async fn post_alarm(user: AuthUser, body: Json<CreateAlarmRequest>) -> Result<Custom<Json<AlarmView>>, Custom<String>> {
+    let time = parse_alarm_time(&body.time, Utc::now()).map_err(|e| Custom(Status::BadRequest, e))?;
+    match fetch_channel_scope(body.channel_id).await.map_err(|e| Custom(Status::BadRequest, e))? {
+        ChannelScope::Guild(guild_id) => user.require_guild(guild_id, WebRole::Member)?,
+        ChannelScope::Direct { recipient_id } => user.require_self_or_owner(recipient_id)?,
+    }
+    create_alarm(user.user_id, body.channel_id, time, &body.message)
+        .await
+        .map(|alarm| Custom(Status::Created, Json(alarm)))
+        .map_err(|e| Custom(Status::BadRequest, e))
+}
+
+#[patch("/alarms/<id>", data = "<body>")]
+pub async fn patch_alarm(user: AuthUser, id: i64, body: Json<UpdateAlarmRequest>) -> Result<Json<AlarmView>, Custom<String>> {
+    let alarm = find_own_alarm(&user, id).await?;
+    let time = body
+        .time
+        .as_deref()
+        .map(|time| parse_alarm_time(time, Utc::now()))
+        .transpose()
+        .map_err(|e| Custom(Status::BadRequest, e))?;
+    update_alarm(alarm, AlarmUpdate { time, message: body.message.clone() })
+        .await
+        .map(Json)
+        .map_err(|e| Custom(Status::BadRequest, e))
+}
+
+#[delete("/alarms/<id>")]
+pub async fn remove_alarm(user: AuthUser, id: i64) -> Result<Status, Custom<String>> {
+    find_own_alarm(&user, id).await?;
+    delete_alarm(id).await.map_err(|e| Custom(Status::InternalServerError, e))?;
+    Ok(Status::NoContent)
+}
@@ -0,0 +1,36 @@
+use rocket::get;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+
+use crate::service::conversation_service::{get_conversation_tree, list_conversations, ConversationFilter, ConversationSummary, ConversationTree};
+use crate::service::web_auth_service::AuthUser;
+use crate::web::api::v1::pagination::{Page, PageParams};
+
+// 예: GET /api/v1/conversations?guild_id=123&q=날씨&page=2
+// 본인이 시작한 대화와 관리하는 길드의 대화만 나옵니다.
+#[get("/conversations?<guild_id>&<user_id>&<q>&<page>&<per_page>")]
+pub async fn list_user_conversations(
+    user: AuthUser,
+    guild_id: Option<u64>,
+    user_id: Option<u64>,
+    q: Option<&str>,
+    page: Option<u64>,
+    per_page: Option<u64>,
+) -> Result<Json<Page<ConversationSummary>>, Custom<String>> {
+    let params = PageParams::new(page, per_page);
+    let filter = ConversationFilter { user_id, guild_id, query: q.map(str::to_string) };
+    let (items, total) = list_conversations(&user, &filter, params.offset(), params.per_page)
+        .await
+        .map_err(|e| Custom(Status::InternalServerError, e))?;
+    Ok(Json(Page::new(items, params, total)))
+}
+
+#[get("/conversations/<id>")]
+pub async fn get_conversation(user: AuthUser, id: i64) -> Result<Json<ConversationTree>, Custom<String>> {
+    get_conversation_tree(&user, id)
+        .await
+        .map_err(|e| Custom(Status::InternalServerError, e))?
+        .map(Json)
+        .ok_or_else(|| Custom(Status::NotFound, format!("conversation not found: {}", id)))
+}
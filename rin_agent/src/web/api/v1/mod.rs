@@ -0,0 +1,4 @@
+pub mod pagination;
+pub mod conversations;
+pub mod alarms;
+pub mod usage;
\ No newline at end of file
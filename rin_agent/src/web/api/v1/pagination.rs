@@ -0,0 +1,48 @@
+use serde::Serialize;
+
+pub const DEFAULT_PER_PAGE: u64 = 20;
+pub const MAX_PER_PAGE: u64 = 100;
+// DB 의 OFFSET 은 i64 이므로 어떤 per_page 에서도 넘치지 않을 만큼으로 page 를 묶습니다.
+pub const MAX_PAGE: u64 = i64::MAX as u64 / MAX_PER_PAGE;
+
+// ?page=2&per_page=50 처럼 받는 쪽수. page 는 1부터 셉니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageParams {
+    pub page: u64,
+    pub per_page: u64,
+}
+
+impl PageParams {
+    pub fn new(page: Option<u64>, per_page: Option<u64>) -> Self {
+        PageParams {
+            page: page.unwrap_or(1).clamp(1, MAX_PAGE),
+            per_page: per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE),
+        }
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.page.saturating_sub(1).saturating_mul(self.per_page)
+    }
+}
+
+// 목록 응답의 공통 모양
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+    pub total_pages: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, params: PageParams, total: u64) -> Self {
+        Page {
+            items,
+            page: params.page,
+            per_page: params.per_page,
+            total,
+            total_pages: total.div_ceil(params.per_page),
+        }
+    }
+}
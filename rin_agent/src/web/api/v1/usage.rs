@@ -0,0 +1,49 @@
+use rocket::get;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use serde::Serialize;
+
+use crate::service::usage_service::{
+    count_usage_groups, summarize_daily_usage, summarize_usage_page, DailyUsage, UsageFilter, UsageGroupBy, UsageSummary,
+};
+use crate::service::web_auth_service::AuthUser;
+use crate::web::api::usage::authorize_usage_filter;
+use crate::web::api::v1::pagination::{Page, PageParams};
+
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub groups: Page<UsageSummary>,
+    // 같은 조건의 하루 단위 추이 (UTC)
+    pub daily: Vec<DailyUsage>,
+}
+
+// 예: GET /api/v1/usage?group_by=user&guild_id=123&days=7&page=1
+// 권한은 /api/usage 와 같습니다.
+#[get("/usage?<group_by>&<days>&<guild_id>&<user_id>&<page>&<per_page>")]
+pub async fn get_usage_report(
+    user: AuthUser,
+    group_by: Option<&str>,
+    days: Option<i64>,
+    guild_id: Option<u64>,
+    user_id: Option<u64>,
+    page: Option<u64>,
+    per_page: Option<u64>,
+) -> Result<Json<UsageReport>, Custom<String>> {
+    let group_by = match group_by {
+        None => UsageGroupBy::Model,
+        Some(value) => UsageGroupBy::parse(value).ok_or_else(|| {
+            Custom(Status::BadRequest, format!("group_by must be one of guild, user, model: {}", value))
+        })?,
+    };
+    authorize_usage_filter(&user, guild_id, user_id)?;
+    let filter = UsageFilter { guild_id, user_id, days: days.unwrap_or(30) };
+    let params = PageParams::new(page, per_page);
+    let internal = |e: String| Custom(Status::InternalServerError, e);
+    let total = count_usage_groups(&filter, group_by).await.map_err(internal)?;
+    let items = summarize_usage_page(&filter, group_by, params.offset(), params.per_page)
+        .await
+        .map_err(internal)?;
+    let daily = summarize_daily_usage(&filter).await.map_err(internal)?;
+    Ok(Json(UsageReport { groups: Page::new(items, params, total), daily }))
+}
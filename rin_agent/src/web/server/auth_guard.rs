@@ -0,0 +1,58 @@
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::status::Custom;
+use rocket::Request;
+
+use crate::libs::logger::{LogLevel, LOGGER};
+use crate::service::web_auth_service::{find_auth_user, parse_bearer, AuthUser, WebRole};
+use crate::setting::web_auth_setting::SESSION_COOKIE;
+
+// Authorization: Bearer 헤더(API 토큰)를 먼저 보고, 없으면 세션 쿠키를 봅니다.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthUser {
+    type Error = String;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let token = match req.headers().get_one("Authorization") {
+            Some(header) => parse_bearer(header).map(str::to_string),
+            None => req.cookies().get(SESSION_COOKIE).map(|c| c.value().to_string()),
+        };
+        let Some(token) = token else {
+            return Outcome::Error((Status::Unauthorized, "login required".to_string()));
+        };
+        match find_auth_user(&token).await {
+            Ok(Some(user)) => Outcome::Success(user),
+            Ok(None) => Outcome::Error((Status::Unauthorized, "invalid or expired token".to_string())),
+            Err(e) => {
+                LOGGER.log(LogLevel::Error, &format!("Web > 인증 확인 실패: {}", e));
+                Outcome::Error((Status::InternalServerError, e))
+            }
+        }
+    }
+}
+
+fn forbidden(message: String) -> Custom<String> {
+    Custom(Status::Forbidden, message)
+}
+
+impl AuthUser {
+    pub fn require_owner(&self) -> Result<(), Custom<String>> {
+        if self.is_owner { Ok(()) } else { Err(forbidden("owner only".to_string())) }
+    }
+
+    pub fn require_guild(&self, guild_id: u64, min_role: WebRole) -> Result<(), Custom<String>> {
+        if self.has_role(guild_id, min_role) {
+            Ok(())
+        } else {
+            Err(forbidden(format!("no {:?} access to guild {}", min_role, guild_id)))
+        }
+    }
+
+    pub fn require_self_or_owner(&self, user_id: u64) -> Result<(), Custom<String>> {
+        if self.is_self_or_owner(user_id) {
+            Ok(())
+        } else {
+            Err(forbidden(format!("no access to user {}", user_id)))
+        }
+    }
+}
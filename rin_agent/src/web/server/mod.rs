@@ -1,2 +1,3 @@
 pub mod server;
-pub mod receipt;
\ No newline at end of file
+pub mod receipt;
+pub mod auth_guard;
\ No newline at end of file
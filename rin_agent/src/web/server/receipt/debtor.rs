@@ -0,0 +1,54 @@
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::serde::Deserialize;
+use rocket::{get, post};
+use serde_json::{json, Value};
+
+use crate::service::debt_service::{clear_debtor, create_debtor, get_debt_summary, DebtSummary};
+use crate::service::web_auth_service::AuthUser;
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct CreateDebtorRequest {
+    name: String,
+}
+
+// 예: GET /api/debts/123456789 -> 사람별 잔액과 합계
+#[get("/debts/<owner_id>")]
+pub async fn get_debt_balances(user: AuthUser, owner_id: u64) -> Result<Json<DebtSummary>, Custom<String>> {
+    user.require_self_or_owner(owner_id)?;
+    get_debt_summary(owner_id)
+        .await
+        .map(Json)
+        .map_err(|e| Custom(Status::InternalServerError, e))
+}
+
+// 같은 이름이 이미 있으면 그 사람을 돌려줍니다.
+#[post("/debts/<owner_id>/debtors", data = "<request>")]
+pub async fn create_debt_debtor(user: AuthUser, owner_id: u64, request: Json<CreateDebtorRequest>) -> Result<Json<Value>, Custom<String>> {
+    user.require_self_or_owner(owner_id)?;
+    let debtor = create_debtor(owner_id, &request.name)
+        .await
+        .map_err(|e| Custom(Status::BadRequest, e))?;
+    Ok(Json(json!({
+        "id": debtor.id,
+        "name": debtor.name,
+        "created_at": debtor.created_at.and_utc().to_rfc3339(),
+    })))
+}
+
+// 한 사람의 남은 영수증을 모두 정산합니다.
+#[post("/debts/<owner_id>/debtors/<name>/clear")]
+pub async fn clear_debt_debtor(user: AuthUser, owner_id: u64, name: &str) -> Result<Json<Value>, Custom<String>> {
+    user.require_self_or_owner(owner_id)?;
+    let (debtor, count, amount) = clear_debtor(owner_id, name)
+        .await
+        .map_err(|e| Custom(Status::BadRequest, e))?;
+    Ok(Json(json!({
+        "debtor_id": debtor.id,
+        "name": debtor.name,
+        "cleared_count": count,
+        "cleared_amount": amount,
+    })))
+}
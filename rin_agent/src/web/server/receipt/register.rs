@@ -1,66 +1,36 @@
-use rocket::{
-  Request, Response, form::{Form, FromForm}, http::Header, post, response::{Responder,Result}, serde::{Serialize, json::Json}
-};
+use rocket::http::Status;
+use rocket::post;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::serde::Deserialize;
 
-#[derive(FromForm)]
-pub struct RegistrationData {
-    username: String,
-    password: String,
-    email: String,
-}
+use crate::service::debt_service::{clear_receipt, record_debt, DebtEntry};
+use crate::service::web_auth_service::AuthUser;
 
-#[derive(Serialize)]
+#[derive(Deserialize)]
 #[serde(crate = "rocket::serde")]
-pub struct RegistrationResult {
-    success: bool,
-    message: String,
-    #[serde(skip)]
-    pub custom_header: Option<String>,
+pub struct RegisterReceiptRequest {
+    name: String,
+    // 양수: 받을 돈, 음수: 갚을 돈
+    amount: i64,
+    memo: Option<String>,
 }
 
-impl<'r> Responder<'r, 'static> for RegistrationResult {
-    fn respond_to(self, req: &'r Request<'_>) -> Result<'static> {
-        let custom_header = self.custom_header.clone();
-        let mut builder = Response::build_from(Json(self).respond_to(req)?);
-
-        if let Some(value) = custom_header {
-            builder.header(rocket::http::Header::new("X-Custom-Header", value));
-        }
-
-        builder.header(rocket::http::Header::new("X-Registration-Status", "Success"))
-            .ok()
-    }
+// 예: POST /api/debts/123456789/receipts {"name": "민수", "amount": 15000, "memo": "점심"}
+#[post("/debts/<owner_id>/receipts", data = "<request>")]
+pub async fn register_receipt(user: AuthUser, owner_id: u64, request: Json<RegisterReceiptRequest>) -> Result<Custom<Json<DebtEntry>>, Custom<String>> {
+    user.require_self_or_owner(owner_id)?;
+    record_debt(owner_id, &request.name, request.amount, request.memo.as_deref())
+        .await
+        .map(|entry| Custom(Status::Created, Json(entry)))
+        .map_err(|e| Custom(Status::BadRequest, e))
 }
 
-pub struct RequestHeaders<'r>(pub &'r rocket::http::HeaderMap<'r>);
-
-#[rocket::async_trait]
-impl<'r> rocket::request::FromRequest<'r> for RequestHeaders<'r> {
-    type Error = ();
-
-    async fn from_request(req: &'r Request<'_>) -> rocket::request::Outcome<Self, Self::Error> {
-        rocket::request::Outcome::Success(RequestHeaders(req.headers()))
-    }
+#[post("/debts/<owner_id>/receipts/<receipt_id>/clear")]
+pub async fn clear_debt_receipt(user: AuthUser, owner_id: u64, receipt_id: i64) -> Result<Json<DebtEntry>, Custom<String>> {
+    user.require_self_or_owner(owner_id)?;
+    clear_receipt(owner_id, receipt_id)
+        .await
+        .map(Json)
+        .map_err(|e| Custom(Status::BadRequest, e))
 }
-
-#[post("/register", data = "<registration_data>")]
-pub async fn register_receipt(
-  headers: RequestHeaders<'_>,
-  registration_data: Form<RegistrationData>
-) -> RegistrationResult {
-    // 예시: 특정 헤더 가져오기
-    let user_agent = headers.0.get_one("User-Agent");
-    
-    // Here you would typically handle the registration logic,
-    // such as saving the data to a database.
-    
-    // For demonstration, we will just return a success message.
-    RegistrationResult {
-        success: true,
-        message: format!(
-            "User '{}' registered successfully with email '{}'.",
-            registration_data.username, registration_data.email
-        ),
-        custom_header: Some("Dynamic-Value-From-Handler".to_string()),
-    }
-}
\ No newline at end of file
@@ -0,0 +1,23 @@
+use rocket::get;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+
+use crate::service::debt_service::{get_debt_history, DebtEntry};
+use crate::service::web_auth_service::AuthUser;
+
+// 예: GET /api/debts/123456789/history?name=민수&include_cleared=true&limit=20
+#[get("/debts/<owner_id>/history?<name>&<include_cleared>&<limit>")]
+pub async fn get_debt_receipts(
+    user: AuthUser,
+    owner_id: u64,
+    name: Option<&str>,
+    include_cleared: Option<bool>,
+    limit: Option<u64>,
+) -> Result<Json<Vec<DebtEntry>>, Custom<String>> {
+    user.require_self_or_owner(owner_id)?;
+    get_debt_history(owner_id, name, include_cleared.unwrap_or(false), limit.unwrap_or(50).min(500))
+        .await
+        .map(Json)
+        .map_err(|e| Custom(Status::BadRequest, e))
+}
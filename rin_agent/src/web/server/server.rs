@@ -3,8 +3,18 @@ use rocket::get;
 use rocket::routes;
 use rocket::catchers;
 
-use crate::web::server::receipt::register::register_receipt;
+use crate::web::server::receipt::debtor::{clear_debt_debtor, create_debt_debtor, get_debt_balances};
+use crate::web::server::receipt::register::{clear_debt_receipt, register_receipt};
+use crate::web::server::receipt::view::get_debt_receipts;
 use super::super::api::status::get_status;
+use super::super::api::usage::get_usage;
+use super::super::api::structured::get_structured_csv;
+use super::super::api::auth::{create_api_token, delete_api_token, get_api_tokens, get_me, login, login_callback, logout};
+use super::super::api::v1::alarms::{get_alarms, patch_alarm, post_alarm, remove_alarm};
+use super::super::api::v1::conversations::{get_conversation, list_user_conversations};
+use super::super::api::v1::usage::get_usage_report;
+use super::super::api::openai::{create_chat_completion, get_openai_models};
+use super::super::api::health::{get_healthz, get_metrics, get_readyz};
 
 #[get("/")]
 pub async fn test_index() -> &'static str {
@@ -16,6 +26,16 @@ pub fn not_found() -> &'static str {
     "404 Not Found"
 }
 
+#[catch(401)]
+pub fn unauthorized() -> &'static str {
+    "401 Unauthorized - login at /api/auth/login or send Authorization: Bearer <token>"
+}
+
+#[catch(403)]
+pub fn forbidden() -> &'static str {
+    "403 Forbidden"
+}
+
 #[get("/query")]
 pub fn test_query() -> &'static str {
     "----------------"
@@ -24,11 +44,39 @@ pub fn test_query() -> &'static str {
 pub fn get_rocket() -> rocket::Rocket<rocket::Build> {
     rocket::build()
         .mount("/", rocket::fs::FileServer::from("./client"))
+        .mount("/", routes![get_healthz, get_readyz, get_metrics])
         .mount("/api/", routes![
             test_index,
             get_status,
+            get_usage,
+            get_structured_csv,
             test_query,
-            register_receipt
+            login,
+            login_callback,
+            logout,
+            get_me,
+            get_api_tokens,
+            create_api_token,
+            delete_api_token,
+            get_debt_balances,
+            create_debt_debtor,
+            clear_debt_debtor,
+            register_receipt,
+            clear_debt_receipt,
+            get_debt_receipts
+        ])
+        .mount("/api/v1/", routes![
+            list_user_conversations,
+            get_conversation,
+            get_alarms,
+            post_alarm,
+            patch_alarm,
+            remove_alarm,
+            get_usage_report
+        ])
+        .mount("/v1/", routes![
+            get_openai_models,
+            create_chat_completion
         ])
-        .register("/", catchers![not_found])
+        .register("/", catchers![not_found, unauthorized, forbidden])
 }
\ No newline at end of file